@@ -424,3 +424,161 @@ fn take_after_equal(i: &mut dyn Iterator<Item = TokenTree>) -> proc_macro2::Toke
       )
       .collect()
 }
+
+/// Derives `crabmole::sort::Sort` for a struct of columns.
+///
+/// One field is marked `#[sort(key)]`: it decides the order, and every field,
+/// the key included, is swapped together so that the rows stay aligned. The
+/// key column must be indexable, and every column needs `len()` and
+/// `swap(i, j)` methods, as `Vec`, `VecDeque`, arrays and slices have.
+/// Values the key's `PartialOrd` cannot order (NaNs) are placed first.
+///
+/// All columns must have the same length: the generated `len` panics if they
+/// do not.
+///
+/// ```rust,ignore
+/// use crabmole::sort::Sort;
+///
+/// #[derive(Sort)]
+/// struct Trades {
+///     #[sort(key)]
+///     time: Vec<u64>,
+///     price: Vec<f64>,
+///     symbol: Vec<String>,
+/// }
+/// ```
+#[proc_macro_derive(Sort, attributes(sort))]
+pub fn derive_sort(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match expand_sort(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_sort(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        syn::Data::Struct(s) => &s.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Sort: can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut key = None;
+    let mut columns = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(i)),
+        };
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("sort")) {
+            match attr.parse_meta()? {
+                syn::Meta::List(list)
+                    if list.nested.len() == 1
+                        && matches!(
+                            &list.nested[0],
+                            syn::NestedMeta::Meta(syn::Meta::Path(p)) if p.is_ident("key")
+                        ) =>
+                {
+                    if key.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "Sort: only one field can be `#[sort(key)]`",
+                        ));
+                    }
+                    key = Some(member.clone());
+                }
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "Sort: expected `#[sort(key)]`",
+                    ))
+                }
+            }
+        }
+        columns.push(member);
+    }
+    let key = key.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "Sort: mark the field to sort by with `#[sort(key)]`",
+        )
+    })?;
+
+    let others = columns.iter().filter(|c| **c != key);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::crabmole::sort::Sort for #name #ty_generics #where_clause {
+            fn len(&self) -> usize {
+                let n = self.#key.len();
+                #(assert!(
+                    self.#others.len() == n,
+                    "sort: parallel slices have different lengths"
+                );)*
+                n
+            }
+
+            fn less(&self, i: usize, j: usize) -> bool {
+                ::crabmole::sort::__less(&self.#key[i], &self.#key[j])
+            }
+
+            fn swap(&mut self, i: usize, j: usize) {
+                #(self.#columns.swap(i, j);)*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(input: syn::DeriveInput) -> String {
+        expand_sort(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_sort_errors() {
+        assert_eq!(
+            expand_err(syn::parse_quote! {
+                struct Trades {
+                    time: Vec<u64>,
+                    price: Vec<f64>,
+                }
+            }),
+            "Sort: mark the field to sort by with `#[sort(key)]`"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote! {
+                struct Trades {
+                    #[sort(key)]
+                    time: Vec<u64>,
+                    #[sort(key)]
+                    price: Vec<f64>,
+                }
+            }),
+            "Sort: only one field can be `#[sort(key)]`"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote! {
+                struct Trades {
+                    #[sort(value)]
+                    time: Vec<u64>,
+                }
+            }),
+            "Sort: expected `#[sort(key)]`"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote! {
+                enum Trades {
+                    Time(Vec<u64>),
+                }
+            }),
+            "Sort: can only be derived for structs"
+        );
+    }
+}
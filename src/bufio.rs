@@ -0,0 +1,1269 @@
+use crate::{
+    bytealg,
+    io::{ByteReader, ByteScanner, ReaderFrom, RuneReader, RuneScanner, WriterTo},
+    unicode::utf8,
+};
+
+mod scan;
+pub use scan::*;
+
+#[cfg(feature = "async-io")]
+mod async_scan;
+#[cfg(feature = "async-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-io")))]
+pub use async_scan::*;
+
+const DEFAULT_BUF_SIZE: usize = 4096;
+const MIN_READ_BUFFER_SIZE: usize = 16;
+const MAX_CONSECUTIVE_EMPTY_READS: usize = 100;
+
+/// Errors returned by [`Reader`], [`Writer`] and [`Scanner`].
+#[derive(Debug)]
+pub enum Error {
+    /// `unread_byte` was called when the previous operation was not a read.
+    InvalidUnreadByte,
+    /// `unread_rune` was called when the previous operation was not a `read_rune`.
+    InvalidUnreadRune,
+    /// The requested data does not fit in the buffer.
+    BufferFull,
+    /// A token is longer than the max token size of a [`Scanner`].
+    TooLong,
+    /// A [`SplitFunc`] asked to advance beyond the input it was given.
+    AdvanceTooFar,
+    /// A [`std::io::Read`] returned an impossible count.
+    BadReadCount,
+    /// A special sentinel error value. It is intended to be
+    /// returned by a [`SplitFunc`] to indicate that the scanning should stop
+    /// with no error. If the token being delivered with this error is not `None`,
+    /// the token is the last token.
+    ///
+    /// The value is useful to stop processing early or when it is necessary to
+    /// deliver a final empty token (which is different from a `None` token).
+    /// One could achieve the same behavior with a custom error value but
+    /// providing one here is tidier.
+    FinalToken,
+    /// An error from the `io` layer, including [`crate::io::Error::Eof`].
+    Io(crate::io::Error),
+}
+
+impl Error {
+    /// Returns true if the error is [`crate::io::Error::Eof`]
+    #[inline]
+    pub const fn is_eof(&self) -> bool {
+        matches!(self, Self::Io(crate::io::Error::Eof))
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidUnreadByte => write!(f, "bufio: invalid use of UnreadByte"),
+            Error::InvalidUnreadRune => write!(f, "bufio: invalid use of UnreadRune"),
+            Error::BufferFull => write!(f, "bufio: buffer full"),
+            Error::TooLong => write!(f, "bufio.Scanner: token too long"),
+            Error::AdvanceTooFar => write!(
+                f,
+                "bufio.Scanner: SplitFunc returns advance count beyond input"
+            ),
+            Error::BadReadCount => write!(f, "bufio.Scanner: Read returned impossible count"),
+            Error::FinalToken => write!(f, "final token"),
+            Error::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<crate::io::Error> for Error {
+    fn from(e: crate::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e.into())
+    }
+}
+
+impl From<Error> for crate::io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            e => crate::io::Error::Io(std::io::Error::other(e)),
+        }
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(e: Error) -> Self {
+        crate::io::Error::from(e).into()
+    }
+}
+
+/// Implements buffering for a [`std::io::Read`] object.
+#[derive(Debug)]
+pub struct Reader<R> {
+    buf: Vec<u8>,
+    rd: R,
+    /// buf read position
+    r: usize,
+    /// buf write position
+    w: usize,
+    err: Option<crate::io::Error>,
+    /// last byte read for unread_byte; -1 means invalid
+    last_byte: isize,
+    /// size of last rune read for unread_rune; -1 means invalid
+    last_rune_size: isize,
+}
+
+impl<R: std::io::Read> Reader<R> {
+    /// Returns a new [`Reader`] whose buffer has the default size.
+    #[inline]
+    pub fn new(rd: R) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, rd)
+    }
+
+    /// Returns a new [`Reader`] whose buffer has at least the specified size.
+    #[inline]
+    pub fn with_capacity(size: usize, rd: R) -> Self {
+        Self {
+            buf: vec![0; size.max(MIN_READ_BUFFER_SIZE)],
+            rd,
+            r: 0,
+            w: 0,
+            err: None,
+            last_byte: -1,
+            last_rune_size: -1,
+        }
+    }
+
+    /// Returns the size of the underlying buffer in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Discards any buffered data, resets all state, and switches
+    /// the buffered reader to read from `r`.
+    #[inline]
+    pub fn reset(&mut self, r: R) {
+        self.rd = r;
+        self.r = 0;
+        self.w = 0;
+        self.err = None;
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.rd
+    }
+
+    /// Consumes the [`Reader`], returning the underlying reader.
+    /// Any buffered data is lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.rd
+    }
+
+    /// Reads a new chunk into the buffer.
+    fn fill(&mut self) {
+        // Slide existing data to beginning.
+        if self.r > 0 {
+            self.buf.copy_within(self.r..self.w, 0);
+            self.w -= self.r;
+            self.r = 0;
+        }
+
+        assert!(self.w < self.buf.len(), "bufio: tried to fill full buffer");
+
+        // Read new data: try a limited number of times.
+        for _ in 0..MAX_CONSECUTIVE_EMPTY_READS {
+            match self.rd.read(&mut self.buf[self.w..]) {
+                Ok(0) => {
+                    self.err = Some(crate::io::Error::Eof);
+                    return;
+                }
+                Ok(n) => {
+                    self.w += n;
+                    return;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.err = Some(e.into());
+                    return;
+                }
+            }
+        }
+        self.err = Some(crate::io::Error::NoProgress);
+    }
+
+    #[inline]
+    fn read_err(&mut self) -> Error {
+        self.err.take().unwrap_or(crate::io::Error::Eof).into()
+    }
+
+    /// Returns the next `n` bytes without advancing the reader. The bytes stop
+    /// being valid at the next read call. If fewer than `n` bytes are available,
+    /// an error explaining why the read is short is returned; the available bytes
+    /// can still be peeked with [`Reader::buffered`]. The error is
+    /// [`Error::BufferFull`] if `n` is larger than the buffer size.
+    ///
+    /// Calling `peek` prevents a [`Reader::unread_byte`] or [`Reader::unread_rune`] call from succeeding
+    /// until the next read operation.
+    pub fn peek(&mut self, n: usize) -> Result<&[u8], Error> {
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+
+        while self.w - self.r < n && self.w - self.r < self.buf.len() && self.err.is_none() {
+            self.fill(); // self.w - self.r < self.buf.len() => buffer is not full
+        }
+
+        if n > self.buf.len() {
+            return Err(Error::BufferFull);
+        }
+
+        // 0 <= n <= self.buf.len()
+        if self.w - self.r < n {
+            return Err(match self.err.take() {
+                Some(e) => e.into(),
+                None => Error::BufferFull,
+            });
+        }
+        Ok(&self.buf[self.r..self.r + n])
+    }
+
+    /// Skips the next `n` bytes, returning the number of bytes discarded.
+    ///
+    /// If `discard` skips fewer than `n` bytes, it also returns an error.
+    /// If 0 <= `n` <= [`Reader::buffered`], `discard` is guaranteed to succeed without
+    /// reading from the underlying [`std::io::Read`].
+    pub fn discard(&mut self, n: usize) -> Result<usize, Error> {
+        if n == 0 {
+            return Ok(0);
+        }
+
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+
+        let mut remain = n;
+        loop {
+            let mut skip = self.buffered();
+            if skip == 0 {
+                self.fill();
+                skip = self.buffered();
+            }
+            skip = skip.min(remain);
+            self.r += skip;
+            remain -= skip;
+            if remain == 0 {
+                return Ok(n);
+            }
+            if self.err.is_some() {
+                return Err(self.read_err());
+            }
+        }
+    }
+
+    /// Reads data into `buf`, returning the number of bytes read.
+    /// The bytes are taken from at most one read on the underlying [`std::io::Read`],
+    /// hence n may be less than `buf.len()`.
+    /// To read exactly `buf.len()` bytes, use `crate::io::read_full`.
+    /// At EOF, the error will be [`crate::io::Error::Eof`].
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            if self.buffered() > 0 {
+                return Ok(0);
+            }
+            return Err(self.read_err());
+        }
+
+        if self.r == self.w {
+            if self.err.is_some() {
+                return Err(self.read_err());
+            }
+
+            if buf.len() >= self.buf.len() {
+                // Large read, empty buffer.
+                // Read directly into buf to avoid copy.
+                return match self.rd.read(buf) {
+                    Ok(0) => Err(Error::Io(crate::io::Error::Eof)),
+                    Ok(n) => {
+                        self.last_byte = buf[n - 1] as isize;
+                        self.last_rune_size = -1;
+                        Ok(n)
+                    }
+                    Err(e) => Err(e.into()),
+                };
+            }
+
+            // One read.
+            self.r = 0;
+            self.w = 0;
+            match self.rd.read(&mut self.buf) {
+                Ok(0) => return Err(Error::Io(crate::io::Error::Eof)),
+                Ok(n) => self.w += n,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        // copy as much as we can
+        let n = crate::copy(&self.buf[self.r..self.w], buf);
+        self.r += n;
+        self.last_byte = self.buf[self.r - 1] as isize;
+        self.last_rune_size = -1;
+        Ok(n)
+    }
+
+    /// Reads and returns a single byte.
+    /// If no byte is available, returns an error.
+    pub fn read_byte(&mut self) -> Result<u8, Error> {
+        self.last_rune_size = -1;
+        while self.r == self.w {
+            if self.err.is_some() {
+                return Err(self.read_err());
+            }
+            self.fill(); // buffer is empty
+        }
+        let c = self.buf[self.r];
+        self.r += 1;
+        self.last_byte = c as isize;
+        Ok(c)
+    }
+
+    /// Unreads the last byte. Only the most recently read byte can be unread.
+    ///
+    /// `unread_byte` returns an error if the most recent method called on the
+    /// [`Reader`] was not a read operation. Notably, [`Reader::peek`], [`Reader::discard`], and
+    /// `write_to` are not considered read operations.
+    pub fn unread_byte(&mut self) -> Result<(), Error> {
+        if self.last_byte < 0 || self.r == 0 && self.w > 0 {
+            return Err(Error::InvalidUnreadByte);
+        }
+
+        // self.r > 0 || self.w == 0
+        if self.r > 0 {
+            self.r -= 1;
+        } else {
+            // self.r == 0 && self.w == 0
+            self.w = 1;
+        }
+        self.buf[self.r] = self.last_byte as u8;
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+        Ok(())
+    }
+
+    /// Reads a single UTF-8 encoded Unicode character and returns the
+    /// rune and its size in bytes. If the encoded rune is invalid, it consumes one byte
+    /// and returns `U+FFFD` with a size of 1.
+    pub fn read_rune(&mut self) -> Result<(char, usize), Error> {
+        while self.r + utf8::UTF_MAX > self.w
+            && !utf8::full_rune(&self.buf[self.r..self.w])
+            && self.err.is_none()
+            && self.w - self.r < self.buf.len()
+        {
+            self.fill(); // self.w - self.r < self.buf.len() => buffer is not full
+        }
+
+        self.last_rune_size = -1;
+        if self.r == self.w {
+            return Err(self.read_err());
+        }
+
+        let (r, size) = if self.buf[self.r] < utf8::RUNE_SELF {
+            (self.buf[self.r] as char, 1)
+        } else {
+            utf8::decode_rune(&self.buf[self.r..self.w])
+        };
+        self.r += size;
+        self.last_byte = self.buf[self.r - 1] as isize;
+        self.last_rune_size = size as isize;
+        Ok((r, size))
+    }
+
+    /// Unreads the last rune. If the most recent method called on
+    /// the [`Reader`] was not a [`Reader::read_rune`], `unread_rune` returns an error. (In this
+    /// regard it is stricter than [`Reader::unread_byte`], which will unread the last byte
+    /// from any read operation.)
+    pub fn unread_rune(&mut self) -> Result<(), Error> {
+        if self.last_rune_size < 0 || self.r < self.last_rune_size as usize {
+            return Err(Error::InvalidUnreadRune);
+        }
+        self.r -= self.last_rune_size as usize;
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+        Ok(())
+    }
+
+    /// Returns the number of bytes that can be read from the current buffer.
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.w - self.r
+    }
+
+    /// Reads until the first occurrence of `delim` in the input, returning the
+    /// range of the buffer holding the data and the error, if any, that stopped the search.
+    fn read_slice_in(&mut self, delim: u8) -> (usize, usize, Option<Error>) {
+        let mut s = 0; // search start index
+        let (start, end, err) = loop {
+            // Search buffer.
+            if let Some(i) = bytealg::index_byte(&self.buf[self.r + s..self.w], delim) {
+                let start = self.r;
+                self.r += s + i + 1;
+                break (start, self.r, None);
+            }
+
+            // Pending error?
+            if self.err.is_some() {
+                let start = self.r;
+                self.r = self.w;
+                break (start, self.w, Some(self.read_err()));
+            }
+
+            // Buffer full?
+            if self.buffered() >= self.buf.len() {
+                self.r = self.w;
+                break (0, self.buf.len(), Some(Error::BufferFull));
+            }
+
+            s = self.w - self.r; // do not rescan area we scanned before
+
+            self.fill(); // buffer is not full
+        };
+
+        // Handle last byte, if any.
+        if end > start {
+            self.last_byte = self.buf[end - 1] as isize;
+            self.last_rune_size = -1;
+        }
+        (start, end, err)
+    }
+
+    /// Reads until the first occurrence of `delim` in the input,
+    /// returning a slice pointing at the bytes in the buffer.
+    /// The bytes stop being valid at the next read.
+    ///
+    /// If `read_slice` encounters the end of the input before finding a delimiter,
+    /// it returns the remaining data, which does not end in `delim`; the next call
+    /// then returns [`crate::io::Error::Eof`]. If an error other than EOF stops the search
+    /// after some data has been read, that data is returned and the error is reported
+    /// by the next call.
+    /// `read_slice` fails with [`Error::BufferFull`] if the buffer fills without a `delim`,
+    /// leaving the data buffered, so it can be inspected with [`Reader::peek`] and skipped with [`Reader::discard`].
+    /// Because the data returned from `read_slice` will be overwritten
+    /// by the next I/O operation, most clients should use
+    /// [`Reader::read_bytes`] or [`Reader::read_string`] instead.
+    pub fn read_slice(&mut self, delim: u8) -> Result<&[u8], Error> {
+        let (start, end, err) = self.read_slice_in(delim);
+        match err {
+            None => Ok(&self.buf[start..end]),
+            Some(Error::BufferFull) => {
+                self.r = 0;
+                self.last_byte = -1;
+                Err(Error::BufferFull)
+            }
+            Some(e) if start == end => Err(e),
+            Some(e) => {
+                if !e.is_eof() {
+                    self.err = Some(e.into());
+                }
+                Ok(&self.buf[start..end])
+            }
+        }
+    }
+
+    /// A low-level line-reading primitive. Most callers should use
+    /// [`Reader::read_bytes`]`(b'\n')` or [`Reader::read_string`]`(b'\n')` instead or use a [`Scanner`].
+    ///
+    /// `read_line` tries to return a single line, not including the end-of-line bytes.
+    /// If the line was too long for the buffer then `is_prefix` (the second element of the returned tuple)
+    /// is set and the beginning of the line is returned. The rest of the line will be returned
+    /// from future calls. `is_prefix` will be false when returning the last fragment
+    /// of the line. The returned buffer is only valid until the next call to
+    /// `read_line`. `read_line` either returns a line or it returns an error,
+    /// never both.
+    ///
+    /// The text returned from `read_line` does not include the line end ("\r\n" or "\n").
+    /// No indication or error is given if the input ends without a final line end.
+    /// Calling [`Reader::unread_byte`] after `read_line` will always unread the last byte read
+    /// (possibly a character belonging to the line end) even if that byte is not
+    /// part of the line returned by `read_line`.
+    pub fn read_line(&mut self) -> Result<(&[u8], bool), Error> {
+        let (start, mut end, err) = self.read_slice_in(b'\n');
+        match err {
+            Some(Error::BufferFull) => {
+                // Handle the case where "\r\n" straddles the buffer.
+                if end > start && self.buf[end - 1] == b'\r' {
+                    // Put the '\r' back on buf and drop it from line.
+                    // Let the next call to read_line check for "\r\n".
+                    assert!(self.r > 0, "bufio: tried to rewind past start of buffer");
+                    self.r -= 1;
+                    end -= 1;
+                }
+                return Ok((&self.buf[start..end], true));
+            }
+            Some(e) if start == end => return Err(e),
+            Some(e) if !e.is_eof() => self.err = Some(e.into()),
+            _ => {}
+        }
+
+        if self.buf[end - 1] == b'\n' {
+            let mut drop = 1;
+            if end - start > 1 && self.buf[end - 2] == b'\r' {
+                drop = 2;
+            }
+            end -= drop;
+        }
+        Ok((&self.buf[start..end], false))
+    }
+
+    /// Reads until the first occurrence of `delim` in the input,
+    /// returning a vector containing the data up to and including the delimiter.
+    /// If `read_bytes` encounters the end of the input before finding a delimiter,
+    /// it returns the data read before EOF, and the next call returns [`crate::io::Error::Eof`].
+    /// If an error other than EOF happens after some data has been read,
+    /// that data is returned and the error is reported by the next call.
+    pub fn read_bytes(&mut self, delim: u8) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        let err = loop {
+            let (start, end, err) = self.read_slice_in(delim);
+            buf.extend_from_slice(&self.buf[start..end]);
+            match err {
+                // got final fragment
+                None => return Ok(buf),
+                // Make a copy of the buffer and keep reading.
+                Some(Error::BufferFull) => {}
+                Some(e) => break e,
+            }
+        };
+
+        if buf.is_empty() {
+            return Err(err);
+        }
+
+        if !err.is_eof() {
+            self.err = Some(err.into());
+        }
+        Ok(buf)
+    }
+
+    /// Like [`Reader::read_bytes`], but returns a [`String`].
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD`.
+    #[inline]
+    pub fn read_string(&mut self, delim: u8) -> Result<String, Error> {
+        self.read_bytes(delim).map(|buf| {
+            String::from_utf8(buf)
+                .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+        })
+    }
+
+    /// Writes the buffer into the writer.
+    fn write_buf<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+        let n = w.write(&self.buf[self.r..self.w])?;
+        self.r += n;
+        Ok(n as u64)
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match Reader::read(self, buf) {
+            Ok(n) => Ok(n),
+            Err(e) if e.is_eof() => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<R: std::io::Read> std::io::BufRead for Reader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.r == self.w {
+            match self.err.take() {
+                None => self.fill(),
+                Some(crate::io::Error::Eof) => return Ok(&[]),
+                Some(e) => return Err(e.into()),
+            }
+            if let Some(e) = self.err.take() {
+                if !e.is_eof() {
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(&self.buf[self.r..self.w])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.r = (self.r + amt).min(self.w);
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+    }
+}
+
+impl<R: std::io::Read> ByteReader for Reader<R> {
+    #[inline]
+    fn read_byte(&mut self) -> Result<u8, crate::io::Error> {
+        Reader::read_byte(self).map_err(Into::into)
+    }
+}
+
+impl<R: std::io::Read> ByteScanner for Reader<R> {
+    #[inline]
+    fn unread_byte(&mut self) -> Result<(), crate::io::Error> {
+        Reader::unread_byte(self).map_err(Into::into)
+    }
+}
+
+impl<R: std::io::Read> RuneReader for Reader<R> {
+    #[inline]
+    fn read_rune(&mut self) -> Result<(char, usize), crate::io::Error> {
+        Reader::read_rune(self).map_err(Into::into)
+    }
+}
+
+impl<R: std::io::Read> RuneScanner for Reader<R> {
+    #[inline]
+    fn unread_rune(&mut self) -> Result<(), crate::io::Error> {
+        Reader::unread_rune(self).map_err(Into::into)
+    }
+}
+
+impl<R: std::io::Read> WriterTo for Reader<R> {
+    /// Writes the buffered data and then reads from the underlying reader until EOF.
+    /// This may make multiple calls to the read method of the underlying [`std::io::Read`].
+    fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+        self.last_byte = -1;
+        self.last_rune_size = -1;
+
+        let mut n = self.write_buf(w)?;
+        if self.w - self.r < self.buf.len() {
+            self.fill(); // buffer not full
+        }
+
+        while self.r < self.w {
+            // self.r < self.w => buffer is not empty
+            n += self.write_buf(w)?;
+            self.fill(); // buffer is empty
+        }
+
+        match self.err.take() {
+            None | Some(crate::io::Error::Eof) => Ok(n),
+            Some(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Implements buffering for an [`std::io::Write`] object.
+/// If an error occurs writing to a [`Writer`], no more data will be
+/// accepted and all subsequent writes, and [`Writer::flush`], will return the error.
+/// After all data has been written, the client should call the
+/// [`Writer::flush`] method to guarantee all data has been forwarded to
+/// the underlying [`std::io::Write`].
+#[derive(Debug)]
+pub struct Writer<W> {
+    err: Option<std::io::Error>,
+    buf: Vec<u8>,
+    n: usize,
+    wr: W,
+}
+
+impl<W: std::io::Write> Writer<W> {
+    /// Returns a new [`Writer`] whose buffer has the default size.
+    #[inline]
+    pub fn new(w: W) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, w)
+    }
+
+    /// Returns a new [`Writer`] whose buffer has at least the specified size.
+    #[inline]
+    pub fn with_capacity(size: usize, w: W) -> Self {
+        Self {
+            err: None,
+            buf: vec![0; if size == 0 { DEFAULT_BUF_SIZE } else { size }],
+            n: 0,
+            wr: w,
+        }
+    }
+
+    /// Returns the size of the underlying buffer in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Discards any unflushed buffered data, clears any error, and
+    /// resets the writer to write its output to `w`.
+    #[inline]
+    pub fn reset(&mut self, w: W) {
+        self.err = None;
+        self.n = 0;
+        self.wr = w;
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.wr
+    }
+
+    #[inline]
+    fn stored_err(&self) -> Option<std::io::Error> {
+        self.err
+            .as_ref()
+            .map(|e| std::io::Error::new(e.kind(), e.to_string()))
+    }
+
+    /// Writes any buffered data to the underlying [`std::io::Write`].
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(e) = self.stored_err() {
+            return Err(e);
+        }
+
+        if self.n == 0 {
+            return Ok(());
+        }
+
+        // Partial writes are fine, keep going until the buffer is drained.
+        let mut written = 0;
+        let mut err = None;
+        while written < self.n {
+            match self.wr.write(&self.buf[written..self.n]) {
+                Ok(0) => {
+                    err = Some(crate::io::Error::ShortWrite.into());
+                    break;
+                }
+                Ok(n) => written += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    err = Some(e);
+                    break;
+                }
+            }
+        }
+
+        if written > 0 && written < self.n {
+            self.buf.copy_within(written..self.n, 0);
+        }
+        self.n -= written;
+        match err {
+            None => Ok(()),
+            Some(e) => {
+                let rst = std::io::Error::new(e.kind(), e.to_string());
+                self.err = Some(e);
+                Err(rst)
+            }
+        }
+    }
+
+    /// Returns how many bytes are unused in the buffer.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.buf.len() - self.n
+    }
+
+    /// Returns an empty buffer with [`Writer::available`] capacity, which is
+    /// intended to be filled and passed to an immediately succeeding write call.
+    /// The buffer is only valid until the next write operation on the [`Writer`].
+    #[inline]
+    pub fn available_buffer(&mut self) -> &mut [u8] {
+        &mut self.buf[self.n..]
+    }
+
+    /// Returns the number of bytes that have been written into the current buffer.
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.n
+    }
+
+    /// Writes a single Unicode code point, returning
+    /// the number of bytes written and any error.
+    pub fn write_rune(&mut self, r: char) -> std::io::Result<usize> {
+        let mut buf = [0; 4];
+        let s = r.encode_utf8(&mut buf);
+        std::io::Write::write_all(self, s.as_bytes())?;
+        Ok(s.len())
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for Writer<W> {
+    /// Writes the contents of `buf` into the buffer.
+    /// If fewer bytes than `buf.len()` are written, the error is returned by the next call.
+    fn write(&mut self, mut buf: &[u8]) -> std::io::Result<usize> {
+        let mut nn = 0;
+        while buf.len() > self.available() && self.err.is_none() {
+            let n = if self.buffered() == 0 {
+                // Large write, empty buffer.
+                // Write directly from buf to avoid copy.
+                match self.wr.write(buf) {
+                    Ok(0) => {
+                        self.err = Some(crate::io::Error::ShortWrite.into());
+                        0
+                    }
+                    Ok(n) => n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => 0,
+                    Err(e) => {
+                        self.err = Some(e);
+                        0
+                    }
+                }
+            } else {
+                let n = crate::copy(buf, &mut self.buf[self.n..]);
+                self.n += n;
+                let _ = Writer::flush(self);
+                n
+            };
+            nn += n;
+            buf = &buf[n..];
+        }
+
+        if let Some(e) = self.stored_err() {
+            if nn > 0 {
+                return Ok(nn);
+            }
+            return Err(e);
+        }
+
+        let n = crate::copy(buf, &mut self.buf[self.n..]);
+        self.n += n;
+        Ok(nn + n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Writer::flush(self)?;
+        self.wr.flush()
+    }
+}
+
+impl<W: std::io::Write> ReaderFrom for Writer<W> {
+    /// Reads data from `r` into the buffer, flushing it to the underlying writer when full.
+    fn read_from<R: std::io::Read + ?Sized>(&mut self, r: &mut R) -> std::io::Result<u64> {
+        if let Some(e) = self.stored_err() {
+            return Err(e);
+        }
+
+        let mut n = 0u64;
+        loop {
+            if self.available() == 0 {
+                Writer::flush(self)?;
+            }
+
+            let mut nr = 0;
+            while nr < MAX_CONSECUTIVE_EMPTY_READS {
+                match r.read(&mut self.buf[self.n..]) {
+                    Ok(0) => return Ok(n),
+                    Ok(m) => {
+                        self.n += m;
+                        n += m as u64;
+                        break;
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => nr += 1,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            if nr == MAX_CONSECUTIVE_EMPTY_READS {
+                return Err(crate::io::Error::NoProgress.into());
+            }
+        }
+    }
+}
+
+/// Stores pointers to a [`Reader`] and a [`Writer`].
+/// It implements [`std::io::Read`] and [`std::io::Write`].
+#[derive(Debug)]
+pub struct ReadWriter<R, W> {
+    /// The buffered reader
+    pub reader: Reader<R>,
+    /// The buffered writer
+    pub writer: Writer<W>,
+}
+
+impl<R, W> ReadWriter<R, W> {
+    /// Allocates a new [`ReadWriter`] that dispatches to `r` and `w`.
+    #[inline]
+    pub const fn new(reader: Reader<R>, writer: Writer<W>) -> Self {
+        Self { reader, writer }
+    }
+}
+
+impl<R: std::io::Read, W> std::io::Read for ReadWriter<R, W> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::io::Read::read(&mut self.reader, buf)
+    }
+}
+
+impl<R, W: std::io::Write> std::io::Write for ReadWriter<R, W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(&mut self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    /// Reads at most `n` bytes per call from the inner reader, like Go's iotest.OneByteReader
+    /// generalised.
+    struct ChunkReader<'a> {
+        data: &'a [u8],
+        n: usize,
+    }
+
+    impl Read for ChunkReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.n.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    /// Returns the data, then a non-EOF error.
+    struct ErrReader<'a>(&'a [u8]);
+
+    impl Read for ErrReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(std::io::Error::other("boom"));
+            }
+            let n = crate::copy(self.0, buf);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_reader_simple() {
+        let data = b"hello world";
+        let mut b = Reader::new(&data[..]);
+        let mut out = Vec::new();
+        b.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+
+        let mut b = Reader::with_capacity(0, ChunkReader { data, n: 1 });
+        assert_eq!(b.size(), MIN_READ_BUFFER_SIZE);
+        let mut out = String::new();
+        b.read_to_string(&mut out).unwrap();
+        assert_eq!(out.as_bytes(), data);
+    }
+
+    #[test]
+    fn test_read_line() {
+        let data = b"line1\r\nline2\nline3";
+        for n in [1, 2, 3, 7, 100] {
+            let mut b = Reader::with_capacity(16, ChunkReader { data, n });
+            let mut lines = Vec::new();
+            loop {
+                match b.read_line() {
+                    Ok((line, prefix)) => {
+                        assert!(!prefix);
+                        lines.push(line.to_vec());
+                    }
+                    Err(e) => {
+                        assert!(e.is_eof());
+                        break;
+                    }
+                }
+            }
+            assert_eq!(
+                lines,
+                vec![b"line1".to_vec(), b"line2".to_vec(), b"line3".to_vec()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_line_too_long() {
+        let mut data = Vec::new();
+        let buf = vec![b'a'; MIN_READ_BUFFER_SIZE];
+        data.extend_from_slice(&buf);
+        data.extend_from_slice(&buf);
+        data.extend_from_slice(b"b\n");
+        let mut b = Reader::with_capacity(MIN_READ_BUFFER_SIZE, &data[..]);
+        let (line, prefix) = b.read_line().unwrap();
+        assert_eq!((line, prefix), (&buf[..], true));
+        let (line, prefix) = b.read_line().unwrap();
+        assert_eq!((line, prefix), (&buf[..], true));
+        let (line, prefix) = b.read_line().unwrap();
+        assert_eq!((line, prefix), (&b"b"[..], false));
+        assert!(b.read_line().unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn test_read_line_straddled_crlf() {
+        // "\r\n" straddles the buffer boundary.
+        let mut data = vec![b'a'; MIN_READ_BUFFER_SIZE - 1];
+        data.extend_from_slice(b"\r\nb\n");
+        let mut b = Reader::with_capacity(MIN_READ_BUFFER_SIZE, &data[..]);
+        let (line, prefix) = b.read_line().unwrap();
+        assert_eq!((line.len(), prefix), (MIN_READ_BUFFER_SIZE - 1, true));
+        let (line, prefix) = b.read_line().unwrap();
+        assert_eq!((line, prefix), (&b""[..], false));
+        let (line, prefix) = b.read_line().unwrap();
+        assert_eq!((line, prefix), (&b"b"[..], false));
+    }
+
+    #[test]
+    fn test_read_slice() {
+        let mut b = Reader::with_capacity(16, &b"a,bc,def"[..]);
+        assert_eq!(b.read_slice(b',').unwrap(), b"a,");
+        assert_eq!(b.read_slice(b',').unwrap(), b"bc,");
+        assert_eq!(b.read_slice(b',').unwrap(), b"def");
+        assert!(b.read_slice(b',').unwrap_err().is_eof());
+
+        let data = [b'x'; 40];
+        let mut b = Reader::with_capacity(16, &data[..]);
+        assert!(matches!(b.read_slice(b','), Err(Error::BufferFull)));
+        assert_eq!(b.buffered(), 16);
+        assert_eq!(b.discard(16).unwrap(), 16);
+        assert_eq!(b.read_bytes(b',').unwrap(), vec![b'x'; 24]);
+
+        // a non-EOF error is reported after the data preceding it
+        let mut b = Reader::new(ErrReader(b"abc"));
+        assert_eq!(b.read_slice(b'\n').unwrap(), b"abc");
+        assert!(matches!(
+            b.read_slice(b'\n'),
+            Err(Error::Io(crate::io::Error::Io(_)))
+        ));
+    }
+
+    #[test]
+    fn test_read_string() {
+        let mut b = Reader::new(&b"hello\nworld"[..]);
+        assert_eq!(b.read_string(b'\n').unwrap(), "hello\n");
+        assert_eq!(b.read_string(b'\n').unwrap(), "world");
+        assert!(b.read_string(b'\n').unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut b = Reader::with_capacity(
+            16,
+            ChunkReader {
+                data: b"abcdefghijklmnopqrstuvwxyz",
+                n: 1,
+            },
+        );
+        assert_eq!(b.peek(1).unwrap(), b"a");
+        assert_eq!(b.peek(4).unwrap(), b"abcd");
+        assert!(matches!(b.peek(32), Err(Error::BufferFull)));
+        let mut buf = [0; 3];
+        assert_eq!(b.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf, b"abc");
+        assert_eq!(b.peek(16).unwrap(), b"defghijklmnopqrs");
+        assert_eq!(b.peek(0).unwrap(), b"");
+        assert!(b.unread_byte().is_err());
+        assert_eq!(b.discard(20).unwrap(), 20);
+        assert!(b.peek(4).unwrap_err().is_eof());
+        assert_eq!(b.buffered(), 3);
+        assert_eq!(b.peek(3).unwrap(), b"xyz");
+    }
+
+    #[test]
+    fn test_unread_byte() {
+        let mut b = Reader::new(&b"ab"[..]);
+        assert!(matches!(b.unread_byte(), Err(Error::InvalidUnreadByte)));
+        assert_eq!(b.read_byte().unwrap(), b'a');
+        b.unread_byte().unwrap();
+        assert!(b.unread_byte().is_err());
+        assert_eq!(b.read_byte().unwrap(), b'a');
+        assert_eq!(b.read_byte().unwrap(), b'b');
+        assert!(b.read_byte().unwrap_err().is_eof());
+        // the last byte read can still be unread after hitting EOF
+        b.unread_byte().unwrap();
+        assert_eq!(b.read_byte().unwrap(), b'b');
+    }
+
+    #[test]
+    fn test_read_rune() {
+        let data = "héllo, 世界!\u{1F600}".as_bytes();
+        for n in [1, 2, 3, 100] {
+            let mut b = Reader::with_capacity(16, ChunkReader { data, n });
+            let mut s = String::new();
+            loop {
+                match b.read_rune() {
+                    Ok((r, size)) => {
+                        assert_eq!(r.len_utf8(), size);
+                        s.push(r);
+                    }
+                    Err(e) => {
+                        assert!(e.is_eof());
+                        break;
+                    }
+                }
+            }
+            assert_eq!(s.as_bytes(), data);
+        }
+
+        let mut b = Reader::new(&b"\xffa\xe4\xb8"[..]);
+        assert_eq!(b.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert_eq!(b.read_rune().unwrap(), ('a', 1));
+        assert_eq!(b.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert_eq!(b.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert!(b.read_rune().unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn test_unread_rune() {
+        let mut b = Reader::new("世a".as_bytes());
+        assert!(matches!(b.unread_rune(), Err(Error::InvalidUnreadRune)));
+        assert_eq!(b.read_rune().unwrap(), ('世', 3));
+        b.unread_rune().unwrap();
+        assert!(b.unread_rune().is_err());
+        assert_eq!(b.read_rune().unwrap(), ('世', 3));
+        assert_eq!(b.read_byte().unwrap(), b'a');
+        assert!(b.unread_rune().is_err());
+        b.unread_byte().unwrap();
+        assert_eq!(b.read_rune().unwrap(), ('a', 1));
+    }
+
+    #[test]
+    fn test_reader_write_to() {
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        let mut b = Reader::with_capacity(16, ChunkReader { data: &data, n: 7 });
+        assert_eq!(b.read_byte().unwrap(), 0);
+        let mut out = Vec::new();
+        assert_eq!(b.write_to(&mut out).unwrap(), 999);
+        assert_eq!(out, &data[1..]);
+    }
+
+    #[test]
+    fn test_writer() {
+        let data: Vec<u8> = (0..8192u32).map(|i| b'a' + (i % 26) as u8).collect();
+        for nwrite in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 20, 100, 200, 1000, 8192] {
+            for bs in [1, 2, 3, 4, 7, 8, 9, 16, 1024, 4096] {
+                let mut w = Writer::with_capacity(bs, Vec::new());
+                let n = w.write(&data[..nwrite]).unwrap();
+                assert_eq!(n, nwrite);
+                assert_eq!(w.buffered() + w.get_ref().len(), nwrite);
+                assert_eq!(w.available(), w.size() - w.buffered());
+                Writer::flush(&mut w).unwrap();
+                assert_eq!(w.buffered(), 0);
+                assert_eq!(w.get_ref(), &data[..nwrite]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_writer_errors() {
+        struct ErrWriter(usize);
+
+        impl Write for ErrWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(std::io::Error::other("full"));
+                }
+                let n = self.0.min(buf.len());
+                self.0 -= n;
+                Ok(n)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut w = Writer::with_capacity(4, ErrWriter(2));
+        assert_eq!(w.write(b"abc").unwrap(), 3);
+        let err = Writer::flush(&mut w).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Other);
+        assert_eq!(w.buffered(), 1);
+        // the error is sticky
+        assert!(w.write(b"d").is_err());
+        assert!(Writer::flush(&mut w).is_err());
+    }
+
+    /// Accepts at most `n` bytes per call.
+    struct PartialWriter {
+        n: usize,
+        data: Vec<u8>,
+    }
+
+    impl Write for PartialWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = self.n.min(buf.len());
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_writer_partial_writes() {
+        let w = PartialWriter { n: 3, data: vec![] };
+        let mut w = Writer::with_capacity(8, w);
+        w.write_all(b"hello, world").unwrap();
+        w.write_all(b"!").unwrap();
+        Writer::flush(&mut w).unwrap();
+        assert_eq!(w.buffered(), 0);
+        assert_eq!(w.get_ref().data, b"hello, world!");
+    }
+
+    #[test]
+    fn test_writer_zero_writes() {
+        // Direct write path, the buffer is empty.
+        let w = PartialWriter { n: 0, data: vec![] };
+        let mut w = Writer::with_capacity(4, w);
+        let err = w.write(b"hello world").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+
+        // Flush path.
+        let w = PartialWriter { n: 0, data: vec![] };
+        let mut w = Writer::with_capacity(4, w);
+        assert_eq!(w.write(b"abc").unwrap(), 3);
+        assert_eq!(w.write(b"hello world").unwrap(), 1);
+        assert_eq!(
+            Writer::flush(&mut w).unwrap_err().kind(),
+            std::io::ErrorKind::WriteZero
+        );
+        assert_eq!(w.buffered(), 4);
+    }
+
+    #[test]
+    fn test_writer_rune_and_read_from() {
+        let mut w = Writer::with_capacity(16, Vec::new());
+        assert_eq!(w.write_rune('世').unwrap(), 3);
+        assert_eq!(w.write_rune('a').unwrap(), 1);
+        let data = [b'z'; 100];
+        assert_eq!(
+            w.read_from(&mut ChunkReader { data: &data, n: 9 }).unwrap(),
+            100
+        );
+        Writer::flush(&mut w).unwrap();
+        let mut expected = "世a".as_bytes().to_vec();
+        expected.extend_from_slice(&data);
+        assert_eq!(w.get_ref(), &expected);
+    }
+
+    #[test]
+    fn test_read_writer() {
+        let mut rw = ReadWriter::new(Reader::new(&b"ping"[..]), Writer::new(Vec::new()));
+        let mut buf = [0; 4];
+        rw.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+        rw.write_all(b"pong").unwrap();
+        rw.flush().unwrap();
+        assert_eq!(rw.writer.get_ref(), b"pong");
+    }
+}
@@ -0,0 +1,215 @@
+use super::{scan::ScanState, scan::Step, Error};
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{
+    io::AsyncRead,
+    ready,
+    stream::{FusedStream, Stream},
+};
+
+/// The asynchronous version of [`Scanner`](super::Scanner).
+///
+/// It drives the same [`SplitFunc`](super::SplitFunc) contract over an [`AsyncRead`],
+/// and yields the tokens as a [`Stream`]. The stream ends at EOF; if scanning stops
+/// with any other error, that error is yielded as the last item.
+///
+/// Unlike [`Scanner`](super::Scanner), tokens are handed out as owned buffers, since
+/// a [`Stream`] item can not borrow from the stream itself.
+pub struct AsyncScanner<R> {
+    /// The reader provided by the client.
+    r: R,
+    state: ScanState,
+    /// A read into the buffer is in flight.
+    reading: bool,
+    /// The stream has ended.
+    finished: bool,
+}
+
+impl<R: core::fmt::Debug> core::fmt::Debug for AsyncScanner<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AsyncScanner")
+            .field("r", &self.r)
+            .field("state", &self.state)
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncScanner<R> {
+    /// Returns a new [`AsyncScanner`] to read from `r`.
+    /// The split function defaults to [`scan_lines`](super::scan_lines).
+    #[inline]
+    pub fn new(r: R) -> Self {
+        Self {
+            r,
+            state: ScanState::new(),
+            reading: false,
+            finished: false,
+        }
+    }
+
+    /// Sets the initial buffer to use when scanning and the maximum
+    /// size of buffer that may be allocated during scanning.
+    /// See [`Scanner::buffer`](super::Scanner::buffer).
+    ///
+    /// # Panics
+    /// Panics if it is called after scanning has started.
+    #[inline]
+    pub fn buffer(&mut self, buf: Vec<u8>, max: usize) {
+        self.state.buffer(buf, max)
+    }
+
+    /// Sets the split function for the [`AsyncScanner`].
+    /// The default split function is [`scan_lines`](super::scan_lines).
+    ///
+    /// # Panics
+    /// Panics if it is called after scanning has started.
+    #[inline]
+    pub fn split<F>(&mut self, split: F)
+    where
+        F: FnMut(&[u8], bool) -> (usize, Option<&[u8]>, Option<Error>) + Send + 'static,
+    {
+        self.state.split(Box::new(split))
+    }
+
+    /// Consumes the [`AsyncScanner`], returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncScanner<R> {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            if !this.reading {
+                match this.state.step() {
+                    Step::Token => return Poll::Ready(Some(Ok(this.state.token().to_vec()))),
+                    Step::Stop => {
+                        this.finished = true;
+                        return Poll::Ready(this.state.err.take().filter(|e| !e.is_eof()).map(Err));
+                    }
+                    Step::Read => this.reading = true,
+                }
+            }
+
+            let rst = ready!(Pin::new(&mut this.r).poll_read(cx, this.state.read_buf()));
+            if !this.state.on_read(rst) {
+                this.reading = false;
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> FusedStream for AsyncScanner<R> {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{scan_runes, scan_words};
+    use super::*;
+    use futures_util::StreamExt;
+
+    /// Hands out at most `n` bytes per read, returning `Pending` before every read.
+    struct SlowReader<'a> {
+        data: &'a [u8],
+        n: usize,
+        ready: bool,
+    }
+
+    impl AsyncRead for SlowReader<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.ready = false;
+            let n = self.n.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    async fn collect<R: AsyncRead + Unpin>(s: AsyncScanner<R>) -> Vec<String> {
+        s.map(|tok| String::from_utf8(tok.unwrap()).unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_async_scan_lines() {
+        let data = b"abc\r\ndef\n\r\n\nghi\r";
+        for n in [1, 2, 5, 100] {
+            let s = AsyncScanner::new(SlowReader {
+                data,
+                n,
+                ready: false,
+            });
+            assert_eq!(collect(s).await, ["abc", "def", "", "", "ghi"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_scan_words_and_runes() {
+        let mut s = AsyncScanner::new(&b" hello \t async\nworld "[..]);
+        s.split(scan_words);
+        assert_eq!(collect(s).await, ["hello", "async", "world"]);
+
+        let data = "a¼\u{FFFD}日".as_bytes();
+        let mut s = AsyncScanner::new(SlowReader {
+            data,
+            n: 1,
+            ready: false,
+        });
+        s.split(scan_runes);
+        assert_eq!(collect(s).await, ["a", "¼", "\u{FFFD}", "日"]);
+    }
+
+    #[tokio::test]
+    async fn test_async_final_token() {
+        let mut s = AsyncScanner::new(&b"1,2,"[..]);
+        s.split(|data: &[u8], at_eof: bool| {
+            if let Some(i) = data.iter().position(|b| *b == b',') {
+                return (i + 1, Some(&data[..i]), None);
+            }
+            if !at_eof {
+                return (0, None, None);
+            }
+            (0, Some(data), Some(Error::FinalToken))
+        });
+        assert_eq!(collect(s).await, ["1", "2", ""]);
+    }
+
+    #[tokio::test]
+    async fn test_async_scan_error() {
+        let data = [b'x'; 100];
+        let mut s = AsyncScanner::new(SlowReader {
+            data: &data,
+            n: 7,
+            ready: false,
+        });
+        s.buffer(Vec::new(), 16);
+        assert!(matches!(s.next().await, Some(Err(Error::TooLong))));
+        assert!(s.is_terminated());
+        assert!(s.next().await.is_none());
+    }
+}
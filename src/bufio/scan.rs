@@ -0,0 +1,898 @@
+use super::{Error, MAX_CONSECUTIVE_EMPTY_READS};
+use crate::{bytealg, unicode::utf8};
+use std::borrow::Cow;
+
+/// The maximum size used to buffer a token
+/// unless the user provides an explicit buffer with [`Scanner::buffer`].
+/// The actual maximum token size may be smaller as the buffer
+/// may need to include, for instance, a newline.
+pub const MAX_SCAN_TOKEN_SIZE: usize = 64 * 1024;
+
+/// Size of initial allocation for buffer.
+const START_BUF_SIZE: usize = 4096;
+
+/// The signature of the split function used to tokenize the
+/// input. The arguments are an initial substring of the remaining unprocessed
+/// data and a flag, `at_eof`, that reports whether the [`std::io::Read`] has no more data
+/// to give. The return values are the number of bytes to advance the input
+/// and the next token to return to the user, if any, plus an error, if any.
+///
+/// Scanning stops if the function returns an error, in which case some of
+/// the input may be discarded. If that error is [`Error::FinalToken`], scanning
+/// stops with no error. A `Some` token delivered with [`Error::FinalToken`]
+/// will be the last token, and a `None` token with [`Error::FinalToken`]
+/// immediately stops the scanning.
+///
+/// Otherwise, the [`Scanner`] advances the input. If the token is not `None`,
+/// the [`Scanner`] returns it to the user. If the token is `None`, the
+/// [`Scanner`] reads more data and continues scanning; if there is no more
+/// data--if `at_eof` was true--the [`Scanner`] returns. If the data does not
+/// yet hold a complete token, for instance if it has no newline while
+/// scanning lines, a split function can return `(0, None, None)` to signal the
+/// [`Scanner`] to read more data into the slice and try again with a
+/// longer slice starting at the same point in the input.
+///
+/// The function is never called with an empty data slice unless `at_eof`
+/// is true. If `at_eof` is true, however, data may be non-empty and,
+/// as always, holds unprocessed text.
+pub trait SplitFunc:
+    for<'a> FnMut(&'a [u8], bool) -> (usize, Option<&'a [u8]>, Option<Error>) + Send
+{
+}
+
+impl<F> SplitFunc for F where
+    F: for<'a> FnMut(&'a [u8], bool) -> (usize, Option<&'a [u8]>, Option<Error>) + Send
+{
+}
+
+#[derive(Debug)]
+enum Token {
+    None,
+    Range(usize, usize),
+    Owned(Vec<u8>),
+}
+
+/// Provides a convenient interface for reading data such as
+/// a file of newline-delimited lines of text. Successive calls to
+/// the [`Scanner::scan`] method will step through the 'tokens' of a file, skipping
+/// the bytes between the tokens. The specification of a token is
+/// defined by a split function of type [`SplitFunc`]; the default split
+/// function breaks the input into lines with line termination stripped. [`Scanner::split`]
+/// functions are defined in this module for scanning a file into
+/// lines, bytes, UTF-8-encoded runes, and space-delimited words. The
+/// client may instead provide a custom split function.
+///
+/// Scanning stops unrecoverably at EOF, the first I/O error, or a token too
+/// large to fit in the [`Scanner::buffer`]. When a scan stops, the reader may have
+/// advanced arbitrarily far past the last token. Programs that need more
+/// control over error handling or large tokens, or must run sequential scans
+/// on a reader, should use [`Reader`](super::Reader) instead.
+pub struct Scanner<R> {
+    /// The reader provided by the client.
+    r: R,
+    state: ScanState,
+}
+
+impl<R: core::fmt::Debug> core::fmt::Debug for Scanner<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Scanner")
+            .field("r", &self.r)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl<R: std::io::Read> Scanner<R> {
+    /// Returns a new [`Scanner`] to read from `r`.
+    /// The split function defaults to [`scan_lines`].
+    #[inline]
+    pub fn new(r: R) -> Self {
+        Self {
+            r,
+            state: ScanState::new(),
+        }
+    }
+
+    /// Returns the first non-EOF error that was encountered by the [`Scanner`].
+    #[inline]
+    pub fn err(&self) -> Option<&Error> {
+        match &self.state.err {
+            Some(e) if e.is_eof() => None,
+            e => e.as_ref(),
+        }
+    }
+
+    /// Returns the most recent token generated by a call to [`Scanner::scan`].
+    /// The underlying slice may point to data that will be overwritten
+    /// by a subsequent call to [`Scanner::scan`].
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        self.state.token()
+    }
+
+    /// Returns the most recent token generated by a call to [`Scanner::scan`]
+    /// as a string. Invalid UTF-8 sequences are replaced with `U+FFFD`.
+    #[inline]
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.bytes())
+    }
+
+    /// Advances the [`Scanner`] to the next token, which will then be
+    /// available through the [`Scanner::bytes`] or [`Scanner::text`] method. It returns false when the
+    /// scan stops, either by reaching the end of the input or an error.
+    /// After `scan` returns false, the [`Scanner::err`] method will return any error that
+    /// occurred during scanning, except that if it was [`crate::io::Error::Eof`], [`Scanner::err`]
+    /// will return `None`.
+    ///
+    /// # Panics
+    /// Panics if the split function returns too many empty
+    /// tokens without advancing the input. This is a common error mode for
+    /// scanners.
+    pub fn scan(&mut self) -> bool {
+        loop {
+            match self.state.step() {
+                Step::Token => return true,
+                Step::Stop => return false,
+                Step::Read => {}
+            }
+
+            // Finally we can read some input.
+            loop {
+                let rst = self.r.read(self.state.read_buf());
+                if !self.state.on_read(rst) {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Sets the initial buffer to use when scanning and the maximum
+    /// size of buffer that may be allocated during scanning. The maximum
+    /// token size is the larger of `max` and `buf.capacity()`. If `max` <= `buf.capacity()`,
+    /// [`Scanner::scan`] will use this buffer only and do no allocation.
+    ///
+    /// By default, [`Scanner::scan`] uses an internal buffer and sets the
+    /// maximum token size to [`MAX_SCAN_TOKEN_SIZE`].
+    ///
+    /// # Panics
+    /// Panics if it is called after scanning has started.
+    #[inline]
+    pub fn buffer(&mut self, buf: Vec<u8>, max: usize) {
+        self.state.buffer(buf, max)
+    }
+
+    /// Sets the split function for the [`Scanner`].
+    /// The default split function is [`scan_lines`].
+    ///
+    /// # Panics
+    /// Panics if it is called after scanning has started.
+    #[inline]
+    pub fn split<F>(&mut self, split: F)
+    where
+        F: FnMut(&[u8], bool) -> (usize, Option<&[u8]>, Option<Error>) + Send + 'static,
+    {
+        self.state.split(Box::new(split))
+    }
+
+    /// Consumes the [`Scanner`], returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+}
+
+/// What the scanning state machine needs next.
+pub(super) enum Step {
+    /// A token is available.
+    Token,
+    /// Scanning has stopped, either at EOF or with an error.
+    Stop,
+    /// More data must be read into [`ScanState::read_buf`].
+    Read,
+}
+
+/// The reader-independent part of a [`Scanner`], shared with the asynchronous scanner.
+pub(super) struct ScanState {
+    /// The function to split the tokens.
+    split: Box<dyn SplitFunc>,
+    /// Maximum size of a token; modified by tests.
+    max_token_size: usize,
+    /// Last token returned by split.
+    token: Token,
+    /// Buffer used as argument to split.
+    buf: Vec<u8>,
+    /// First non-processed byte in buf.
+    start: usize,
+    /// End of data in buf.
+    end: usize,
+    /// Sticky error.
+    pub(super) err: Option<Error>,
+    /// Count of successive empty tokens.
+    empties: usize,
+    /// Count of successive reads returning no data.
+    empty_reads: usize,
+    /// Scan has been called; buffer is in use.
+    scan_called: bool,
+    /// Scan has finished.
+    done: bool,
+}
+
+impl core::fmt::Debug for ScanState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ScanState")
+            .field("max_token_size", &self.max_token_size)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("err", &self.err)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ScanState {
+    pub(super) fn new() -> Self {
+        Self {
+            split: Box::new(scan_lines),
+            max_token_size: MAX_SCAN_TOKEN_SIZE,
+            token: Token::None,
+            buf: Vec::new(),
+            start: 0,
+            end: 0,
+            err: None,
+            empties: 0,
+            empty_reads: 0,
+            scan_called: false,
+            done: false,
+        }
+    }
+
+    /// Returns the most recent token.
+    pub(super) fn token(&self) -> &[u8] {
+        match &self.token {
+            Token::None => &[],
+            Token::Range(start, end) => &self.buf[*start..*end],
+            Token::Owned(buf) => buf,
+        }
+    }
+
+    /// Tries to produce the next token from the buffered data, preparing the
+    /// buffer for a read if more data is needed.
+    pub(super) fn step(&mut self) -> Step {
+        if self.done {
+            return Step::Stop;
+        }
+        self.scan_called = true;
+
+        // See if we can get a token with what we already have.
+        // If we've run out of data but have an error, give the split function
+        // a chance to recover any remaining, possibly empty token.
+        if self.end > self.start || self.err.is_some() {
+            let at_eof = self.err.is_some();
+            let data = &self.buf[self.start..self.end];
+            let (advance, token, err) = (self.split)(data, at_eof);
+            let token = token.map(|tok| locate(&self.buf, tok));
+            if let Some(err) = err {
+                if let Error::FinalToken = err {
+                    self.done = true;
+                    // When token is not None, it means the scanning stops
+                    // with a trailing empty token in the read data.
+                    // We return a token here so the caller can process it.
+                    return match token {
+                        Some(token) => {
+                            self.token = token;
+                            Step::Token
+                        }
+                        None => {
+                            self.token = Token::None;
+                            Step::Stop
+                        }
+                    };
+                }
+                self.set_err(err);
+                return Step::Stop;
+            }
+            if !self.advance(advance) {
+                return Step::Stop;
+            }
+            if let Some(token) = token {
+                self.token = token;
+                if self.err.is_none() || advance > 0 {
+                    self.empties = 0;
+                } else {
+                    // Returning tokens not advancing input at EOF.
+                    self.empties += 1;
+                    if self.empties > MAX_CONSECUTIVE_EMPTY_READS {
+                        panic!("bufio.Scan: too many empty tokens without progressing");
+                    }
+                }
+                return Step::Token;
+            }
+            self.token = Token::None;
+        }
+
+        // We cannot generate a token with what we are holding.
+        // If we've already hit EOF or an I/O error, we are done.
+        if self.err.is_some() {
+            // Shut it down.
+            self.start = 0;
+            self.end = 0;
+            return Step::Stop;
+        }
+
+        // Must read more data.
+        // First, shift data to beginning of buffer if there's lots of empty space
+        // or space is needed.
+        if self.start > 0 && (self.end == self.buf.len() || self.start > self.buf.len() / 2) {
+            self.buf.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+
+        // Is the buffer full? If so, resize.
+        if self.end == self.buf.len() {
+            if self.buf.len() >= self.max_token_size || self.buf.len() > usize::MAX / 2 {
+                self.set_err(Error::TooLong);
+                return Step::Stop;
+            }
+            let new_size = match self.buf.len() * 2 {
+                0 => START_BUF_SIZE,
+                n => n,
+            }
+            .min(self.max_token_size);
+            let mut new_buf = vec![0; new_size];
+            new_buf[..self.end - self.start].copy_from_slice(&self.buf[self.start..self.end]);
+            self.buf = new_buf;
+            self.end -= self.start;
+            self.start = 0;
+        }
+
+        self.empty_reads = 0;
+        Step::Read
+    }
+
+    /// Returns the free part of the buffer, to be filled by a read.
+    #[inline]
+    pub(super) fn read_buf(&mut self) -> &mut [u8] {
+        &mut self.buf[self.end..]
+    }
+
+    /// Records the result of a read into [`ScanState::read_buf`], reporting
+    /// whether the read should be retried. Make sure we don't get stuck with
+    /// a misbehaving reader. Officially we don't need to do this, but let's
+    /// be extra careful: Scanner is for safe, simple jobs.
+    pub(super) fn on_read(&mut self, rst: std::io::Result<usize>) -> bool {
+        match rst {
+            Ok(n) if n > self.buf.len() - self.end => self.set_err(Error::BadReadCount),
+            Ok(0) => self.set_err(Error::Io(crate::io::Error::Eof)),
+            Ok(n) => {
+                self.end += n;
+                self.empties = 0;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                self.empty_reads += 1;
+                if self.empty_reads <= MAX_CONSECUTIVE_EMPTY_READS {
+                    return true;
+                }
+                self.set_err(Error::Io(crate::io::Error::NoProgress));
+            }
+            Err(e) => self.set_err(e.into()),
+        }
+        false
+    }
+
+    /// Consumes `n` bytes of the buffer. It reports whether the advance was legal.
+    fn advance(&mut self, n: usize) -> bool {
+        if n > self.end - self.start {
+            self.set_err(Error::AdvanceTooFar);
+            return false;
+        }
+        self.start += n;
+        true
+    }
+
+    /// Records the first error encountered.
+    fn set_err(&mut self, err: Error) {
+        match &self.err {
+            None => self.err = Some(err),
+            Some(e) if e.is_eof() => self.err = Some(err),
+            _ => {}
+        }
+    }
+
+    pub(super) fn buffer(&mut self, mut buf: Vec<u8>, max: usize) {
+        if self.scan_called {
+            panic!("buffer called after scan");
+        }
+        buf.resize(buf.capacity(), 0);
+        self.max_token_size = max.max(buf.len());
+        self.buf = buf;
+    }
+
+    pub(super) fn split(&mut self, split: Box<dyn SplitFunc>) {
+        if self.scan_called {
+            panic!("split called after scan");
+        }
+        self.split = split;
+    }
+}
+
+/// Records where `tok` lives: a range of `buf`, or an owned copy when the
+/// split function returned data from elsewhere.
+fn locate(buf: &[u8], tok: &[u8]) -> Token {
+    let base = buf.as_ptr() as usize;
+    let ptr = tok.as_ptr() as usize;
+    if ptr >= base && ptr + tok.len() <= base + buf.len() {
+        let start = ptr - base;
+        Token::Range(start, start + tok.len())
+    } else {
+        Token::Owned(tok.to_vec())
+    }
+}
+
+/// A split function for a [`Scanner`] that returns each byte as a token.
+pub fn scan_bytes(data: &[u8], at_eof: bool) -> (usize, Option<&[u8]>, Option<Error>) {
+    if at_eof && data.is_empty() {
+        return (0, None, None);
+    }
+    (1, Some(&data[..1]), None)
+}
+
+static ERROR_RUNE: &[u8] = "\u{FFFD}".as_bytes();
+
+/// A split function for a [`Scanner`] that returns each
+/// UTF-8-encoded rune as a token. The sequence of runes returned is
+/// equivalent to that from a range loop over the input as a string, which
+/// means that erroneous UTF-8 encodings translate to `U+FFFD` = "\xef\xbf\xbd".
+/// Because of the [`Scanner`] interface, this makes it impossible for the client to
+/// distinguish correctly encoded replacement runes from encoding errors.
+pub fn scan_runes(data: &[u8], at_eof: bool) -> (usize, Option<&[u8]>, Option<Error>) {
+    if at_eof && data.is_empty() {
+        return (0, None, None);
+    }
+
+    // Fast path 1: ASCII.
+    if data[0] < utf8::RUNE_SELF {
+        return (1, Some(&data[..1]), None);
+    }
+
+    // Fast path 2: Correct UTF-8 decode without error.
+    let (_, width) = utf8::decode_rune(data);
+    if width > 1 {
+        // It's a valid encoding. Width cannot be one for a correctly encoded
+        // non-ASCII rune.
+        return (width, Some(&data[..width]), None);
+    }
+
+    // We know it's an error: we have width==1 and implicitly r==utf8::RUNE_ERROR.
+    // Is the error because there wasn't a full rune to be decoded?
+    // full_rune distinguishes correctly between erroneous and incomplete encodings.
+    if !at_eof && !utf8::full_rune(data) {
+        // Incomplete; get more bytes.
+        return (0, None, None);
+    }
+
+    // We have a real UTF-8 encoding error. Return a properly encoded error rune
+    // but advance only one byte. This matches the behavior of a range loop over
+    // an incorrectly encoded string.
+    (1, Some(ERROR_RUNE), None)
+}
+
+/// Drops a terminal \r from the data.
+#[inline]
+fn drop_cr(data: &[u8]) -> &[u8] {
+    match data {
+        [rest @ .., b'\r'] => rest,
+        _ => data,
+    }
+}
+
+/// A split function for a [`Scanner`] that returns each line of
+/// text, stripped of any trailing end-of-line marker. The returned line may
+/// be empty. The end-of-line marker is one optional carriage return followed
+/// by one mandatory newline. In regular expression notation, it is `\r?\n`.
+/// The last non-empty line of input will be returned even if it has no
+/// newline.
+pub fn scan_lines(data: &[u8], at_eof: bool) -> (usize, Option<&[u8]>, Option<Error>) {
+    if at_eof && data.is_empty() {
+        return (0, None, None);
+    }
+    if let Some(i) = bytealg::index_byte(data, b'\n') {
+        // We have a full newline-terminated line.
+        return (i + 1, Some(drop_cr(&data[..i])), None);
+    }
+    // If we're at EOF, we have a final, non-terminated line. Return it.
+    if at_eof {
+        return (data.len(), Some(drop_cr(data)), None);
+    }
+    // Request more data.
+    (0, None, None)
+}
+
+/// Reports whether the character is a Unicode white space character.
+/// We avoid going through the full Unicode tables, but check validity of the implementation
+/// against [`char::is_whitespace`] in the tests.
+fn is_space(r: char) -> bool {
+    if r <= '\u{00FF}' {
+        // Obvious ASCII ones: \t through \r plus space. Plus two Latin-1 oddballs.
+        return matches!(
+            r,
+            ' ' | '\t' | '\n' | '\u{000B}' | '\u{000C}' | '\r' | '\u{0085}' | '\u{00A0}'
+        );
+    }
+    // High-valued ones.
+    if ('\u{2000}'..='\u{200a}').contains(&r) {
+        return true;
+    }
+    matches!(
+        r,
+        '\u{1680}' | '\u{2028}' | '\u{2029}' | '\u{202f}' | '\u{205f}' | '\u{3000}'
+    )
+}
+
+/// A split function for a [`Scanner`] that returns each
+/// space-separated word of text, with surrounding spaces deleted. It will
+/// never return an empty string. The definition of space is set by
+/// [`char::is_whitespace`].
+pub fn scan_words(data: &[u8], at_eof: bool) -> (usize, Option<&[u8]>, Option<Error>) {
+    // Skip leading spaces.
+    let mut start = 0;
+    while start < data.len() {
+        let (r, width) = utf8::decode_rune(&data[start..]);
+        if !is_space(r) {
+            break;
+        }
+        start += width;
+    }
+
+    // Scan until space, marking end of word.
+    let mut i = start;
+    while i < data.len() {
+        let (r, width) = utf8::decode_rune(&data[i..]);
+        if is_space(r) {
+            return (i + width, Some(&data[start..i]), None);
+        }
+        i += width;
+    }
+
+    // If we're at EOF, we have a final, non-empty, non-terminated word. Return it.
+    if at_eof && data.len() > start {
+        return (data.len(), Some(&data[start..]), None);
+    }
+
+    // Request more data.
+    (start, None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// Reads at most `n` bytes per call.
+    struct SlowReader<'a> {
+        data: &'a [u8],
+        n: usize,
+    }
+
+    impl Read for SlowReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.n.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn collect<R: Read>(s: &mut Scanner<R>) -> Vec<String> {
+        let mut toks = Vec::new();
+        while s.scan() {
+            toks.push(s.text().into_owned());
+        }
+        toks
+    }
+
+    const SCAN_TESTS: &[&[u8]] = &[
+        b"",
+        b"a",
+        b"\xc2\xbc",
+        b"\xe2\x98\xb9",
+        b"\x81",         // UTF-8 error
+        b"\xef\xbf\xbd", // correctly encoded RuneError
+        b"abcdefgh",
+        b"abc def\n\t\tgh    ",
+        b"abc\xc2\xbc\xe2\x98\xb9\x81\xef\xbf\xbd\xe6\x97\xa5\xe6\x9c\xac\xe8\xaa\x9e\x82abc",
+    ];
+
+    #[test]
+    fn test_scan_byte() {
+        for (n, test) in SCAN_TESTS.iter().enumerate() {
+            let mut s = Scanner::new(*test);
+            s.split(scan_bytes);
+            let mut i = 0;
+            while s.scan() {
+                assert_eq!(s.bytes(), &test[i..i + 1], "#{n}");
+                i += 1;
+            }
+            assert_eq!(i, test.len(), "#{n}");
+            assert!(s.err().is_none());
+        }
+    }
+
+    #[test]
+    fn test_scan_rune() {
+        for (n, test) in SCAN_TESTS.iter().enumerate() {
+            let bytes = *test;
+            let mut s = Scanner::new(bytes);
+            s.split(scan_runes);
+            let mut expected = Vec::new();
+            let mut i = 0;
+            while i < bytes.len() {
+                let (r, width) = utf8::decode_rune(&bytes[i..]);
+                expected.push(r);
+                i += width;
+            }
+            let mut runes = Vec::new();
+            while s.scan() {
+                let text = s.text();
+                let mut chars = text.chars();
+                runes.push(chars.next().unwrap());
+                assert!(chars.next().is_none(), "#{n}");
+            }
+            assert_eq!(runes, expected, "#{n}");
+            assert!(s.err().is_none());
+        }
+    }
+
+    const WORD_TESTS: &[&str] = &[
+        "",
+        " ",
+        "\n",
+        "a",
+        " a ",
+        "abc def",
+        " abc def ",
+        " abc\tdef\nghi\rjkl\u{000B}mno\u{000C}pqr\u{0085}stu\u{00a0}\n",
+        "\u{2000}\u{2001}\u{2002}abc\u{2003}\u{2004}\u{2005}\u{2006}\u{2007}\u{2008}\u{2009}\u{200a}\u{200b}\u{2028}\u{2029}\u{3000}",
+    ];
+
+    #[test]
+    fn test_space() {
+        for r in (0..=0x10FFFFu32).filter_map(char::from_u32) {
+            assert_eq!(is_space(r), r.is_whitespace(), "{:?}", r);
+        }
+    }
+
+    #[test]
+    fn test_scan_words() {
+        for (n, test) in WORD_TESTS.iter().enumerate() {
+            let mut s = Scanner::new(test.as_bytes());
+            s.split(scan_words);
+            let words = collect(&mut s);
+            let expected: Vec<String> = test
+                .split(char::is_whitespace)
+                .filter(|w| !w.is_empty())
+                .map(String::from)
+                .collect();
+            assert_eq!(words, expected, "#{n}");
+            assert!(s.err().is_none());
+        }
+    }
+
+    /// Generates a line of `n` bytes, ending in a newline, derived from the index.
+    fn gen_line(n: usize, add_newline: bool) -> Vec<u8> {
+        let mut buf: Vec<u8> = (0..n).map(|i| b'A' + (i % 50) as u8).collect();
+        if add_newline {
+            buf.push(b'\n');
+        }
+        buf
+    }
+
+    #[test]
+    fn test_scan_long_lines() {
+        // build a buffer of lines of increasing length, some of which
+        // are longer than the small initial buffer
+        const SMALL_MAX_TOKEN_SIZE: usize = 256;
+        let mut data = Vec::new();
+        let mut lines = Vec::new();
+        for i in 0..2 * SMALL_MAX_TOKEN_SIZE {
+            let line = gen_line(i, true);
+            data.extend_from_slice(&line);
+            lines.push(line);
+        }
+        let mut s = Scanner::new(SlowReader { data: &data, n: 1 });
+        s.split(scan_lines);
+        s.buffer(Vec::new(), SMALL_MAX_TOKEN_SIZE);
+        let mut line_num = 0;
+        while s.scan() {
+            let line = &lines[line_num];
+            assert_eq!(s.bytes(), &line[..line.len() - 1], "line {line_num}");
+            line_num += 1;
+        }
+        // the scanner stops at the first line that does not fit
+        assert_eq!(line_num, SMALL_MAX_TOKEN_SIZE);
+        assert!(matches!(s.err(), Some(Error::TooLong)));
+    }
+
+    #[test]
+    fn test_scan_line_no_newline_and_cr() {
+        let data = b"abc\r\ndef\n\r\n\nghi\r";
+        for n in [1, 2, 5, 100] {
+            let mut s = Scanner::new(SlowReader { data, n });
+            assert_eq!(collect(&mut s), ["abc", "def", "", "", "ghi"]);
+            assert!(s.err().is_none());
+        }
+    }
+
+    #[test]
+    fn test_scan_custom_split() {
+        // split on commas, returning tokens as slices of the input
+        let mut s = Scanner::new(&b"a,bb,,ccc"[..]);
+        s.split(|data: &[u8], at_eof: bool| {
+            if let Some(i) = data.iter().position(|b| *b == b',') {
+                return (i + 1, Some(&data[..i]), None);
+            }
+            if at_eof && !data.is_empty() {
+                return (data.len(), Some(data), None);
+            }
+            (0, None, None)
+        });
+        assert_eq!(collect(&mut s), ["a", "bb", "", "ccc"]);
+    }
+
+    #[test]
+    fn test_err_at_eof() {
+        // Test that the error is returned with the final non-EOF token.
+        let mut s = Scanner::new(&b"1 2 33"[..]);
+        s.split(|data: &[u8], at_eof: bool| {
+            let (advance, token, err) = scan_words(data, at_eof);
+            if token.is_some_and(|t| t.len() > 1) {
+                if !at_eof {
+                    // not yet
+                    return (0, None, None);
+                }
+                return (0, token, Some(Error::Io(crate::io::Error::UnexpectedEof)));
+            }
+            (advance, token, err)
+        });
+        let mut n = 0;
+        while s.scan() {
+            n += 1;
+        }
+        assert_eq!(n, 2);
+        assert!(matches!(
+            s.err(),
+            Some(Error::Io(crate::io::Error::UnexpectedEof))
+        ));
+    }
+
+    #[test]
+    fn test_final_token() {
+        // split on commas; the final token may be empty.
+        for (input, expected) in [
+            ("", vec![""]),
+            ("1", vec!["1"]),
+            ("1,", vec!["1", ""]),
+            ("1,2", vec!["1", "2"]),
+        ] {
+            let mut s = Scanner::new(input.as_bytes());
+            s.split(|data: &[u8], at_eof: bool| {
+                if let Some(i) = data.iter().position(|b| *b == b',') {
+                    return (i + 1, Some(&data[..i]), None);
+                }
+                if !at_eof {
+                    return (0, None, None);
+                }
+                // There is one final token to be delivered, which may be the empty string.
+                // Returning FinalToken here tells scan there are no more tokens after this
+                // but does not trigger an error to be returned from err.
+                (0, Some(data), Some(Error::FinalToken))
+            });
+            assert_eq!(collect(&mut s), expected, "{input:?}");
+            assert!(s.err().is_none());
+            assert!(!s.scan());
+        }
+
+        // a None token with FinalToken stops immediately
+        let mut s = Scanner::new(&b"a\nb\nstop\nc\n"[..]);
+        s.split(|data: &[u8], at_eof: bool| {
+            let (advance, token, err) = scan_lines(data, at_eof);
+            if token == Some(b"stop") {
+                return (0, None, Some(Error::FinalToken));
+            }
+            (advance, token, err)
+        });
+        assert_eq!(collect(&mut s), ["a", "b"]);
+        assert!(s.err().is_none());
+    }
+
+    #[test]
+    fn test_empty_tokens() {
+        let mut s = Scanner::new(&b"1,2,3,"[..]);
+        s.split(|data: &[u8], at_eof: bool| {
+            if let Some(i) = data.iter().position(|b| *b == b',') {
+                return (i + 1, Some(&data[..i]), None);
+            }
+            (
+                0,
+                Some(data),
+                if at_eof {
+                    Some(Error::FinalToken)
+                } else {
+                    None
+                },
+            )
+        });
+        assert_eq!(collect(&mut s), ["1", "2", "3", ""]);
+        assert!(s.err().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "too many empty tokens")]
+    fn test_dont_loop_forever() {
+        let mut s = Scanner::new(&b"abc"[..]);
+        // advances one byte at a time, then returns empty tokens at EOF forever
+        s.split(|data: &[u8], _: bool| {
+            if !data.is_empty() {
+                return (1, Some(&data[..1]), None);
+            }
+            (0, Some(data), None)
+        });
+        let mut count = 0;
+        while s.scan() {
+            count += 1;
+            assert!(count <= 1000, "looping");
+        }
+    }
+
+    #[test]
+    fn test_advance_too_far() {
+        let mut s = Scanner::new(&b"abc"[..]);
+        s.split(|data: &[u8], _: bool| (data.len() + 1, None, None));
+        assert!(!s.scan());
+        assert!(matches!(s.err(), Some(Error::AdvanceTooFar)));
+    }
+
+    #[test]
+    fn test_read_error() {
+        struct ErrReader(bool);
+
+        impl Read for ErrReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0 {
+                    return Err(std::io::Error::other("boom"));
+                }
+                self.0 = true;
+                buf[..4].copy_from_slice(b"a\nbc");
+                Ok(4)
+            }
+        }
+
+        let mut s = Scanner::new(ErrReader(false));
+        // the trailing partial line is still delivered
+        assert_eq!(collect(&mut s), ["a", "bc"]);
+        assert!(matches!(s.err(), Some(Error::Io(crate::io::Error::Io(_)))));
+    }
+
+    #[test]
+    fn test_buffer() {
+        let data = gen_line(100, true);
+        let mut s = Scanner::new(&data[..]);
+        s.buffer(Vec::with_capacity(10), 20);
+        assert!(!s.scan());
+        assert!(matches!(s.err(), Some(Error::TooLong)));
+
+        let mut s = Scanner::new(&data[..]);
+        s.buffer(Vec::with_capacity(200), 0);
+        assert!(s.scan());
+        assert_eq!(s.bytes(), &data[..100]);
+        assert!(!s.scan());
+    }
+
+    #[test]
+    #[should_panic(expected = "split called after scan")]
+    fn test_split_after_scan() {
+        let mut s = Scanner::new(&b"a"[..]);
+        s.scan();
+        s.split(scan_bytes);
+    }
+}
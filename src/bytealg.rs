@@ -0,0 +1,325 @@
+#[cfg(target_arch = "x86_64")]
+mod sse2;
+#[cfg(target_arch = "x86_64")]
+use sse2 as arch;
+
+mod generic;
+#[cfg(not(target_arch = "x86_64"))]
+use generic as arch;
+
+/// The prime base used in Rabin-Karp algorithm.
+pub const PRIME_RK: u32 = 16777619;
+
+/// Returns the index of the first instance of `c` in `b`, or `None` if `c` is not present in `b`.
+#[inline]
+pub fn index_byte(b: &[u8], c: u8) -> Option<usize> {
+    arch::index_byte(b, c)
+}
+
+/// Returns the index of the last instance of `c` in `s`, or `None` if `c` is not present in `s`.
+#[inline]
+pub fn last_index_byte(s: &[u8], c: u8) -> Option<usize> {
+    arch::last_index_byte(s, c)
+}
+
+/// Counts the number of instances of `c` in `b`.
+#[inline]
+pub fn count(b: &[u8], c: u8) -> usize {
+    arch::count(b, c)
+}
+
+/// Reports whether `a` and `b` are the same length and contain the same bytes.
+#[inline]
+pub fn equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && arch::equal(a, b)
+}
+
+/// Returns the index of the first instance of `sep` in `s`, or `None` if `sep` is not present in `s`.
+///
+/// The search scans for the first byte of `sep` with [`index_byte`], and
+/// switches to [`index_rabin_karp`] once too many candidates turn out to be
+/// false positives.
+pub fn index(s: &[u8], sep: &[u8]) -> Option<usize> {
+    let n = sep.len();
+    match n {
+        0 => return Some(0),
+        1 => return index_byte(s, sep[0]),
+        _ if n == s.len() => return equal(s, sep).then_some(0),
+        _ if n > s.len() => return None,
+        _ => {}
+    }
+
+    let c0 = sep[0];
+    let c1 = sep[1];
+    let mut i = 0;
+    let mut fails = 0;
+    let t = s.len() - n + 1;
+    while i < t {
+        if s[i] != c0 {
+            match index_byte(&s[i + 1..t], c0) {
+                Some(o) => i += o + 1,
+                None => return None,
+            }
+        }
+        if s[i + 1] == c1 && equal(&s[i..i + n], sep) {
+            return Some(i);
+        }
+        i += 1;
+        fails += 1;
+        if fails >= 4 + (i >> 4) && i < t {
+            // Give up on index_byte, it isn't skipping ahead
+            // far enough to be better than Rabin-Karp.
+            // Experiments (using IndexPeriodic) suggest
+            // the cutover is about 16 byte skips.
+            // TODO: if large prefixes of sep are matching
+            // we should cutover at even larger average skips,
+            // because equal becomes that much more expensive.
+            // This code does not take that effect into account.
+            return index_rabin_karp(&s[i..], sep).map(|j| i + j);
+        }
+    }
+    None
+}
+
+/// Returns the index of the last instance of `sep` in `s`, or `None` if `sep` is not present in `s`.
+pub fn last_index(s: &[u8], sep: &[u8]) -> Option<usize> {
+    let n = sep.len();
+    match n {
+        0 => Some(s.len()),
+        1 => last_index_byte(s, sep[0]),
+        _ if n == s.len() => equal(s, sep).then_some(0),
+        _ if n > s.len() => None,
+        _ => last_index_rabin_karp(s, sep),
+    }
+}
+
+/// Returns the hash and the appropriate multiplicative
+/// factor for use in Rabin-Karp algorithm.
+pub fn hash_str(sep: &[u8]) -> (u32, u32) {
+    let hash = sep.iter().fold(0u32, |h, b| {
+        h.wrapping_mul(PRIME_RK).wrapping_add(*b as u32)
+    });
+    (hash, pow_rk(sep.len()))
+}
+
+/// Returns the hash of the reverse of `sep` and the
+/// appropriate multiplicative factor for use in Rabin-Karp algorithm.
+pub fn hash_str_rev(sep: &[u8]) -> (u32, u32) {
+    let hash = sep.iter().rev().fold(0u32, |h, b| {
+        h.wrapping_mul(PRIME_RK).wrapping_add(*b as u32)
+    });
+    (hash, pow_rk(sep.len()))
+}
+
+/// Returns `PRIME_RK` to the power of `n`, modulo 2^32.
+#[inline]
+fn pow_rk(mut n: usize) -> u32 {
+    let (mut pow, mut sq) = (1u32, PRIME_RK);
+    while n > 0 {
+        if n & 1 != 0 {
+            pow = pow.wrapping_mul(sq);
+        }
+        sq = sq.wrapping_mul(sq);
+        n >>= 1;
+    }
+    pow
+}
+
+/// Uses the Rabin-Karp search algorithm to return the index of the
+/// first occurrence of `sep` in `s`, or `None` if not present.
+pub fn index_rabin_karp(s: &[u8], sep: &[u8]) -> Option<usize> {
+    // Rabin-Karp search
+    let (hashss, pow) = hash_str(sep);
+    let n = sep.len();
+    if s.len() < n {
+        return None;
+    }
+    let mut h = s[..n].iter().fold(0u32, |h, b| {
+        h.wrapping_mul(PRIME_RK).wrapping_add(*b as u32)
+    });
+    if h == hashss && equal(&s[..n], sep) {
+        return Some(0);
+    }
+    for i in n..s.len() {
+        h = h
+            .wrapping_mul(PRIME_RK)
+            .wrapping_add(s[i] as u32)
+            .wrapping_sub(pow.wrapping_mul(s[i - n] as u32));
+        if h == hashss && equal(&s[i + 1 - n..=i], sep) {
+            return Some(i + 1 - n);
+        }
+    }
+    None
+}
+
+/// Uses the Rabin-Karp search algorithm to return the last index of the
+/// occurrence of `sep` in `s`, or `None` if not present.
+pub fn last_index_rabin_karp(s: &[u8], sep: &[u8]) -> Option<usize> {
+    // Rabin-Karp search from the end of the string
+    let (hashss, pow) = hash_str_rev(sep);
+    let n = sep.len();
+    if s.len() < n {
+        return None;
+    }
+    let last = s.len() - n;
+    let mut h = s[last..].iter().rev().fold(0u32, |h, b| {
+        h.wrapping_mul(PRIME_RK).wrapping_add(*b as u32)
+    });
+    if h == hashss && equal(&s[last..], sep) {
+        return Some(last);
+    }
+    for i in (0..last).rev() {
+        h = h
+            .wrapping_mul(PRIME_RK)
+            .wrapping_add(s[i] as u32)
+            .wrapping_sub(pow.wrapping_mul(s[i + n] as u32));
+        if h == hashss && equal(&s[i..i + n], sep) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn naive_index(s: &[u8], sep: &[u8]) -> Option<usize> {
+        if sep.is_empty() {
+            return Some(0);
+        }
+        s.windows(sep.len()).position(|w| w == sep)
+    }
+
+    fn naive_last_index(s: &[u8], sep: &[u8]) -> Option<usize> {
+        if sep.is_empty() {
+            return Some(s.len());
+        }
+        s.windows(sep.len()).rposition(|w| w == sep)
+    }
+
+    #[test]
+    fn test_index_byte() {
+        // Every length around the vector width, with the needle at every position.
+        let mut buf = [0u8; 80];
+        for n in 0..buf.len() {
+            let b = &mut buf[..n];
+            b.fill(b'x');
+            assert_eq!(index_byte(b, b'y'), None);
+            assert_eq!(last_index_byte(b, b'y'), None);
+            assert_eq!(count(b, b'y'), 0);
+            assert_eq!(count(b, b'x'), n);
+            assert_eq!(generic::index_byte(b, b'y'), None);
+            assert_eq!(generic::last_index_byte(b, b'y'), None);
+            assert_eq!(generic::count(b, b'x'), n);
+            for i in 0..n {
+                b[i] = b'y';
+                assert_eq!(index_byte(b, b'y'), Some(i), "n={n} i={i}");
+                assert_eq!(last_index_byte(b, b'y'), Some(i), "n={n} i={i}");
+                assert_eq!(generic::index_byte(b, b'y'), Some(i), "n={n} i={i}");
+                assert_eq!(generic::last_index_byte(b, b'y'), Some(i), "n={n} i={i}");
+                assert_eq!(count(b, b'y'), 1);
+                b[i] = b'x';
+            }
+        }
+
+        // Bytes with the high bit set must not confuse the lane masks.
+        let b: Vec<u8> = (0..=255).collect();
+        for c in 0..=255u8 {
+            assert_eq!(index_byte(&b, c), Some(c as usize));
+            assert_eq!(last_index_byte(&b, c), Some(c as usize));
+            assert_eq!(generic::index_byte(&b, c), Some(c as usize));
+            assert_eq!(generic::last_index_byte(&b, c), Some(c as usize));
+            assert_eq!(count(&b, c), 1);
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        for _ in 0..2000 {
+            let n = rng.gen_range(0..200);
+            let s: Vec<u8> = (0..n).map(|_| rng.gen_range(b'a'..=b'c')).collect();
+            let c = rng.gen_range(b'a'..=b'd');
+            assert_eq!(index_byte(&s, c), s.iter().position(|b| *b == c));
+            assert_eq!(last_index_byte(&s, c), s.iter().rposition(|b| *b == c));
+            assert_eq!(count(&s, c), s.iter().filter(|b| **b == c).count());
+            assert_eq!(generic::count(&s, c), s.iter().filter(|b| **b == c).count());
+
+            let m = rng.gen_range(0..8);
+            let sep: Vec<u8> = (0..m).map(|_| rng.gen_range(b'a'..=b'c')).collect();
+            assert_eq!(index(&s, &sep), naive_index(&s, &sep), "{s:?} {sep:?}");
+            assert_eq!(last_index(&s, &sep), naive_last_index(&s, &sep));
+            if !sep.is_empty() && sep.len() <= s.len() {
+                assert_eq!(index_rabin_karp(&s, &sep), naive_index(&s, &sep));
+                assert_eq!(last_index_rabin_karp(&s, &sep), naive_last_index(&s, &sep));
+            }
+
+            let mut t = s.clone();
+            assert!(equal(&s, &t));
+            assert!(generic::equal(&s, &t));
+            if n > 0 {
+                let i = rng.gen_range(0..n);
+                t[i] ^= 0x80;
+                assert!(!equal(&s, &t));
+                assert!(!generic::equal(&s, &t));
+            }
+        }
+    }
+
+    #[test]
+    fn test_index() {
+        let tests: &[(&[u8], &[u8], Option<usize>, Option<usize>)] = &[
+            (b"", b"", Some(0), Some(0)),
+            (b"", b"a", None, None),
+            (b"fo", b"foo", None, None),
+            (b"foo", b"foo", Some(0), Some(0)),
+            (b"foo", b"fox", None, None),
+            (b"oofofoofooo", b"f", Some(2), Some(7)),
+            (b"oofofoofooo", b"foo", Some(4), Some(7)),
+            (b"barfoobarfoo", b"foo", Some(3), Some(9)),
+            (b"foo", b"", Some(0), Some(3)),
+            (b"abcABCabc", b"A", Some(3), Some(3)),
+            (
+                b"jrzm6jjhorimglljrea4w3rlgosts0w2gia17hno2td4qd1jz",
+                b"jz",
+                Some(47),
+                Some(47),
+            ),
+            (
+                b"xx-xxxxxx-xxxxxxx-xxxxxxxxxx-xxxxxxxxxxxxxxxx-xxxxyxxxx",
+                b"xxxxy",
+                Some(46),
+                Some(46),
+            ),
+        ];
+        for (s, sep, first, last) in tests {
+            assert_eq!(index(s, sep), *first, "index({s:?}, {sep:?})");
+            assert_eq!(last_index(s, sep), *last, "last_index({s:?}, {sep:?})");
+        }
+
+        // Periodic input forces the cutover to Rabin-Karp.
+        let mut s = b"ab".repeat(1000);
+        s.extend_from_slice(b"abc");
+        assert_eq!(index(&s, b"abc"), Some(2000));
+        assert_eq!(index(&s, b"abd"), None);
+        assert_eq!(last_index(&s, b"ba"), Some(1999));
+    }
+
+    #[test]
+    fn test_hash_str() {
+        let (h, pow) = hash_str(b"ab");
+        assert_eq!(
+            h,
+            (b'a' as u32)
+                .wrapping_mul(PRIME_RK)
+                .wrapping_add(b'b' as u32)
+        );
+        assert_eq!(pow, PRIME_RK.wrapping_mul(PRIME_RK));
+        let (h, _) = hash_str_rev(b"ab");
+        assert_eq!(h, hash_str(b"ba").0);
+        assert_eq!(hash_str(b"").1, 1);
+    }
+}
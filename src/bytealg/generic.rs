@@ -0,0 +1,63 @@
+//! Portable implementations, processing a machine word at a time.
+
+use core::mem::size_of;
+
+const WORD: usize = size_of::<usize>();
+/// `0x0101...01`
+const LO: usize = usize::MAX / 0xFF;
+/// `0x8080...80`
+const HI: usize = LO << 7;
+
+/// Reports whether any byte of `x` is zero.
+#[inline(always)]
+const fn has_zero(x: usize) -> bool {
+    x.wrapping_sub(LO) & !x & HI != 0
+}
+
+#[inline(always)]
+fn word(chunk: &[u8]) -> usize {
+    let mut buf = [0; WORD];
+    buf.copy_from_slice(chunk);
+    usize::from_ne_bytes(buf)
+}
+
+pub(super) fn index_byte(b: &[u8], c: u8) -> Option<usize> {
+    let rep = LO * c as usize;
+    let mut chunks = b.chunks_exact(WORD);
+    let mut i = 0;
+    for chunk in &mut chunks {
+        if has_zero(word(chunk) ^ rep) {
+            break;
+        }
+        i += WORD;
+    }
+    b[i..].iter().position(|x| *x == c).map(|j| i + j)
+}
+
+pub(super) fn last_index_byte(s: &[u8], c: u8) -> Option<usize> {
+    let rep = LO * c as usize;
+    let mut chunks = s.rchunks_exact(WORD);
+    let mut i = s.len();
+    for chunk in &mut chunks {
+        if has_zero(word(chunk) ^ rep) {
+            break;
+        }
+        i -= WORD;
+    }
+    s[..i].iter().rposition(|x| *x == c)
+}
+
+pub(super) fn count(b: &[u8], c: u8) -> usize {
+    b.iter().filter(|x| **x == c).count()
+}
+
+pub(super) fn equal(a: &[u8], b: &[u8]) -> bool {
+    let mut x = a.chunks_exact(WORD);
+    let mut y = b.chunks_exact(WORD);
+    for (x, y) in (&mut x).zip(&mut y) {
+        if word(x) != word(y) {
+            return false;
+        }
+    }
+    x.remainder() == y.remainder()
+}
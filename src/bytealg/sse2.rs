@@ -0,0 +1,113 @@
+//! SSE2 implementations. SSE2 is part of the x86_64 baseline, so these need
+//! no runtime feature detection.
+
+use super::generic;
+use core::arch::x86_64::{
+    __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+};
+
+const LANES: usize = 16;
+
+/// Returns a bit mask of the lanes of `s[i..i + LANES]` equal to `needle`.
+#[inline(always)]
+fn mask(s: &[u8], i: usize, needle: __m128i) -> u32 {
+    let chunk = &s[i..i + LANES];
+    // SAFETY: `chunk` is exactly LANES bytes long, and SSE2 is always available on x86_64.
+    unsafe {
+        let v = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+        _mm_movemask_epi8(_mm_cmpeq_epi8(v, needle)) as u32
+    }
+}
+
+#[inline(always)]
+fn splat(c: u8) -> __m128i {
+    // SAFETY: SSE2 is always available on x86_64.
+    unsafe { _mm_set1_epi8(c as i8) }
+}
+
+pub(super) fn index_byte(b: &[u8], c: u8) -> Option<usize> {
+    let n = b.len();
+    if n < LANES {
+        return generic::index_byte(b, c);
+    }
+    let needle = splat(c);
+    let mut i = 0;
+    while i + LANES <= n {
+        let m = mask(b, i, needle);
+        if m != 0 {
+            return Some(i + m.trailing_zeros() as usize);
+        }
+        i += LANES;
+    }
+    if i < n {
+        // The last load overlaps bytes already known not to match.
+        let i = n - LANES;
+        let m = mask(b, i, needle);
+        if m != 0 {
+            return Some(i + m.trailing_zeros() as usize);
+        }
+    }
+    None
+}
+
+pub(super) fn last_index_byte(s: &[u8], c: u8) -> Option<usize> {
+    if s.len() < LANES {
+        return generic::last_index_byte(s, c);
+    }
+    let needle = splat(c);
+    let mut i = s.len();
+    while i >= LANES {
+        i -= LANES;
+        let m = mask(s, i, needle);
+        if m != 0 {
+            return Some(i + 31 - m.leading_zeros() as usize);
+        }
+    }
+    if i > 0 {
+        // The last load overlaps bytes already known not to match.
+        let m = mask(s, 0, needle);
+        if m != 0 {
+            return Some(31 - m.leading_zeros() as usize);
+        }
+    }
+    None
+}
+
+pub(super) fn count(b: &[u8], c: u8) -> usize {
+    let needle = splat(c);
+    let mut n = 0;
+    let mut i = 0;
+    while i + LANES <= b.len() {
+        n += mask(b, i, needle).count_ones() as usize;
+        i += LANES;
+    }
+    n + generic::count(&b[i..], c)
+}
+
+pub(super) fn equal(a: &[u8], b: &[u8]) -> bool {
+    let n = a.len();
+    if n < LANES {
+        return generic::equal(a, b);
+    }
+    let mut i = 0;
+    while i + LANES <= n {
+        let chunk = &b[i..i + LANES];
+        // SAFETY: `chunk` is exactly LANES bytes long, and SSE2 is always available on x86_64.
+        let needle = unsafe { _mm_loadu_si128(chunk.as_ptr() as *const __m128i) };
+        if mask(a, i, needle) != 0xFFFF {
+            return false;
+        }
+        i += LANES;
+    }
+    if i < n {
+        // The last load overlaps bytes already known to be equal.
+        let i = n - LANES;
+        let chunk = &b[i..i + LANES];
+        // SAFETY: `chunk` is exactly LANES bytes long, and SSE2 is always available on x86_64.
+        let needle = unsafe { _mm_loadu_si128(chunk.as_ptr() as *const __m128i) };
+        if mask(a, i, needle) != 0xFFFF {
+            return false;
+        }
+    }
+    true
+}
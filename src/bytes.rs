@@ -0,0 +1,797 @@
+use crate::{
+    bytealg, case,
+    unicode::utf8::{self, RUNE_ERROR, RUNE_SELF},
+};
+use core::cmp::Ordering;
+
+mod buffer;
+pub use buffer::*;
+
+mod reader;
+pub use reader::*;
+
+/// Reports whether `a` and `b` are the same length and contain the same bytes.
+#[inline]
+pub fn equal(a: &[u8], b: &[u8]) -> bool {
+    bytealg::equal(a, b)
+}
+
+/// Returns an integer comparing two byte slices lexicographically.
+#[inline]
+pub fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    a.cmp(b)
+}
+
+/// Reports whether `subslice` is within `b`.
+#[inline]
+pub fn contains(b: &[u8], subslice: &[u8]) -> bool {
+    index(b, subslice).is_some()
+}
+
+/// Reports whether any of the UTF-8-encoded code points in `chars` are within `b`.
+#[inline]
+pub fn contains_any(b: &[u8], chars: &str) -> bool {
+    index_any(b, chars).is_some()
+}
+
+/// Reports whether the rune is contained in the UTF-8-encoded byte slice `b`.
+#[inline]
+pub fn contains_rune(b: &[u8], r: char) -> bool {
+    index_rune(b, r).is_some()
+}
+
+/// Reports whether any of the UTF-8-encoded code points `r` within `b` satisfy `f(r)`.
+#[inline]
+pub fn contains_func(b: &[u8], f: impl FnMut(char) -> bool) -> bool {
+    index_func(b, f).is_some()
+}
+
+/// Counts the number of non-overlapping instances of `sep` in `s`.
+/// If `sep` is empty, `count` returns 1 + the number of UTF-8-encoded code points in `s`.
+pub fn count(mut s: &[u8], sep: &[u8]) -> usize {
+    // special case
+    if sep.is_empty() {
+        return utf8::rune_count(s) + 1;
+    }
+    if sep.len() == 1 {
+        return bytealg::count(s, sep[0]);
+    }
+    let mut n = 0;
+    while let Some(i) = index(s, sep) {
+        n += 1;
+        s = &s[i + sep.len()..];
+    }
+    n
+}
+
+/// Slices `s` around the first instance of `sep`,
+/// returning the text before and after `sep`.
+/// If `sep` does not appear in `s`, `cut` returns `None`.
+#[inline]
+pub fn cut<'a>(s: &'a [u8], sep: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    index(s, sep).map(|i| (&s[..i], &s[i + sep.len()..]))
+}
+
+/// Returns `s` without the provided leading `prefix` byte slice,
+/// or `None` if `s` doesn't start with `prefix`.
+/// If `prefix` is the empty byte slice, `cut_prefix` returns `Some(s)`.
+#[inline]
+pub fn cut_prefix<'a>(s: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    s.strip_prefix(prefix)
+}
+
+/// Returns `s` without the provided ending `suffix` byte slice,
+/// or `None` if `s` doesn't end with `suffix`.
+/// If `suffix` is the empty byte slice, `cut_suffix` returns `Some(s)`.
+#[inline]
+pub fn cut_suffix<'a>(s: &'a [u8], suffix: &[u8]) -> Option<&'a [u8]> {
+    s.strip_suffix(suffix)
+}
+
+/// Reports whether `s` and `t`, interpreted as UTF-8 strings,
+/// are equal under simple Unicode case-folding, which is a more general
+/// form of case-insensitivity.
+pub fn equal_fold(s: &[u8], t: &[u8]) -> bool {
+    let mut t = utf8::runes(t);
+    for (_, sr, _) in utf8::runes(s) {
+        match t.next() {
+            Some((_, tr, _)) if case::equal_fold(sr, tr) => {}
+            _ => return false,
+        }
+    }
+    // One string is empty. Are both?
+    t.next().is_none()
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded code points.
+/// It splits the slice `s` around each instance of one or more consecutive white space
+/// characters, as defined by [`char::is_whitespace`], returning a vector of subslices of `s` or an
+/// empty vector if `s` contains only white space.
+#[inline]
+pub fn fields(s: &[u8]) -> Vec<&[u8]> {
+    fields_func(s, char::is_whitespace)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded code points.
+/// It splits the slice `s` at each run of code points `c` satisfying `f(c)` and
+/// returns a vector of subslices of `s`. If all code points in `s` satisfy `f(c)`, or
+/// `s` is empty, an empty vector is returned.
+pub fn fields_func(s: &[u8], mut f: impl FnMut(char) -> bool) -> Vec<&[u8]> {
+    let mut fields = Vec::new();
+    // Set to the start of the current field, or `None` if we are between fields.
+    let mut start = None;
+    for (i, r, _) in utf8::runes(s) {
+        match (start, f(r)) {
+            (Some(st), true) => {
+                fields.push(&s[st..i]);
+                start = None;
+            }
+            (None, false) => start = Some(i),
+            _ => {}
+        }
+    }
+    // Last field might end at EOF.
+    if let Some(st) = start {
+        fields.push(&s[st..]);
+    }
+    fields
+}
+
+/// Tests whether the byte slice `s` begins with `prefix`.
+#[inline]
+pub fn has_prefix(s: &[u8], prefix: &[u8]) -> bool {
+    s.starts_with(prefix)
+}
+
+/// Tests whether the byte slice `s` ends with `suffix`.
+#[inline]
+pub fn has_suffix(s: &[u8], suffix: &[u8]) -> bool {
+    s.ends_with(suffix)
+}
+
+/// Returns the index of the first instance of `sep` in `s`, or `None` if `sep` is not present in `s`.
+#[inline]
+pub fn index(s: &[u8], sep: &[u8]) -> Option<usize> {
+    bytealg::index(s, sep)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded Unicode code points.
+/// It returns the byte index of the first occurrence in `s` of any of the Unicode
+/// code points in `chars`. It returns `None` if `chars` is empty or if there is no code
+/// point in common.
+#[inline]
+pub fn index_any(s: &[u8], chars: &str) -> Option<usize> {
+    index_func(s, |r| chars.contains(r))
+}
+
+/// Returns the index of the first instance of `c` in `b`, or `None` if `c` is not present in `b`.
+#[inline]
+pub fn index_byte(b: &[u8], c: u8) -> Option<usize> {
+    bytealg::index_byte(b, c)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded code points.
+/// It returns the byte index in `s` of the first Unicode
+/// code point satisfying `f(c)`, or `None` if none do.
+#[inline]
+pub fn index_func(s: &[u8], mut f: impl FnMut(char) -> bool) -> Option<usize> {
+    utf8::runes(s).find(|(_, r, _)| f(*r)).map(|(i, _, _)| i)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded code points.
+/// It returns the byte index of the first occurrence in `s` of the given rune.
+/// It returns `None` if rune is not present in `s`.
+/// If `r` is [`char::REPLACEMENT_CHARACTER`], it returns the first instance of any
+/// invalid UTF-8 byte sequence.
+#[inline]
+pub fn index_rune(s: &[u8], r: char) -> Option<usize> {
+    if (r as u32) < RUNE_SELF as u32 {
+        return index_byte(s, r as u8);
+    }
+    index_func(s, |c| c == r)
+}
+
+/// Concatenates the elements of `s` to create a new byte slice. The separator
+/// `sep` is placed between elements in the resulting slice.
+pub fn join<S: AsRef<[u8]>>(s: &[S], sep: &[u8]) -> Vec<u8> {
+    let mut b = Vec::new();
+    for (i, v) in s.iter().enumerate() {
+        if i > 0 {
+            b.extend_from_slice(sep);
+        }
+        b.extend_from_slice(v.as_ref());
+    }
+    b
+}
+
+/// Returns the index of the last instance of `sep` in `s`, or `None` if `sep` is not present in `s`.
+#[inline]
+pub fn last_index(s: &[u8], sep: &[u8]) -> Option<usize> {
+    bytealg::last_index(s, sep)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded Unicode code
+/// points. It returns the byte index of the last occurrence in `s` of any of
+/// the Unicode code points in `chars`. It returns `None` if `chars` is empty or if
+/// there is no code point in common.
+#[inline]
+pub fn last_index_any(s: &[u8], chars: &str) -> Option<usize> {
+    last_index_func(s, |r| chars.contains(r))
+}
+
+/// Returns the index of the last instance of `c` in `s`, or `None` if `c` is not present in `s`.
+#[inline]
+pub fn last_index_byte(s: &[u8], c: u8) -> Option<usize> {
+    bytealg::last_index_byte(s, c)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded code points.
+/// It returns the byte index in `s` of the last Unicode
+/// code point satisfying `f(c)`, or `None` if none do.
+#[inline]
+pub fn last_index_func(s: &[u8], mut f: impl FnMut(char) -> bool) -> Option<usize> {
+    utf8::runes(s)
+        .rev()
+        .find(|(_, r, _)| f(*r))
+        .map(|(i, _, _)| i)
+}
+
+/// Returns a copy of the byte slice `s` with all its characters modified
+/// according to the mapping function. If mapping returns `None`, the character is
+/// dropped from the byte slice with no replacement. The characters in `s` and the
+/// output are interpreted as UTF-8-encoded code points.
+pub fn map(mut mapping: impl FnMut(char) -> Option<char>, s: &[u8]) -> Vec<u8> {
+    // In the worst case, the slice can grow when mapped, making
+    // things unpleasant. But it's so rare we barge in assuming it's
+    // fine. It could also shrink but that falls out naturally.
+    let mut b = Vec::with_capacity(s.len());
+    let mut buf = [0; utf8::UTF_MAX];
+    for (_, r, _) in utf8::runes(s) {
+        if let Some(r) = mapping(r) {
+            b.extend_from_slice(r.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    b
+}
+
+/// Returns a new byte slice consisting of `count` copies of `b`.
+#[inline]
+pub fn repeat(b: &[u8], count: usize) -> Vec<u8> {
+    b.repeat(count)
+}
+
+/// Returns a copy of the slice `s` with the first `n`
+/// non-overlapping instances of `old` replaced by `new`.
+/// If `old` is empty, it matches at the beginning of the slice
+/// and after each UTF-8 sequence, yielding up to k+1 replacements
+/// for a k-rune slice.
+/// If `n < 0`, there is no limit on the number of replacements.
+pub fn replace(s: &[u8], old: &[u8], new: &[u8], n: isize) -> Vec<u8> {
+    let m = if old != new && n != 0 {
+        // Compute number of replacements.
+        count(s, old)
+    } else {
+        0
+    };
+    if m == 0 {
+        // Just return a copy.
+        return s.to_vec();
+    }
+    let n = if n < 0 || m < n as usize {
+        m
+    } else {
+        n as usize
+    };
+
+    // Apply replacements to buffer.
+    let mut t = Vec::with_capacity(s.len() + n * new.len());
+    let mut start = 0;
+    for i in 0..n {
+        let mut j = start;
+        if old.is_empty() {
+            if i > 0 {
+                j += utf8::decode_rune(&s[start..]).1;
+            }
+        } else {
+            j += index(&s[start..], old).unwrap_or(0);
+        }
+        t.extend_from_slice(&s[start..j]);
+        t.extend_from_slice(new);
+        start = j + old.len();
+    }
+    t.extend_from_slice(&s[start..]);
+    t
+}
+
+/// Returns a copy of the slice `s` with all
+/// non-overlapping instances of `old` replaced by `new`.
+/// If `old` is empty, it matches at the beginning of the slice
+/// and after each UTF-8 sequence, yielding up to k+1 replacements
+/// for a k-rune slice.
+#[inline]
+pub fn replace_all(s: &[u8], old: &[u8], new: &[u8]) -> Vec<u8> {
+    replace(s, old, new, -1)
+}
+
+/// Interprets `s` as a sequence of UTF-8-encoded code points.
+/// It returns a vector of runes (Unicode code points) equivalent to `s`.
+#[inline]
+pub fn runes(s: &[u8]) -> Vec<char> {
+    utf8::runes(s).map(|(_, r, _)| r).collect()
+}
+
+/// Splits a slice into its UTF-8 sequences, one per Unicode code point (still slices of bytes),
+/// up to a maximum of `n` byte slices (`n < 0` means no limit). Invalid UTF-8 sequences are
+/// chopped into individual bytes.
+fn explode(s: &[u8], n: isize) -> Vec<&[u8]> {
+    let l = utf8::rune_count(s);
+    let n = if n < 0 || n as usize > l {
+        l
+    } else {
+        n as usize
+    };
+    let mut a = Vec::with_capacity(n);
+    let mut runes = utf8::runes(s);
+    for _ in 1..n {
+        let (i, _, size) = runes.next().unwrap();
+        a.push(&s[i..i + size]);
+    }
+    if n > 0 {
+        a.push(&s[runes.next().map_or(s.len(), |(i, _, _)| i)..]);
+    }
+    a
+}
+
+/// Generic split: splits after each instance of `sep`,
+/// including `sep_save` bytes of `sep` in the subslices.
+fn gen_split<'a>(mut s: &'a [u8], sep: &[u8], sep_save: usize, n: isize) -> Vec<&'a [u8]> {
+    if n == 0 {
+        return Vec::new();
+    }
+    if sep.is_empty() {
+        return explode(s, n);
+    }
+    let n = if n < 0 { count(s, sep) + 1 } else { n as usize }.min(s.len() + 1);
+
+    let mut a = Vec::with_capacity(n);
+    while a.len() < n - 1 {
+        match index(s, sep) {
+            Some(m) => {
+                a.push(&s[..m + sep_save]);
+                s = &s[m + sep.len()..];
+            }
+            None => break,
+        }
+    }
+    a.push(s);
+    a
+}
+
+/// Slices `s` into subslices separated by `sep` and returns a vector of
+/// the subslices between those separators.
+/// If `sep` is empty, `split_n` splits after each UTF-8 sequence.
+///
+/// The count determines the number of subslices to return:
+///
+/// - `n > 0`: at most `n` subslices; the last subslice will be the unsplit remainder.
+/// - `n == 0`: the result is empty (zero subslices)
+/// - `n < 0`: all subslices
+#[inline]
+pub fn split_n<'a>(s: &'a [u8], sep: &[u8], n: isize) -> Vec<&'a [u8]> {
+    gen_split(s, sep, 0, n)
+}
+
+/// Slices `s` into subslices after each instance of `sep` and
+/// returns a vector of those subslices.
+/// If `sep` is empty, `split_after_n` splits after each UTF-8 sequence.
+///
+/// The count determines the number of subslices to return, as in [`split_n`].
+#[inline]
+pub fn split_after_n<'a>(s: &'a [u8], sep: &[u8], n: isize) -> Vec<&'a [u8]> {
+    gen_split(s, sep, sep.len(), n)
+}
+
+/// Slices `s` into all subslices separated by `sep` and returns a vector of
+/// the subslices between those separators.
+/// If `sep` is empty, `split` splits after each UTF-8 sequence.
+/// It is equivalent to [`split_n`] with a count of -1.
+#[inline]
+pub fn split<'a>(s: &'a [u8], sep: &[u8]) -> Vec<&'a [u8]> {
+    gen_split(s, sep, 0, -1)
+}
+
+/// Slices `s` into all subslices after each instance of `sep` and
+/// returns a vector of those subslices.
+/// If `sep` is empty, `split_after` splits after each UTF-8 sequence.
+/// It is equivalent to [`split_after_n`] with a count of -1.
+#[inline]
+pub fn split_after<'a>(s: &'a [u8], sep: &[u8]) -> Vec<&'a [u8]> {
+    gen_split(s, sep, sep.len(), -1)
+}
+
+/// Treats `s` as UTF-8-encoded bytes and returns a copy with all Unicode letters that begin
+/// words mapped to their title case.
+pub fn title(s: &[u8]) -> Vec<u8> {
+    // Use a closure here to remember state.
+    // Hackish but effective. Depends on map scanning in order and calling
+    // the closure once per rune.
+    let mut prev = ' ';
+    map(
+        |r| {
+            let rst = if case::is_separator(prev) {
+                case::to_title(r)
+            } else {
+                r
+            };
+            prev = r;
+            Some(rst)
+        },
+        s,
+    )
+}
+
+/// Returns a copy of the byte slice `s` with all Unicode letters mapped to
+/// their lower case.
+#[inline]
+pub fn to_lower(s: &[u8]) -> Vec<u8> {
+    if s.is_ascii() {
+        return s.to_ascii_lowercase();
+    }
+    map(|r| Some(case::to_lower(r)), s)
+}
+
+/// Treats `s` as UTF-8-encoded bytes and returns a copy with all the Unicode letters mapped to
+/// their title case.
+#[inline]
+pub fn to_title(s: &[u8]) -> Vec<u8> {
+    map(|r| Some(case::to_title(r)), s)
+}
+
+/// Returns a copy of the byte slice `s` with all Unicode letters mapped to
+/// their upper case.
+#[inline]
+pub fn to_upper(s: &[u8]) -> Vec<u8> {
+    if s.is_ascii() {
+        return s.to_ascii_uppercase();
+    }
+    map(|r| Some(case::to_upper(r)), s)
+}
+
+/// Treats `s` as UTF-8-encoded bytes and returns a copy with each run of bytes
+/// representing invalid UTF-8 replaced with the bytes in `replacement`, which may be empty.
+pub fn to_valid_utf8(s: &[u8], replacement: &[u8]) -> Vec<u8> {
+    let mut b = Vec::with_capacity(s.len() + replacement.len());
+    let mut invalid = false; // previous byte was from an invalid UTF-8 sequence
+    for (i, r, size) in utf8::runes(s) {
+        if r == RUNE_ERROR && size == 1 {
+            if !invalid {
+                invalid = true;
+                b.extend_from_slice(replacement);
+            }
+            continue;
+        }
+        invalid = false;
+        b.extend_from_slice(&s[i..i + size]);
+    }
+    b
+}
+
+/// Returns a subslice of `s` by slicing off all leading and
+/// trailing UTF-8-encoded code points contained in `cutset`.
+#[inline]
+pub fn trim<'a>(s: &'a [u8], cutset: &str) -> &'a [u8] {
+    trim_func(s, |r| cutset.contains(r))
+}
+
+/// Returns a subslice of `s` by slicing off all leading
+/// UTF-8-encoded code points contained in `cutset`.
+#[inline]
+pub fn trim_left<'a>(s: &'a [u8], cutset: &str) -> &'a [u8] {
+    trim_left_func(s, |r| cutset.contains(r))
+}
+
+/// Returns a subslice of `s` by slicing off all trailing
+/// UTF-8-encoded code points that are contained in `cutset`.
+#[inline]
+pub fn trim_right<'a>(s: &'a [u8], cutset: &str) -> &'a [u8] {
+    trim_right_func(s, |r| cutset.contains(r))
+}
+
+/// Returns a subslice of `s` by slicing off all leading and trailing
+/// UTF-8-encoded code points `c` that satisfy `f(c)`.
+#[inline]
+pub fn trim_func(s: &[u8], mut f: impl FnMut(char) -> bool) -> &[u8] {
+    trim_right_func(trim_left_func(s, &mut f), f)
+}
+
+/// Treats `s` as UTF-8-encoded bytes and returns a subslice of `s` by slicing off
+/// all leading UTF-8-encoded code points `c` that satisfy `f(c)`.
+#[inline]
+pub fn trim_left_func(s: &[u8], mut f: impl FnMut(char) -> bool) -> &[u8] {
+    match utf8::runes(s).find(|(_, r, _)| !f(*r)) {
+        Some((i, _, _)) => &s[i..],
+        None => &[],
+    }
+}
+
+/// Returns a subslice of `s` by slicing off all trailing
+/// UTF-8-encoded code points `c` that satisfy `f(c)`.
+#[inline]
+pub fn trim_right_func(s: &[u8], mut f: impl FnMut(char) -> bool) -> &[u8] {
+    match utf8::runes(s).rev().find(|(_, r, _)| !f(*r)) {
+        Some((i, _, size)) => &s[..i + size],
+        None => &[],
+    }
+}
+
+/// Returns a subslice of `s` by slicing off all leading and
+/// trailing white space, as defined by [`char::is_whitespace`].
+#[inline]
+pub fn trim_space(s: &[u8]) -> &[u8] {
+    trim_func(s, char::is_whitespace)
+}
+
+/// Returns `s` without the provided leading `prefix` slice.
+/// If `s` doesn't start with `prefix`, `s` is returned unchanged.
+#[inline]
+pub fn trim_prefix<'a>(s: &'a [u8], prefix: &[u8]) -> &'a [u8] {
+    s.strip_prefix(prefix).unwrap_or(s)
+}
+
+/// Returns `s` without the provided trailing `suffix` slice.
+/// If `s` doesn't end with `suffix`, `s` is returned unchanged.
+#[inline]
+pub fn trim_suffix<'a>(s: &'a [u8], suffix: &[u8]) -> &'a [u8] {
+    s.strip_suffix(suffix).unwrap_or(s)
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index() {
+        let tests: &[(&[u8], &[u8], Option<usize>, Option<usize>)] = &[
+            (b"", b"", Some(0), Some(0)),
+            (b"", b"a", None, None),
+            (b"fo", b"foo", None, None),
+            (b"foo", b"foo", Some(0), Some(0)),
+            (b"oofofoofooo", b"f", Some(2), Some(7)),
+            (b"oofofoofooo", b"foo", Some(4), Some(7)),
+            (b"barfoobarfoo", b"foo", Some(3), Some(9)),
+            (b"foo", b"", Some(0), Some(3)),
+            (b"foo", b"o", Some(1), Some(2)),
+            (b"abcABCabc", b"A", Some(3), Some(3)),
+            (b"x\xffy\xff", b"\xff", Some(1), Some(3)),
+        ];
+        for (s, sep, first, last) in tests {
+            assert_eq!(index(s, sep), *first, "index({s:?}, {sep:?})");
+            assert_eq!(last_index(s, sep), *last, "last_index({s:?}, {sep:?})");
+            assert_eq!(contains(s, sep), first.is_some());
+        }
+
+        let any: &[(&[u8], &str, Option<usize>, Option<usize>)] = &[
+            (b"", "", None, None),
+            (b"a", "", None, None),
+            (b"aaa", "a", Some(0), Some(2)),
+            (b"abc", "xcz", Some(2), Some(2)),
+            ("a☺b☻c☹d".as_bytes(), "uvw☻xyz", Some(5), Some(5)),
+            (b"\x80a\x80", "\u{FFFD}", Some(0), Some(2)),
+            (b"\x80a\x80", "a", Some(1), Some(1)),
+        ];
+        for (s, chars, first, last) in any {
+            assert_eq!(index_any(s, chars), *first, "index_any({s:?}, {chars:?})");
+            assert_eq!(
+                last_index_any(s, chars),
+                *last,
+                "last_index_any({s:?}, {chars:?})"
+            );
+        }
+
+        assert_eq!(index_rune("a☺b".as_bytes(), '☺'), Some(1));
+        assert_eq!(index_rune("a☺b".as_bytes(), 'b'), Some(4));
+        assert_eq!(index_rune(b"a\xffb", '\u{FFFD}'), Some(1));
+        assert_eq!(index_rune(b"ab", 'c'), None);
+        assert_eq!(index_byte(b"abc", b'c'), Some(2));
+        assert_eq!(last_index_byte(b"abcabc", b'a'), Some(3));
+        assert_eq!(index_func(b"12a3", |r| r.is_alphabetic()), Some(2));
+        assert_eq!(last_index_func(b"a12a3", |r| r.is_alphabetic()), Some(3));
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(count(b"", b""), 1);
+        assert_eq!(count("a☺b".as_bytes(), b""), 4);
+        assert_eq!(count(b"\xff\xff", b""), 3);
+        assert_eq!(count(b"aaaaa", b"aa"), 2);
+        assert_eq!(count(b"ababab", b"b"), 3);
+        assert_eq!(count(b"abc", b"abcd"), 0);
+    }
+
+    #[test]
+    fn test_split() {
+        let tests: &[(&[u8], &[u8], isize, &[&[u8]])] = &[
+            (b"", b"", -1, &[]),
+            (b"abcd", b"", 2, &[b"a", b"bcd"]),
+            (b"abcd", b"", 4, &[b"a", b"b", b"c", b"d"]),
+            (b"abcd", b"", -1, &[b"a", b"b", b"c", b"d"]),
+            (b"a\xffb", b"", -1, &[b"a", b"\xff", b"b"]),
+            (b"1,2,3,4", b",", -1, &[b"1", b"2", b"3", b"4"]),
+            (b"1,2,3,4", b",", 0, &[]),
+            (b"1,2,3,4", b",", 2, &[b"1", b"2,3,4"]),
+            (
+                b"1....2....3....4",
+                b"...",
+                -1,
+                &[b"1", b".2", b".3", b".4"],
+            ),
+            (b"", b",", -1, &[b""]),
+        ];
+        for (s, sep, n, out) in tests {
+            let got = split_n(s, sep, *n);
+            assert_eq!(&got, out, "split_n({s:?}, {sep:?}, {n})");
+            assert_eq!(join(&got, sep), if *n == 0 { &[][..] } else { s });
+        }
+
+        let tests: &[(&[u8], &[u8], isize, &[&[u8]])] = &[
+            (b"abcd", b"", -1, &[b"a", b"b", b"c", b"d"]),
+            (b"1,2,3,4", b",", -1, &[b"1,", b"2,", b"3,", b"4"]),
+            (b"1,2,3,4", b",", 2, &[b"1,", b"2,3,4"]),
+            (
+                b"1....2....3....4",
+                b"...",
+                -1,
+                &[b"1...", b".2...", b".3...", b".4"],
+            ),
+        ];
+        for (s, sep, n, out) in tests {
+            let got = split_after_n(s, sep, *n);
+            assert_eq!(&got, out, "split_after_n({s:?}, {sep:?}, {n})");
+            assert_eq!(join(&got, b""), *s);
+        }
+        assert_eq!(split(b"a,b", b","), [b"a", b"b"]);
+        assert_eq!(split_after(b"a,b", b","), [&b"a,"[..], b"b"]);
+    }
+
+    #[test]
+    fn test_fields() {
+        let tests: &[(&[u8], &[&[u8]])] = &[
+            (b"", &[]),
+            (b" ", &[]),
+            (b" \t ", &[]),
+            (b"\x20\n\x20", &[]),
+            (b"  abc  ", &[b"abc"]),
+            (b"1 2 3 4", &[b"1", b"2", b"3", b"4"]),
+            (b"1  2  3  4", &[b"1", b"2", b"3", b"4"]),
+            (b"1\t\t2\t\t3\t4", &[b"1", b"2", b"3", b"4"]),
+            (
+                "1\u{2028}2\u{2029}3\u{3000}4".as_bytes(),
+                &[b"1", b"2", b"3", b"4"],
+            ),
+            (b"\x80\xff 1", &[b"\x80\xff", b"1"]),
+        ];
+        for (s, out) in tests {
+            assert_eq!(&fields(s), out, "fields({s:?})");
+        }
+        assert_eq!(
+            fields_func(b"x1y2z", |r| r.is_ascii_digit()),
+            [b"x", b"y", b"z"]
+        );
+    }
+
+    #[test]
+    fn test_cut() {
+        assert_eq!(cut(b"abc", b"b"), Some((&b"a"[..], &b"c"[..])));
+        assert_eq!(cut(b"abc", b"a"), Some((&b""[..], &b"bc"[..])));
+        assert_eq!(cut(b"abc", b"abc"), Some((&b""[..], &b""[..])));
+        assert_eq!(cut(b"abc", b"d"), None);
+        assert_eq!(cut_prefix(b"abc", b"a"), Some(&b"bc"[..]));
+        assert_eq!(cut_prefix(b"abc", b"b"), None);
+        assert_eq!(cut_suffix(b"abc", b"c"), Some(&b"ab"[..]));
+        assert_eq!(cut_suffix(b"abc", b"b"), None);
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(to_upper(b"abc"), b"ABC");
+        assert_eq!(to_upper("ȺȾ".as_bytes()), "ȺȾ".as_bytes());
+        assert_eq!(to_lower("ÀÈ".as_bytes()), "àè".as_bytes());
+        assert_eq!(to_lower(b"AB\xffC"), "ab\u{FFFD}c".as_bytes());
+        assert_eq!(title(b"hello gopher"), b"Hello Gopher");
+        assert_eq!(to_title("ǆ".as_bytes()), "ǅ".as_bytes());
+        assert!(equal_fold(b"abc", b"ABC"));
+        assert!(equal_fold("σ".as_bytes(), "Σ".as_bytes()));
+        assert!(!equal_fold(b"abc", b"abd"));
+        assert!(!equal_fold(b"abc", b"ab"));
+    }
+
+    #[test]
+    fn test_trim() {
+        let tests: &[(&[u8], &str, &[u8], &[u8], &[u8])] = &[
+            (b"abba", "a", b"bb", b"bba", b"abb"),
+            (b"abba", "ab", b"", b"", b""),
+            (b"<tag>", "<>", b"tag", b"tag>", b"<tag"),
+            (b"\xffab\xff", "\u{FFFD}", b"ab", b"ab\xff", b"\xffab"),
+            (b"abba", "", b"abba", b"abba", b"abba"),
+            (b"", "123", b"", b"", b""),
+        ];
+        for (s, cutset, both, left, right) in tests {
+            assert_eq!(trim(s, cutset), *both, "trim({s:?}, {cutset:?})");
+            assert_eq!(trim_left(s, cutset), *left, "trim_left({s:?}, {cutset:?})");
+            assert_eq!(
+                trim_right(s, cutset),
+                *right,
+                "trim_right({s:?}, {cutset:?})"
+            );
+        }
+        assert_eq!(
+            trim_space(" \t\n a lone gopher \n\t\r\n".as_bytes()),
+            b"a lone gopher"
+        );
+        assert_eq!(trim_space(b" \xff "), b"\xff");
+        assert_eq!(trim_prefix(b"aabb", b"a"), b"abb");
+        assert_eq!(trim_suffix(b"aabb", b"b"), b"aab");
+        assert_eq!(trim_func(b"123abc456", |r| r.is_ascii_digit()), b"abc");
+    }
+
+    #[test]
+    fn test_replace() {
+        let tests: &[(&[u8], &[u8], &[u8], isize, &[u8])] = &[
+            (b"hello", b"l", b"L", 0, b"hello"),
+            (b"hello", b"l", b"L", -1, b"heLLo"),
+            (b"hello", b"x", b"X", -1, b"hello"),
+            (b"", b"x", b"X", -1, b""),
+            (b"radar", b"r", b"<r>", -1, b"<r>ada<r>"),
+            (b"", b"", b"<>", -1, b"<>"),
+            (b"banana", b"a", b"<>", 1, b"b<>nana"),
+            (b"banana", b"", b"<>", -1, b"<>b<>a<>n<>a<>n<>a<>"),
+            (b"banana", b"", b"<>", 2, b"<>b<>anana"),
+            ("☺☻☹".as_bytes(), b"", b"<>", -1, "<>☺<>☻<>☹<>".as_bytes()),
+            (b"\xff\xff", b"", b"<>", -1, b"<>\xff<>\xff<>"),
+        ];
+        for (s, old, new, n, out) in tests {
+            assert_eq!(
+                replace(s, old, new, *n),
+                *out,
+                "replace({s:?}, {old:?}, {new:?}, {n})"
+            );
+        }
+        assert_eq!(replace_all(b"banana", b"an", b"AN"), b"bANANa");
+    }
+
+    #[test]
+    fn test_misc() {
+        assert!(equal(b"abc", b"abc"));
+        assert!(!equal(b"abc", b"abd"));
+        assert_eq!(compare(b"a", b"b"), Ordering::Less);
+        assert_eq!(compare(b"", b""), Ordering::Equal);
+        assert_eq!(repeat(b"-", 3), b"---");
+        assert_eq!(runes(b"a\xff\xe2\x98\xba"), ['a', '\u{FFFD}', '☺']);
+        assert_eq!(
+            map(|r| if r == 'a' { None } else { Some(r) }, b"banana"),
+            b"bnn"
+        );
+        assert!(contains_rune("a☺".as_bytes(), '☺'));
+        assert!(contains_any(b"abc", "xa"));
+        assert!(contains_func(b"ab1", |r| r.is_ascii_digit()));
+        assert!(has_prefix(b"abc", b"ab"));
+        assert!(has_suffix(b"abc", b"bc"));
+
+        let tests: &[(&[u8], &[u8], &[u8])] = &[
+            (b"", b"\xef\xbf\xbd", b""),
+            (b"abc", b"\xef\xbf\xbd", b"abc"),
+            (b"\xc0\xafabc", b"\xef\xbf\xbd", b"\xef\xbf\xbdabc"),
+            (b"a\xffb\xC0\xAFc\xff", b"", b"abc"),
+            (
+                b"a\xffb\xC0\xAFc\xff",
+                b"\xef\xbf\xbd",
+                b"a\xef\xbf\xbdb\xef\xbf\xbdc\xef\xbf\xbd",
+            ),
+            ("\u{FFFD}".as_bytes(), b"-", "\u{FFFD}".as_bytes()),
+        ];
+        for (s, rep, out) in tests {
+            assert_eq!(to_valid_utf8(s, rep), *out, "to_valid_utf8({s:?}, {rep:?})");
+        }
+    }
+}
@@ -0,0 +1,964 @@
+use crate::{
+    bytealg,
+    io::{ByteReader, ByteScanner, Error, ReaderFrom, RuneReader, RuneScanner, WriterTo},
+    unicode::utf8,
+};
+use std::borrow::Cow;
+
+/// Smallest capacity allocated for a buffer.
+const SMALL_BUFFER_SIZE: usize = 64;
+
+/// The minimum slice size passed to a read call by
+/// [`Buffer::read_from`](ReaderFrom::read_from). As long as the [`Buffer`] has at least `MIN_READ` bytes beyond
+/// what is required to hold the contents of `r`, `read_from` will not grow the
+/// underlying buffer.
+pub const MIN_READ: usize = 512;
+
+/// The kind of the last read operation, so that
+/// [`Buffer::unread_rune`] and [`Buffer::unread_byte`] can check for invalid usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadOp {
+    /// Any other read operation.
+    Read,
+    /// Non-read operation.
+    Invalid,
+    /// Read rune of the given size.
+    ReadRune(usize),
+}
+
+/// A variable-sized buffer of bytes with read and write methods.
+/// The zero value (`Buffer::new()`) for [`Buffer`] is an empty buffer ready to use.
+#[derive(Debug, Clone)]
+pub struct Buffer {
+    /// Contents are the bytes `buf[off..end]`; `buf[end..]` is spare room for writes.
+    buf: Vec<u8>,
+    /// Read at `buf[off]`, write at `buf[end]`.
+    off: usize,
+    end: usize,
+    /// Last read operation, so that unread_* can work correctly.
+    last_read: ReadOp,
+}
+
+impl Default for Buffer {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates and initializes a new [`Buffer`] using `buf` as its
+/// initial contents. The new [`Buffer`] takes ownership of `buf`.
+/// It can also be used to set the initial size of the internal buffer for writing:
+/// pass `Vec::with_capacity(n)`.
+///
+/// In most cases, [`Buffer::new`] (or just declaring a [`Buffer`]) is
+/// sufficient to initialize a [`Buffer`].
+#[inline]
+pub fn new_buffer(buf: Vec<u8>) -> Buffer {
+    Buffer::from(buf)
+}
+
+/// Creates and initializes a new [`Buffer`] using string `s` as its
+/// initial contents. It is intended to prepare a buffer to read an existing
+/// string.
+#[inline]
+pub fn new_buffer_string(s: &str) -> Buffer {
+    Buffer::from(s)
+}
+
+impl From<Vec<u8>> for Buffer {
+    #[inline]
+    fn from(mut buf: Vec<u8>) -> Self {
+        let end = buf.len();
+        buf.resize(buf.capacity(), 0);
+        Self {
+            buf,
+            off: 0,
+            end,
+            last_read: ReadOp::Invalid,
+        }
+    }
+}
+
+impl From<&[u8]> for Buffer {
+    #[inline]
+    fn from(buf: &[u8]) -> Self {
+        Self::from(buf.to_vec())
+    }
+}
+
+impl From<String> for Buffer {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::from(s.into_bytes())
+    }
+}
+
+impl From<&str> for Buffer {
+    #[inline]
+    fn from(s: &str) -> Self {
+        Self::from(s.as_bytes())
+    }
+}
+
+impl Buffer {
+    /// Returns an empty [`Buffer`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            off: 0,
+            end: 0,
+            last_read: ReadOp::Invalid,
+        }
+    }
+
+    /// Returns a slice of length `self.len()` holding the unread portion of the buffer,
+    /// like Go's `Buffer.Bytes` (`bytes` would clash with [`std::io::Read::bytes`]).
+    /// The slice is valid for use only until the next buffer modification (that is,
+    /// only until the next call to a method like `read`, `write`, `reset`, or `truncate`).
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[self.off..self.end]
+    }
+
+    /// Returns an empty buffer with [`Buffer::available`] bytes of room.
+    /// The buffer is intended to be filled and then committed with [`Buffer::commit`],
+    /// the counterpart of Go's `b.Write(b.AvailableBuffer()[:n])`, which the borrow
+    /// rules do not allow. The buffer is only valid until the next buffer modification.
+    #[inline]
+    pub fn available_buffer(&mut self) -> &mut [u8] {
+        &mut self.buf[self.end..]
+    }
+
+    /// Appends the first `n` bytes of [`Buffer::available_buffer`] to the buffer.
+    ///
+    /// # Panics
+    /// Panics if `n` is larger than [`Buffer::available`].
+    #[inline]
+    pub fn commit(&mut self, n: usize) {
+        assert!(n <= self.available(), "bytes.Buffer: commit out of range");
+        self.last_read = ReadOp::Invalid;
+        self.end += n;
+    }
+
+    /// Returns the contents of the unread portion of the buffer
+    /// as a string. Invalid UTF-8 sequences are replaced with `U+FFFD`.
+    #[inline]
+    pub fn string(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.as_bytes())
+    }
+
+    /// Reports whether the unread portion of the buffer is empty.
+    #[inline]
+    fn empty(&self) -> bool {
+        self.end <= self.off
+    }
+
+    /// Returns the number of bytes of the unread portion of the buffer;
+    /// `self.len() == self.as_bytes().len()`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.end - self.off
+    }
+
+    /// Reports whether the unread portion of the buffer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.empty()
+    }
+
+    /// Returns the capacity of the buffer's underlying byte slice, that is, the
+    /// total space allocated for the buffer's data.
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns how many bytes are unused in the buffer.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.buf.len() - self.end
+    }
+
+    /// Discards all but the first `n` unread bytes from the buffer
+    /// but continues to use the same allocated storage.
+    ///
+    /// # Panics
+    /// Panics if `n` is greater than the length of the buffer.
+    pub fn truncate(&mut self, n: usize) {
+        if n == 0 {
+            self.reset();
+            return;
+        }
+        self.last_read = ReadOp::Invalid;
+        if n > self.len() {
+            panic!("bytes.Buffer: truncation out of range");
+        }
+        self.end = self.off + n;
+    }
+
+    /// Resets the buffer to be empty,
+    /// but it retains the underlying storage for use by future writes.
+    /// `reset` is the same as [`Buffer::truncate`]`(0)`.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.off = 0;
+        self.end = 0;
+        self.last_read = ReadOp::Invalid;
+    }
+
+    /// Grows the buffer to guarantee space for `n` more bytes.
+    /// It returns the index where bytes should be written.
+    fn grow_inner(&mut self, n: usize) -> usize {
+        let m = self.len();
+        // If buffer is empty, reset to recover space.
+        if m == 0 && self.off != 0 {
+            self.reset();
+        }
+        // Try to grow by means of a reslice.
+        if n <= self.available() {
+            return self.end;
+        }
+        if self.buf.is_empty() && n <= SMALL_BUFFER_SIZE {
+            self.buf = vec![0; SMALL_BUFFER_SIZE];
+            return 0;
+        }
+        let c = self.buf.len();
+        if m < c / 2 && n <= c / 2 - m {
+            // We can slide things down instead of allocating a new
+            // slice. We only need m+n <= c to slide, but
+            // we instead let capacity get twice as large so we
+            // don't spend all our time copying.
+            self.buf.copy_within(self.off..self.end, 0);
+        } else {
+            // Double the capacity at least, so that repeated writes stay amortized O(1).
+            let needed = m
+                .checked_add(n)
+                .expect("bytes.Buffer: too large")
+                .max(c.saturating_mul(2));
+            let mut buf = vec![0; needed];
+            buf[..m].copy_from_slice(&self.buf[self.off..self.end]);
+            self.buf = buf;
+        }
+        // Restore self.off and self.end.
+        self.off = 0;
+        self.end = m;
+        m
+    }
+
+    /// Grows the buffer's capacity, if necessary, to guarantee space for
+    /// another `n` bytes. After `grow(n)`, at least `n` bytes can be written to the
+    /// buffer without another allocation.
+    #[inline]
+    pub fn grow(&mut self, n: usize) {
+        self.grow_inner(n);
+    }
+
+    /// Appends the contents of `s` to the buffer, growing the buffer as
+    /// needed. The return value is the length of `s`.
+    #[inline]
+    pub fn write_str(&mut self, s: &str) -> usize {
+        self.write_bytes(s.as_bytes())
+    }
+
+    /// Appends the contents of `p` to the buffer, growing the buffer as
+    /// needed. The return value is the length of `p`.
+    pub fn write_bytes(&mut self, p: &[u8]) -> usize {
+        self.last_read = ReadOp::Invalid;
+        let m = self.grow_inner(p.len());
+        self.buf[m..m + p.len()].copy_from_slice(p);
+        self.end = m + p.len();
+        p.len()
+    }
+
+    /// Appends the UTF-8 encoding of Unicode code point `r` to the
+    /// buffer, returning its length.
+    #[inline]
+    pub fn write_rune(&mut self, r: char) -> usize {
+        let mut buf = [0; 4];
+        self.write_str(r.encode_utf8(&mut buf))
+    }
+
+    /// Reads the next `p.len()` bytes from the buffer or until the buffer
+    /// is drained. The return value is the number of bytes read. If the
+    /// buffer has no data to return, the error is [`Error::Eof`] (unless `p.len()` is zero).
+    pub fn read(&mut self, p: &mut [u8]) -> Result<usize, Error> {
+        self.last_read = ReadOp::Invalid;
+        if self.empty() {
+            // Buffer is empty, reset to recover space.
+            self.reset();
+            if p.is_empty() {
+                return Ok(0);
+            }
+            return Err(Error::Eof);
+        }
+        let n = crate::copy(&self.buf[self.off..self.end], p);
+        self.off += n;
+        if n > 0 {
+            self.last_read = ReadOp::Read;
+        }
+        Ok(n)
+    }
+
+    /// Returns a slice containing the next `n` bytes from the buffer,
+    /// advancing the buffer as if the bytes had been returned by `read`.
+    /// If there are fewer than `n` bytes in the buffer, `next` returns the entire buffer.
+    /// The slice is only valid until the next call to a read or write method.
+    pub fn next(&mut self, n: usize) -> &[u8] {
+        self.last_read = ReadOp::Invalid;
+        let n = n.min(self.len());
+        let start = self.off;
+        self.off += n;
+        if n > 0 {
+            self.last_read = ReadOp::Read;
+        }
+        &self.buf[start..start + n]
+    }
+
+    /// Reads and returns the next byte from the buffer.
+    /// If no byte is available, it returns error [`Error::Eof`].
+    pub fn read_byte(&mut self) -> Result<u8, Error> {
+        if self.empty() {
+            // Buffer is empty, reset to recover space.
+            self.reset();
+            return Err(Error::Eof);
+        }
+        let c = self.buf[self.off];
+        self.off += 1;
+        self.last_read = ReadOp::Read;
+        Ok(c)
+    }
+
+    /// Reads and returns the next UTF-8-encoded
+    /// Unicode code point from the buffer.
+    /// If no bytes are available, the error returned is [`Error::Eof`].
+    /// If the bytes are an erroneous UTF-8 encoding, it
+    /// consumes one byte and returns `U+FFFD`, 1.
+    pub fn read_rune(&mut self) -> Result<(char, usize), Error> {
+        if self.empty() {
+            // Buffer is empty, reset to recover space.
+            self.reset();
+            return Err(Error::Eof);
+        }
+        let c = self.buf[self.off];
+        if c < utf8::RUNE_SELF {
+            self.off += 1;
+            self.last_read = ReadOp::ReadRune(1);
+            return Ok((c as char, 1));
+        }
+        let (r, n) = utf8::decode_rune(&self.buf[self.off..self.end]);
+        self.off += n;
+        self.last_read = ReadOp::ReadRune(n);
+        Ok((r, n))
+    }
+
+    /// Unreads the last rune returned by [`Buffer::read_rune`].
+    /// If the most recent read or write operation on the buffer was
+    /// not a successful [`Buffer::read_rune`], `unread_rune` returns an error. (In this regard
+    /// it is stricter than [`Buffer::unread_byte`], which will unread the last byte
+    /// from any read operation.)
+    pub fn unread_rune(&mut self) -> Result<(), Error> {
+        let n =
+            match self.last_read {
+                ReadOp::ReadRune(n) => n,
+                _ => return Err(Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "bytes.Buffer: UnreadRune: previous operation was not a successful ReadRune",
+                ))),
+            };
+        if self.off >= n {
+            self.off -= n;
+        }
+        self.last_read = ReadOp::Invalid;
+        Ok(())
+    }
+
+    /// Unreads the last byte returned by the most recent successful
+    /// read operation that read at least one byte. If a write has happened since
+    /// the last read, if the last read returned an error, or if the read read zero
+    /// bytes, `unread_byte` returns an error.
+    pub fn unread_byte(&mut self) -> Result<(), Error> {
+        if self.last_read == ReadOp::Invalid {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "bytes.Buffer: UnreadByte: previous operation was not a successful read",
+            )));
+        }
+        self.last_read = ReadOp::Invalid;
+        if self.off > 0 {
+            self.off -= 1;
+        }
+        Ok(())
+    }
+
+    /// Reads until the first occurrence of `delim` in the input,
+    /// returning a vector containing the data up to and including the delimiter.
+    /// If `read_bytes` encounters the end of the buffer before finding a delimiter,
+    /// it returns the data read before the end together with [`Error::Eof`],
+    /// so a final unterminated line is not mistaken for a terminated one.
+    /// `read_bytes` returns `Ok` if and only if the returned data ends in `delim`.
+    pub fn read_bytes(&mut self, delim: u8) -> Result<Vec<u8>, (Vec<u8>, Error)> {
+        self.read_slice(delim)
+            .map(<[u8]>::to_vec)
+            .map_err(|(s, e)| (s.to_vec(), e))
+    }
+
+    /// Like [`Buffer::read_bytes`] but returns a reference to internal buffer data.
+    fn read_slice(&mut self, delim: u8) -> Result<&[u8], (&[u8], Error)> {
+        if self.empty() {
+            self.last_read = ReadOp::Invalid;
+            return Err((&[], Error::Eof));
+        }
+        let start = self.off;
+        let found = bytealg::index_byte(&self.buf[start..self.end], delim);
+        let end = match found {
+            Some(i) => start + i + 1,
+            None => self.end,
+        };
+        self.off = end;
+        self.last_read = ReadOp::Read;
+        match found {
+            Some(_) => Ok(&self.buf[start..end]),
+            None => Err((&self.buf[start..end], Error::Eof)),
+        }
+    }
+
+    /// Reads until the first occurrence of `delim` in the input,
+    /// returning a string containing the data up to and including the delimiter.
+    /// Reaching the end of the buffer behaves as in [`Buffer::read_bytes`].
+    ///
+    /// Unlike Go, which returns the bytes as they are, invalid UTF-8 sequences
+    /// are replaced with `U+FFFD`; use [`Buffer::read_bytes`] to get the
+    /// exact bytes.
+    pub fn read_string(&mut self, delim: u8) -> Result<String, (String, Error)> {
+        let lossy = |s: &[u8]| String::from_utf8_lossy(s).into_owned();
+        self.read_slice(delim)
+            .map(lossy)
+            .map_err(|(s, e)| (lossy(s), e))
+    }
+}
+
+impl std::io::Read for Buffer {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match Buffer::read(self, buf) {
+            Err(Error::Eof) => Ok(0),
+            rst => rst.map_err(Into::into),
+        }
+    }
+}
+
+impl std::io::BufRead for Buffer {
+    #[inline]
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.as_bytes())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.next(amt);
+    }
+}
+
+impl std::io::Write for Buffer {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(self.write_bytes(buf))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl core::fmt::Write for Buffer {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        Buffer::write_str(self, s);
+        Ok(())
+    }
+}
+
+impl ByteReader for Buffer {
+    #[inline]
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        Buffer::read_byte(self)
+    }
+}
+
+impl ByteScanner for Buffer {
+    #[inline]
+    fn unread_byte(&mut self) -> Result<(), Error> {
+        Buffer::unread_byte(self)
+    }
+}
+
+impl RuneReader for Buffer {
+    #[inline]
+    fn read_rune(&mut self) -> Result<(char, usize), Error> {
+        Buffer::read_rune(self)
+    }
+}
+
+impl RuneScanner for Buffer {
+    #[inline]
+    fn unread_rune(&mut self) -> Result<(), Error> {
+        Buffer::unread_rune(self)
+    }
+}
+
+impl ReaderFrom for Buffer {
+    /// Reads data from `r` until EOF and appends it to the buffer, growing
+    /// the buffer as needed. The return value is the number of bytes read. Any
+    /// error except EOF encountered during the read is also returned.
+    fn read_from<R: std::io::Read + ?Sized>(&mut self, r: &mut R) -> std::io::Result<u64> {
+        self.last_read = ReadOp::Invalid;
+        let mut n = 0;
+        loop {
+            let i = self.grow_inner(MIN_READ);
+            match r.read(&mut self.buf[i..]) {
+                Ok(0) => return Ok(n),
+                Ok(m) => {
+                    self.end = i + m;
+                    n += m as u64;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl WriterTo for Buffer {
+    /// Writes data to `w` until the buffer is drained or an error occurs.
+    /// The return value is the number of bytes written.
+    fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+        self.last_read = ReadOp::Invalid;
+        let mut n = 0;
+        while !self.empty() {
+            let n_bytes = self.len();
+            match w.write(self.as_bytes()) {
+                // The writer made no progress and reported no error.
+                Ok(0) => return Err(Error::ShortWrite.into()),
+                Ok(m) => {
+                    if m > n_bytes {
+                        panic!("bytes.Buffer.WriteTo: invalid Write count");
+                    }
+                    self.off += m;
+                    n += m as u64;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        // Buffer is now empty; reset.
+        self.reset();
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{ByteWriter, StringWriter};
+    use std::io::{Read, Write};
+
+    const N: usize = 10000;
+
+    fn test_bytes() -> Vec<u8> {
+        (0..N).map(|i| b'a' + (i % 26) as u8).collect()
+    }
+
+    /// Verify that contents of buf match the string s.
+    fn check(name: &str, buf: &Buffer, s: &[u8]) {
+        assert_eq!(buf.as_bytes().len(), buf.len(), "{name}");
+        assert_eq!(buf.len(), s.len(), "{name}");
+        assert_eq!(buf.as_bytes(), s, "{name}");
+    }
+
+    /// Fill buf through n writes of byte slice fub.
+    /// The initial contents of buf corresponds to the string s;
+    /// the result is the final contents of buf returned as a string.
+    fn fill_bytes(name: &str, buf: &mut Buffer, mut s: Vec<u8>, n: usize, fub: &[u8]) -> Vec<u8> {
+        check(&format!("{name} (fill 1)"), buf, &s);
+        for _ in 0..n {
+            let m = buf.write_bytes(fub);
+            assert_eq!(m, fub.len(), "{name} (fill 2)");
+            s.extend_from_slice(fub);
+            check(&format!("{name} (fill 4)"), buf, &s);
+        }
+        s
+    }
+
+    /// Empty buf through repeated reads into fub.
+    /// The initial contents of buf corresponds to the string s.
+    fn empty(name: &str, buf: &mut Buffer, s: &[u8], fub: &mut [u8]) {
+        check(&format!("{name} (empty 1)"), buf, s);
+        let mut s = s;
+        loop {
+            let n = match buf.read(fub) {
+                Ok(n) => n,
+                Err(e) => {
+                    assert!(e.is_eof(), "{name}");
+                    break;
+                }
+            };
+            if n == 0 {
+                break;
+            }
+            assert_eq!(&fub[..n], &s[..n], "{name}");
+            s = &s[n..];
+            check(&format!("{name} (empty 3)"), buf, s);
+        }
+        check(&format!("{name} (empty 4)"), buf, b"");
+    }
+
+    #[test]
+    fn test_new_buffer() {
+        let data = test_bytes();
+        let buf = new_buffer(data.clone());
+        check("NewBuffer", &buf, &data);
+        let buf = new_buffer_string("hello");
+        check("NewBufferString", &buf, b"hello");
+        assert_eq!(buf.string(), "hello");
+    }
+
+    #[test]
+    fn test_basic_operations() {
+        let data = test_bytes();
+        let mut buf = Buffer::new();
+        for _ in 0..5 {
+            check("TestBasicOperations (1)", &buf, b"");
+
+            buf.reset();
+            check("TestBasicOperations (2)", &buf, b"");
+
+            buf.truncate(0);
+            check("TestBasicOperations (3)", &buf, b"");
+
+            assert_eq!(buf.write_bytes(&data[..1]), 1);
+            check("TestBasicOperations (4)", &buf, b"a");
+
+            buf.write_byte(data[1]).unwrap();
+            check("TestBasicOperations (5)", &buf, b"ab");
+
+            assert_eq!(buf.write_bytes(&data[2..26]), 24);
+            check("TestBasicOperations (6)", &buf, &data[..26]);
+
+            buf.truncate(26);
+            check("TestBasicOperations (7)", &buf, &data[..26]);
+
+            buf.truncate(20);
+            check("TestBasicOperations (8)", &buf, &data[..20]);
+
+            empty(
+                "TestBasicOperations (9)",
+                &mut buf,
+                &data[..20],
+                &mut [0; 5],
+            );
+            empty("TestBasicOperations (10)", &mut buf, b"", &mut [0; 100]);
+
+            buf.write_byte(data[1]).unwrap();
+            assert_eq!(buf.read_byte().unwrap(), data[1]);
+            assert!(buf.read_byte().unwrap_err().is_eof());
+        }
+    }
+
+    #[test]
+    fn test_large_writes() {
+        let data = test_bytes();
+        let mut buf = Buffer::new();
+        let mut limit = 30;
+        if N < limit {
+            limit = N;
+        }
+        for i in (3..limit).step_by(3) {
+            let s = fill_bytes("TestLargeWrites (1)", &mut buf, Vec::new(), 5, &data);
+            empty(
+                "TestLargeWrites (2)",
+                &mut buf,
+                &s,
+                &mut vec![0; data.len() / i],
+            );
+        }
+        check("TestLargeWrites (3)", &buf, b"");
+    }
+
+    #[test]
+    fn test_mixed_reads_and_writes() {
+        let data = test_bytes();
+        let mut buf = Buffer::new();
+        let mut s = Vec::new();
+        for i in 0..50 {
+            let wlen = (i * 37 + 11) % data.len();
+            if i % 2 == 0 {
+                s = fill_bytes("TestMixedReadsAndWrites (1)", &mut buf, s, 1, &data[..wlen]);
+            } else {
+                buf.write_string(core::str::from_utf8(&data[..wlen]).unwrap())
+                    .unwrap();
+                s.extend_from_slice(&data[..wlen]);
+            }
+
+            let rlen = (i * 53 + 7) % 500;
+            let mut fub = vec![0; rlen];
+            let n = buf.read(&mut fub).unwrap_or(0);
+            s.drain(..n);
+        }
+        let mut fub = vec![0; buf.len()];
+        empty("TestMixedReadsAndWrites (2)", &mut buf, &s, &mut fub);
+    }
+
+    #[test]
+    fn test_capacity_and_grow() {
+        let mut buf = Buffer::new();
+        assert_eq!(buf.cap(), 0);
+        buf.write_str("hello");
+        assert_eq!(buf.cap(), SMALL_BUFFER_SIZE);
+
+        for (start, grow) in [(0, 100), (100, 1000), (1000, 10000)] {
+            let x = vec![b'x'; start];
+            let mut buf = new_buffer(x.clone());
+            buf.grow(grow);
+            assert!(buf.cap() - buf.len() >= grow);
+            let cap = buf.cap();
+            buf.write_bytes(&vec![b'y'; grow]);
+            // no reallocation after growing
+            assert_eq!(buf.cap(), cap);
+            assert_eq!(&buf.as_bytes()[..start], &x[..]);
+        }
+
+        // reads make room for writes by sliding instead of reallocating
+        let mut buf = Buffer::new();
+        buf.grow(1000);
+        let cap = buf.cap();
+        buf.write_bytes(&[b'z'; 1000]);
+        buf.next(900);
+        buf.write_bytes(&[b'w'; 200]);
+        assert_eq!(buf.cap(), cap);
+        assert_eq!(buf.len(), 300);
+    }
+
+    #[test]
+    fn test_available_buffer() {
+        let mut buf = new_buffer_string("x=");
+        buf.grow(16);
+        assert!(buf.available() >= 16);
+        let avail = buf.available_buffer();
+        avail[..2].copy_from_slice(b"42");
+        buf.commit(2);
+        assert_eq!(buf.as_bytes(), b"x=42");
+        assert!(buf.unread_byte().is_err());
+    }
+
+    #[test]
+    fn test_read_from_and_write_to() {
+        let data = test_bytes();
+        let mut buf = Buffer::new();
+        for i in (0..data.len()).step_by(997) {
+            let n = buf.read_from(&mut &data[..i]).unwrap();
+            assert_eq!(n as usize, i);
+            let mut out = Vec::new();
+            let n = buf.write_to(&mut out).unwrap();
+            assert_eq!(n as usize, i);
+            assert_eq!(out, &data[..i]);
+            check("TestReadFrom", &buf, b"");
+        }
+    }
+
+    /// Accepts at most `n` bytes per call.
+    struct ShortWriter {
+        n: usize,
+        data: Vec<u8>,
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = self.n.min(buf.len());
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_to_short_writes() {
+        let mut buf = new_buffer_string("hello, world");
+        let mut w = ShortWriter { n: 5, data: vec![] };
+        assert_eq!(buf.write_to(&mut w).unwrap(), 12);
+        assert_eq!(w.data, b"hello, world");
+        check("TestWriteToShort", &buf, b"");
+
+        let mut buf = new_buffer_string("hello");
+        let mut w = ShortWriter { n: 0, data: vec![] };
+        let err = buf.write_to(&mut w).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+        check("TestWriteToZero", &buf, b"hello");
+    }
+
+    #[test]
+    fn test_rune_io() {
+        const NRUNE: u32 = 1000;
+        // Built a test slice while we write the data
+        let mut b = Vec::new();
+        let mut buf = Buffer::new();
+        let runes: Vec<char> = (0..NRUNE).filter_map(|r| char::from_u32(r * 7)).collect();
+        for r in runes.iter().copied() {
+            let mut tmp = [0; 4];
+            let s = r.encode_utf8(&mut tmp);
+            b.extend_from_slice(s.as_bytes());
+            assert_eq!(buf.write_rune(r), s.len());
+        }
+
+        // Check the resulting bytes
+        assert_eq!(buf.as_bytes(), &b[..]);
+
+        // Check that unread_rune works
+        let mut p = vec![0; b.len()];
+        buf.read(&mut p).unwrap();
+        let mut buf = new_buffer(b.clone());
+        for r in runes.iter().copied() {
+            let (nr, size) = buf.read_rune().unwrap();
+            assert_eq!((nr, size), (r, r.len_utf8()));
+            buf.unread_rune().unwrap();
+            assert!(buf.unread_rune().is_err());
+            let (nr, _) = buf.read_rune().unwrap();
+            assert_eq!(nr, r);
+        }
+
+        // invalid encodings decode as U+FFFD, one byte at a time
+        let mut buf = new_buffer(b"\xe4\xb8a".to_vec());
+        assert_eq!(buf.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert_eq!(buf.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert_eq!(buf.read_rune().unwrap(), ('a', 1));
+        assert!(buf.read_rune().unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn test_next() {
+        let b = [0, 1, 2, 3, 4];
+        let mut tmp = [0; 5];
+        for i in 0..=5 {
+            for j in i..=5 {
+                for k in 0..=6 {
+                    // 0 <= i <= j <= 5; 0 <= k <= 6
+                    // Check that if we start with a buffer
+                    // of length j at offset i and ask for
+                    // Next(k), we get the right bytes.
+                    let mut buf = new_buffer(b[..j].to_vec());
+                    let n = buf.read(&mut tmp[..i]).unwrap();
+                    assert_eq!(n, i);
+                    let bb = buf.next(k).to_vec();
+                    let want = k.min(j - i);
+                    assert_eq!(bb.len(), want);
+                    for (l, v) in bb.iter().enumerate() {
+                        assert_eq!(*v as usize, l + i);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_bytes() {
+        let tests: &[(&str, u8, &[&str])] = &[
+            ("", 0, &[]),
+            ("a\x00", 0, &["a\x00"]),
+            ("abbbaaaba", b'b', &["ab", "b", "b", "aaab", "a"]),
+            ("hello\x01world", 1, &["hello\x01", "world"]),
+            ("foo\nbar", 0, &["foo\nbar"]),
+            (
+                "alpha\nbeta\ngamma\n",
+                b'\n',
+                &["alpha\n", "beta\n", "gamma\n"],
+            ),
+            ("alpha\nbeta\ngamma", b'\n', &["alpha\n", "beta\n", "gamma"]),
+        ];
+        for (buffer, delim, expected) in tests {
+            let mut buf = new_buffer_string(buffer);
+            let mut got = Vec::new();
+            loop {
+                match buf.read_bytes(*delim) {
+                    Ok(b) => {
+                        assert_eq!(b.last(), Some(delim));
+                        got.push(String::from_utf8(b).unwrap());
+                    }
+                    Err((b, e)) => {
+                        assert!(e.is_eof());
+                        if !b.is_empty() {
+                            got.push(String::from_utf8(b).unwrap());
+                        }
+                        break;
+                    }
+                }
+            }
+            assert_eq!(&got, expected, "{buffer:?}");
+
+            let mut buf = new_buffer_string(buffer);
+            let mut got = Vec::new();
+            let rest = loop {
+                match buf.read_string(*delim) {
+                    Ok(s) => got.push(s),
+                    Err((s, e)) => {
+                        assert!(e.is_eof());
+                        break s;
+                    }
+                }
+            };
+            if !rest.is_empty() {
+                got.push(rest);
+            }
+            assert_eq!(&got, expected, "{buffer:?}");
+        }
+    }
+
+    #[test]
+    fn test_unread_byte() {
+        let mut b = Buffer::new();
+
+        // check at EOF
+        assert!(b.unread_byte().is_err());
+        assert!(b.read_byte().unwrap_err().is_eof());
+        assert!(b.unread_byte().is_err());
+
+        b.write_str("abcdefghijklmnopqrstuvwxyz");
+
+        // after unsuccessful read
+        assert_eq!(b.read(&mut []).unwrap(), 0);
+        assert!(b.unread_byte().is_err());
+
+        // after successful read
+        b.read_bytes(b'm').unwrap();
+        b.unread_byte().unwrap();
+        assert_eq!(b.read_byte().unwrap(), b'm');
+    }
+
+    #[test]
+    #[should_panic(expected = "truncation out of range")]
+    fn test_truncate_out_of_range() {
+        let mut b = new_buffer_string("abc");
+        b.truncate(4);
+    }
+
+    #[test]
+    fn test_io_traits() {
+        let mut b = Buffer::new();
+        write!(b, "{}-{:?}", 1, "two").unwrap();
+        b.flush().unwrap();
+        let mut s = String::new();
+        b.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "1-\"two\"");
+        assert!(b.is_empty());
+
+        let mut b = new_buffer_string("one\ntwo\n");
+        let lines: Vec<String> = std::io::BufRead::lines(&mut b)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, ["one", "two"]);
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{
+    io::{ByteReader, ByteScanner, Error, ReaderAt, RuneReader, RuneScanner, WriterTo},
+    unicode::utf8,
+};
+use std::io::SeekFrom;
+
+#[inline]
+fn invalid_input(msg: &'static str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, msg)
+}
+
+/// Implements the [`std::io::Read`], [`ReaderAt`], [`ByteReader`], [`ByteScanner`],
+/// [`RuneReader`], [`RuneScanner`], [`std::io::Seek`], and [`WriterTo`] traits by reading
+/// from a byte slice.
+#[derive(Debug, Clone, Copy)]
+pub struct Reader<'a> {
+    s: &'a [u8],
+    /// current reading index
+    i: u64,
+    /// index of previous rune; or `None`
+    prev_rune: Option<u64>,
+}
+
+/// Returns a new [`Reader`] reading from `s`.
+#[inline]
+pub const fn new_reader(s: &[u8]) -> Reader<'_> {
+    Reader::new(s)
+}
+
+impl<'a> Reader<'a> {
+    /// Returns a new [`Reader`] reading from `s`.
+    #[inline]
+    pub const fn new(s: &'a [u8]) -> Self {
+        Self {
+            s,
+            i: 0,
+            prev_rune: None,
+        }
+    }
+
+    /// Returns the number of bytes of the unread portion of the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        (self.s.len() as u64).saturating_sub(self.i) as usize
+    }
+
+    /// Reports whether the unread portion of the slice is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the original length of the underlying byte slice.
+    /// `size` is the number of bytes available for reading via [`ReaderAt::read_at`].
+    /// The returned value is always the same and is not affected by calls
+    /// to any other method.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.s.len() as u64
+    }
+
+    /// Resets the [`Reader`] to be reading from `s`.
+    #[inline]
+    pub fn reset(&mut self, s: &'a [u8]) {
+        *self = Self::new(s);
+    }
+
+    #[inline]
+    fn remaining(&self) -> &'a [u8] {
+        &self.s[(self.i as usize).min(self.s.len())..]
+    }
+}
+
+impl std::io::Read for Reader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.is_empty() {
+            return Ok(0);
+        }
+        self.prev_rune = None;
+        let n = crate::copy(self.remaining(), buf);
+        self.i += n as u64;
+        Ok(n)
+    }
+}
+
+impl std::io::BufRead for Reader<'_> {
+    #[inline]
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.prev_rune = None;
+        self.i += amt.min(self.len()) as u64;
+    }
+}
+
+impl ReaderAt for Reader<'_> {
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        if off >= self.size() {
+            return Ok(0);
+        }
+        Ok(crate::copy(&self.s[off as usize..], buf))
+    }
+}
+
+impl ByteReader for Reader<'_> {
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        self.prev_rune = None;
+        let b = *self.remaining().first().ok_or(Error::Eof)?;
+        self.i += 1;
+        Ok(b)
+    }
+}
+
+impl ByteScanner for Reader<'_> {
+    fn unread_byte(&mut self) -> Result<(), Error> {
+        if self.i == 0 {
+            return Err(Error::Io(invalid_input(
+                "bytes.Reader.UnreadByte: at beginning of slice",
+            )));
+        }
+        self.prev_rune = None;
+        self.i -= 1;
+        Ok(())
+    }
+}
+
+impl RuneReader for Reader<'_> {
+    fn read_rune(&mut self) -> Result<(char, usize), Error> {
+        let rest = self.remaining();
+        if rest.is_empty() {
+            self.prev_rune = None;
+            return Err(Error::Eof);
+        }
+        self.prev_rune = Some(self.i);
+        let (ch, size) = utf8::decode_rune(rest);
+        self.i += size as u64;
+        Ok((ch, size))
+    }
+}
+
+impl RuneScanner for Reader<'_> {
+    fn unread_rune(&mut self) -> Result<(), Error> {
+        if self.i == 0 {
+            return Err(Error::Io(invalid_input(
+                "bytes.Reader.UnreadRune: at beginning of slice",
+            )));
+        }
+        match self.prev_rune.take() {
+            Some(prev) => {
+                self.i = prev;
+                Ok(())
+            }
+            None => Err(Error::Io(invalid_input(
+                "bytes.Reader.UnreadRune: previous operation was not ReadRune",
+            ))),
+        }
+    }
+}
+
+impl std::io::Seek for Reader<'_> {
+    /// Implements the [`std::io::Seek`] trait. Seeking past the end of the slice is allowed,
+    /// subsequent reads return EOF.
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.prev_rune = None;
+        let abs = match pos {
+            SeekFrom::Start(off) => Some(off),
+            SeekFrom::Current(off) => self.i.checked_add_signed(off),
+            SeekFrom::End(off) => self.size().checked_add_signed(off),
+        };
+        match abs {
+            Some(abs) => {
+                self.i = abs;
+                Ok(abs)
+            }
+            None => Err(invalid_input("bytes.Reader.Seek: negative position")),
+        }
+    }
+}
+
+impl WriterTo for Reader<'_> {
+    fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+        self.prev_rune = None;
+        let s = self.remaining();
+        if s.is_empty() {
+            return Ok(0);
+        }
+        let m = w.write(s)?;
+        if m > s.len() {
+            panic!("bytes.Reader.WriteTo: invalid Write count");
+        }
+        self.i += m as u64;
+        if m != s.len() {
+            return Err(Error::ShortWrite.into());
+        }
+        Ok(m as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Seek};
+
+    #[test]
+    fn test_reader() {
+        let mut r = new_reader(b"0123456789");
+        let tests: &[(SeekFrom, u64, usize, &str)] = &[
+            (SeekFrom::Start(0), 0, 10, "0123456789"),
+            (SeekFrom::Start(1), 1, 1, "1"),
+            (SeekFrom::Current(1), 3, 2, "34"),
+            (SeekFrom::Start(1), 1, 1, "1"),
+            (SeekFrom::Current(1), 3, 2, "34"),
+            (SeekFrom::Current(-1), 4, 1, "4"),
+            (SeekFrom::Start(0), 0, 10, "0123456789"),
+            (SeekFrom::End(-3), 7, 10, "789"),
+            (SeekFrom::Start(20), 20, 10, ""),
+        ];
+        for (i, (pos, seek, n, want)) in tests.iter().enumerate() {
+            assert_eq!(r.seek(*pos).unwrap(), *seek, "{i}");
+            let mut buf = vec![0; *n];
+            let m = r.read(&mut buf).unwrap();
+            assert_eq!(&buf[..m], want.as_bytes(), "{i}");
+        }
+        assert!(r.seek(SeekFrom::Current(-100)).is_err());
+    }
+
+    #[test]
+    fn test_read_after_big_seek() {
+        let mut r = new_reader(b"0123456789");
+        r.seek(SeekFrom::Start((1 << 31) + 5)).unwrap();
+        let mut buf = [0; 10];
+        assert_eq!(r.read(&mut buf).unwrap(), 0);
+        assert_eq!(r.len(), 0);
+    }
+
+    #[test]
+    fn test_reader_at() {
+        let r = new_reader(b"0123456789");
+        let tests: &[(u64, usize, &str)] = &[
+            (0, 10, "0123456789"),
+            (1, 10, "123456789"),
+            (1, 9, "123456789"),
+            (11, 10, ""),
+            (0, 0, ""),
+        ];
+        for (off, n, want) in tests {
+            let mut buf = vec![0; *n];
+            let m = r.read_at(&mut buf, *off).unwrap();
+            assert_eq!(&buf[..m], want.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_bytes_and_runes() {
+        let mut r = new_reader("a☺".as_bytes());
+        assert!(r.unread_byte().is_err());
+        assert!(r.unread_rune().is_err());
+        assert_eq!(r.read_byte().unwrap(), b'a');
+        assert!(r.unread_rune().is_err());
+        r.unread_byte().unwrap();
+        assert_eq!(r.read_rune().unwrap(), ('a', 1));
+        assert_eq!(r.read_rune().unwrap(), ('☺', 3));
+        assert!(r.read_rune().unwrap_err().is_eof());
+        // a failed read_rune invalidates unread_rune
+        assert!(r.unread_rune().is_err());
+        r.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(r.read_rune().unwrap(), ('☺', 3));
+        r.unread_rune().unwrap();
+        assert_eq!(r.len(), 3);
+        assert!(r.unread_rune().is_err());
+        assert_eq!(r.size(), 4);
+
+        // invalid encodings decode as U+FFFD, one byte at a time
+        let mut r = new_reader(b"\xe4\xb8a");
+        assert_eq!(r.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert_eq!(r.read_rune().unwrap(), ('\u{FFFD}', 1));
+        assert_eq!(r.read_rune().unwrap(), ('a', 1));
+    }
+
+    #[test]
+    fn test_reader_write_to() {
+        let s = "0<10>1<20>2<30>3<40>4<50>5<60>6<70>7<80>8<90>9<100>";
+        for i in (0..s.len()).step_by(7) {
+            let mut r = new_reader(&s.as_bytes()[i..]);
+            let mut b = Vec::new();
+            let n = r.write_to(&mut b).unwrap();
+            assert_eq!(n as usize, s.len() - i);
+            assert_eq!(b, &s.as_bytes()[i..]);
+            assert_eq!(r.len(), 0);
+        }
+    }
+
+    #[test]
+    fn test_reader_len_size() {
+        let mut r = new_reader(b"abc");
+        let mut buf = [0; 1];
+        r.read_exact(&mut buf).unwrap();
+        assert_eq!(r.len(), 2);
+        assert_eq!(r.size(), 3);
+        r.reset(b"hello");
+        assert_eq!((r.len(), r.size()), (5, 5));
+    }
+}
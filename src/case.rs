@@ -0,0 +1,73 @@
+//! Single code point case mappings with Go's `unicode` semantics, shared by
+//! the `bytes` and `strings` modules.
+
+/// Maps `r` to upper case. Unlike [`char::to_uppercase`], a code point
+/// whose upper case form is longer than one code point maps to itself,
+/// as in Go's `unicode.ToUpper`.
+#[inline]
+pub(crate) fn to_upper(r: char) -> char {
+    if r.is_ascii() {
+        return r.to_ascii_uppercase();
+    }
+    single(r.to_uppercase()).unwrap_or(r)
+}
+
+/// Maps `r` to lower case, see [`to_upper`].
+#[inline]
+pub(crate) fn to_lower(r: char) -> char {
+    if r.is_ascii() {
+        return r.to_ascii_lowercase();
+    }
+    match r {
+        // The simple lower case mapping drops the combining dot.
+        '\u{0130}' => 'i',
+        _ => single(r.to_lowercase()).unwrap_or(r),
+    }
+}
+
+/// Maps `r` to title case. Title case only differs from upper case
+/// for the Latin digraphs.
+#[inline]
+pub(crate) fn to_title(r: char) -> char {
+    match r {
+        '\u{01C4}'..='\u{01C6}' => '\u{01C5}',
+        '\u{01C7}'..='\u{01C9}' => '\u{01C8}',
+        '\u{01CA}'..='\u{01CC}' => '\u{01CB}',
+        '\u{01F1}'..='\u{01F3}' => '\u{01F2}',
+        _ => to_upper(r),
+    }
+}
+
+/// Reports whether `r` and `t` are equal under simple Unicode case-folding.
+#[inline]
+pub(crate) fn equal_fold(r: char, t: char) -> bool {
+    if r == t {
+        return true;
+    }
+    if r.is_ascii() && t.is_ascii() {
+        return r.eq_ignore_ascii_case(&t);
+    }
+    to_lower(r) == to_lower(t) || to_upper(r) == to_upper(t)
+}
+
+/// Reports whether the rune could mark a word boundary, as used by `title`.
+pub(crate) fn is_separator(r: char) -> bool {
+    // ASCII alphanumerics and underscore are not separators
+    if r.is_ascii() {
+        return !(r.is_ascii_alphanumeric() || r == '_');
+    }
+    // Letters and digits are not separators
+    if r.is_alphabetic() || r.is_numeric() {
+        return false;
+    }
+    // Otherwise, all we can do for now is treat spaces as separators.
+    r.is_whitespace()
+}
+
+#[inline]
+fn single(mut it: impl Iterator<Item = char>) -> Option<char> {
+    match (it.next(), it.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
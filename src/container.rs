@@ -0,0 +1,10 @@
+mod arena;
+
+/// Go `container/heap` library
+pub mod heap;
+
+/// Go `container/list` library
+pub mod list;
+
+/// Go `container/ring` library
+pub mod ring;
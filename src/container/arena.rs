@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Identifies a value in one particular [`Arena`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(super) struct Key {
+    arena: usize,
+    index: usize,
+    generation: u32,
+}
+
+impl Key {
+    #[inline]
+    pub(super) const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// A generational slab: removed slots are recycled, and bumping the slot's
+/// generation on removal makes stale keys to it fail lookups instead of
+/// aliasing whatever is stored there next. Every arena has its own id, so
+/// keys minted by another arena never match either.
+#[derive(Debug)]
+pub(super) struct Arena<T> {
+    id: usize,
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+impl<T> Arena<T> {
+    #[inline]
+    pub(super) fn with_capacity(cap: usize) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            slots: Vec::with_capacity(cap),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Stores `value` and returns its key.
+    pub(super) fn insert(&mut self, value: T) -> Key {
+        self.len += 1;
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index].value = Some(value);
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.key(index)
+    }
+
+    #[inline]
+    pub(super) fn contains(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    #[inline]
+    pub(super) fn get(&self, key: Key) -> Option<&T> {
+        if key.arena != self.id {
+            return None;
+        }
+        match self.slots.get(key.index) {
+            Some(slot) if slot.generation == key.generation => slot.value.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub(super) fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        if key.arena != self.id {
+            return None;
+        }
+        match self.slots.get_mut(key.index) {
+            Some(slot) if slot.generation == key.generation => slot.value.as_mut(),
+            _ => None,
+        }
+    }
+
+    pub(super) fn remove(&mut self, key: Key) -> Option<T> {
+        if !self.contains(key) {
+            return None;
+        }
+        let slot = &mut self.slots[key.index];
+        let value = slot.value.take();
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        self.len -= 1;
+        value
+    }
+
+    /// Removes every value, invalidating all outstanding keys.
+    pub(super) fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.value.take().is_some() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index);
+            }
+        }
+        self.len = 0;
+    }
+
+    /// Returns the live value at `index`, ignoring generations.
+    ///
+    /// Only for indexes reached through the links the containers maintain themselves.
+    #[inline]
+    pub(super) fn node(&self, index: usize) -> &T {
+        self.slots[index]
+            .value
+            .as_ref()
+            .expect("container: dangling link")
+    }
+
+    /// Like [`Arena::node`], but mutable.
+    #[inline]
+    pub(super) fn node_mut(&mut self, index: usize) -> &mut T {
+        self.slots[index]
+            .value
+            .as_mut()
+            .expect("container: dangling link")
+    }
+
+    /// Returns the key of the live value at `index`.
+    #[inline]
+    pub(super) fn key(&self, index: usize) -> Key {
+        Key {
+            arena: self.id,
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Heap operations for any type that implements [`Heap`]. A heap is a tree
+//! with the property that each node is the minimum-valued node in its subtree.
+//!
+//! The minimum element in the tree is the root, at index 0.
+//!
+//! A heap is a common way to implement a priority queue. To build a priority
+//! queue, implement [`Heap`] with the (negative) priority as the ordering for
+//! the `less` method, so [`push`] adds items while [`pop`] removes the
+//! highest-priority item from the queue. Unlike [`BinaryHeap`], an item whose
+//! priority changed in place can be re-established with [`fix`].
+//!
+//! [`Heap`]: crate::container::heap::Heap
+//! [`push`]: crate::container::heap::push
+//! [`pop`]: crate::container::heap::pop
+//! [`fix`]: crate::container::heap::fix
+//! [`BinaryHeap`]: alloc::collections::BinaryHeap
+use crate::sort::Sort;
+
+/// Golang `heap.Interface` in Rust.
+///
+/// Any type that implements it may be used as a min-heap with the
+/// following invariants (established after [`init`] has been called or if
+/// the data is empty or sorted):
+///
+/// `!h.less(j, i)` for `0 <= i < h.len()` and `2*i+1 <= j <= 2*i+2` and `j < h.len()`
+///
+/// Note that [`Heap::push`] and [`Heap::pop`] are for package heap's
+/// implementation to call. To add and remove things from the heap, use
+/// [`push`] and [`pop`].
+pub trait Heap: Sort {
+    /// Item
+    type Item;
+
+    /// Adds `x` as element `len()`.
+    fn push(&mut self, x: Self::Item);
+
+    /// Removes and returns element `len() - 1`.
+    fn pop(&mut self) -> Option<Self::Item>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PartialOrd + core::fmt::Debug> Heap for ::alloc::vec::Vec<T> {
+    type Item = T;
+
+    fn push(&mut self, x: T) {
+        ::alloc::vec::Vec::push(self, x);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        ::alloc::vec::Vec::pop(self)
+    }
+}
+
+/// Establishes the heap invariants required by the other routines in this package.
+/// `init` is idempotent with respect to the heap invariants
+/// and may be called whenever the heap invariants may have been invalidated.
+/// The complexity is O(n) where n = h.len().
+#[inline]
+pub fn init(h: &mut impl Heap) {
+    // heapify
+    let n = h.len();
+    for i in (0..n / 2).rev() {
+        down(h, i, n);
+    }
+}
+
+/// Pushes the element `x` onto the heap.
+/// The complexity is O(log n) where n = h.len().
+#[inline]
+pub fn push<H: Heap>(h: &mut H, x: H::Item) {
+    h.push(x);
+    up(h, h.len() - 1);
+}
+
+/// Removes and returns the minimum element (according to `less`) from the heap,
+/// or `None` if the heap is empty.
+/// The complexity is O(log n) where n = h.len().
+/// `pop` is equivalent to [`remove`]`(h, 0)`.
+#[inline]
+pub fn pop<H: Heap>(h: &mut H) -> Option<H::Item> {
+    let n = h.len().checked_sub(1)?;
+    h.swap(0, n);
+    down(h, 0, n);
+    h.pop()
+}
+
+/// Removes and returns the element at index `i` from the heap,
+/// or `None` if `i` is out of bounds.
+/// The complexity is O(log n) where n = h.len().
+#[inline]
+pub fn remove<H: Heap>(h: &mut H, i: usize) -> Option<H::Item> {
+    let n = h.len().checked_sub(1)?;
+    if i > n {
+        return None;
+    }
+    if n != i {
+        h.swap(i, n);
+        if !down(h, i, n) {
+            up(h, i);
+        }
+    }
+    h.pop()
+}
+
+/// Re-establishes the heap ordering after the element at index `i` has changed its value.
+/// Changing the value of the element at index `i` and then calling `fix` is equivalent to,
+/// but less expensive than, calling [`remove`]`(h, i)` followed by a [`push`] of the new value.
+/// The complexity is O(log n) where n = h.len().
+///
+/// # Panics
+///
+/// Panics if `i` is out of bounds.
+#[inline]
+pub fn fix(h: &mut impl Heap, i: usize) {
+    assert!(i < h.len(), "heap: index out of range");
+    if !down(h, i, h.len()) {
+        up(h, i);
+    }
+}
+
+fn up(h: &mut impl Heap, mut j: usize) {
+    while j > 0 {
+        let i = (j - 1) / 2; // parent
+        if !h.less(j, i) {
+            break;
+        }
+        h.swap(i, j);
+        j = i;
+    }
+}
+
+fn down(h: &mut impl Heap, i0: usize, n: usize) -> bool {
+    let mut i = i0;
+    loop {
+        let j1 = 2 * i + 1;
+        if j1 >= n {
+            break;
+        }
+        let mut j = j1; // left child
+        let j2 = j1 + 1;
+        if j2 < n && h.less(j2, j1) {
+            j = j2; // = 2*i + 2  // right child
+        }
+        if !h.less(j, i) {
+            break;
+        }
+        h.swap(i, j);
+        i = j;
+    }
+    i > i0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn verify<T: PartialOrd + core::fmt::Debug>(h: &Vec<T>, i: usize) {
+        let n = h.len();
+        let j1 = 2 * i + 1;
+        let j2 = 2 * i + 2;
+        if j1 < n {
+            assert!(
+                !h.less(j1, i),
+                "heap invariant invalidated [{i}] = {:?} > [{j1}] = {:?}",
+                h[i],
+                h[j1]
+            );
+            verify(h, j1);
+        }
+        if j2 < n {
+            assert!(
+                !h.less(j2, i),
+                "heap invariant invalidated [{i}] = {:?} > [{j2}] = {:?}",
+                h[i],
+                h[j2]
+            );
+            verify(h, j2);
+        }
+    }
+
+    #[test]
+    fn test_init0() {
+        let mut h = vec![0; 20];
+        init(&mut h);
+        verify(&h, 0);
+
+        for i in 1..=h.len() {
+            let x = pop(&mut h).unwrap();
+            verify(&h, 0);
+            assert_eq!(x, 0, "{i}th pop got {x}; want 0");
+        }
+        assert_eq!(pop(&mut h), None);
+    }
+
+    #[test]
+    fn test_init1() {
+        let mut h = (1..=20).rev().collect::<Vec<i32>>();
+        init(&mut h);
+        verify(&h, 0);
+
+        for i in 1..=h.len() as i32 {
+            let x = pop(&mut h).unwrap();
+            verify(&h, 0);
+            assert_eq!(x, i, "{i}th pop got {x}; want {i}");
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut h = Vec::new();
+        verify(&h, 0);
+
+        for i in (11..=20).rev() {
+            h.push(i);
+        }
+        init(&mut h);
+        verify(&h, 0);
+
+        for i in (1..=10).rev() {
+            push(&mut h, i);
+            verify(&h, 0);
+        }
+
+        for i in 1..=20 {
+            let x = pop(&mut h).unwrap();
+            if i < 20 {
+                push(&mut h, 20 + i);
+            }
+            verify(&h, 0);
+            assert_eq!(x, i, "{i}th pop got {x}; want {i}");
+        }
+    }
+
+    #[test]
+    fn test_remove0() {
+        let mut h = Vec::new();
+        for i in 0..10 {
+            push(&mut h, i);
+        }
+        verify(&h, 0);
+
+        while !h.is_empty() {
+            let i = h.len() - 1;
+            let x = remove(&mut h, i).unwrap();
+            assert_eq!(x, i, "Remove({i}) got {x}; want {i}");
+            verify(&h, 0);
+        }
+    }
+
+    #[test]
+    fn test_remove1() {
+        let mut h = Vec::new();
+        for i in 0..10 {
+            push(&mut h, i);
+        }
+        verify(&h, 0);
+
+        for i in 0..10 {
+            let x = remove(&mut h, 0).unwrap();
+            assert_eq!(x, i, "Remove(0) got {x}; want {i}");
+            verify(&h, 0);
+        }
+        assert_eq!(remove(&mut h, 0), None);
+    }
+
+    #[test]
+    fn test_remove2() {
+        const N: usize = 10;
+
+        let mut h = Vec::new();
+        for i in 0..N {
+            push(&mut h, i);
+        }
+        verify(&h, 0);
+
+        let mut m = [false; N];
+        while !h.is_empty() {
+            let i = (h.len() - 1) / 2;
+            let x = remove(&mut h, i).unwrap();
+            m[x] = true;
+            verify(&h, 0);
+        }
+        assert!(m.iter().all(|b| *b), "Remove did not return every element");
+        assert_eq!(remove(&mut h, 3), None);
+    }
+
+    #[test]
+    fn test_fix() {
+        let mut h = Vec::new();
+        verify(&h, 0);
+
+        for i in (1..=200).rev() {
+            push(&mut h, i);
+        }
+        verify(&h, 0);
+
+        assert_eq!(h[0], 1, "Expected head to be 1, was {}", h[0]);
+        h[0] = 210;
+        fix(&mut h, 0);
+        verify(&h, 0);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let elem = rng.gen_range(0..h.len());
+            if rng.gen_bool(0.5) {
+                h[elem] *= 2;
+            } else {
+                h[elem] /= 2;
+            }
+            fix(&mut h, elem);
+            verify(&h, 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "heap: index out of range")]
+    fn test_fix_out_of_range() {
+        let mut h = vec![1, 2, 3];
+        fix(&mut h, 3);
+    }
+
+    // An Item is something we manage in a priority queue.
+    #[derive(Debug)]
+    struct Item {
+        value: &'static str, // The value of the item; arbitrary.
+        priority: i32,       // The priority of the item in the queue.
+        // The index is needed by update and is maintained by the heap methods.
+        index: usize, // The index of the item in the heap.
+    }
+
+    // A PriorityQueue implements Heap and holds Items.
+    #[derive(Default)]
+    struct PriorityQueue(Vec<Item>);
+
+    impl Sort for PriorityQueue {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+
+        fn less(&self, i: usize, j: usize) -> bool {
+            // We want pop to give us the highest, not lowest, priority so we use greater than here.
+            self.0[i].priority > self.0[j].priority
+        }
+
+        fn swap(&mut self, i: usize, j: usize) {
+            self.0.swap(i, j);
+            self.0[i].index = i;
+            self.0[j].index = j;
+        }
+    }
+
+    impl Heap for PriorityQueue {
+        type Item = Item;
+
+        fn push(&mut self, mut x: Item) {
+            x.index = self.0.len();
+            self.0.push(x);
+        }
+
+        fn pop(&mut self) -> Option<Item> {
+            self.0.pop()
+        }
+    }
+
+    impl PriorityQueue {
+        // update modifies the priority of an Item in the queue.
+        fn update(&mut self, index: usize, priority: i32) {
+            self.0[index].priority = priority;
+            fix(self, index);
+        }
+    }
+
+    #[test]
+    fn test_priority_queue() {
+        let items = [("banana", 3), ("apple", 2), ("pear", 4)];
+
+        // Create a priority queue, put the items in it, and
+        // establish the priority queue (heap) invariants.
+        let mut pq = PriorityQueue(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, (value, priority))| Item {
+                    value,
+                    priority: *priority,
+                    index,
+                })
+                .collect(),
+        );
+        init(&mut pq);
+
+        // Insert a new item and then modify its priority.
+        push(
+            &mut pq,
+            Item {
+                value: "orange",
+                priority: 1,
+                index: 0,
+            },
+        );
+        let index =
+            pq.0.iter()
+                .find(|item| item.value == "orange")
+                .unwrap()
+                .index;
+        pq.update(index, 5);
+
+        // Take the items out; they arrive in decreasing priority order.
+        let mut got = Vec::new();
+        while let Some(item) = pop(&mut pq) {
+            got.push((item.priority, item.value));
+        }
+        assert_eq!(
+            got,
+            [(5, "orange"), (4, "pear"), (3, "banana"), (2, "apple")]
+        );
+    }
+}
@@ -0,0 +1,744 @@
+//! A doubly linked list.
+//!
+//! The nodes live in a slab owned by the [`List`], so handing out an
+//! [`Element`] does not borrow the list: handles stay valid across
+//! insertions and moves, and become stale (every lookup with them fails, like
+//! an element whose `list` is `nil` in Go) once their element is removed.
+//!
+//! To iterate over a list (where `l` is a `List<T>`):
+//!
+//! ```
+//! # use crabmole::container::list::List;
+//! # let mut l = List::new();
+//! # l.push_back(1);
+//! let mut e = l.front();
+//! while let Some(elem) = e {
+//!     // do something with l.get(elem)
+//!     e = l.next(elem);
+//! }
+//! ```
+//!
+//! [`List`]: crate::container::list::List
+//! [`Element`]: crate::container::list::Element
+use super::arena::{Arena, Key};
+
+/// A handle to an element of a [`List`].
+///
+/// It is only meaningful to the list that returned it, and only until the
+/// element is removed; a [`List`] ignores handles it does not hold.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Element(Key);
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+/// Represents a doubly linked list.
+pub struct List<T> {
+    nodes: Arena<Node<T>>,
+    front: Option<usize>,
+    back: Option<usize>,
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> List<T> {
+    /// Returns an initialized list.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Returns an initialized list with room for `cap` elements.
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        Self {
+            nodes: Arena::with_capacity(cap),
+            front: None,
+            back: None,
+        }
+    }
+
+    /// Clears list `l`, invalidating every [`Element`] it handed out.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.front = None;
+        self.back = None;
+    }
+
+    /// Returns the number of elements of list `l`.
+    /// The complexity is O(1).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Reports whether the list has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 0
+    }
+
+    /// Returns the first element of list `l` or `None` if the list is empty.
+    #[inline]
+    pub fn front(&self) -> Option<Element> {
+        self.front.map(|i| self.element(i))
+    }
+
+    /// Returns the last element of list `l` or `None` if the list is empty.
+    #[inline]
+    pub fn back(&self) -> Option<Element> {
+        self.back.map(|i| self.element(i))
+    }
+
+    /// Returns the next list element or `None`.
+    #[inline]
+    pub fn next(&self, e: Element) -> Option<Element> {
+        self.nodes
+            .get(e.0)
+            .and_then(|n| n.next)
+            .map(|i| self.element(i))
+    }
+
+    /// Returns the previous list element or `None`.
+    #[inline]
+    pub fn prev(&self, e: Element) -> Option<Element> {
+        self.nodes
+            .get(e.0)
+            .and_then(|n| n.prev)
+            .map(|i| self.element(i))
+    }
+
+    /// Reports whether `e` is an element of list `l`.
+    #[inline]
+    pub fn contains(&self, e: Element) -> bool {
+        self.nodes.contains(e.0)
+    }
+
+    /// Returns the value stored with `e`, or `None` if `e` is not an element of list `l`.
+    #[inline]
+    pub fn get(&self, e: Element) -> Option<&T> {
+        self.nodes.get(e.0).map(|n| &n.value)
+    }
+
+    /// Returns the value stored with `e` mutably, or `None` if `e` is not an element of list `l`.
+    #[inline]
+    pub fn get_mut(&mut self, e: Element) -> Option<&mut T> {
+        self.nodes.get_mut(e.0).map(|n| &mut n.value)
+    }
+
+    /// Removes `e` from `l` if `e` is an element of list `l`.
+    /// It returns the element value, or `None` if `e` is not an element of list `l`.
+    #[inline]
+    pub fn remove(&mut self, e: Element) -> Option<T> {
+        if !self.contains(e) {
+            return None;
+        }
+        self.unlink(e.0.index());
+        self.nodes.remove(e.0).map(|n| n.value)
+    }
+
+    /// Inserts a new element `e` with value `v` at the front of list `l` and returns `e`.
+    #[inline]
+    pub fn push_front(&mut self, v: T) -> Element {
+        self.insert_value(v, None)
+    }
+
+    /// Inserts a new element `e` with value `v` at the back of list `l` and returns `e`.
+    #[inline]
+    pub fn push_back(&mut self, v: T) -> Element {
+        self.insert_value(v, self.back)
+    }
+
+    /// Inserts a new element `e` with value `v` immediately before `mark` and returns `e`.
+    /// If `mark` is not an element of `l`, the list is not modified and `v` is dropped.
+    #[inline]
+    pub fn insert_before(&mut self, v: T, mark: Element) -> Option<Element> {
+        let at = self.nodes.get(mark.0)?.prev;
+        Some(self.insert_value(v, at))
+    }
+
+    /// Inserts a new element `e` with value `v` immediately after `mark` and returns `e`.
+    /// If `mark` is not an element of `l`, the list is not modified and `v` is dropped.
+    #[inline]
+    pub fn insert_after(&mut self, v: T, mark: Element) -> Option<Element> {
+        if !self.contains(mark) {
+            return None;
+        }
+        Some(self.insert_value(v, Some(mark.0.index())))
+    }
+
+    /// Moves element `e` to the front of list `l`.
+    /// If `e` is not an element of `l`, the list is not modified.
+    #[inline]
+    pub fn move_to_front(&mut self, e: Element) {
+        if !self.contains(e) || self.front == Some(e.0.index()) {
+            return;
+        }
+        self.move_after_index(e.0.index(), None);
+    }
+
+    /// Moves element `e` to the back of list `l`.
+    /// If `e` is not an element of `l`, the list is not modified.
+    #[inline]
+    pub fn move_to_back(&mut self, e: Element) {
+        if !self.contains(e) || self.back == Some(e.0.index()) {
+            return;
+        }
+        self.unlink(e.0.index());
+        self.link(e.0.index(), self.back);
+    }
+
+    /// Moves element `e` to its new position before `mark`.
+    /// If `e` or `mark` is not an element of `l`, or `e == mark`, the list is not modified.
+    #[inline]
+    pub fn move_before(&mut self, e: Element, mark: Element) {
+        if !self.contains(e) || e == mark || !self.contains(mark) {
+            return;
+        }
+        let (e, mark) = (e.0.index(), mark.0.index());
+        if self.nodes.node(mark).prev == Some(e) {
+            return;
+        }
+        self.unlink(e);
+        self.link(e, self.nodes.node(mark).prev);
+    }
+
+    /// Moves element `e` to its new position after `mark`.
+    /// If `e` or `mark` is not an element of `l`, or `e == mark`, the list is not modified.
+    #[inline]
+    pub fn move_after(&mut self, e: Element, mark: Element) {
+        if !self.contains(e) || e == mark || !self.contains(mark) {
+            return;
+        }
+        self.move_after_index(e.0.index(), Some(mark.0.index()));
+    }
+
+    /// Returns an iterator over the values of list `l`, from front to back.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.front,
+            back: self.back,
+            len: self.len(),
+        }
+    }
+
+    #[inline]
+    fn element(&self, index: usize) -> Element {
+        Element(self.nodes.key(index))
+    }
+
+    /// Stores `v` and links it after `at`, or at the front if `at` is `None`.
+    #[inline]
+    fn insert_value(&mut self, v: T, at: Option<usize>) -> Element {
+        let key = self.nodes.insert(Node {
+            value: v,
+            prev: None,
+            next: None,
+        });
+        self.link(key.index(), at);
+        Element(key)
+    }
+
+    #[inline]
+    fn move_after_index(&mut self, e: usize, at: Option<usize>) {
+        if at == Some(e) {
+            return;
+        }
+        self.unlink(e);
+        self.link(e, at);
+    }
+
+    /// Links the detached node `index` after `at`, or at the front if `at` is `None`.
+    fn link(&mut self, index: usize, at: Option<usize>) {
+        let next = match at {
+            Some(at) => self.nodes.node_mut(at).next.replace(index),
+            None => self.front.replace(index),
+        };
+        match next {
+            Some(next) => self.nodes.node_mut(next).prev = Some(index),
+            None => self.back = Some(index),
+        }
+        let node = self.nodes.node_mut(index);
+        node.prev = at;
+        node.next = next;
+    }
+
+    /// Detaches node `index` from its neighbours.
+    fn unlink(&mut self, index: usize) {
+        let node = self.nodes.node_mut(index);
+        let (prev, next) = (node.prev.take(), node.next.take());
+        match prev {
+            Some(prev) => self.nodes.node_mut(prev).next = next,
+            None => self.front = next,
+        }
+        match next {
+            Some(next) => self.nodes.node_mut(next).prev = prev,
+            None => self.back = prev,
+        }
+    }
+}
+
+impl<T: Clone> List<T> {
+    /// Inserts a copy of another list at the back of list `l`.
+    #[inline]
+    pub fn push_back_list(&mut self, other: &List<T>) {
+        for v in other.iter() {
+            self.push_back(v.clone());
+        }
+    }
+
+    /// Inserts a copy of another list at the front of list `l`.
+    #[inline]
+    pub fn push_front_list(&mut self, other: &List<T>) {
+        for v in other.iter().rev() {
+            self.push_front(v.clone());
+        }
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.push_back(v);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut l = Self::new();
+        l.extend(iter);
+        l
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`List`], created by [`List::iter`].
+pub struct Iter<'a, T> {
+    list: &'a List<T>,
+    front: Option<usize>,
+    back: Option<usize>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.nodes.node(self.front?);
+        self.front = node.next;
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.nodes.node(self.back?);
+        self.back = node.prev;
+        self.len -= 1;
+        Some(&node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn check_list_len<T>(l: &List<T>, len: usize) -> bool {
+        let n = l.len();
+        assert_eq!(n, len, "l.len() = {n}, want {len}");
+        n == len
+    }
+
+    fn check_list_pointers<T>(l: &List<T>, es: &[Element]) {
+        if !check_list_len(l, es.len()) {
+            return;
+        }
+
+        // zero length lists must have no front or back
+        if es.is_empty() {
+            assert_eq!(l.front(), None);
+            assert_eq!(l.back(), None);
+            return;
+        }
+
+        // check internal and external prev/next connections
+        for (i, e) in es.iter().enumerate() {
+            let prev = if i > 0 { Some(es[i - 1]) } else { None };
+            assert_eq!(l.prev(*e), prev, "elt[{i}]: bad prev");
+
+            let next = es.get(i + 1).copied();
+            assert_eq!(l.next(*e), next, "elt[{i}]: bad next");
+        }
+
+        assert_eq!(l.front(), Some(es[0]));
+        assert_eq!(l.back(), Some(es[es.len() - 1]));
+    }
+
+    #[test]
+    fn test_list() {
+        let mut l = List::new();
+        check_list_pointers(&l, &[]);
+
+        // Single element list
+        let e = l.push_front("a");
+        check_list_pointers(&l, &[e]);
+        l.move_to_front(e);
+        check_list_pointers(&l, &[e]);
+        l.move_to_back(e);
+        check_list_pointers(&l, &[e]);
+        assert_eq!(l.remove(e), Some("a"));
+        check_list_pointers(&l, &[]);
+
+        // Bigger list
+        let e2 = l.push_front("2");
+        let e1 = l.push_front("1");
+        let e3 = l.push_back("3");
+        let e4 = l.push_back("banana");
+        check_list_pointers(&l, &[e1, e2, e3, e4]);
+
+        l.remove(e2);
+        check_list_pointers(&l, &[e1, e3, e4]);
+
+        l.move_to_front(e3); // move from middle
+        check_list_pointers(&l, &[e3, e1, e4]);
+
+        l.move_to_front(e1);
+        l.move_to_back(e3); // move from middle
+        check_list_pointers(&l, &[e1, e4, e3]);
+
+        l.move_to_front(e3); // move from back
+        check_list_pointers(&l, &[e3, e1, e4]);
+        l.move_to_front(e3); // should be no-op
+        check_list_pointers(&l, &[e3, e1, e4]);
+
+        l.move_to_back(e3); // move from front
+        check_list_pointers(&l, &[e1, e4, e3]);
+        l.move_to_back(e3); // should be no-op
+        check_list_pointers(&l, &[e1, e4, e3]);
+
+        let e2 = l.insert_before("2", e1).unwrap(); // insert before front
+        check_list_pointers(&l, &[e2, e1, e4, e3]);
+        l.remove(e2);
+        let e2 = l.insert_before("2", e4).unwrap(); // insert before middle
+        check_list_pointers(&l, &[e1, e2, e4, e3]);
+        l.remove(e2);
+        let e2 = l.insert_before("2", e3).unwrap(); // insert before back
+        check_list_pointers(&l, &[e1, e4, e2, e3]);
+        l.remove(e2);
+
+        let e2 = l.insert_after("2", e1).unwrap(); // insert after front
+        check_list_pointers(&l, &[e1, e2, e4, e3]);
+        l.remove(e2);
+        let e2 = l.insert_after("2", e4).unwrap(); // insert after middle
+        check_list_pointers(&l, &[e1, e4, e2, e3]);
+        l.remove(e2);
+        let e2 = l.insert_after("2", e3).unwrap(); // insert after back
+        check_list_pointers(&l, &[e1, e4, e3, e2]);
+        l.remove(e2);
+
+        // Check standard iteration.
+        let mut sum = 0;
+        let mut e = l.front();
+        while let Some(elem) = e {
+            if let Ok(i) = l.get(elem).unwrap().parse::<i32>() {
+                sum += i;
+            }
+            e = l.next(elem);
+        }
+        assert_eq!(sum, 4, "sum over l = {sum}, want 4");
+
+        // Clear all elements by iterating
+        let mut e = l.front();
+        while let Some(elem) = e {
+            let next = l.next(elem);
+            l.remove(elem);
+            e = next;
+        }
+        check_list_pointers(&l, &[]);
+    }
+
+    fn check_list<T: PartialEq + core::fmt::Debug>(l: &List<T>, es: &[T]) {
+        if !check_list_len(l, es.len()) {
+            return;
+        }
+        assert!(l.iter().eq(es.iter()), "list = {l:?}, want {es:?}");
+        assert!(l.iter().rev().eq(es.iter().rev()));
+    }
+
+    #[test]
+    fn test_extending() {
+        let mut l1 = List::new();
+        let mut l2 = List::new();
+
+        l1.push_back(1);
+        l1.push_back(2);
+        l1.push_back(3);
+
+        l2.push_back(4);
+        l2.push_back(5);
+
+        let mut l3 = List::new();
+        l3.push_back_list(&l1);
+        check_list(&l3, &[1, 2, 3]);
+        l3.push_back_list(&l2);
+        check_list(&l3, &[1, 2, 3, 4, 5]);
+
+        let mut l3 = List::new();
+        l3.push_front_list(&l2);
+        check_list(&l3, &[4, 5]);
+        l3.push_front_list(&l1);
+        check_list(&l3, &[1, 2, 3, 4, 5]);
+
+        check_list(&l1, &[1, 2, 3]);
+        check_list(&l2, &[4, 5]);
+
+        let mut l3 = List::new();
+        l3.push_back_list(&l1);
+        check_list(&l3, &[1, 2, 3]);
+
+        let mut l3 = List::new();
+        l3.push_front_list(&l1);
+        check_list(&l3, &[1, 2, 3]);
+
+        let mut l3 = List::new();
+        l1.push_back_list(&l3);
+        check_list(&l1, &[1, 2, 3]);
+        l1.push_front_list(&l3);
+        check_list(&l1, &[1, 2, 3]);
+        l3.extend([6, 7]);
+        check_list(&l3, &[6, 7]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut l = List::new();
+        let e1 = l.push_back(1);
+        let e2 = l.push_back(2);
+        check_list_pointers(&l, &[e1, e2]);
+        let e = l.front().unwrap();
+        assert_eq!(l.remove(e), Some(1));
+        check_list_pointers(&l, &[e2]);
+        assert_eq!(l.remove(e), None);
+        check_list_pointers(&l, &[e2]);
+    }
+
+    #[test]
+    fn test_issue4103() {
+        let mut l1 = List::new();
+        l1.push_back(1);
+        l1.push_back(2);
+
+        let mut l2 = List::new();
+        l2.push_back(3);
+        l2.push_back(4);
+
+        let e = l1.front().unwrap();
+        assert_eq!(l2.remove(e), None); // l2 should not change because e is not an element of l2
+        assert_eq!(l2.len(), 2, "l2.len() = {}, want 2", l2.len());
+
+        assert_eq!(l1.insert_before(8, e), l1.prev(e));
+        assert_eq!(l1.len(), 3, "l1.len() = {}, want 3", l1.len());
+    }
+
+    #[test]
+    fn test_issue6349() {
+        let mut l = List::new();
+        l.push_back(1);
+        l.push_back(2);
+
+        let e = l.front().unwrap();
+        l.remove(e);
+        assert_eq!(l.get(e), None, "e.value = {:?}, want None", l.get(e));
+        assert_eq!(l.next(e), None, "e.next() != None");
+        assert_eq!(l.prev(e), None, "e.prev() != None");
+
+        // A stale handle must not alias the slot reused by a new element.
+        let e3 = l.push_back(3);
+        assert_ne!(e, e3);
+        assert_eq!(l.get(e), None);
+        assert_eq!(l.get(e3), Some(&3));
+    }
+
+    #[test]
+    fn test_move() {
+        let mut l = List::new();
+        let e1 = l.push_back(1);
+        let e2 = l.push_back(2);
+        let e3 = l.push_back(3);
+        let e4 = l.push_back(4);
+
+        l.move_after(e3, e3);
+        check_list_pointers(&l, &[e1, e2, e3, e4]);
+        l.move_before(e2, e2);
+        check_list_pointers(&l, &[e1, e2, e3, e4]);
+
+        l.move_after(e3, e2);
+        check_list_pointers(&l, &[e1, e2, e3, e4]);
+        l.move_before(e2, e3);
+        check_list_pointers(&l, &[e1, e2, e3, e4]);
+
+        l.move_before(e2, e4);
+        check_list_pointers(&l, &[e1, e3, e2, e4]);
+        let (e2, e3) = (e3, e2);
+
+        l.move_before(e4, e1);
+        check_list_pointers(&l, &[e4, e1, e2, e3]);
+        let (e1, e2, e3, e4) = (e4, e1, e2, e3);
+
+        l.move_after(e4, e1);
+        check_list_pointers(&l, &[e1, e4, e2, e3]);
+        let (e2, e3, e4) = (e4, e2, e3);
+
+        l.move_after(e2, e3);
+        check_list_pointers(&l, &[e1, e3, e2, e4]);
+    }
+
+    // Test that a list l is not modified when calling insert_before with a mark that is not an element of l.
+    #[test]
+    fn test_insert_before_unknown_mark() {
+        let mut l = List::new();
+        l.push_back(1);
+        l.push_back(2);
+        l.push_back(3);
+        let mut other = List::new();
+        let mark = other.push_back(0);
+        assert_eq!(l.insert_before(1, mark), None);
+        check_list(&l, &[1, 2, 3]);
+    }
+
+    // Test that a list l is not modified when calling insert_after with a mark that is not an element of l.
+    #[test]
+    fn test_insert_after_unknown_mark() {
+        let mut l = List::new();
+        l.push_back(1);
+        l.push_back(2);
+        l.push_back(3);
+        let mut other = List::new();
+        let mark = other.push_back(0);
+        assert_eq!(l.insert_after(1, mark), None);
+        check_list(&l, &[1, 2, 3]);
+    }
+
+    // Test that a list l is not modified when calling move_after or move_before with a mark that is not an element of l.
+    #[test]
+    fn test_move_unknown_mark() {
+        let mut l1 = List::new();
+        let e1 = l1.push_back(1);
+
+        let mut l2 = List::new();
+        let e2 = l2.push_back(2);
+
+        l1.move_after(e1, e2);
+        check_list(&l1, &[1]);
+        check_list(&l2, &[2]);
+
+        l1.move_before(e1, e2);
+        check_list(&l1, &[1]);
+        check_list(&l2, &[2]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut l: List<i32> = (1..=3).collect();
+        let e = l.front().unwrap();
+        l.clear();
+        check_list(&l, &[]);
+        assert!(l.is_empty());
+        let e1 = l.push_back(4);
+        assert!(!l.contains(e));
+        assert_eq!(l.get(e), None);
+        check_list_pointers(&l, &[e1]);
+    }
+
+    // The handle semantics an LRU cache relies on: a map from keys to elements
+    // that stay valid while the list is reordered.
+    #[test]
+    fn test_lru() {
+        struct Lru {
+            cap: usize,
+            ll: List<(u32, &'static str)>,
+            cache: HashMap<u32, Element>,
+        }
+
+        impl Lru {
+            fn get(&mut self, key: u32) -> Option<&'static str> {
+                let e = *self.cache.get(&key)?;
+                self.ll.move_to_front(e);
+                self.ll.get(e).map(|(_, v)| *v)
+            }
+
+            fn add(&mut self, key: u32, value: &'static str) {
+                if let Some(e) = self.cache.get(&key).copied() {
+                    self.ll.move_to_front(e);
+                    self.ll.get_mut(e).unwrap().1 = value;
+                    return;
+                }
+                let e = self.ll.push_front((key, value));
+                self.cache.insert(key, e);
+                if self.ll.len() > self.cap {
+                    let oldest = self.ll.back().unwrap();
+                    let (key, _) = self.ll.remove(oldest).unwrap();
+                    self.cache.remove(&key);
+                }
+            }
+        }
+
+        let mut lru = Lru {
+            cap: 2,
+            ll: List::new(),
+            cache: HashMap::new(),
+        };
+        lru.add(1, "one");
+        lru.add(2, "two");
+        assert_eq!(lru.get(1), Some("one"));
+        lru.add(3, "three"); // evicts 2
+        assert_eq!(lru.get(2), None);
+        assert_eq!(lru.get(3), Some("three"));
+        lru.add(1, "uno");
+        lru.add(4, "four"); // evicts 3
+        assert_eq!(lru.get(3), None);
+        assert_eq!(lru.get(1), Some("uno"));
+        assert_eq!(lru.get(4), Some("four"));
+        assert_eq!(format!("{:?}", lru.ll), r#"[(4, "four"), (1, "uno")]"#);
+    }
+}
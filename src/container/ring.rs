@@ -0,0 +1,501 @@
+//! Operations on circular lists.
+//!
+//! Go's `*Ring` is both the ring and each of its elements. Here the elements
+//! of any number of rings live in one [`Rings`] slab, and a [`Ring`] is a
+//! copyable handle to one of them: a ring is simply the cycle of elements
+//! reachable from a handle.
+//!
+//! [`Rings`]: crate::container::ring::Rings
+//! [`Ring`]: crate::container::ring::Ring
+use super::arena::{Arena, Key};
+
+/// A handle to an element of a circular list, or ring, stored in a [`Rings`].
+///
+/// Rings do not have a beginning or end; a handle to any ring element serves
+/// as reference to the entire ring.
+///
+/// Besides [`Rings::contains`], [`Rings::get`], [`Rings::get_mut`] and
+/// [`Rings::remove`], which report it, the methods of [`Rings`] panic when
+/// given a handle to an element that was removed or that belongs to another
+/// [`Rings`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Ring(Key);
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    prev: usize,
+    next: usize,
+}
+
+/// The storage for the elements of rings.
+pub struct Rings<T> {
+    nodes: Arena<Node<T>>,
+}
+
+impl<T> Default for Rings<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Rings<T> {
+    /// Returns an empty storage.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Returns an empty storage with room for `cap` elements.
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        Self {
+            nodes: Arena::with_capacity(cap),
+        }
+    }
+
+    /// Creates a ring of one element holding `value`.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Ring {
+        let key = self.nodes.insert(Node {
+            value,
+            prev: 0,
+            next: 0,
+        });
+        let node = self.nodes.node_mut(key.index());
+        node.prev = key.index();
+        node.next = key.index();
+        Ring(key)
+    }
+
+    /// Creates a ring of `n` elements holding `T::default()`,
+    /// or returns `None` if `n` is zero.
+    #[inline]
+    pub fn new_ring(&mut self, n: usize) -> Option<Ring>
+    where
+        T: Default,
+    {
+        self.collect_ring(core::iter::repeat_with(T::default).take(n))
+    }
+
+    /// Creates a ring holding `values` in order and returns the element holding
+    /// the first one, or returns `None` if `values` is empty.
+    pub fn collect_ring<I: IntoIterator<Item = T>>(&mut self, values: I) -> Option<Ring> {
+        let mut values = values.into_iter();
+        let r = self.insert(values.next()?);
+        for value in values {
+            let s = self.insert(value);
+            // Linking before r appends to the ring.
+            let p = self.prev(r);
+            self.link(p, s);
+        }
+        Some(r)
+    }
+
+    /// Reports whether `r` is an element of these rings.
+    #[inline]
+    pub fn contains(&self, r: Ring) -> bool {
+        self.nodes.contains(r.0)
+    }
+
+    /// Returns the value held by `r`, or `None` if `r` is not an element of these rings.
+    #[inline]
+    pub fn get(&self, r: Ring) -> Option<&T> {
+        self.nodes.get(r.0).map(|n| &n.value)
+    }
+
+    /// Returns the value held by `r` mutably, or `None` if `r` is not an element of these rings.
+    #[inline]
+    pub fn get_mut(&mut self, r: Ring) -> Option<&mut T> {
+        self.nodes.get_mut(r.0).map(|n| &mut n.value)
+    }
+
+    /// Returns the next ring element.
+    #[inline]
+    pub fn next(&self, r: Ring) -> Ring {
+        let i = self.index(r);
+        self.ring(self.nodes.node(i).next)
+    }
+
+    /// Returns the previous ring element.
+    #[inline]
+    pub fn prev(&self, r: Ring) -> Ring {
+        let i = self.index(r);
+        self.ring(self.nodes.node(i).prev)
+    }
+
+    /// Golang's `Ring.Move` in Rust.
+    ///
+    /// Moves `n % len(r)` elements backward (`n < 0`) or forward (`n >= 0`)
+    /// in the ring and returns that ring element.
+    pub fn move_by(&self, r: Ring, n: isize) -> Ring {
+        let mut i = self.index(r);
+        if n < 0 {
+            for _ in n..0 {
+                i = self.nodes.node(i).prev;
+            }
+        } else {
+            for _ in 0..n {
+                i = self.nodes.node(i).next;
+            }
+        }
+        self.ring(i)
+    }
+
+    /// Computes the number of elements in ring `r`.
+    /// It executes in time proportional to the number of elements.
+    pub fn len(&self, r: Ring) -> usize {
+        let start = self.index(r);
+        let mut n = 1;
+        let mut p = self.nodes.node(start).next;
+        while p != start {
+            n += 1;
+            p = self.nodes.node(p).next;
+        }
+        n
+    }
+
+    /// Connects ring `r` with ring `s` such that `r.next()`
+    /// becomes `s` and returns the original value for `r.next()`.
+    ///
+    /// If `r` and `s` point to the same ring, linking
+    /// them removes the elements between `r` and `s` from the ring.
+    /// The removed elements form a subring and the result is a
+    /// reference to that subring (if no elements were removed,
+    /// the result is still the original value for `r.next()`,
+    /// and not `None`).
+    ///
+    /// If `r` and `s` point to different rings, linking
+    /// them creates a single ring with the elements of `s` inserted
+    /// after `r`. The result points to the element following the
+    /// last element of `s` after insertion.
+    pub fn link(&mut self, r: Ring, s: Ring) -> Ring {
+        let (r, s) = (self.index(r), self.index(s));
+        let n = self.nodes.node(r).next;
+        let p = self.nodes.node(s).prev;
+        self.nodes.node_mut(r).next = s;
+        self.nodes.node_mut(s).prev = r;
+        self.nodes.node_mut(n).prev = p;
+        self.nodes.node_mut(p).next = n;
+        self.ring(n)
+    }
+
+    /// Removes `n % len(r)` elements from the ring `r`, starting
+    /// at `r.next()`. If `n % len(r) == 0`, `r` remains unchanged.
+    /// The result is the removed subring, or `None` if `n` is zero.
+    ///
+    /// The removed elements stay in storage as a ring of their own;
+    /// [`Rings::remove`] them to free them.
+    #[inline]
+    pub fn unlink(&mut self, r: Ring, n: usize) -> Option<Ring> {
+        if n == 0 {
+            return None;
+        }
+        let s = self.move_by(r, n as isize + 1);
+        Some(self.link(r, s))
+    }
+
+    /// Golang's `Ring.Do` in Rust.
+    ///
+    /// Calls function `f` on each element of the ring, in forward order.
+    pub fn for_each<F: FnMut(&T)>(&self, r: Ring, mut f: F) {
+        let start = self.index(r);
+        let mut p = start;
+        loop {
+            let node = self.nodes.node(p);
+            f(&node.value);
+            p = node.next;
+            if p == start {
+                break;
+            }
+        }
+    }
+
+    /// Like [`Rings::for_each`], but `f` may modify the values.
+    pub fn for_each_mut<F: FnMut(&mut T)>(&mut self, r: Ring, mut f: F) {
+        let start = self.index(r);
+        let mut p = start;
+        loop {
+            let node = self.nodes.node_mut(p);
+            f(&mut node.value);
+            p = node.next;
+            if p == start {
+                break;
+            }
+        }
+    }
+
+    /// Removes element `r` from its ring and from storage, and returns its value,
+    /// or `None` if `r` is not an element of these rings.
+    pub fn remove(&mut self, r: Ring) -> Option<T> {
+        if !self.contains(r) {
+            return None;
+        }
+        let Node { prev, next, .. } = *self.nodes.node(r.0.index());
+        self.nodes.node_mut(prev).next = next;
+        self.nodes.node_mut(next).prev = prev;
+        self.nodes.remove(r.0).map(|n| n.value)
+    }
+
+    #[inline]
+    fn index(&self, r: Ring) -> usize {
+        assert!(self.contains(r), "ring: element is not in these rings");
+        r.0.index()
+    }
+
+    #[inline]
+    fn ring(&self, index: usize) -> Ring {
+        Ring(self.nodes.key(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dump(rings: &Rings<i32>, r: Option<Ring>) -> Vec<i32> {
+        let mut values = Vec::new();
+        if let Some(r) = r {
+            rings.for_each(r, |v| values.push(*v));
+        }
+        values
+    }
+
+    fn verify(rings: &Rings<i32>, r: Option<Ring>, n: usize, sum: i32) {
+        let r = match r {
+            Some(r) => r,
+            None => {
+                assert_eq!(n, 0, "r.len() == 0; expected {n}");
+                return;
+            }
+        };
+
+        // len
+        let got = rings.len(r);
+        assert_eq!(got, n, "r.len() == {got}; expected {n}");
+
+        // iteration
+        let mut got = 0;
+        let mut s = 0;
+        rings.for_each(r, |p| {
+            got += 1;
+            s += *p;
+        });
+        assert_eq!(
+            got, n,
+            "number of forward iterations == {got}; expected {n}"
+        );
+        if sum >= 0 {
+            assert_eq!(s, sum, "forward ring sum = {s}; expected {sum}");
+        }
+
+        // connections
+        let mut p = r;
+        let mut q = rings.next(r);
+        loop {
+            assert_eq!(
+                rings.prev(q),
+                p,
+                "prev = {p:?}, expected q.prev = {:?}",
+                rings.prev(q)
+            );
+            if q == r {
+                break;
+            }
+            p = q;
+            q = rings.next(q);
+        }
+        assert_eq!(
+            p,
+            rings.prev(r),
+            "prev = {p:?}, expected r.prev = {:?}",
+            rings.prev(r)
+        );
+
+        // move_by
+        let n = n as isize;
+        assert_eq!(rings.move_by(r, 0), r, "r.move_by(0) != r");
+        assert_eq!(rings.move_by(r, n), r, "r.move_by({n}) != r");
+        assert_eq!(rings.move_by(r, -n), r, "r.move_by({}) != r", -n);
+        for i in 0..10 {
+            let ni = n + i;
+            let mi = ni % n;
+            assert_eq!(rings.move_by(r, ni), rings.move_by(r, mi));
+            assert_eq!(rings.move_by(r, -ni), rings.move_by(r, -mi));
+        }
+    }
+
+    fn make_n(rings: &mut Rings<i32>, n: i32) -> Option<Ring> {
+        rings.collect_ring(1..=n)
+    }
+
+    fn sum_n(n: i32) -> i32 {
+        (n * n + n) / 2
+    }
+
+    #[test]
+    fn test_corner_cases() {
+        let mut rings = Rings::new();
+
+        // Ring of one element
+        let r1 = rings.insert(0);
+        verify(&rings, Some(r1), 1, 0);
+        // Link a one-element ring to itself
+        rings.link(r1, r1);
+        verify(&rings, Some(r1), 1, 0);
+        // Move
+        assert_eq!(rings.move_by(r1, 1), r1);
+        // Unlink
+        assert_eq!(rings.unlink(r1, 0), None);
+        verify(&rings, Some(r1), 1, 0);
+        rings.unlink(r1, 1);
+        verify(&rings, Some(r1), 1, 0);
+    }
+
+    #[test]
+    fn test_new() {
+        let mut rings = Rings::new();
+        for i in 0..10 {
+            let r = rings.new_ring(i);
+            verify(&rings, r, i, -1);
+        }
+        for i in 0..10 {
+            let r = make_n(&mut rings, i);
+            verify(&rings, r, i as usize, sum_n(i));
+        }
+    }
+
+    #[test]
+    fn test_link1() {
+        let mut rings = Rings::new();
+        let r1a = make_n(&mut rings, 1).unwrap();
+        let r1b = rings.insert(0);
+        let r2a = rings.link(r1a, r1b);
+        verify(&rings, Some(r2a), 2, 1);
+        assert_eq!(r2a, r1a, "a) 2-element link failed");
+
+        let next = rings.next(r2a);
+        let r2b = rings.link(r2a, next);
+        verify(&rings, Some(r2b), 2, 1);
+        assert_eq!(r2b, rings.next(r2a), "b) 2-element link failed");
+
+        let r1c = rings.link(r2b, r2b);
+        verify(&rings, Some(r1c), 1, 1);
+        verify(&rings, Some(r2b), 1, 0);
+    }
+
+    #[test]
+    fn test_link2() {
+        let mut rings = Rings::new();
+        let r1a = rings.insert(42);
+        let r1b = rings.insert(77);
+        let r10 = make_n(&mut rings, 10).unwrap();
+
+        verify(&rings, Some(r1a), 1, 42);
+
+        rings.link(r1a, r1b);
+        verify(&rings, Some(r1a), 2, 42 + 77);
+
+        verify(&rings, Some(r10), 10, sum_n(10));
+
+        rings.link(r10, r1a);
+        verify(&rings, Some(r10), 12, sum_n(10) + 42 + 77);
+    }
+
+    #[test]
+    fn test_link3() {
+        let mut rings = Rings::new();
+        let r = rings.insert(0);
+        let mut n = 1;
+        for i in 1..10 {
+            n += i;
+            let s = rings.new_ring(i).unwrap();
+            let got = rings.link(r, s);
+            verify(&rings, Some(got), n, -1);
+        }
+    }
+
+    #[test]
+    fn test_unlink() {
+        let mut rings = Rings::new();
+        let r10 = make_n(&mut rings, 10).unwrap();
+        let s10 = rings.move_by(r10, 6);
+
+        let sum10 = sum_n(10);
+
+        verify(&rings, Some(r10), 10, sum10);
+        verify(&rings, Some(s10), 10, sum10);
+
+        let r0 = rings.unlink(r10, 0);
+        verify(&rings, r0, 0, 0);
+
+        let r1 = rings.unlink(r10, 1);
+        verify(&rings, r1, 1, 2);
+        verify(&rings, Some(r10), 9, sum10 - 2);
+
+        let r9 = rings.unlink(r10, 9);
+        verify(&rings, r9, 9, sum10 - 2);
+        verify(&rings, Some(r10), 9, sum10 - 2);
+    }
+
+    #[test]
+    fn test_link_unlink() {
+        let mut rings = Rings::new();
+        for i in 1..4 {
+            let ri = rings.new_ring(i).unwrap();
+            for j in 0..i {
+                let rj = rings.unlink(ri, j);
+                verify(&rings, rj, j, -1);
+                verify(&rings, Some(ri), i - j, -1);
+                if let Some(rj) = rj {
+                    rings.link(ri, rj);
+                }
+                verify(&rings, Some(ri), i, -1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut rings = Rings::new();
+        let r = make_n(&mut rings, 5).unwrap();
+        let third = rings.move_by(r, 2);
+        assert_eq!(rings.remove(third), Some(3));
+        assert!(!rings.contains(third));
+        assert_eq!(rings.get(third), None);
+        assert_eq!(rings.remove(third), None);
+        verify(&rings, Some(r), 4, sum_n(5) - 3);
+        assert_eq!(dump(&rings, Some(r)), [1, 2, 4, 5]);
+
+        // The freed slot is reused without reviving the stale handle.
+        let s = rings.insert(6);
+        assert_ne!(s, third);
+        assert_eq!(rings.get(third), None);
+        verify(&rings, Some(s), 1, 6);
+
+        rings.for_each_mut(r, |v| *v *= 10);
+        assert_eq!(dump(&rings, Some(r)), [10, 20, 40, 50]);
+
+        // Removing the last element of a ring.
+        assert_eq!(rings.remove(s), Some(6));
+    }
+
+    #[test]
+    #[should_panic(expected = "ring: element is not in these rings")]
+    fn test_stale_handle() {
+        let mut rings = Rings::new();
+        let r = rings.insert(1);
+        rings.remove(r);
+        rings.next(r);
+    }
+
+    #[test]
+    #[should_panic(expected = "ring: element is not in these rings")]
+    fn test_foreign_handle() {
+        let mut a = Rings::new();
+        let mut b = Rings::new();
+        b.insert(1);
+        let r = a.insert(1);
+        b.len(r);
+    }
+}
@@ -41,7 +41,6 @@ impl_uvarint_with_buf! {
     usize_with_buf::usize::USIZE_LEN => Usize
 }
 
-
 macro_rules! impl_uvarint {
     ($($name:ident::$size:expr => $rst: ident), +$(,)?) => {
         $(
@@ -78,4 +77,4 @@ impl_uvarint! {
     u64::U64_LEN => U64Size,
     u128::U128_LEN => U128Size,
     usize::USIZE_LEN => Usize
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-// TODO: implement Go's internal/bytealg first
+// TODO: finish the port on top of `crate::bytealg`
 
 // use std::collections::HashMap;
 
@@ -28,7 +28,7 @@
 
 // #[inline]
 // fn get_line(data: &str) -> (&str, &str) {
-//     let i = data.find(|x| x == (b'\n' as char));
+//     let i = crate::bytealg::index_byte(data.as_bytes(), b'\n');
 
 //     match i {
 //         Some(mut i) => {
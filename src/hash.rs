@@ -0,0 +1,154 @@
+/// Implements [`std::io::Write`] for hashes by forwarding to [`Hash::write`].
+macro_rules! impl_io_write {
+    ($($ty:ty),+ $(,)?) => {$(
+        #[cfg(feature = "std")]
+        impl std::io::Write for $ty {
+            #[inline]
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                $crate::hash::Hash::write(self, buf);
+                Ok(buf.len())
+            }
+
+            #[inline]
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    )+};
+}
+
+/// Go `hash/adler32` library
+#[cfg(feature = "adler32")]
+#[cfg_attr(docsrs, doc(cfg(feature = "adler32")))]
+pub mod adler32;
+
+/// Go `hash/crc32` library
+#[cfg(feature = "crc32")]
+#[cfg_attr(docsrs, doc(cfg(feature = "crc32")))]
+pub mod crc32;
+
+/// Go `hash/crc64` library
+#[cfg(feature = "crc64")]
+#[cfg_attr(docsrs, doc(cfg(feature = "crc64")))]
+pub mod crc64;
+
+/// Go `hash/fnv` library
+#[cfg(feature = "fnv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fnv")))]
+pub mod fnv;
+
+/// The common interface implemented by all hash functions.
+///
+/// Hash implementations also implement [`std::io::Write`] when the `std`
+/// feature is enabled, so they can be fed by the same pipelines as the
+/// encoders. Since both traits have a `write` method, call it as
+/// `Hash::write(&mut h, p)` when both are in scope.
+pub trait Hash {
+    /// Adds more data to the running hash. It never fails.
+    fn write(&mut self, p: &[u8]);
+
+    /// Writes the current hash to `dst[..self.size()]`, without changing the
+    /// underlying hash state.
+    ///
+    /// # Panics
+    /// Panics if `dst` is shorter than [`Hash::size`].
+    fn sum_into(&self, dst: &mut [u8]);
+
+    /// Appends the current hash to `b`, without changing the underlying hash
+    /// state.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn sum(&self, b: &mut alloc::vec::Vec<u8>) {
+        let n = b.len();
+        b.resize(n + self.size(), 0);
+        self.sum_into(&mut b[n..]);
+    }
+
+    /// Resets the hash to its initial state.
+    fn reset(&mut self);
+
+    /// Returns the number of bytes [`Hash::sum`] will return.
+    fn size(&self) -> usize;
+
+    /// Returns the hash's underlying block size.
+    /// [`Hash::write`] must be able to accept any amount
+    /// of data, but it may operate more efficiently if all writes
+    /// are a multiple of the block size.
+    fn block_size(&self) -> usize;
+}
+
+/// The common interface implemented by all 32-bit hash functions.
+pub trait Hash32: Hash {
+    /// Returns the current hash.
+    fn sum32(&self) -> u32;
+}
+
+/// The common interface implemented by all 64-bit hash functions.
+pub trait Hash64: Hash {
+    /// Returns the current hash.
+    fn sum64(&self) -> u64;
+}
+
+/// Saves and restores the internal state of a hash, so that hashing can be
+/// checkpointed and resumed later.
+///
+/// The state is laid out as Go's hashes marshal it with `MarshalBinary`,
+/// so checkpoints can be exchanged with Go programs.
+pub trait MarshalBinary {
+    /// Returns the length of the marshaled state.
+    fn marshaled_size(&self) -> usize;
+
+    /// Writes the state to `dst[..self.marshaled_size()]`.
+    ///
+    /// # Panics
+    /// Panics if `dst` is shorter than [`MarshalBinary::marshaled_size`].
+    fn marshal_binary_into(&self, dst: &mut [u8]);
+
+    /// Returns the marshaled state.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn marshal_binary(&self) -> alloc::vec::Vec<u8> {
+        let mut b = alloc::vec![0; self.marshaled_size()];
+        self.marshal_binary_into(&mut b);
+        b
+    }
+
+    /// Restores the state marshaled by [`MarshalBinary::marshal_binary`].
+    fn unmarshal_binary(&mut self, b: &[u8]) -> Result<(), Error>;
+}
+
+/// Errors returned when restoring a marshaled hash state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Error {
+    /// The state was not marshaled by this kind of hash.
+    InvalidIdentifier,
+    /// The state does not have the length this kind of hash marshals.
+    InvalidSize,
+    /// The state was marshaled by a CRC using a different table.
+    TableMismatch,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::InvalidIdentifier => write!(f, "hash: invalid hash state identifier"),
+            Error::InvalidSize => write!(f, "hash: invalid hash state size"),
+            Error::TableMismatch => write!(f, "hash: tables do not match"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Checks the identifier and length of a marshaled state, and returns the
+/// bytes following the identifier.
+fn check_marshaled<'a>(b: &'a [u8], magic: &[u8], size: usize) -> Result<&'a [u8], Error> {
+    if !b.starts_with(magic) {
+        return Err(Error::InvalidIdentifier);
+    }
+    if b.len() != size {
+        return Err(Error::InvalidSize);
+    }
+    Ok(&b[magic.len()..])
+}
@@ -0,0 +1,184 @@
+//! Implements the Adler-32 checksum.
+//!
+//! It is defined in RFC 1950:
+//!
+//! Adler-32 is composed of two sums accumulated per byte: s1 is
+//! the sum of all bytes, s2 is the sum of all s1 values. Both sums
+//! are done modulo 65521. s1 is initialized to 1, s2 to zero.  The
+//! Adler-32 checksum is stored as s2*65536 + s1 in most-
+//! significant-byte first (network) order.
+
+use super::{check_marshaled, Error, Hash, Hash32, MarshalBinary};
+
+/// mod is the largest prime that is less than 65536.
+const MOD: u32 = 65521;
+/// nmax is the largest n such that
+/// 255 * n * (n+1) / 2 + (n+1) * (mod-1) <= 2^32-1.
+/// It is mentioned in RFC 1950 (search for "5552").
+const NMAX: usize = 5552;
+
+/// The size of an Adler-32 checksum in bytes.
+pub const SIZE: usize = 4;
+
+/// The low 16 bits are s1, the high 16 bits are s2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Digest(u32);
+
+impl Default for Digest {
+    #[inline]
+    fn default() -> Self {
+        new()
+    }
+}
+
+/// Returns a new [`Hash32`] computing the Adler-32 checksum. Its sum method
+/// will lay the value out in big-endian byte order.
+#[inline]
+pub const fn new() -> Digest {
+    Digest(1)
+}
+
+impl Hash for Digest {
+    #[inline]
+    fn write(&mut self, p: &[u8]) {
+        self.0 = update(self.0, p);
+    }
+
+    #[inline]
+    fn sum_into(&self, dst: &mut [u8]) {
+        dst[..SIZE].copy_from_slice(&self.0.to_be_bytes());
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.0 = 1;
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        SIZE
+    }
+
+    #[inline]
+    fn block_size(&self) -> usize {
+        4
+    }
+}
+
+impl Hash32 for Digest {
+    #[inline]
+    fn sum32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl_io_write!(Digest);
+
+const MAGIC: &[u8] = b"adl\x01";
+const MARSHALED_SIZE: usize = MAGIC.len() + 4;
+
+impl MarshalBinary for Digest {
+    #[inline]
+    fn marshaled_size(&self) -> usize {
+        MARSHALED_SIZE
+    }
+
+    fn marshal_binary_into(&self, dst: &mut [u8]) {
+        let dst = &mut dst[..MARSHALED_SIZE];
+        dst[..4].copy_from_slice(MAGIC);
+        dst[4..].copy_from_slice(&self.0.to_be_bytes());
+    }
+
+    fn unmarshal_binary(&mut self, b: &[u8]) -> Result<(), Error> {
+        let b = check_marshaled(b, MAGIC, MARSHALED_SIZE)?;
+        self.0 = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        Ok(())
+    }
+}
+
+/// Add p to the running checksum d.
+fn update(d: u32, p: &[u8]) -> u32 {
+    let (mut s1, mut s2) = (d & 0xffff, d >> 16);
+    for p in p.chunks(NMAX) {
+        let mut chunks = p.chunks_exact(4);
+        for c in &mut chunks {
+            s1 += c[0] as u32;
+            s2 += s1;
+            s1 += c[1] as u32;
+            s2 += s1;
+            s1 += c[2] as u32;
+            s2 += s1;
+            s1 += c[3] as u32;
+            s2 += s1;
+        }
+        for &x in chunks.remainder() {
+            s1 += x as u32;
+            s2 += s1;
+        }
+        s1 %= MOD;
+        s2 %= MOD;
+    }
+    s2 << 16 | s1
+}
+
+/// Returns the Adler-32 checksum of data.
+#[inline]
+pub fn checksum(data: &[u8]) -> u32 {
+    update(1, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOLDEN: &[(u32, &str)] = &[
+        (0x00000001, ""),
+        (0x00620062, "a"),
+        (0x012600c4, "ab"),
+        (0x024d0127, "abc"),
+        (0x03d8018b, "abcd"),
+        (0x05c801f0, "abcde"),
+        (0x081e0256, "abcdef"),
+        (0x0adb02bd, "abcdefg"),
+        (0x0e000325, "abcdefgh"),
+        (0x091e01de, "123456789"),
+        (0x63461002, "I am the very model of a modern major general"),
+        (0x5bdc0fda, "The quick brown fox jumps over the lazy dog"),
+        (
+            0x39111dd0,
+            "Even if I could be Shakespeare, I think I should still choose to be Faraday. - A. Huxley",
+        ),
+    ];
+
+    #[test]
+    fn test_golden() {
+        for &(want, input) in GOLDEN {
+            assert_eq!(checksum(input.as_bytes()), want, "adler32({input:?})");
+
+            let (a, b) = input.as_bytes().split_at(input.len() / 2);
+            let mut d = new();
+            Hash::write(&mut d, a);
+            let state = d.marshal_binary();
+            let mut resumed = Digest::default();
+            resumed.unmarshal_binary(&state).unwrap();
+            Hash::write(&mut resumed, b);
+            assert_eq!(resumed.sum32(), want, "resumed adler32({input:?})");
+        }
+        assert_eq!(new().marshal_binary(), b"adl\x01\x00\x00\x00\x01");
+    }
+
+    #[test]
+    fn test_large() {
+        // Long runs of 0xff are the worst case for overflowing the sums
+        // between reductions.
+        assert_eq!(checksum(&[0xff; 5553]), 0x8e299c8b);
+        assert_eq!(checksum(&[0; 1000]), 0x03e80001);
+
+        let mut d = new();
+        std::io::Write::write_all(&mut d, &[0xff; 5553]).unwrap();
+        let mut b = vec![];
+        d.sum(&mut b);
+        assert_eq!(b, [0x8e, 0x29, 0x9c, 0x8b]);
+        assert_eq!(d.unmarshal_binary(b"adl\x01\x00"), Err(Error::InvalidSize));
+    }
+}
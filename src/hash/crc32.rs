@@ -0,0 +1,393 @@
+//! Implements the 32-bit cyclic redundancy check, or CRC-32, checksum.
+//! See <https://en.wikipedia.org/wiki/Cyclic_redundancy_check> for
+//! information.
+//!
+//! Polynomials are represented in LSB-first form also known as reversed
+//! representation.
+//!
+//! See <https://en.wikipedia.org/wiki/Mathematics_of_cyclic_redundancy_checks#Reversed_representations_and_reciprocal_polynomials>
+//! for information.
+//!
+//! On x86_64, the IEEE and Castagnoli polynomials use carry-less
+//! multiplication and the CRC instructions of SSE4.2 when the CPU has them.
+//! With the `std` feature they are detected at runtime, otherwise only the
+//! target features enabled at compile time are used. Everything else falls
+//! back to slicing-by-8.
+
+use super::{check_marshaled, Error, Hash, Hash32, MarshalBinary};
+
+mod generic;
+use generic::{simple_make_table, simple_update, slicing_make_table, slicing_update, Slicing8};
+
+#[cfg(target_arch = "x86_64")]
+mod amd64;
+#[cfg(target_arch = "x86_64")]
+use amd64 as arch;
+
+#[cfg(not(target_arch = "x86_64"))]
+mod otherarch;
+#[cfg(not(target_arch = "x86_64"))]
+use otherarch as arch;
+
+/// The size of a CRC-32 checksum in bytes.
+pub const SIZE: usize = 4;
+
+/// IEEE is by far and away the most common CRC-32 polynomial.
+/// Used by ethernet (IEEE 802.3), v.42, fddi, gzip, zip, png, ...
+pub const IEEE: u32 = 0xedb88320;
+
+/// Castagnoli's polynomial, used in iSCSI.
+/// Has better error detection characteristics than IEEE.
+/// <https://dx.doi.org/10.1109/26.231911>
+pub const CASTAGNOLI: u32 = 0x82f63b78;
+
+/// Koopman's polynomial.
+/// Also has better error detection characteristics than IEEE.
+/// <https://dx.doi.org/10.1109/DSN.2002.1028931>
+pub const KOOPMAN: u32 = 0xeb31d82e;
+
+/// A 256-word table representing the polynomial for efficient processing.
+///
+/// It dereferences to the `[u32; 256]` lookup table, and remembers the
+/// polynomial it was made from so that [`update`] can pick the fastest
+/// implementation for it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Table {
+    poly: u32,
+    entries: [u32; 256],
+}
+
+impl Table {
+    /// Returns the polynomial the table was made from.
+    #[inline]
+    pub const fn poly(&self) -> u32 {
+        self.poly
+    }
+}
+
+impl core::ops::Deref for Table {
+    type Target = [u32; 256];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+/// The table for the [`IEEE`] polynomial.
+pub static IEEE_TABLE: Table = make_table(IEEE);
+
+static CASTAGNOLI_TABLE: Table = make_table(CASTAGNOLI);
+
+// Slicing-by-8 tables, used when the CPU has no instructions for the
+// polynomial.
+static IEEE_TABLE8: Slicing8 = slicing_make_table(IEEE);
+static CASTAGNOLI_TABLE8: Slicing8 = slicing_make_table(CASTAGNOLI);
+
+/// Returns a [`Table`] constructed from the specified polynomial.
+/// The contents of this [`Table`] must not be modified.
+#[inline]
+pub const fn make_table(poly: u32) -> Table {
+    Table {
+        poly,
+        entries: simple_make_table(poly),
+    }
+}
+
+fn update_ieee(crc: u32, p: &[u8]) -> u32 {
+    if arch::ieee_available() {
+        // SAFETY: the CPU supports the instructions `arch::update_ieee` uses.
+        unsafe { arch::update_ieee(crc, p) }
+    } else {
+        slicing_update(crc, &IEEE_TABLE8, p)
+    }
+}
+
+fn update_castagnoli(crc: u32, p: &[u8]) -> u32 {
+    if arch::castagnoli_available() {
+        // SAFETY: the CPU supports the instructions `arch::update_castagnoli` uses.
+        unsafe { arch::update_castagnoli(crc, p) }
+    } else {
+        slicing_update(crc, &CASTAGNOLI_TABLE8, p)
+    }
+}
+
+/// Returns the result of adding the bytes in p to the crc.
+#[inline]
+pub fn update(crc: u32, tab: &Table, p: &[u8]) -> u32 {
+    match tab.poly {
+        IEEE => update_ieee(crc, p),
+        CASTAGNOLI => update_castagnoli(crc, p),
+        _ => simple_update(crc, &tab.entries, p),
+    }
+}
+
+/// Returns the CRC-32 checksum of data
+/// using the polynomial represented by the [`Table`].
+#[inline]
+pub fn checksum(data: &[u8], tab: &Table) -> u32 {
+    update(0, tab, data)
+}
+
+/// Returns the CRC-32 checksum of data
+/// using the [`IEEE`] polynomial.
+#[inline]
+pub fn checksum_ieee(data: &[u8]) -> u32 {
+    update_ieee(0, data)
+}
+
+/// Represents the partial evaluation of a checksum.
+#[derive(Debug, Clone)]
+pub struct Digest<'a> {
+    crc: u32,
+    tab: &'a Table,
+}
+
+/// Creates a new [`Hash32`] computing the CRC-32 checksum using the
+/// polynomial represented by the [`Table`]. Its sum method will lay the
+/// value out in big-endian byte order.
+#[inline]
+pub const fn new(tab: &Table) -> Digest<'_> {
+    Digest { crc: 0, tab }
+}
+
+/// Creates a new [`Hash32`] computing the CRC-32 checksum using the
+/// [`IEEE`] polynomial. Its sum method will lay the value out in
+/// big-endian byte order.
+#[inline]
+pub const fn new_ieee() -> Digest<'static> {
+    new(&IEEE_TABLE)
+}
+
+/// Creates a new [`Hash32`] computing the CRC-32 checksum using the
+/// [`CASTAGNOLI`] polynomial, without making a table for it.
+#[inline]
+pub const fn new_castagnoli() -> Digest<'static> {
+    new(&CASTAGNOLI_TABLE)
+}
+
+impl Hash for Digest<'_> {
+    #[inline]
+    fn write(&mut self, p: &[u8]) {
+        self.crc = update(self.crc, self.tab, p);
+    }
+
+    #[inline]
+    fn sum_into(&self, dst: &mut [u8]) {
+        dst[..SIZE].copy_from_slice(&self.crc.to_be_bytes());
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.crc = 0;
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        SIZE
+    }
+
+    #[inline]
+    fn block_size(&self) -> usize {
+        1
+    }
+}
+
+impl Hash32 for Digest<'_> {
+    #[inline]
+    fn sum32(&self) -> u32 {
+        self.crc
+    }
+}
+
+impl_io_write!(Digest<'_>);
+
+const MAGIC: &[u8] = b"crc\x01";
+const MARSHALED_SIZE: usize = MAGIC.len() + 4 + 4;
+
+impl MarshalBinary for Digest<'_> {
+    #[inline]
+    fn marshaled_size(&self) -> usize {
+        MARSHALED_SIZE
+    }
+
+    fn marshal_binary_into(&self, dst: &mut [u8]) {
+        let dst = &mut dst[..MARSHALED_SIZE];
+        dst[..4].copy_from_slice(MAGIC);
+        dst[4..8].copy_from_slice(&table_sum(self.tab).to_be_bytes());
+        dst[8..].copy_from_slice(&self.crc.to_be_bytes());
+    }
+
+    fn unmarshal_binary(&mut self, b: &[u8]) -> Result<(), Error> {
+        let b = check_marshaled(b, MAGIC, MARSHALED_SIZE)?;
+        if table_sum(self.tab) != u32::from_be_bytes([b[0], b[1], b[2], b[3]]) {
+            return Err(Error::TableMismatch);
+        }
+        self.crc = u32::from_be_bytes([b[4], b[5], b[6], b[7]]);
+        Ok(())
+    }
+}
+
+/// Returns the IEEE checksum of table t, laid out in big-endian byte order.
+fn table_sum(t: &Table) -> u32 {
+    t.entries
+        .iter()
+        .fold(0, |crc, x| update_ieee(crc, &x.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    struct Golden {
+        ieee: u32,
+        castagnoli: u32,
+        koopman: u32,
+        input: &'static str,
+    }
+
+    const GOLDEN: &[Golden] = &[
+        Golden { ieee: 0x0, castagnoli: 0x0, koopman: 0x0, input: "" },
+        Golden { ieee: 0xe8b7be43, castagnoli: 0xc1d04330, koopman: 0x0da2aa8a, input: "a" },
+        Golden { ieee: 0x9e83486d, castagnoli: 0xe2a22936, koopman: 0x31ec935a, input: "ab" },
+        Golden { ieee: 0x352441c2, castagnoli: 0x364b3fb7, koopman: 0xba2322ac, input: "abc" },
+        Golden { ieee: 0xed82cd11, castagnoli: 0x92c80a31, koopman: 0xe0a6bcf7, input: "abcd" },
+        Golden { ieee: 0x8587d865, castagnoli: 0xc450d697, koopman: 0xac046415, input: "abcde" },
+        Golden { ieee: 0x4b8e39ef, castagnoli: 0x53bceff1, koopman: 0x7589981b, input: "abcdef" },
+        Golden { ieee: 0x312a6aa6, castagnoli: 0xe627f441, koopman: 0x7999acb5, input: "abcdefg" },
+        Golden { ieee: 0xaeef2a50, castagnoli: 0x0a9421b7, koopman: 0xd5cc0e40, input: "abcdefgh" },
+        Golden { ieee: 0xcbf43926, castagnoli: 0xe3069283, koopman: 0x2d3dd0ae, input: "123456789" },
+        Golden {
+            ieee: 0x0773ad0c,
+            castagnoli: 0x6e5786cd,
+            koopman: 0x1d3c335a,
+            input: "I am the very model of a modern major general",
+        },
+        Golden {
+            ieee: 0x414fa339,
+            castagnoli: 0x22620404,
+            koopman: 0xe021db90,
+            input: "The quick brown fox jumps over the lazy dog",
+        },
+        Golden {
+            ieee: 0x8c79fd79,
+            castagnoli: 0x297a88ed,
+            koopman: 0x5e625378,
+            input: "Even if I could be Shakespeare, I think I should still choose to be Faraday. - A. Huxley",
+        },
+    ];
+
+    #[test]
+    fn test_golden() {
+        let koopman = make_table(KOOPMAN);
+        for g in GOLDEN {
+            let input = g.input.as_bytes();
+            assert_eq!(checksum_ieee(input), g.ieee, "IEEE({:?})", g.input);
+            assert_eq!(checksum(input, &IEEE_TABLE), g.ieee, "IEEE({:?})", g.input);
+            assert_eq!(
+                checksum(input, &make_table(CASTAGNOLI)),
+                g.castagnoli,
+                "Castagnoli({:?})",
+                g.input
+            );
+            assert_eq!(
+                checksum(input, &koopman),
+                g.koopman,
+                "Koopman({:?})",
+                g.input
+            );
+
+            // Feeding the input in two parts gives the same checksum.
+            let (a, b) = input.split_at(input.len() / 2);
+            assert_eq!(update(checksum_ieee(a), &IEEE_TABLE, b), g.ieee);
+            let mut d = new_castagnoli();
+            Hash::write(&mut d, a);
+            Hash::write(&mut d, b);
+            assert_eq!(d.sum32(), g.castagnoli);
+        }
+    }
+
+    #[test]
+    fn test_arch_matches_simple() {
+        // Compare the accelerated and slicing-by-8 implementations with the
+        // byte at a time one, across lengths and alignments.
+        let mut rng = rand::thread_rng();
+        let data = (0..4096 + 64).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let ieee = make_table(IEEE);
+        let castagnoli = make_table(CASTAGNOLI);
+        let lens = (0..300).chain([511, 512, 513, 1000, 4095, 4096]);
+        for n in lens {
+            for offset in [0, 1, 7, 15] {
+                let p = &data[offset..offset + n];
+                let crc = rng.gen();
+                let want = simple_update(crc, &ieee, p);
+                assert_eq!(update_ieee(crc, p), want, "IEEE len {n} offset {offset}");
+                assert_eq!(slicing_update(crc, &IEEE_TABLE8, p), want);
+                let want = simple_update(crc, &castagnoli, p);
+                assert_eq!(
+                    update_castagnoli(crc, p),
+                    want,
+                    "Castagnoli len {n} offset {offset}"
+                );
+                assert_eq!(slicing_update(crc, &CASTAGNOLI_TABLE8, p), want);
+            }
+        }
+    }
+
+    #[test]
+    fn test_digest() {
+        let mut d = new_ieee();
+        assert_eq!((d.size(), d.block_size()), (SIZE, 1));
+        Hash::write(&mut d, b"123456789");
+        assert_eq!(d.sum32(), 0xcbf43926);
+
+        let mut b = b"crc".to_vec();
+        d.sum(&mut b);
+        assert_eq!(b, b"crc\xcb\xf4\x39\x26");
+
+        d.reset();
+        assert_eq!(d.sum32(), 0);
+
+        let koopman = make_table(KOOPMAN);
+        assert_eq!(koopman.poly(), KOOPMAN);
+        assert_eq!(koopman[128], KOOPMAN);
+        let mut d = new(&koopman);
+        let input = GOLDEN.last().unwrap().input.as_bytes();
+        std::io::copy(&mut &input[..], &mut d).unwrap();
+        assert_eq!(d.sum32(), 0x5e625378);
+    }
+
+    #[test]
+    fn test_marshal() {
+        assert_eq!(
+            new_ieee().marshal_binary(),
+            b"crc\x01\xca\x87\x91\x4d\x00\x00\x00\x00"
+        );
+        for g in GOLDEN {
+            let (a, b) = g.input.as_bytes().split_at(g.input.len() / 2);
+            let mut d = new_castagnoli();
+            Hash::write(&mut d, a);
+            let state = d.marshal_binary();
+            let castagnoli = make_table(CASTAGNOLI);
+            let mut resumed = new(&castagnoli);
+            resumed.unmarshal_binary(&state).unwrap();
+            Hash::write(&mut resumed, b);
+            assert_eq!(
+                resumed.sum32(),
+                g.castagnoli,
+                "resumed Castagnoli({:?})",
+                g.input
+            );
+
+            assert_eq!(
+                new_ieee().unmarshal_binary(&state),
+                Err(Error::TableMismatch)
+            );
+        }
+        assert_eq!(
+            new_ieee().unmarshal_binary(b"crc\x02\x00\x00\x00\x00\x00\x00\x00\x00"),
+            Err(Error::InvalidIdentifier)
+        );
+    }
+}
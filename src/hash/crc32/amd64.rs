@@ -0,0 +1,135 @@
+//! x86_64 implementations: the SSE4.2 CRC32 instruction for the Castagnoli
+//! polynomial, and folding with carry-less multiplication for IEEE.
+
+use super::{generic::slicing_update, IEEE_TABLE8};
+use core::arch::x86_64::{
+    __m128i, _mm_and_si128, _mm_clmulepi64_si128, _mm_crc32_u64, _mm_crc32_u8, _mm_cvtsi32_si128,
+    _mm_extract_epi32, _mm_loadu_si128, _mm_set_epi32, _mm_set_epi64x, _mm_srli_si128,
+    _mm_xor_si128,
+};
+
+/// Inputs shorter than this are left to slicing-by-8, since folding needs
+/// four 16-byte blocks to start with.
+const CLMUL_CUTOFF: usize = 64;
+
+// Constants for folding with the IEEE polynomial P (bit-reflected, shifted
+// left by one), from Intel's "Fast CRC Computation for Generic Polynomials
+// Using PCLMULQDQ Instruction".
+const K1: i64 = 0x154442bd4; // x^(4*128+32) mod P
+const K2: i64 = 0x1c6e41596; // x^(4*128-32) mod P
+const K3: i64 = 0x1751997d0; // x^(128+32) mod P
+const K4: i64 = 0x0ccaa009e; // x^(128-32) mod P
+const K5: i64 = 0x163cd6124; // x^64 mod P
+const P_X: i64 = 0x1db710641; // P
+const U_PRIME: i64 = 0x1f7011641; // floor(x^64 / P)
+
+#[inline]
+pub(super) fn ieee_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("pclmulqdq") && std::is_x86_feature_detected!("sse4.1")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(all(target_feature = "pclmulqdq", target_feature = "sse4.1"))
+    }
+}
+
+/// Updates the IEEE CRC by folding 16-byte blocks with carry-less
+/// multiplication, and leaves the tail to slicing-by-8.
+///
+/// # Safety
+/// The CPU must support PCLMULQDQ and SSE4.1.
+#[target_feature(enable = "pclmulqdq,sse4.1")]
+pub(super) unsafe fn update_ieee(crc: u32, mut p: &[u8]) -> u32 {
+    if p.len() < CLMUL_CUTOFF {
+        return slicing_update(crc, &IEEE_TABLE8, p);
+    }
+
+    // Fold four blocks at a time, with the initial CRC folded into the first.
+    let mut x3 = _mm_xor_si128(next(&mut p), _mm_cvtsi32_si128(!crc as i32));
+    let mut x2 = next(&mut p);
+    let mut x1 = next(&mut p);
+    let mut x0 = next(&mut p);
+    let k1k2 = _mm_set_epi64x(K2, K1);
+    while p.len() >= 64 {
+        x3 = fold(x3, next(&mut p), k1k2);
+        x2 = fold(x2, next(&mut p), k1k2);
+        x1 = fold(x1, next(&mut p), k1k2);
+        x0 = fold(x0, next(&mut p), k1k2);
+    }
+
+    // Fold the four accumulators into one, then the remaining blocks into it.
+    let k3k4 = _mm_set_epi64x(K4, K3);
+    let mut x = fold(x3, x2, k3k4);
+    x = fold(x, x1, k3k4);
+    x = fold(x, x0, k3k4);
+    while p.len() >= 16 {
+        x = fold(x, next(&mut p), k3k4);
+    }
+
+    // Reduce 128 bits to 64.
+    let low32 = _mm_set_epi32(0, 0, 0, !0);
+    x = _mm_xor_si128(_mm_clmulepi64_si128(x, k3k4, 0x10), _mm_srli_si128(x, 8));
+    x = _mm_xor_si128(
+        _mm_clmulepi64_si128(_mm_and_si128(x, low32), _mm_set_epi64x(0, K5), 0x00),
+        _mm_srli_si128(x, 4),
+    );
+
+    // Barrett reduction from 64 bits to 32.
+    let pu = _mm_set_epi64x(U_PRIME, P_X);
+    let t1 = _mm_clmulepi64_si128(_mm_and_si128(x, low32), pu, 0x10);
+    let t2 = _mm_clmulepi64_si128(_mm_and_si128(t1, low32), pu, 0x00);
+    let crc = !(_mm_extract_epi32(_mm_xor_si128(x, t2), 1) as u32);
+
+    slicing_update(crc, &IEEE_TABLE8, p)
+}
+
+/// Loads the first 16 bytes of `p` and advances it past them.
+#[inline(always)]
+fn next(p: &mut &[u8]) -> __m128i {
+    let (block, rest) = p.split_at(16);
+    *p = rest;
+    // SAFETY: `block` is 16 bytes long, and SSE2 is always available on x86_64.
+    unsafe { _mm_loadu_si128(block.as_ptr() as *const __m128i) }
+}
+
+/// Folds `a` forward by the distance the `keys` are computed for, and adds
+/// it to `b`.
+#[inline]
+#[target_feature(enable = "pclmulqdq,sse4.1")]
+unsafe fn fold(a: __m128i, b: __m128i, keys: __m128i) -> __m128i {
+    let lo = _mm_clmulepi64_si128(a, keys, 0x00);
+    let hi = _mm_clmulepi64_si128(a, keys, 0x11);
+    _mm_xor_si128(_mm_xor_si128(b, lo), hi)
+}
+
+#[inline]
+pub(super) fn castagnoli_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("sse4.2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "sse4.2")
+    }
+}
+
+/// Updates the Castagnoli CRC with the CRC32 instruction.
+///
+/// # Safety
+/// The CPU must support SSE4.2.
+#[target_feature(enable = "sse4.2")]
+pub(super) unsafe fn update_castagnoli(crc: u32, p: &[u8]) -> u32 {
+    let mut crc = !crc as u64;
+    let mut chunks = p.chunks_exact(8);
+    for c in &mut chunks {
+        crc = _mm_crc32_u64(crc, u64::from_le_bytes(c.try_into().unwrap()));
+    }
+    let mut crc = crc as u32;
+    for &b in chunks.remainder() {
+        crc = _mm_crc32_u8(crc, b);
+    }
+    !crc
+}
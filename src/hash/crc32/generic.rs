@@ -0,0 +1,81 @@
+//! Architecture-independent table-driven CRC-32 implementations.
+
+/// Eight 256-word tables for the slicing-by-8 algorithm.
+pub(super) type Slicing8 = [[u32; 256]; 8];
+
+/// Inputs shorter than this are processed a byte at a time, where the
+/// slicing-by-8 tables do not pay off.
+const SLICING8_CUTOFF: usize = 16;
+
+/// Returns the table for the given polynomial.
+pub(super) const fn simple_make_table(poly: u32) -> [u32; 256] {
+    let mut t = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        t[i] = crc;
+        i += 1;
+    }
+    t
+}
+
+/// Uses the simple algorithm to update the CRC, given a table.
+pub(super) fn simple_update(crc: u32, tab: &[u32; 256], p: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &v in p {
+        crc = tab[(crc as u8 ^ v) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Returns the slicing-by-8 tables for the given polynomial.
+pub(super) const fn slicing_make_table(poly: u32) -> Slicing8 {
+    let mut t = [[0; 256]; 8];
+    t[0] = simple_make_table(poly);
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = t[0][i];
+        let mut j = 1;
+        while j < 8 {
+            crc = t[0][(crc & 0xff) as usize] ^ (crc >> 8);
+            t[j][i] = crc;
+            j += 1;
+        }
+        i += 1;
+    }
+    t
+}
+
+/// Uses the slicing-by-8 algorithm to update the CRC, given the tables.
+pub(super) fn slicing_update(crc: u32, tab: &Slicing8, mut p: &[u8]) -> u32 {
+    let mut crc = crc;
+    if p.len() >= SLICING8_CUTOFF {
+        crc = !crc;
+        while p.len() > 8 {
+            crc ^= u32::from_le_bytes([p[0], p[1], p[2], p[3]]);
+            crc = tab[0][p[7] as usize]
+                ^ tab[1][p[6] as usize]
+                ^ tab[2][p[5] as usize]
+                ^ tab[3][p[4] as usize]
+                ^ tab[4][(crc >> 24) as usize]
+                ^ tab[5][((crc >> 16) & 0xff) as usize]
+                ^ tab[6][((crc >> 8) & 0xff) as usize]
+                ^ tab[7][(crc & 0xff) as usize];
+            p = &p[8..];
+        }
+        crc = !crc;
+    }
+    if p.is_empty() {
+        return crc;
+    }
+    simple_update(crc, &tab[0], p)
+}
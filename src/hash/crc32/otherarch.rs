@@ -0,0 +1,19 @@
+//! Stubs for architectures without accelerated CRC-32 implementations.
+
+#[inline]
+pub(super) fn ieee_available() -> bool {
+    false
+}
+
+pub(super) unsafe fn update_ieee(_crc: u32, _p: &[u8]) -> u32 {
+    unreachable!("crc32: no accelerated IEEE implementation")
+}
+
+#[inline]
+pub(super) fn castagnoli_available() -> bool {
+    false
+}
+
+pub(super) unsafe fn update_castagnoli(_crc: u32, _p: &[u8]) -> u32 {
+    unreachable!("crc32: no accelerated Castagnoli implementation")
+}
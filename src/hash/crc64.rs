@@ -0,0 +1,326 @@
+//! Implements the 64-bit cyclic redundancy check, or CRC-64, checksum.
+//! See <https://en.wikipedia.org/wiki/Cyclic_redundancy_check> for
+//! information.
+
+use super::{check_marshaled, Error, Hash, Hash64, MarshalBinary};
+
+/// The size of a CRC-64 checksum in bytes.
+pub const SIZE: usize = 8;
+
+/// The ISO polynomial, defined in ISO 3309 and used in HDLC.
+pub const ISO: u64 = 0xD800000000000000;
+
+/// The ECMA polynomial, defined in ECMA 182.
+pub const ECMA: u64 = 0xC96C5795D7870F42;
+
+/// A 256-word table representing the polynomial for efficient processing.
+///
+/// It dereferences to the `[u64; 256]` lookup table, and remembers the
+/// polynomial it was made from so that [`update`] can use slicing-by-8 for
+/// the predefined ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Table {
+    poly: u64,
+    entries: [u64; 256],
+}
+
+impl Table {
+    /// Returns the polynomial the table was made from.
+    #[inline]
+    pub const fn poly(&self) -> u64 {
+        self.poly
+    }
+}
+
+impl core::ops::Deref for Table {
+    type Target = [u64; 256];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+type Slicing8 = [[u64; 256]; 8];
+
+static SLICING8_TABLE_ISO: Slicing8 = make_slicing_by8_table(ISO);
+static SLICING8_TABLE_ECMA: Slicing8 = make_slicing_by8_table(ECMA);
+
+/// Returns a [`Table`] constructed from the specified polynomial.
+/// The contents of this [`Table`] must not be modified.
+#[inline]
+pub const fn make_table(poly: u64) -> Table {
+    Table {
+        poly,
+        entries: simple_make_table(poly),
+    }
+}
+
+const fn simple_make_table(poly: u64) -> [u64; 256] {
+    let mut t = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        t[i] = crc;
+        i += 1;
+    }
+    t
+}
+
+const fn make_slicing_by8_table(poly: u64) -> Slicing8 {
+    let mut helper_table = [[0; 256]; 8];
+    helper_table[0] = simple_make_table(poly);
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = helper_table[0][i];
+        let mut j = 1;
+        while j < 8 {
+            crc = helper_table[0][(crc & 0xff) as usize] ^ (crc >> 8);
+            helper_table[j][i] = crc;
+            j += 1;
+        }
+        i += 1;
+    }
+    helper_table
+}
+
+/// Returns the result of adding the bytes in p to the crc.
+pub fn update(crc: u64, tab: &Table, mut p: &[u8]) -> u64 {
+    let mut crc = !crc;
+    // Tables other than the predefined ones are not worth extending for
+    // slicing-by-8, and small inputs are faster a byte at a time.
+    let helper_table = match tab.poly {
+        ISO if p.len() >= 64 => Some(&SLICING8_TABLE_ISO),
+        ECMA if p.len() >= 64 => Some(&SLICING8_TABLE_ECMA),
+        _ => None,
+    };
+    if let Some(helper_table) = helper_table {
+        // Update using slicing-by-8
+        while p.len() > 8 {
+            crc ^= u64::from_le_bytes([p[0], p[1], p[2], p[3], p[4], p[5], p[6], p[7]]);
+            crc = helper_table[7][(crc & 0xff) as usize]
+                ^ helper_table[6][((crc >> 8) & 0xff) as usize]
+                ^ helper_table[5][((crc >> 16) & 0xff) as usize]
+                ^ helper_table[4][((crc >> 24) & 0xff) as usize]
+                ^ helper_table[3][((crc >> 32) & 0xff) as usize]
+                ^ helper_table[2][((crc >> 40) & 0xff) as usize]
+                ^ helper_table[1][((crc >> 48) & 0xff) as usize]
+                ^ helper_table[0][(crc >> 56) as usize];
+            p = &p[8..];
+        }
+    }
+    // For reminders or small sizes
+    for &v in p {
+        crc = tab.entries[(crc as u8 ^ v) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Returns the CRC-64 checksum of data
+/// using the polynomial represented by the [`Table`].
+#[inline]
+pub fn checksum(data: &[u8], tab: &Table) -> u64 {
+    update(0, tab, data)
+}
+
+/// Represents the partial evaluation of a checksum.
+#[derive(Debug, Clone)]
+pub struct Digest<'a> {
+    crc: u64,
+    tab: &'a Table,
+}
+
+/// Creates a new [`Hash64`] computing the CRC-64 checksum using the
+/// polynomial represented by the [`Table`]. Its sum method will lay the
+/// value out in big-endian byte order.
+#[inline]
+pub const fn new(tab: &Table) -> Digest<'_> {
+    Digest { crc: 0, tab }
+}
+
+impl Hash for Digest<'_> {
+    #[inline]
+    fn write(&mut self, p: &[u8]) {
+        self.crc = update(self.crc, self.tab, p);
+    }
+
+    #[inline]
+    fn sum_into(&self, dst: &mut [u8]) {
+        dst[..SIZE].copy_from_slice(&self.crc.to_be_bytes());
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.crc = 0;
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        SIZE
+    }
+
+    #[inline]
+    fn block_size(&self) -> usize {
+        1
+    }
+}
+
+impl Hash64 for Digest<'_> {
+    #[inline]
+    fn sum64(&self) -> u64 {
+        self.crc
+    }
+}
+
+impl_io_write!(Digest<'_>);
+
+const MAGIC: &[u8] = b"crc\x02";
+const MARSHALED_SIZE: usize = MAGIC.len() + 8 + 8;
+
+impl MarshalBinary for Digest<'_> {
+    #[inline]
+    fn marshaled_size(&self) -> usize {
+        MARSHALED_SIZE
+    }
+
+    fn marshal_binary_into(&self, dst: &mut [u8]) {
+        let dst = &mut dst[..MARSHALED_SIZE];
+        dst[..4].copy_from_slice(MAGIC);
+        dst[4..12].copy_from_slice(&table_sum(self.tab).to_be_bytes());
+        dst[12..].copy_from_slice(&self.crc.to_be_bytes());
+    }
+
+    fn unmarshal_binary(&mut self, b: &[u8]) -> Result<(), Error> {
+        let b = check_marshaled(b, MAGIC, MARSHALED_SIZE)?;
+        let (sum, crc) = b.split_at(8);
+        if table_sum(self.tab) != u64::from_be_bytes(sum.try_into().unwrap()) {
+            return Err(Error::TableMismatch);
+        }
+        self.crc = u64::from_be_bytes(crc.try_into().unwrap());
+        Ok(())
+    }
+}
+
+/// Returns the ISO checksum of table t, laid out in big-endian byte order.
+fn table_sum(t: &Table) -> u64 {
+    static ISO_TABLE: Table = make_table(ISO);
+    t.entries
+        .iter()
+        .fold(0, |crc, x| update(crc, &ISO_TABLE, &x.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    struct Golden {
+        iso: u64,
+        ecma: u64,
+        input: &'static str,
+    }
+
+    const GOLDEN: &[Golden] = &[
+        Golden { iso: 0x0, ecma: 0x0, input: "" },
+        Golden { iso: 0x3420000000000000, ecma: 0x330284772e652b05, input: "a" },
+        Golden { iso: 0x36c4200000000000, ecma: 0xbc6573200e84b046, input: "ab" },
+        Golden { iso: 0x3776c42000000000, ecma: 0x2cd8094a1a277627, input: "abc" },
+        Golden { iso: 0x336776c420000000, ecma: 0x3c9d28596e5960ba, input: "abcd" },
+        Golden { iso: 0x32d36776c4200000, ecma: 0x040bdf58fb0895f2, input: "abcde" },
+        Golden { iso: 0x3002d36776c42000, ecma: 0xd08e9f8545a700f4, input: "abcdef" },
+        Golden { iso: 0x31b002d36776c420, ecma: 0xec20a3a8cc710e66, input: "abcdefg" },
+        Golden { iso: 0x0e21b002d36776c4, ecma: 0x67b4f30a647a0c59, input: "abcdefgh" },
+        Golden { iso: 0xb90956c775a41001, ecma: 0x995dc9bbdf1939fa, input: "123456789" },
+        Golden {
+            iso: 0x8f62eb1b4ef55576,
+            ecma: 0x7ec3036c69d7e717,
+            input: "I am the very model of a modern major general",
+        },
+        Golden {
+            iso: 0x4ef14e19f4c6e28e,
+            ecma: 0x5b5eb8c2e54aa1c4,
+            input: "The quick brown fox jumps over the lazy dog",
+        },
+        Golden {
+            iso: 0xf6c1e2a8c26c5cfc,
+            ecma: 0x7ad25fafa1710407,
+            input: "Even if I could be Shakespeare, I think I should still choose to be Faraday. - A. Huxley",
+        },
+    ];
+
+    #[test]
+    fn test_golden() {
+        let iso = make_table(ISO);
+        let ecma = make_table(ECMA);
+        for g in GOLDEN {
+            let input = g.input.as_bytes();
+            assert_eq!(checksum(input, &iso), g.iso, "ISO({:?})", g.input);
+            assert_eq!(checksum(input, &ecma), g.ecma, "ECMA({:?})", g.input);
+
+            let (a, b) = input.split_at(input.len() / 2);
+            let mut d = new(&ecma);
+            Hash::write(&mut d, a);
+            Hash::write(&mut d, b);
+            assert_eq!(d.sum64(), g.ecma);
+        }
+    }
+
+    #[test]
+    fn test_slicing_matches_simple() {
+        let mut rng = rand::thread_rng();
+        let data = (0..1000).map(|_| rng.gen()).collect::<Vec<u8>>();
+        for poly in [ISO, ECMA] {
+            let tab = make_table(poly);
+            for n in [0, 7, 63, 64, 65, 100, 999, 1000] {
+                let p = &data[..n];
+                let crc: u64 = rng.gen();
+                let want = p
+                    .iter()
+                    .fold(!crc, |crc, &v| tab[(crc as u8 ^ v) as usize] ^ (crc >> 8));
+                assert_eq!(update(crc, &tab, p), !want, "poly {poly:#x} len {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_marshal() {
+        let iso = make_table(ISO);
+        let mut d = new(&iso);
+        assert_eq!(
+            d.marshal_binary(),
+            b"crc\x02s\xba\x84\x84\xbb\xcd]\xef\x00\x00\x00\x00\x00\x00\x00\x00"
+        );
+
+        let input = GOLDEN.last().unwrap().input.as_bytes();
+        let (a, b) = input.split_at(input.len() / 2);
+        Hash::write(&mut d, a);
+        let state = d.marshal_binary();
+        let mut resumed = new(&iso);
+        resumed.unmarshal_binary(&state).unwrap();
+        Hash::write(&mut resumed, b);
+        assert_eq!(resumed.sum64(), 0xf6c1e2a8c26c5cfc);
+
+        let ecma = make_table(ECMA);
+        assert_eq!(
+            new(&ecma).unmarshal_binary(&state),
+            Err(Error::TableMismatch)
+        );
+        assert_eq!(
+            resumed.unmarshal_binary(&state[..state.len() - 1]),
+            Err(Error::InvalidSize)
+        );
+        assert_eq!(
+            resumed.unmarshal_binary(b"crc\x01"),
+            Err(Error::InvalidIdentifier)
+        );
+    }
+}
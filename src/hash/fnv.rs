@@ -0,0 +1,264 @@
+//! Implements FNV-1 and FNV-1a, non-cryptographic hash functions
+//! created by Glenn Fowler, Landon Curt Noll, and Phong Vo.
+//! See <https://en.wikipedia.org/wiki/Fowler-Noll-Vo_hash_function>.
+//!
+//! All the hash types implement [`MarshalBinary`] to save and restore their
+//! internal state.
+
+use super::{check_marshaled, Error, Hash, Hash32, Hash64, MarshalBinary};
+
+const OFFSET32: u32 = 2166136261;
+const OFFSET64: u64 = 14695981039346656037;
+const OFFSET128: u128 = 0x6c62272e07bb0142_62b821756295c58d;
+const PRIME32: u32 = 16777619;
+const PRIME64: u64 = 1099511628211;
+const PRIME128: u128 = 1 << 88 | 0x13b;
+
+macro_rules! fnv {
+    (
+        $(#[$attr:meta])*
+        $name:ident, $new:ident, $ty:ty, $offset:ident, $magic:literal, |$hash:ident, $c:ident| $step:block
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub struct $name($ty);
+
+        impl Default for $name {
+            #[inline]
+            fn default() -> Self {
+                $new()
+            }
+        }
+
+        #[doc = concat!("Returns a new [`", stringify!($name), "`].")]
+        #[inline]
+        pub const fn $new() -> $name {
+            $name($offset)
+        }
+
+        impl Hash for $name {
+            #[inline]
+            fn write(&mut self, data: &[u8]) {
+                let mut $hash = self.0;
+                for &$c in data $step
+                self.0 = $hash;
+            }
+
+            #[inline]
+            fn sum_into(&self, dst: &mut [u8]) {
+                dst[..core::mem::size_of::<$ty>()].copy_from_slice(&self.0.to_be_bytes());
+            }
+
+            #[inline]
+            fn reset(&mut self) {
+                self.0 = $offset;
+            }
+
+            #[inline]
+            fn size(&self) -> usize {
+                core::mem::size_of::<$ty>()
+            }
+
+            #[inline]
+            fn block_size(&self) -> usize {
+                1
+            }
+        }
+
+        impl_io_write!($name);
+
+        impl MarshalBinary for $name {
+            #[inline]
+            fn marshaled_size(&self) -> usize {
+                $magic.len() + core::mem::size_of::<$ty>()
+            }
+
+            fn marshal_binary_into(&self, dst: &mut [u8]) {
+                let dst = &mut dst[..self.marshaled_size()];
+                dst[..$magic.len()].copy_from_slice($magic);
+                dst[$magic.len()..].copy_from_slice(&self.0.to_be_bytes());
+            }
+
+            fn unmarshal_binary(&mut self, b: &[u8]) -> Result<(), Error> {
+                let b = check_marshaled(b, $magic, self.marshaled_size())?;
+                self.0 = <$ty>::from_be_bytes(b.try_into().unwrap());
+                Ok(())
+            }
+        }
+    };
+}
+
+fnv! {
+    /// 32-bit FNV-1 hash.
+    Sum32, new32, u32, OFFSET32, b"fnv\x01", |hash, c| {
+        hash = hash.wrapping_mul(PRIME32);
+        hash ^= c as u32;
+    }
+}
+
+fnv! {
+    /// 32-bit FNV-1a hash.
+    Sum32a, new32a, u32, OFFSET32, b"fnv\x02", |hash, c| {
+        hash ^= c as u32;
+        hash = hash.wrapping_mul(PRIME32);
+    }
+}
+
+fnv! {
+    /// 64-bit FNV-1 hash.
+    Sum64, new64, u64, OFFSET64, b"fnv\x03", |hash, c| {
+        hash = hash.wrapping_mul(PRIME64);
+        hash ^= c as u64;
+    }
+}
+
+fnv! {
+    /// 64-bit FNV-1a hash.
+    Sum64a, new64a, u64, OFFSET64, b"fnv\x04", |hash, c| {
+        hash ^= c as u64;
+        hash = hash.wrapping_mul(PRIME64);
+    }
+}
+
+fnv! {
+    /// 128-bit FNV-1 hash.
+    Sum128, new128, u128, OFFSET128, b"fnv\x05", |hash, c| {
+        hash = hash.wrapping_mul(PRIME128);
+        hash ^= c as u128;
+    }
+}
+
+fnv! {
+    /// 128-bit FNV-1a hash.
+    Sum128a, new128a, u128, OFFSET128, b"fnv\x06", |hash, c| {
+        hash ^= c as u128;
+        hash = hash.wrapping_mul(PRIME128);
+    }
+}
+
+impl Hash32 for Sum32 {
+    #[inline]
+    fn sum32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Hash32 for Sum32a {
+    #[inline]
+    fn sum32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Hash64 for Sum64 {
+    #[inline]
+    fn sum64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Hash64 for Sum64a {
+    #[inline]
+    fn sum64(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUTS: &[&str] = &["", "a", "ab", "abc", "123456789"];
+
+    const GOLDEN32: &[u32] = &[0x811c9dc5, 0x050c5d7e, 0x70772d38, 0x439c2f4b, 0x24148816];
+    const GOLDEN32A: &[u32] = &[0x811c9dc5, 0xe40c292c, 0x4d2505ca, 0x1a47e90b, 0xbb86b11c];
+    const GOLDEN64: &[u64] = &[
+        0xcbf29ce484222325,
+        0xaf63bd4c8601b7be,
+        0x08326707b4eb37b8,
+        0xd8dcca186bafadcb,
+        0xa72ffc362bf916d6,
+    ];
+    const GOLDEN64A: &[u64] = &[
+        0xcbf29ce484222325,
+        0xaf63dc4c8601ec8c,
+        0x089c4407b545986a,
+        0xe71fa2190541574b,
+        0x06d5573923c6cdfc,
+    ];
+    const GOLDEN128: &[u128] = &[
+        0x6c62272e07bb014262b821756295c58d,
+        0xd228cb69101a8caf78912b704e4a141e,
+        0x0880945aeeab1be95aa073305526c088,
+        0xa68bb2a4348b5822836dbc78c6aee73b,
+        0x8bea2c73be03b30fd4142fb1ec2c2066,
+    ];
+    const GOLDEN128A: &[u128] = &[
+        0x6c62272e07bb014262b821756295c58d,
+        0xd228cb696f1a8caf78912b704e4a8964,
+        0x08809544bbab1be95aa0733055b69a62,
+        0xa68d622cec8b5822836dbc7977af7f3b,
+        0xda2d42a08d04e4585dd325117f71d504,
+    ];
+
+    /// Checks the hash of every input, the bytes [`Hash::sum`] appends, and
+    /// that a state marshaled halfway resumes to the same hash.
+    fn check<H>(mut h: H, golden: &[u128])
+    where
+        H: Hash + MarshalBinary + Copy + PartialEq + core::fmt::Debug,
+    {
+        let fresh = h;
+        for (input, &want) in INPUTS.iter().zip(golden) {
+            h.reset();
+            Hash::write(&mut h, input.as_bytes());
+            let mut sum = vec![];
+            h.sum(&mut sum);
+            let want = &want.to_be_bytes()[16 - h.size()..];
+            assert_eq!(sum, want, "{h:?}: {input:?}");
+
+            let (a, b) = input.as_bytes().split_at(input.len() / 2);
+            let mut half = fresh;
+            Hash::write(&mut half, a);
+            let state = half.marshal_binary();
+            let mut resumed = fresh;
+            resumed.unmarshal_binary(&state).unwrap();
+            Hash::write(&mut resumed, b);
+            assert_eq!(resumed, h, "resumed {h:?}: {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_golden() {
+        let widen32 = |g: &[u32]| g.iter().map(|&x| x as u128).collect::<Vec<_>>();
+        let widen64 = |g: &[u64]| g.iter().map(|&x| x as u128).collect::<Vec<_>>();
+        check(new32(), &widen32(GOLDEN32));
+        check(new32a(), &widen32(GOLDEN32A));
+        check(new64(), &widen64(GOLDEN64));
+        check(new64a(), &widen64(GOLDEN64A));
+        check(new128(), GOLDEN128);
+        check(new128a(), GOLDEN128A);
+
+        let mut h = new64a();
+        Hash::write(&mut h, b"123456789");
+        assert_eq!(h.sum64(), GOLDEN64A[4]);
+        assert_eq!(Sum32::default().sum32(), OFFSET32);
+    }
+
+    #[test]
+    fn test_marshal_errors() {
+        let state = new32().marshal_binary();
+        assert_eq!(state, b"fnv\x01\x81\x1c\x9d\xc5");
+        assert_eq!(
+            new32a().unmarshal_binary(&state),
+            Err(Error::InvalidIdentifier)
+        );
+        assert_eq!(
+            new32().unmarshal_binary(&state[..7]),
+            Err(Error::InvalidSize)
+        );
+        assert_eq!(
+            new128().marshal_binary(),
+            b"fnv\x05\x6c\x62\x27\x2e\x07\xbb\x01\x42\x62\xb8\x21\x75\x62\x95\xc5\x8d"
+        );
+    }
+}
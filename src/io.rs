@@ -8,6 +8,18 @@ pub mod pipe;
 #[cfg_attr(docsrs, doc(cfg(feature = "async-pipe")))]
 pub mod async_pipe;
 
+mod multi;
+pub use multi::*;
+
+mod section;
+pub use section::*;
+
+#[cfg(feature = "async-io")]
+mod async_util;
+#[cfg(feature = "async-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-io")))]
+pub use async_util::*;
+
 /// Error for pipe
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg(any(feature = "pipe", feature = "async-pipe"))]
@@ -60,7 +72,214 @@ pub trait WriteCloser: std::io::Write + Closer {}
 impl<T: std::io::Write + Closer> WriteCloser for T {}
 
 /// The trait that groups the basic [`std::io::Read`], [`std::io::Write`] and [`Closer`].
-pub trait ReadWriteCloser: std::io::Read + Closer {}
+pub trait ReadWriteCloser: std::io::Read + std::io::Write + Closer {}
+
+impl<T: std::io::Read + std::io::Write + Closer> ReadWriteCloser for T {}
+
+/// The trait that groups the basic [`std::io::Read`] and [`std::io::Seek`].
+pub trait ReadSeeker: std::io::Read + std::io::Seek {}
+
+impl<T: std::io::Read + std::io::Seek> ReadSeeker for T {}
+
+/// The trait that groups the basic [`std::io::Read`], [`std::io::Seek`] and [`Closer`].
+pub trait ReadSeekCloser: std::io::Read + std::io::Seek + Closer {}
+
+impl<T: std::io::Read + std::io::Seek + Closer> ReadSeekCloser for T {}
+
+/// The trait that groups the basic [`std::io::Write`] and [`std::io::Seek`].
+pub trait WriteSeeker: std::io::Write + std::io::Seek {}
+
+impl<T: std::io::Write + std::io::Seek> WriteSeeker for T {}
+
+/// The trait that groups the basic [`std::io::Read`], [`std::io::Write`] and [`std::io::Seek`].
+pub trait ReadWriteSeeker: std::io::Read + std::io::Write + std::io::Seek {}
+
+impl<T: std::io::Read + std::io::Write + std::io::Seek> ReadWriteSeeker for T {}
+
+/// ByteReader is the trait that wraps the `read_byte` method.
+///
+/// `read_byte` reads and returns the next byte from the input or
+/// any error encountered. If `read_byte` returns an error, no input
+/// byte was consumed, and the returned byte value is undefined.
+///
+/// `read_byte` provides an efficient trait for byte-at-time
+/// processing. A [`std::io::Read`] that does not implement ByteReader
+/// can be wrapped using `bufio::Reader` to add this method.
+pub trait ByteReader {
+    /// Reads and returns the next byte, [`Error::Eof`] if there are no more bytes.
+    fn read_byte(&mut self) -> Result<u8, Error>;
+}
+
+/// ByteScanner is the trait that adds the `unread_byte` method to the
+/// basic `read_byte` method.
+///
+/// `unread_byte` causes the next call to `read_byte` to return the last byte read.
+/// If the last operation was not a successful call to `read_byte`, `unread_byte` may
+/// return an error, unread the last byte read (or the byte prior to the
+/// last-unread byte), or (in implementations that support the [`std::io::Seek`] trait)
+/// seek to one byte before the current offset.
+pub trait ByteScanner: ByteReader {
+    /// Unreads the last byte read.
+    fn unread_byte(&mut self) -> Result<(), Error>;
+}
+
+/// ByteWriter is the trait that wraps the `write_byte` method.
+pub trait ByteWriter {
+    /// Writes a single byte.
+    fn write_byte(&mut self, c: u8) -> std::io::Result<()>;
+}
+
+impl<T: std::io::Write + ?Sized> ByteWriter for T {
+    #[inline]
+    fn write_byte(&mut self, c: u8) -> std::io::Result<()> {
+        self.write_all(&[c])
+    }
+}
+
+/// RuneReader is the trait that wraps the `read_rune` method.
+///
+/// `read_rune` reads a single encoded Unicode character
+/// and returns the rune and its size in bytes. If no character is
+/// available, an error will be returned.
+pub trait RuneReader {
+    /// Reads a single UTF-8 encoded Unicode character.
+    fn read_rune(&mut self) -> Result<(char, usize), Error>;
+}
+
+/// RuneScanner is the trait that adds the `unread_rune` method to the
+/// basic `read_rune` method.
+///
+/// `unread_rune` causes the next call to `read_rune` to return the last rune read.
+/// If the last operation was not a successful call to `read_rune`, `unread_rune` may
+/// return an error, unread the last rune read (or the rune prior to the
+/// last-unread rune), or (in implementations that support the [`std::io::Seek`] trait)
+/// seek to the start of the rune before the current offset.
+pub trait RuneScanner: RuneReader {
+    /// Unreads the last rune read.
+    fn unread_rune(&mut self) -> Result<(), Error>;
+}
+
+/// StringWriter is the trait that wraps the `write_string` method.
+pub trait StringWriter {
+    /// Writes the contents of the string `s`.
+    fn write_string(&mut self, s: &str) -> std::io::Result<usize>;
+}
+
+impl<T: std::io::Write + ?Sized> StringWriter for T {
+    #[inline]
+    fn write_string(&mut self, s: &str) -> std::io::Result<usize> {
+        self.write(s.as_bytes())
+    }
+}
+
+/// Writes the contents of the string `s` to `w`, which accepts a slice of bytes.
+#[inline]
+pub fn write_string<W: StringWriter + ?Sized>(w: &mut W, s: &str) -> std::io::Result<usize> {
+    w.write_string(s)
+}
+
+impl ByteReader for &[u8] {
+    #[inline]
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        match self.split_first() {
+            Some((b, rest)) => {
+                *self = rest;
+                Ok(*b)
+            }
+            None => Err(Error::Eof),
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> ByteReader for std::io::Cursor<T> {
+    #[inline]
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        let pos = self.position();
+        let b = usize::try_from(pos)
+            .ok()
+            .and_then(|pos| self.get_ref().as_ref().get(pos).copied())
+            .ok_or(Error::Eof)?;
+        self.set_position(pos + 1);
+        Ok(b)
+    }
+}
+
+impl<T: AsRef<[u8]>> ByteScanner for std::io::Cursor<T> {
+    #[inline]
+    fn unread_byte(&mut self) -> Result<(), Error> {
+        let pos = self.position();
+        if pos == 0 {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "unread_byte: at beginning of slice",
+            )));
+        }
+        self.set_position(pos - 1);
+        Ok(())
+    }
+}
+
+/// Returns a [`ReadCloser`] with a no-op `close` method wrapping
+/// the provided reader `r`.
+/// If `r` implements [`WriterTo`], the returned [`ReadCloser`] will implement [`WriterTo`]
+/// by forwarding calls to `r`.
+#[inline]
+pub const fn nop_closer<R>(r: R) -> NopCloser<R> {
+    NopCloser(r)
+}
+
+/// A wrapper with a no-op `close` method, see [`nop_closer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct NopCloser<T>(T);
+
+impl<T> NopCloser<T> {
+    /// Consumes the [`NopCloser`], returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Closer for NopCloser<T> {
+    #[inline]
+    fn close(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for NopCloser<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for NopCloser<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<S: std::io::Seek> std::io::Seek for NopCloser<S> {
+    #[inline]
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+impl<R: WriterTo> WriterTo for NopCloser<R> {
+    #[inline]
+    fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+        self.0.write_to(w)
+    }
+}
 
 /// Closer is the trait that wraps the basic `close` method.
 ///
@@ -70,3 +289,759 @@ pub trait Closer {
     /// Close
     fn close(&mut self) -> std::io::Result<()>;
 }
+
+/// Errors returned by the helper functions of this module,
+/// which mirror the sentinel errors of Go's `io` package.
+#[derive(Debug)]
+pub enum Error {
+    /// EOF is the error returned when no more input is available.
+    /// Functions should return EOF only to signal a graceful end of input.
+    /// If the EOF occurs unexpectedly in a structured data stream,
+    /// the appropriate error is either [`Error::UnexpectedEof`] or some other error
+    /// giving more detail.
+    Eof,
+    /// Means that EOF was encountered in the
+    /// middle of reading a fixed-size block or data structure.
+    UnexpectedEof,
+    /// Means that a read required a longer buffer than was provided.
+    ShortBuffer,
+    /// Means that a write accepted fewer bytes than requested
+    /// but failed to return an explicit error.
+    ShortWrite,
+    /// Returned by some clients of a reader when
+    /// many calls to `read` have failed to return any data or error,
+    /// usually the sign of a broken reader implementation.
+    NoProgress,
+    /// Means that a write returned an impossible count.
+    InvalidWrite,
+    /// An error returned by the underlying reader or writer.
+    Io(std::io::Error),
+}
+
+impl Error {
+    /// Returns true if the error is [`Error::Eof`]
+    #[inline]
+    pub const fn is_eof(&self) -> bool {
+        matches!(self, Self::Eof)
+    }
+
+    /// Returns true if the error is [`Error::UnexpectedEof`]
+    #[inline]
+    pub const fn is_unexpected_eof(&self) -> bool {
+        matches!(self, Self::UnexpectedEof)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Eof => write!(f, "EOF"),
+            Error::UnexpectedEof => write!(f, "unexpected EOF"),
+            Error::ShortBuffer => write!(f, "short buffer"),
+            Error::ShortWrite => write!(f, "short write"),
+            Error::NoProgress => write!(f, "multiple Read calls return no data or error"),
+            Error::InvalidWrite => write!(f, "invalid write result"),
+            Error::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        if e.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+            // unwrap safe here because we have checked the inner error above.
+            return *e.into_inner().unwrap().downcast::<Error>().unwrap();
+        }
+        Error::Io(e)
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(e: Error) -> Self {
+        let kind = match e {
+            Error::Io(e) => return e,
+            Error::Eof | Error::UnexpectedEof => std::io::ErrorKind::UnexpectedEof,
+            Error::ShortWrite => std::io::ErrorKind::WriteZero,
+            Error::ShortBuffer | Error::InvalidWrite => std::io::ErrorKind::InvalidInput,
+            Error::NoProgress => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
+
+/// WriterTo is the trait that wraps the `write_to` method.
+///
+/// `write_to` writes data to `w` until there's no more data to write or
+/// when an error occurs. The return value is the number of bytes written.
+/// Any error encountered during the write is also returned.
+///
+/// [`copy_from`] copies from types implementing it without the intermediate buffer used by [`copy`].
+pub trait WriterTo {
+    /// Writes data to `w`.
+    fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64>;
+}
+
+/// ReaderFrom is the trait that wraps the `read_from` method.
+///
+/// `read_from` reads data from `r` until EOF or error.
+/// The return value is the number of bytes read.
+/// Any error except EOF encountered during the read is also returned.
+///
+/// [`copy_into`] copies into types implementing it without the intermediate buffer used by [`copy`].
+pub trait ReaderFrom {
+    /// Reads data from `r`.
+    fn read_from<R: std::io::Read + ?Sized>(&mut self, r: &mut R) -> std::io::Result<u64>;
+}
+
+/// ReaderAt is the trait that wraps the basic `read_at` method.
+///
+/// `read_at` reads up to `buf.len()` bytes into `buf` starting at offset `off` in the
+/// underlying input source. It returns the number of bytes read (0 <= n <= `buf.len()`),
+/// `Ok(0)` means the offset is at or beyond the end of the input.
+///
+/// Unlike [`std::io::Read`], `read_at` takes `&self` and does not use or
+/// modify a shared seek offset, so clients of `read_at` can execute parallel
+/// `read_at` calls on the same input source.
+pub trait ReaderAt {
+    /// Reads up to `buf.len()` bytes at offset `off`.
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize>;
+}
+
+/// WriterAt is the trait that wraps the basic `write_at` method.
+///
+/// `write_at` writes `buf.len()` bytes from `buf` to the underlying data stream
+/// at offset `off`. It returns the number of bytes written from `buf`
+/// (0 <= n <= `buf.len()`) and any error encountered that caused the write to stop early.
+/// The impls in this crate return an error rather than a short `Ok`.
+///
+/// If `write_at` is writing to a destination with a seek offset,
+/// `write_at` should not affect nor be affected by the underlying
+/// seek offset.
+pub trait WriterAt {
+    /// Writes `buf` at offset `off`.
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize>;
+}
+
+impl<T: ReaderAt + ?Sized> ReaderAt for &T {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        (**self).read_at(buf, off)
+    }
+}
+
+impl<T: ReaderAt + ?Sized> ReaderAt for Box<T> {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        (**self).read_at(buf, off)
+    }
+}
+
+impl<T: ReaderAt + ?Sized> ReaderAt for alloc::sync::Arc<T> {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        (**self).read_at(buf, off)
+    }
+}
+
+impl ReaderAt for [u8] {
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        match usize::try_from(off) {
+            Ok(off) if off < self.len() => Ok(crate::copy(&self[off..], buf)),
+            _ => Ok(0),
+        }
+    }
+}
+
+impl ReaderAt for Vec<u8> {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        self.as_slice().read_at(buf, off)
+    }
+}
+
+impl<T: WriterAt + ?Sized> WriterAt for &mut T {
+    #[inline]
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        (**self).write_at(buf, off)
+    }
+}
+
+impl<T: WriterAt + ?Sized> WriterAt for Box<T> {
+    #[inline]
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        (**self).write_at(buf, off)
+    }
+}
+
+impl WriterAt for [u8] {
+    /// Writes all of `buf`, or returns an error and writes nothing if it
+    /// does not fit before the end of the slice.
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        match usize::try_from(off)
+            .ok()
+            .and_then(|off| Some(off..off.checked_add(buf.len())?))
+        {
+            Some(r) if r.end <= self.len() => {
+                self[r].copy_from_slice(buf);
+                Ok(buf.len())
+            }
+            _ if buf.is_empty() => Ok(0),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::WriteZero,
+                "write_at: out of range",
+            )),
+        }
+    }
+}
+
+impl WriterAt for Vec<u8> {
+    /// Grows the vector as needed, zero filling any gap before `off`.
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        let off = usize::try_from(off).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "offset overflow")
+        })?;
+        let end = off.checked_add(buf.len()).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "offset overflow")
+        })?;
+        if end > self.len() {
+            self.resize(end, 0);
+        }
+        self[off..end].copy_from_slice(buf);
+        Ok(buf.len())
+    }
+}
+
+/// On Windows this uses `seek_read`, which moves the file's cursor, so
+/// `read_at` must not be mixed with [`std::io::Read`] or [`std::io::Seek`]
+/// on the same file there.
+#[cfg(any(unix, windows))]
+impl ReaderAt for std::fs::File {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::FileExt::read_at(self, buf, off)
+        }
+
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::FileExt::seek_read(self, buf, off)
+        }
+    }
+}
+
+/// On Windows this uses `seek_write`, which moves the file's cursor, so
+/// `write_at` must not be mixed with [`std::io::Write`] or [`std::io::Seek`]
+/// on the same file there.
+#[cfg(any(unix, windows))]
+impl WriterAt for &std::fs::File {
+    #[inline]
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::FileExt::write_at(*self, buf, off)
+        }
+
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::FileExt::seek_write(*self, buf, off)
+        }
+    }
+}
+
+/// Same as the impl for `&File`, including the cursor caveat on Windows.
+#[cfg(any(unix, windows))]
+impl WriterAt for std::fs::File {
+    #[inline]
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        (&*self).write_at(buf, off)
+    }
+}
+
+const DEFAULT_BUFFER_SIZE: usize = 32 * 1024;
+
+/// Reads from `r` into `buf` until it has read at least `min` bytes.
+/// It returns the number of bytes copied.
+///
+/// The error is [`Error::Eof`] only if no bytes were read.
+/// If an EOF happens after reading fewer than `min` bytes,
+/// [`Error::UnexpectedEof`] is returned.
+/// If `min` is greater than the length of `buf`, [`Error::ShortBuffer`] is returned.
+/// On return, n >= min if and only if the result is `Ok`.
+/// If `r` returns an error having read at least `min` bytes, the error is dropped.
+pub fn read_at_least<R: std::io::Read + ?Sized>(
+    r: &mut R,
+    buf: &mut [u8],
+    min: usize,
+) -> Result<usize, Error> {
+    if buf.len() < min {
+        return Err(Error::ShortBuffer);
+    }
+
+    let mut n = 0;
+    while n < min {
+        match r.read(&mut buf[n..]) {
+            Ok(0) if n == 0 => return Err(Error::Eof),
+            Ok(0) => return Err(Error::UnexpectedEof),
+            Ok(nn) => n += nn,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(n)
+}
+
+/// Reads exactly `buf.len()` bytes from `r` into `buf`.
+/// It returns the number of bytes copied.
+///
+/// The error is [`Error::Eof`] only if no bytes were read.
+/// If an EOF happens after reading some but not all the bytes,
+/// [`Error::UnexpectedEof`] is returned.
+/// On return, n == `buf.len()` if and only if the result is `Ok`.
+/// If `r` returns an error having read at least `buf.len()` bytes, the error is dropped.
+#[inline]
+pub fn read_full<R: std::io::Read + ?Sized>(r: &mut R, buf: &mut [u8]) -> Result<usize, Error> {
+    let min = buf.len();
+    read_at_least(r, buf, min)
+}
+
+/// Copies `n` bytes (or until an error) from `src` to `dst`.
+/// It returns the number of bytes copied.
+///
+/// On return, written == n if and only if the result is `Ok`.
+/// If fewer than `n` bytes are available in `src`, [`Error::Eof`] is returned.
+pub fn copy_n<W, R>(dst: &mut W, src: &mut R, n: u64) -> Result<u64, Error>
+where
+    W: std::io::Write + ?Sized,
+    R: std::io::Read + ?Sized,
+{
+    let written = copy(dst, &mut std::io::Read::take(src, n))?;
+    if written == n {
+        return Ok(n);
+    }
+    // src stopped early; must have been EOF.
+    Err(Error::Eof)
+}
+
+/// Copies from `src` to `dst` until either EOF is reached
+/// on `src` or an error occurs. It returns the number of bytes
+/// copied.
+///
+/// A successful copy returns `Ok`, not [`Error::Eof`].
+/// Because `copy` is defined to read from `src` until EOF, it does
+/// not treat an EOF from `read` as an error to be reported.
+///
+/// Rust has no runtime interface checks, so the `WriterTo`/`ReaderFrom` fast paths
+/// Go's `io.Copy` takes are the separate entry points [`copy_from`] and [`copy_into`].
+pub fn copy<W, R>(dst: &mut W, src: &mut R) -> Result<u64, Error>
+where
+    W: std::io::Write + ?Sized,
+    R: std::io::Read + ?Sized,
+{
+    let mut buf = vec![0; DEFAULT_BUFFER_SIZE];
+    copy_buffer(dst, src, &mut buf)
+}
+
+/// Identical to [`copy`] except that it stages through the
+/// provided buffer rather than allocating a temporary one.
+///
+/// # Panics
+/// Panics if `buf` is empty.
+pub fn copy_buffer<W, R>(dst: &mut W, src: &mut R, buf: &mut [u8]) -> Result<u64, Error>
+where
+    W: std::io::Write + ?Sized,
+    R: std::io::Read + ?Sized,
+{
+    assert!(!buf.is_empty(), "empty buffer in copy_buffer");
+
+    let mut written = 0u64;
+    loop {
+        let nr = match src.read(buf) {
+            Ok(0) => return Ok(written),
+            Ok(nr) => nr,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        // Partial writes are allowed, only a write of nothing stops the copy.
+        let mut off = 0;
+        while off < nr {
+            match dst.write(&buf[off..nr]) {
+                Ok(0) => return Err(Error::ShortWrite),
+                Ok(nw) if nw > nr - off => return Err(Error::InvalidWrite),
+                Ok(nw) => {
+                    off += nw;
+                    written += nw as u64;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Like [`copy`], but copies with `src`'s [`WriterTo::write_to`] instead of
+/// staging through a buffer.
+#[inline]
+pub fn copy_from<W, R>(dst: &mut W, src: &mut R) -> Result<u64, Error>
+where
+    W: std::io::Write + ?Sized,
+    R: WriterTo + ?Sized,
+{
+    src.write_to(dst).map_err(Into::into)
+}
+
+/// Like [`copy`], but copies with `dst`'s [`ReaderFrom::read_from`] instead of
+/// staging through a buffer.
+#[inline]
+pub fn copy_into<W, R>(dst: &mut W, src: &mut R) -> Result<u64, Error>
+where
+    W: ReaderFrom + ?Sized,
+    R: std::io::Read + ?Sized,
+{
+    dst.read_from(src).map_err(Into::into)
+}
+
+/// Returns a reader that reads from `r`
+/// but stops with EOF after `n` bytes.
+/// The underlying implementation is a [`LimitedReader`].
+#[inline]
+pub const fn limit_reader<R>(r: R, n: u64) -> LimitedReader<R> {
+    LimitedReader::new(r, n)
+}
+
+/// A LimitedReader reads from `r` but limits the amount of
+/// data returned to just `n` bytes. Each call to `read`
+/// updates `n` to reflect the new amount remaining.
+/// `read` returns EOF when `n == 0` or when the underlying `r` returns EOF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LimitedReader<R> {
+    r: R,
+    n: u64,
+}
+
+impl<R> LimitedReader<R> {
+    /// Returns a reader that reads from `r` but stops with EOF after `n` bytes.
+    #[inline]
+    pub const fn new(r: R, n: u64) -> Self {
+        Self { r, n }
+    }
+
+    /// Returns the max bytes remaining.
+    #[inline]
+    pub const fn remaining(&self) -> u64 {
+        self.n
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub const fn get_ref(&self) -> &R {
+        &self.r
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.r
+    }
+
+    /// Consumes the [`LimitedReader`], returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.n == 0 {
+            return Ok(0);
+        }
+
+        let max = buf.len().min(usize::try_from(self.n).unwrap_or(usize::MAX));
+        let n = self.r.read(&mut buf[..max])?;
+        self.n -= n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Closer> Closer for LimitedReader<R> {
+    fn close(&mut self) -> std::io::Result<()> {
+        self.r.close()
+    }
+}
+
+/// Returns a reader that writes to `w` what it reads from `r`.
+/// All reads from `r` performed through it are matched with
+/// corresponding writes to `w`. There is no internal buffering -
+/// the write must complete before the read completes.
+/// Any error encountered while writing is reported as a read error.
+#[inline]
+pub const fn tee_reader<R, W>(r: R, w: W) -> TeeReader<R, W> {
+    TeeReader::new(r, w)
+}
+
+/// A reader that writes to `w` what it reads from `r`, see [`tee_reader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TeeReader<R, W> {
+    r: R,
+    w: W,
+}
+
+impl<R, W> TeeReader<R, W> {
+    /// Returns a reader that writes to `w` what it reads from `r`.
+    #[inline]
+    pub const fn new(r: R, w: W) -> Self {
+        Self { r, w }
+    }
+
+    /// Consumes the [`TeeReader`], returning the underlying reader and writer.
+    #[inline]
+    pub fn into_inner(self) -> (R, W) {
+        (self.r, self.w)
+    }
+}
+
+impl<R: std::io::Read, W: std::io::Write> std::io::Read for TeeReader<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.r.read(buf)?;
+        if n > 0 {
+            self.w.write_all(&buf[..n])?;
+        }
+        Ok(n)
+    }
+}
+
+impl<R: Closer, W> Closer for TeeReader<R, W> {
+    /// Closes the underlying reader.
+    fn close(&mut self) -> std::io::Result<()> {
+        self.r.close()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_at_least() {
+        let mut r = Cursor::new(b"0123".to_vec());
+        let mut buf = [0; 2];
+        assert_eq!(read_at_least(&mut r, &mut buf, 2).unwrap(), 2);
+        assert_eq!(&buf, b"01");
+        assert_eq!(read_at_least(&mut r, &mut buf, 2).unwrap(), 2);
+        assert_eq!(&buf, b"23");
+        assert!(read_at_least(&mut r, &mut buf, 2).unwrap_err().is_eof());
+        assert!(matches!(
+            read_at_least(&mut r, &mut buf, 3).unwrap_err(),
+            Error::ShortBuffer
+        ));
+        assert_eq!(read_at_least(&mut r, &mut buf, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_full() {
+        let mut r = Cursor::new(b"abc".to_vec());
+        let mut buf = [0; 2];
+        assert_eq!(read_full(&mut r, &mut buf).unwrap(), 2);
+        assert_eq!(&buf, b"ab");
+        assert!(read_full(&mut r, &mut buf).unwrap_err().is_unexpected_eof());
+        assert!(read_full(&mut r, &mut buf).unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn test_copy_n() {
+        let mut rb = Cursor::new(b"hello, world.".to_vec());
+        let mut wb = Vec::new();
+        assert_eq!(copy_n(&mut wb, &mut rb, 5).unwrap(), 5);
+        assert_eq!(wb, b"hello");
+
+        let mut rb = Cursor::new(b"foo".to_vec());
+        let mut wb = Vec::new();
+        assert!(copy_n(&mut wb, &mut rb, 5).unwrap_err().is_eof());
+        assert_eq!(wb, b"foo");
+    }
+
+    #[test]
+    fn test_copy() {
+        let mut rb = Cursor::new(b"hello, world.".to_vec());
+        let mut wb = Vec::new();
+        assert_eq!(copy(&mut wb, &mut rb).unwrap(), 13);
+        assert_eq!(wb, b"hello, world.");
+
+        let mut rb = Cursor::new(b"hello, world.".to_vec());
+        let mut wb = Vec::new();
+        let mut buf = [0; 1];
+        assert_eq!(copy_buffer(&mut wb, &mut rb, &mut buf).unwrap(), 13);
+        assert_eq!(wb, b"hello, world.");
+    }
+
+    // Panics if copied through a buffer, so only the fast paths work.
+    struct FastOnly(Vec<u8>);
+
+    impl std::io::Read for FastOnly {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            panic!("read called instead of write_to")
+        }
+    }
+
+    impl std::io::Write for FastOnly {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            panic!("write called instead of read_from")
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl WriterTo for FastOnly {
+        fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+            w.write_all(&self.0)?;
+            Ok(core::mem::take(&mut self.0).len() as u64)
+        }
+    }
+
+    impl ReaderFrom for FastOnly {
+        fn read_from<R: std::io::Read + ?Sized>(&mut self, r: &mut R) -> std::io::Result<u64> {
+            r.read_to_end(&mut self.0).map(|n| n as u64)
+        }
+    }
+
+    #[test]
+    fn test_copy_fast_paths() {
+        let mut src = FastOnly(b"hello, world.".to_vec());
+        let mut wb = Vec::new();
+        assert_eq!(copy_from(&mut wb, &mut src).unwrap(), 13);
+        assert_eq!(wb, b"hello, world.");
+
+        let mut dst = FastOnly(Vec::new());
+        let mut rb = Cursor::new(b"hello, world.".to_vec());
+        assert_eq!(copy_into(&mut dst, &mut rb).unwrap(), 13);
+        assert_eq!(dst.0, b"hello, world.");
+    }
+
+    /// Accepts at most `n` bytes per call.
+    struct ShortWriter {
+        n: usize,
+        data: Vec<u8>,
+    }
+
+    impl std::io::Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = self.n.min(buf.len());
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_copy_short_write() {
+        let mut w = ShortWriter { n: 2, data: vec![] };
+        let mut rb = Cursor::new(b"hello".to_vec());
+        assert_eq!(copy(&mut w, &mut rb).unwrap(), 5);
+        assert_eq!(w.data, b"hello");
+
+        let mut w = ShortWriter { n: 0, data: vec![] };
+        let mut rb = Cursor::new(b"hello".to_vec());
+        assert!(matches!(
+            copy(&mut w, &mut rb).unwrap_err(),
+            Error::ShortWrite
+        ));
+    }
+
+    #[test]
+    fn test_writer_at() {
+        let mut b = [0u8; 5];
+        assert_eq!(b[..].write_at(b"abc", 1).unwrap(), 3);
+        assert_eq!(&b, b"\0abc\0");
+        let err = b[..].write_at(b"xyz", 3).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+        assert_eq!(&b, b"\0abc\0");
+        assert!(b[..].write_at(b"x", u64::MAX).is_err());
+        assert_eq!(b[..].write_at(b"", 9).unwrap(), 0);
+
+        let mut v = b"ab".to_vec();
+        assert_eq!(v.write_at(b"cd", 3).unwrap(), 2);
+        assert_eq!(v, b"ab\0cd");
+        let err = v.write_at(b"x", usize::MAX as u64).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_error_round_trip() {
+        let err: std::io::Error = Error::UnexpectedEof.into();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(Error::from(err).is_unexpected_eof());
+    }
+
+    #[test]
+    fn test_limit_reader() {
+        use std::io::Read;
+
+        let mut lr = limit_reader(Cursor::new(b"hello, world".to_vec()), 5);
+        let mut buf = Vec::new();
+        assert_eq!(lr.read_to_end(&mut buf).unwrap(), 5);
+        assert_eq!(buf, b"hello");
+        assert_eq!(lr.remaining(), 0);
+    }
+
+    #[test]
+    fn test_tee_reader() {
+        use std::io::Read;
+
+        let src = b"hello, world";
+        let mut dst = [0; 12];
+        let mut wb = Vec::new();
+        let mut r = tee_reader(&src[..], &mut wb);
+        assert_eq!(read_full(&mut r, &mut dst).unwrap(), src.len());
+        assert_eq!(&dst, src);
+        assert_eq!(r.read(&mut dst).unwrap(), 0);
+        assert_eq!(wb, src);
+    }
+
+    #[test]
+    fn test_composite_traits() {
+        fn assert_read_write_closer<T: ReadWriteCloser>(_: &T) {}
+        fn assert_read_seek_closer<T: ReadSeekCloser>(_: &T) {}
+        fn assert_read_write_seeker<T: ReadWriteSeeker>(_: &T) {}
+
+        let c = nop_closer(Cursor::new(Vec::<u8>::new()));
+        assert_read_write_closer(&c);
+        assert_read_seek_closer(&c);
+        assert_read_write_seeker(&c);
+    }
+
+    #[test]
+    fn test_byte_scanner() {
+        let mut c = Cursor::new(b"ab");
+        assert_eq!(c.read_byte().unwrap(), b'a');
+        c.unread_byte().unwrap();
+        assert_eq!(c.read_byte().unwrap(), b'a');
+        assert_eq!(c.read_byte().unwrap(), b'b');
+        assert!(c.read_byte().unwrap_err().is_eof());
+
+        let mut s = &b"x"[..];
+        assert_eq!(s.read_byte().unwrap(), b'x');
+        assert!(s.read_byte().unwrap_err().is_eof());
+
+        let mut w = Vec::new();
+        w.write_byte(b'a').unwrap();
+        write_string(&mut w, "bc").unwrap();
+        assert_eq!(w, b"abc");
+    }
+}
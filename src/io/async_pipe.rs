@@ -1,7 +1,11 @@
 use super::PipeError;
+use core::{
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
 use std::sync::Once;
 
-use alloc::sync::Arc;
+use alloc::{collections::VecDeque, sync::Arc, vec::Vec};
 use async_channel::{bounded, unbounded, Receiver, Sender};
 use async_lock::Mutex;
 use futures_util::{select, FutureExt};
@@ -225,6 +229,356 @@ pub fn pipe() -> (PipeReader, PipeWriter) {
     )
 }
 
+#[derive(Debug)]
+struct BufferedState {
+    buf: VecDeque<u8>,
+    rerr: Option<PipeError>,
+    werr: Option<PipeError>,
+    readers: Vec<Waker>,
+    writers: Vec<Waker>,
+}
+
+impl BufferedState {
+    /// Adds `waker` to `wakers`, unless a waker that wakes the same task is
+    /// already there, so that a task polled again and again while it waits
+    /// is only registered once.
+    #[inline]
+    fn register(wakers: &mut Vec<Waker>, waker: &Waker) {
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    #[inline]
+    fn wake_readers(&mut self) {
+        self.readers.drain(..).for_each(Waker::wake);
+    }
+
+    #[inline]
+    fn wake_writers(&mut self) {
+        self.writers.drain(..).for_each(Waker::wake);
+    }
+}
+
+#[derive(Debug)]
+struct BufferedInner {
+    cap: usize,
+    state: std::sync::Mutex<BufferedState>,
+}
+
+impl BufferedInner {
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, BufferedState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lock().buf.len()
+    }
+
+    #[inline]
+    fn available(&self) -> usize {
+        self.cap - self.len()
+    }
+}
+
+/// A [`BufferedPipeReader`] is the read half of a buffered pipe.
+#[derive(Debug)]
+pub struct BufferedPipeReader {
+    inner: Arc<BufferedInner>,
+}
+
+impl BufferedPipeReader {
+    /// Reads data from the pipe, waiting until the internal buffer
+    /// holds at least one byte or the write end is closed.
+    /// Data still buffered when the write end is closed can be read,
+    /// after that the error the write end is closed with is returned,
+    /// which is [`PipeError::Eof`].
+    pub async fn read(&self, buf: &mut [u8]) -> Result<usize, PipeError> {
+        futures_util::future::poll_fn(|cx| self.poll_read_in(cx, buf)).await
+    }
+
+    fn poll_read_in(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize, PipeError>> {
+        let mut state = self.inner.lock();
+        if state.rerr.is_some() {
+            return Poll::Ready(Err(PipeError::Closed));
+        }
+
+        if !state.buf.is_empty() {
+            let n = buf.len().min(state.buf.len());
+            for (dst, src) in buf.iter_mut().zip(state.buf.drain(..n)) {
+                *dst = src;
+            }
+            state.wake_writers();
+            return Poll::Ready(Ok(n));
+        }
+
+        if let Some(err) = state.werr {
+            return Poll::Ready(Err(err));
+        }
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        BufferedState::register(&mut state.readers, cx.waker());
+        Poll::Pending
+    }
+
+    /// Returns the number of bytes buffered and ready to be read.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if there is no buffered data.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many bytes can be written before the writer waits.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.inner.available()
+    }
+
+    /// Returns the capacity of the pipe.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.cap
+    }
+
+    #[inline]
+    fn close_read(&self) {
+        let mut state = self.inner.lock();
+        if state.rerr.is_none() {
+            state.rerr = Some(PipeError::Closed);
+        }
+        state.wake_readers();
+        state.wake_writers();
+    }
+}
+
+impl Drop for BufferedPipeReader {
+    fn drop(&mut self) {
+        self.close_read();
+    }
+}
+
+impl super::Closer for BufferedPipeReader {
+    /// Closes the reader; subsequent writes to the write half of the pipe
+    /// will return the error [`PipeError::Closed`].
+    fn close(&mut self) -> std::io::Result<()> {
+        self.close_read();
+        Ok(())
+    }
+}
+
+impl futures_util::io::AsyncRead for BufferedPipeReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read(cx, buf)
+    }
+}
+
+impl futures_util::io::AsyncRead for &BufferedPipeReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.poll_read_in(cx, buf).map(|rst| match rst {
+            Ok(n) => Ok(n),
+            Err(PipeError::Eof) => Ok(0),
+            Err(e) => Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, e)),
+        })
+    }
+}
+
+/// A [`BufferedPipeWriter`] is the write half of a buffered pipe.
+#[derive(Debug)]
+pub struct BufferedPipeWriter {
+    inner: Arc<BufferedInner>,
+}
+
+impl BufferedPipeWriter {
+    /// Writes data to the pipe, waiting while the internal buffer is full
+    /// until all the data has been buffered or the read end is closed.
+    /// If the read end is closed, [`PipeError::Closed`] is returned.
+    ///
+    /// If the pipe is closed after part of `buf` has been buffered, the
+    /// number of bytes buffered is returned, and the error is returned by
+    /// the next call.
+    pub async fn write(&self, buf: &[u8]) -> Result<usize, PipeError> {
+        let mut n = 0;
+        loop {
+            let nw =
+                match futures_util::future::poll_fn(|cx| self.poll_write_in(cx, &buf[n..])).await {
+                    Ok(nw) => nw,
+                    Err(_) if n > 0 => return Ok(n),
+                    Err(e) => return Err(e),
+                };
+            n += nw;
+            if n == buf.len() {
+                return Ok(n);
+            }
+        }
+    }
+
+    fn poll_write_in(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, PipeError>> {
+        let mut state = self.inner.lock();
+        if state.werr.is_some() {
+            return Poll::Ready(Err(PipeError::Closed));
+        }
+
+        if let Some(err) = state.rerr {
+            return Poll::Ready(Err(err));
+        }
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let space = self.inner.cap - state.buf.len();
+        if space > 0 {
+            let n = space.min(buf.len());
+            state.buf.extend(&buf[..n]);
+            state.wake_readers();
+            return Poll::Ready(Ok(n));
+        }
+
+        BufferedState::register(&mut state.writers, cx.waker());
+        Poll::Pending
+    }
+
+    /// Returns the number of bytes buffered and not yet read.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if there is no buffered data.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many bytes can be written before the writer waits.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.inner.available()
+    }
+
+    /// Returns the capacity of the pipe.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.cap
+    }
+
+    #[inline]
+    fn close_write(&self) {
+        let mut state = self.inner.lock();
+        if state.werr.is_none() {
+            state.werr = Some(PipeError::Eof);
+        }
+        state.wake_readers();
+        state.wake_writers();
+    }
+}
+
+impl Drop for BufferedPipeWriter {
+    fn drop(&mut self) {
+        self.close_write();
+    }
+}
+
+impl super::Closer for BufferedPipeWriter {
+    /// Closes the writer; once the buffered data is consumed, subsequent reads
+    /// from the read half of the pipe will return [`PipeError::Eof`].
+    fn close(&mut self) -> std::io::Result<()> {
+        self.close_write();
+        Ok(())
+    }
+}
+
+impl futures_util::io::AsyncWrite for BufferedPipeWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.close_write();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl futures_util::io::AsyncWrite for &BufferedPipeWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.poll_write_in(cx, buf)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::BrokenPipe, e))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.close_write();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Creates an asynchronous in-memory pipe with an internal buffer of
+/// `capacity` bytes.
+///
+/// Unlike [`pipe`], the writer can run ahead of the reader: a write
+/// only waits when the buffer is full, and a read only waits when the
+/// buffer is empty. Closing (or dropping) either half has the same
+/// semantics as [`pipe`], except that data already buffered can still be
+/// read after the write half is closed.
+///
+/// # Panics
+/// Panics if `capacity` is zero.
+pub fn buffered(capacity: usize) -> (BufferedPipeReader, BufferedPipeWriter) {
+    assert!(
+        capacity > 0,
+        "pipe: buffered pipe capacity must be non-zero"
+    );
+    let inner = Arc::new(BufferedInner {
+        cap: capacity,
+        state: std::sync::Mutex::new(BufferedState {
+            buf: VecDeque::with_capacity(capacity),
+            rerr: None,
+            werr: None,
+            readers: Vec::new(),
+            writers: Vec::new(),
+        }),
+    });
+    (
+        BufferedPipeReader {
+            inner: inner.clone(),
+        },
+        BufferedPipeWriter { inner },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,4 +643,83 @@ mod tests {
         let nn = rx.recv().await.unwrap();
         assert_eq!(nn, 0, "final read got {}", nn);
     }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_buffered_pipe() {
+        let (r, w) = buffered(8);
+        assert_eq!(w.write(b"hello").await.unwrap(), 5);
+        assert_eq!(r.len(), 5);
+        assert_eq!(w.available(), 3);
+
+        let mut buf = [0; 3];
+        assert_eq!(r.read(&mut buf).await.unwrap(), 3);
+        assert_eq!(&buf, b"hel");
+
+        drop(w);
+        assert_eq!(r.read(&mut buf).await.unwrap(), 2);
+        assert_eq!(&buf[..2], b"lo");
+        assert_eq!(r.read(&mut buf).await.unwrap_err(), PipeError::Eof);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_buffered_pipe_backpressure() {
+        use futures_util::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut r, mut w) = buffered(4);
+        let data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+        let expected = data.clone();
+        let handle = tokio::spawn(async move {
+            w.write_all(&data).await.unwrap();
+            assert!(w.len() <= w.capacity());
+        });
+
+        let mut got = Vec::new();
+        r.read_to_end(&mut got).await.unwrap();
+        handle.await.unwrap();
+        assert_eq!(got, expected);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_buffered_pipe_close_read() {
+        let (r, w) = buffered(2);
+        drop(r);
+        assert_eq!(w.write(b"a").await.unwrap_err(), PipeError::Closed);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_buffered_pipe_partial_write() {
+        let (r, w) = buffered(2);
+        let handle = tokio::spawn(async move {
+            // Only two bytes fit before the reader goes away.
+            assert_eq!(w.write(b"abcd").await.unwrap(), 2);
+            assert_eq!(w.write(b"cd").await.unwrap_err(), PipeError::Closed);
+        });
+        while r.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+        drop(r);
+        handle.await.unwrap();
+    }
+
+    #[test]
+    fn test_buffered_pipe_waker_registered_once() {
+        let (r, w) = buffered(1);
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut buf = [0; 1];
+        for _ in 0..10 {
+            assert!(r.poll_read_in(&mut cx, &mut buf).is_pending());
+        }
+        assert_eq!(r.inner.lock().readers.len(), 1);
+
+        assert!(w.poll_write_in(&mut cx, b"a").is_ready());
+        for _ in 0..10 {
+            assert!(w.poll_write_in(&mut cx, b"b").is_pending());
+        }
+        assert_eq!(w.inner.lock().writers.len(), 1);
+    }
 }
@@ -0,0 +1,403 @@
+use super::Closer;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{
+    io::{AsyncRead, AsyncWrite},
+    ready,
+};
+
+/// The asynchronous version of [`MultiReader`](super::MultiReader).
+///
+/// A reader that's the logical concatenation of the provided input readers.
+/// They're read sequentially. Once all inputs have returned EOF,
+/// `poll_read` will return EOF (`Ok(0)`).
+#[derive(Debug, Clone)]
+pub struct AsyncMultiReader<R> {
+    readers: Vec<R>,
+    idx: usize,
+}
+
+impl<R> AsyncMultiReader<R> {
+    /// Returns a [`AsyncMultiReader`] that's the logical concatenation of the provided input readers.
+    #[inline]
+    pub fn new(readers: impl IntoIterator<Item = R>) -> Self {
+        Self {
+            readers: readers.into_iter().collect(),
+            idx: 0,
+        }
+    }
+
+    /// Consumes the [`AsyncMultiReader`], returning the underlying readers
+    /// which have not been drained yet.
+    #[inline]
+    pub fn into_inner(mut self) -> Vec<R> {
+        self.readers.drain(..self.idx);
+        self.readers
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncMultiReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        while this.idx < this.readers.len() {
+            let n = ready!(Pin::new(&mut this.readers[this.idx]).poll_read(cx, buf))?;
+            if n == 0 && !buf.is_empty() {
+                // This reader is at EOF, move on to the next one.
+                this.idx += 1;
+                continue;
+            }
+            return Poll::Ready(Ok(n));
+        }
+        Poll::Ready(Ok(0))
+    }
+}
+
+impl<R: Closer> Closer for AsyncMultiReader<R> {
+    /// Closes all the underlying readers, returning the first error encountered.
+    fn close(&mut self) -> std::io::Result<()> {
+        let mut rst = Ok(());
+        for r in self.readers.iter_mut() {
+            if let Err(e) = r.close() {
+                if rst.is_ok() {
+                    rst = Err(e);
+                }
+            }
+        }
+        rst
+    }
+}
+
+/// The asynchronous version of [`MultiWriter`](super::MultiWriter).
+///
+/// A writer that duplicates its writes to all the provided writers.
+///
+/// Because a write may be pending on one writer after it completed on
+/// another, data accepted by `poll_write` is staged in an internal buffer
+/// and delivered to every writer before more data is accepted.
+/// Use `poll_flush` (or `poll_close`) to make sure all the writers have received the data.
+#[derive(Debug, Clone)]
+pub struct AsyncMultiWriter<W> {
+    writers: Vec<W>,
+    offsets: Vec<usize>,
+    pending: Vec<u8>,
+}
+
+impl<W> AsyncMultiWriter<W> {
+    /// Creates a writer that duplicates its writes to all the provided writers.
+    #[inline]
+    pub fn new(writers: impl IntoIterator<Item = W>) -> Self {
+        let writers = writers.into_iter().collect::<Vec<_>>();
+        Self {
+            offsets: vec![0; writers.len()],
+            writers,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Consumes the [`AsyncMultiWriter`], returning the underlying writers.
+    #[inline]
+    pub fn into_inner(self) -> Vec<W> {
+        self.writers
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncMultiWriter<W> {
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let mut done = true;
+        for (w, off) in self.writers.iter_mut().zip(self.offsets.iter_mut()) {
+            while *off < self.pending.len() {
+                match Pin::new(&mut *w).poll_write(cx, &self.pending[*off..]) {
+                    Poll::Ready(Ok(0)) => return Poll::Ready(Err(super::Error::ShortWrite.into())),
+                    Poll::Ready(Ok(n)) => *off += n,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => {
+                        done = false;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !done {
+            return Poll::Pending;
+        }
+
+        self.pending.clear();
+        self.offsets.iter_mut().for_each(|off| *off = 0);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncMultiWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        this.pending.extend_from_slice(buf);
+        if let Poll::Ready(Err(e)) = this.poll_drain(cx) {
+            return Poll::Ready(Err(e));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        for w in this.writers.iter_mut() {
+            ready!(Pin::new(w).poll_flush(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        for w in this.writers.iter_mut() {
+            ready!(Pin::new(w).poll_close(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: Closer> Closer for AsyncMultiWriter<W> {
+    /// Closes all the underlying writers, returning the first error encountered.
+    fn close(&mut self) -> std::io::Result<()> {
+        let mut rst = Ok(());
+        for w in self.writers.iter_mut() {
+            if let Err(e) = w.close() {
+                if rst.is_ok() {
+                    rst = Err(e);
+                }
+            }
+        }
+        rst
+    }
+}
+
+/// The asynchronous version of [`LimitedReader`](super::LimitedReader).
+///
+/// Reads from `r` but limits the amount of data returned to just `n` bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AsyncLimitedReader<R> {
+    r: R,
+    n: u64,
+}
+
+impl<R> AsyncLimitedReader<R> {
+    /// Returns a reader that reads from `r` but stops with EOF after `n` bytes.
+    #[inline]
+    pub const fn new(r: R, n: u64) -> Self {
+        Self { r, n }
+    }
+
+    /// Returns the max bytes remaining.
+    #[inline]
+    pub const fn remaining(&self) -> u64 {
+        self.n
+    }
+
+    /// Consumes the [`AsyncLimitedReader`], returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncLimitedReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        if this.n == 0 {
+            return Poll::Ready(Ok(0));
+        }
+
+        let max = buf.len().min(usize::try_from(this.n).unwrap_or(usize::MAX));
+        let n = ready!(Pin::new(&mut this.r).poll_read(cx, &mut buf[..max]))?;
+        this.n -= n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<R: Closer> Closer for AsyncLimitedReader<R> {
+    fn close(&mut self) -> std::io::Result<()> {
+        self.r.close()
+    }
+}
+
+/// The asynchronous version of [`TeeReader`](super::TeeReader).
+///
+/// All reads from `r` performed through it are matched with
+/// corresponding writes to `w`. Data read from `r` is handed to the
+/// caller only after it has been completely written to `w`.
+#[derive(Debug, Clone)]
+pub struct AsyncTeeReader<R, W> {
+    r: R,
+    w: W,
+    buf: Vec<u8>,
+    written: usize,
+    served: usize,
+}
+
+impl<R, W> AsyncTeeReader<R, W> {
+    /// Returns a reader that writes to `w` what it reads from `r`.
+    #[inline]
+    pub const fn new(r: R, w: W) -> Self {
+        Self {
+            r,
+            w,
+            buf: Vec::new(),
+            written: 0,
+            served: 0,
+        }
+    }
+
+    /// Consumes the [`AsyncTeeReader`], returning the underlying reader and writer.
+    #[inline]
+    pub fn into_inner(self) -> (R, W) {
+        (self.r, self.w)
+    }
+}
+
+impl<R: AsyncRead + Unpin, W: AsyncWrite + Unpin> AsyncRead for AsyncTeeReader<R, W> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        if this.served == this.buf.len() {
+            this.buf.resize(buf.len(), 0);
+            this.written = 0;
+            this.served = 0;
+            let n = match Pin::new(&mut this.r).poll_read(cx, &mut this.buf) {
+                Poll::Ready(Ok(n)) => n,
+                rst => {
+                    // Nothing was read, so drop the scratch space rather
+                    // than tee and return it on the next call.
+                    this.buf.clear();
+                    return rst;
+                }
+            };
+            this.buf.truncate(n);
+            if n == 0 {
+                return Poll::Ready(Ok(0));
+            }
+        }
+
+        while this.written < this.buf.len() {
+            let n = ready!(Pin::new(&mut this.w).poll_write(cx, &this.buf[this.written..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(super::Error::ShortWrite.into()));
+            }
+            this.written += n;
+        }
+
+        let n = crate::copy(&this.buf[this.served..], buf);
+        this.served += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<R: Closer, W> Closer for AsyncTeeReader<R, W> {
+    /// Closes the underlying reader.
+    fn close(&mut self) -> std::io::Result<()> {
+        self.r.close()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+    #[tokio::test]
+    async fn test_async_multi_reader() {
+        let mut mr = AsyncMultiReader::new([
+            Cursor::new(b"foo ".to_vec()),
+            Cursor::new(Vec::new()),
+            Cursor::new(b"bar baz".to_vec()),
+        ]);
+        let mut got = Vec::new();
+        mr.read_to_end(&mut got).await.unwrap();
+        assert_eq!(got, b"foo bar baz");
+    }
+
+    #[tokio::test]
+    async fn test_async_multi_writer() {
+        let mut mw = AsyncMultiWriter::new([Cursor::new(Vec::new()), Cursor::new(Vec::new())]);
+        mw.write_all(b"Hello, ").await.unwrap();
+        mw.write_all(b"world.").await.unwrap();
+        mw.flush().await.unwrap();
+        for w in mw.into_inner() {
+            assert_eq!(w.into_inner(), b"Hello, world.");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_limit_reader() {
+        let mut lr = AsyncLimitedReader::new(Cursor::new(b"hello, world".to_vec()), 5);
+        let mut got = Vec::new();
+        lr.read_to_end(&mut got).await.unwrap();
+        assert_eq!(got, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_async_tee_reader() {
+        let mut r = AsyncTeeReader::new(
+            Cursor::new(b"hello, world".to_vec()),
+            Cursor::new(Vec::new()),
+        );
+        let mut got = Vec::new();
+        r.read_to_end(&mut got).await.unwrap();
+        assert_eq!(got, b"hello, world");
+        let (_, w) = r.into_inner();
+        assert_eq!(w.into_inner(), b"hello, world");
+    }
+
+    /// Returns `Pending` once before every read.
+    struct PendingReader<R> {
+        r: R,
+        pending: bool,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for PendingReader<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Pin::new(&mut self.r).poll_read(cx, buf)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_tee_reader_pending() {
+        let src = PendingReader {
+            r: Cursor::new(b"hello".to_vec()),
+            pending: false,
+        };
+        let mut r = AsyncTeeReader::new(src, Cursor::new(Vec::new()));
+        let mut got = Vec::new();
+        r.read_to_end(&mut got).await.unwrap();
+        assert_eq!(got, b"hello");
+        let (_, w) = r.into_inner();
+        assert_eq!(w.into_inner(), b"hello");
+    }
+}
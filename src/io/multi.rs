@@ -0,0 +1,220 @@
+use super::{Closer, Error, WriterTo};
+
+/// A reader that's the logical concatenation of the provided input readers.
+/// They're read sequentially. Once all inputs have returned EOF,
+/// `read` will return EOF (`Ok(0)`).
+///
+/// Use `Box<dyn Read>` as `R` to concatenate readers of different types.
+#[derive(Debug, Clone)]
+pub struct MultiReader<R> {
+    readers: Vec<R>,
+    idx: usize,
+}
+
+impl<R> MultiReader<R> {
+    /// Returns a [`MultiReader`] that's the logical concatenation of the provided input readers.
+    #[inline]
+    pub fn new(readers: impl IntoIterator<Item = R>) -> Self {
+        Self {
+            readers: readers.into_iter().collect(),
+            idx: 0,
+        }
+    }
+
+    /// Consumes the [`MultiReader`], returning the underlying readers
+    /// which have not been drained yet.
+    #[inline]
+    pub fn into_inner(mut self) -> Vec<R> {
+        self.readers.drain(..self.idx);
+        self.readers
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for MultiReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.idx < self.readers.len() {
+            let n = self.readers[self.idx].read(buf)?;
+            if n == 0 && !buf.is_empty() {
+                // This reader is at EOF, move on to the next one.
+                self.idx += 1;
+                continue;
+            }
+            return Ok(n);
+        }
+        Ok(0)
+    }
+}
+
+impl<R: std::io::Read> WriterTo for MultiReader<R> {
+    fn write_to<W: std::io::Write + ?Sized>(&mut self, w: &mut W) -> std::io::Result<u64> {
+        let mut buf = vec![0; 32 * 1024];
+        let mut sum = 0;
+        while self.idx < self.readers.len() {
+            sum += super::copy_buffer(w, &mut self.readers[self.idx], &mut buf)?;
+            self.idx += 1;
+        }
+        Ok(sum)
+    }
+}
+
+impl<R: Closer> Closer for MultiReader<R> {
+    /// Closes all the underlying readers, returning the first error encountered.
+    fn close(&mut self) -> std::io::Result<()> {
+        let mut rst = Ok(());
+        for r in self.readers.iter_mut() {
+            if let Err(e) = r.close() {
+                if rst.is_ok() {
+                    rst = Err(e);
+                }
+            }
+        }
+        rst
+    }
+}
+
+/// A writer that duplicates its writes to all the provided writers,
+/// similar to the Unix tee(1) command.
+///
+/// Each write is written to each listed writer, one at a time, with
+/// [`write_all`](std::io::Write::write_all). If a listed writer returns an
+/// error, that overall write operation stops and returns the error; it does
+/// not continue down the list.
+///
+/// Use `Box<dyn Write>` as `W` to fan out to writers of different types.
+#[derive(Debug, Clone)]
+pub struct MultiWriter<W> {
+    writers: Vec<W>,
+}
+
+impl<W> MultiWriter<W> {
+    /// Creates a writer that duplicates its writes to all the provided writers.
+    #[inline]
+    pub fn new(writers: impl IntoIterator<Item = W>) -> Self {
+        Self {
+            writers: writers.into_iter().collect(),
+        }
+    }
+
+    /// Consumes the [`MultiWriter`], returning the underlying writers.
+    #[inline]
+    pub fn into_inner(self) -> Vec<W> {
+        self.writers
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for MultiWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Each write must be complete, but may take several calls.
+        for w in self.writers.iter_mut() {
+            w.write_all(buf).map_err(|e| match e.kind() {
+                std::io::ErrorKind::WriteZero => Error::ShortWrite.into(),
+                _ => e,
+            })?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        for w in self.writers.iter_mut() {
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Closer> Closer for MultiWriter<W> {
+    /// Closes all the underlying writers, returning the first error encountered.
+    fn close(&mut self) -> std::io::Result<()> {
+        let mut rst = Ok(());
+        for w in self.writers.iter_mut() {
+            if let Err(e) = w.close() {
+                if rst.is_ok() {
+                    rst = Err(e);
+                }
+            }
+        }
+        rst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read, Write};
+
+    #[test]
+    fn test_multi_reader() {
+        let mut mr = MultiReader::new([
+            Cursor::new(b"foo ".to_vec()),
+            Cursor::new(Vec::new()),
+            Cursor::new(b"bar baz".to_vec()),
+        ]);
+        let mut buf = [0; 3];
+        let mut got = Vec::new();
+        loop {
+            let n = mr.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            assert!(n <= 3);
+            got.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(got, b"foo bar baz");
+    }
+
+    #[test]
+    fn test_multi_reader_write_to() {
+        let mut mr = MultiReader::new([&b"foo "[..], &b"bar"[..]]);
+        let mut got = Vec::new();
+        assert_eq!(mr.write_to(&mut got).unwrap(), 7);
+        assert_eq!(got, b"foo bar");
+    }
+
+    #[test]
+    fn test_multi_writer() {
+        let mut mw = MultiWriter::new([Vec::new(), Vec::new()]);
+        mw.write_all(b"Hello, ").unwrap();
+        mw.write_all(b"world.").unwrap();
+        for w in mw.into_inner() {
+            assert_eq!(w, b"Hello, world.");
+        }
+    }
+
+    #[test]
+    fn test_multi_writer_short_write() {
+        let mut short = [0u8; 3];
+        let mut long = Vec::new();
+        let mut mw = MultiWriter::new([
+            Box::new(Cursor::new(&mut short[..])) as Box<dyn Write>,
+            Box::new(&mut long),
+        ]);
+        assert_eq!(
+            mw.write(b"abcdef").unwrap_err().kind(),
+            std::io::ErrorKind::WriteZero
+        );
+    }
+
+    /// Accepts at most two bytes per call.
+    struct TwoByteWriter(Vec<u8>);
+
+    impl Write for TwoByteWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(2);
+            self.0.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_multi_writer_partial_write() {
+        let mut mw = MultiWriter::new([TwoByteWriter(Vec::new()), TwoByteWriter(Vec::new())]);
+        assert_eq!(mw.write(b"Hello, world.").unwrap(), 13);
+        for w in mw.into_inner() {
+            assert_eq!(w.0, b"Hello, world.");
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use super::PipeError;
 use std::sync::Once;
 
-use alloc::sync::Arc;
+use alloc::{collections::VecDeque, sync::Arc};
 use crossbeam_channel::{bounded, select, unbounded, Receiver, Sender};
-use parking_lot::Mutex;
+use parking_lot::{Condvar, Mutex};
 
 #[derive(Debug)]
 #[repr(transparent)]
@@ -222,6 +222,284 @@ pub fn pipe() -> (PipeReader, PipeWriter) {
     )
 }
 
+#[derive(Debug)]
+struct BufferedState {
+    buf: VecDeque<u8>,
+    rerr: Option<PipeError>,
+    werr: Option<PipeError>,
+}
+
+#[derive(Debug)]
+struct BufferedInner {
+    cap: usize,
+    state: Mutex<BufferedState>,
+    cond: Condvar,
+}
+
+impl BufferedInner {
+    #[inline]
+    fn len(&self) -> usize {
+        self.state.lock().buf.len()
+    }
+
+    #[inline]
+    fn available(&self) -> usize {
+        self.cap - self.len()
+    }
+}
+
+/// A [`BufferedPipeReader`] is the read half of a buffered pipe.
+#[derive(Debug)]
+pub struct BufferedPipeReader {
+    inner: Arc<BufferedInner>,
+}
+
+impl BufferedPipeReader {
+    /// Reads data from the pipe, blocking until the internal buffer
+    /// holds at least one byte or the write end is closed.
+    /// Data still buffered when the write end is closed can be read,
+    /// after that the error the write end is closed with is returned,
+    /// which is [`PipeError::Eof`].
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, PipeError> {
+        let mut state = self.inner.state.lock();
+        loop {
+            if state.rerr.is_some() {
+                return Err(PipeError::Closed);
+            }
+
+            if !state.buf.is_empty() {
+                let n = buf.len().min(state.buf.len());
+                for (dst, src) in buf.iter_mut().zip(state.buf.drain(..n)) {
+                    *dst = src;
+                }
+                self.inner.cond.notify_all();
+                return Ok(n);
+            }
+
+            if let Some(err) = state.werr {
+                return Err(err);
+            }
+
+            if buf.is_empty() {
+                return Ok(0);
+            }
+
+            self.inner.cond.wait(&mut state);
+        }
+    }
+
+    /// Returns the number of bytes buffered and ready to be read.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if there is no buffered data.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many bytes can be written before the writer blocks.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.inner.available()
+    }
+
+    /// Returns the capacity of the pipe.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.cap
+    }
+
+    #[inline]
+    fn close_read(&self) {
+        let mut state = self.inner.state.lock();
+        if state.rerr.is_none() {
+            state.rerr = Some(PipeError::Closed);
+        }
+        self.inner.cond.notify_all();
+    }
+}
+
+impl Drop for BufferedPipeReader {
+    fn drop(&mut self) {
+        self.close_read();
+    }
+}
+
+impl super::Closer for BufferedPipeReader {
+    /// Closes the reader; subsequent writes to the write half of the pipe
+    /// will return the error [`PipeError::Closed`].
+    fn close(&mut self) -> std::io::Result<()> {
+        self.close_read();
+        Ok(())
+    }
+}
+
+impl std::io::Read for BufferedPipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::io::Read::read(&mut &*self, buf)
+    }
+}
+
+impl std::io::Read for &BufferedPipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match BufferedPipeReader::read(self, buf) {
+            Ok(n) => Ok(n),
+            Err(PipeError::Eof) => Ok(0),
+            Err(e) => Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, e)),
+        }
+    }
+}
+
+/// A [`BufferedPipeWriter`] is the write half of a buffered pipe.
+#[derive(Debug)]
+pub struct BufferedPipeWriter {
+    inner: Arc<BufferedInner>,
+}
+
+impl BufferedPipeWriter {
+    /// Writes data to the pipe, blocking while the internal buffer is full
+    /// until all the data has been buffered or the read end is closed.
+    /// If the read end is closed, [`PipeError::Closed`] is returned.
+    ///
+    /// If the pipe is closed after part of `buf` has been buffered, the
+    /// number of bytes buffered is returned, and the error is returned by
+    /// the next call.
+    pub fn write(&self, buf: &[u8]) -> Result<usize, PipeError> {
+        let mut state = self.inner.state.lock();
+        let mut n = 0;
+        loop {
+            let err = if state.werr.is_some() {
+                Some(PipeError::Closed)
+            } else {
+                state.rerr
+            };
+            if let Some(err) = err {
+                return if n > 0 { Ok(n) } else { Err(err) };
+            }
+
+            if n == buf.len() {
+                return Ok(n);
+            }
+
+            let space = self.inner.cap - state.buf.len();
+            if space > 0 {
+                let nw = space.min(buf.len() - n);
+                state.buf.extend(&buf[n..n + nw]);
+                n += nw;
+                self.inner.cond.notify_all();
+                continue;
+            }
+
+            self.inner.cond.wait(&mut state);
+        }
+    }
+
+    /// Returns the number of bytes buffered and not yet read.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if there is no buffered data.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many bytes can be written before the writer blocks.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.inner.available()
+    }
+
+    /// Returns the capacity of the pipe.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.cap
+    }
+
+    #[inline]
+    fn close_write(&self) {
+        let mut state = self.inner.state.lock();
+        if state.werr.is_none() {
+            state.werr = Some(PipeError::Eof);
+        }
+        self.inner.cond.notify_all();
+    }
+}
+
+impl Drop for BufferedPipeWriter {
+    fn drop(&mut self) {
+        self.close_write();
+    }
+}
+
+impl super::Closer for BufferedPipeWriter {
+    /// Closes the writer; once the buffered data is consumed, subsequent reads
+    /// from the read half of the pipe will return [`PipeError::Eof`].
+    fn close(&mut self) -> std::io::Result<()> {
+        self.close_write();
+        Ok(())
+    }
+}
+
+impl std::io::Write for BufferedPipeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        std::io::Write::write(&mut &*self, buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::io::Write for &BufferedPipeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        BufferedPipeWriter::write(self, buf)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::BrokenPipe, e))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Creates a synchronous in-memory pipe with an internal buffer of
+/// `capacity` bytes.
+///
+/// Unlike [`pipe`], the writer can run ahead of the reader: a write
+/// only blocks when the buffer is full, and a read only blocks when the
+/// buffer is empty. Closing (or dropping) either half has the same
+/// semantics as [`pipe`], except that data already buffered can still be
+/// read after the write half is closed.
+///
+/// # Panics
+/// Panics if `capacity` is zero.
+pub fn buffered(capacity: usize) -> (BufferedPipeReader, BufferedPipeWriter) {
+    assert!(
+        capacity > 0,
+        "pipe: buffered pipe capacity must be non-zero"
+    );
+    let inner = Arc::new(BufferedInner {
+        cap: capacity,
+        state: Mutex::new(BufferedState {
+            buf: VecDeque::with_capacity(capacity),
+            rerr: None,
+            werr: None,
+        }),
+        cond: Condvar::new(),
+    });
+    (
+        BufferedPipeReader {
+            inner: inner.clone(),
+        },
+        BufferedPipeWriter { inner },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,4 +564,65 @@ mod tests {
         let nn = rx.recv().unwrap();
         assert_eq!(nn, 0, "final read got {}", nn);
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_buffered_pipe() {
+        let (r, w) = buffered(8);
+        assert_eq!(w.write(b"hello").unwrap(), 5);
+        assert_eq!(r.len(), 5);
+        assert_eq!(w.available(), 3);
+
+        let mut buf = [0; 3];
+        assert_eq!(r.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf, b"hel");
+
+        drop(w);
+        assert_eq!(r.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], b"lo");
+        assert_eq!(r.read(&mut buf).unwrap_err(), PipeError::Eof);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_buffered_pipe_backpressure() {
+        use std::io::{Read, Write};
+
+        let (mut r, mut w) = buffered(4);
+        let data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+        let expected = data.clone();
+        let handle = std::thread::spawn(move || {
+            w.write_all(&data).unwrap();
+            assert!(w.len() <= w.capacity());
+        });
+
+        let mut got = Vec::new();
+        r.read_to_end(&mut got).unwrap();
+        handle.join().unwrap();
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_buffered_pipe_close_read() {
+        let (r, w) = buffered(2);
+        drop(r);
+        assert_eq!(w.write(b"a").unwrap_err(), PipeError::Closed);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_buffered_pipe_partial_write() {
+        let (r, w) = buffered(2);
+        let handle = std::thread::spawn(move || {
+            // Only two bytes fit before the reader goes away.
+            assert_eq!(w.write(b"abcd").unwrap(), 2);
+            assert_eq!(w.write(b"cd").unwrap_err(), PipeError::Closed);
+        });
+        while r.len() < 2 {
+            std::thread::yield_now();
+        }
+        drop(r);
+        handle.join().unwrap();
+    }
 }
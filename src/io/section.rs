@@ -0,0 +1,397 @@
+use super::{ReaderAt, WriterAt};
+use std::io::SeekFrom;
+
+#[inline]
+fn invalid_offset() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, "Seek: invalid offset")
+}
+
+#[inline]
+fn invalid_whence() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, "Seek: invalid whence")
+}
+
+/// Implements [`std::io::Read`], [`std::io::Seek`], and [`ReaderAt`] on a section
+/// of an underlying [`ReaderAt`].
+///
+/// Reads through a [`SectionReader`] only move its own offset, so several
+/// [`SectionReader`]s can read from the same underlying source concurrently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectionReader<R> {
+    r: R,
+    /// constant after creation
+    base: u64,
+    off: u64,
+    /// constant after creation
+    limit: u64,
+    /// constant after creation
+    n: u64,
+}
+
+impl<R: ReaderAt> SectionReader<R> {
+    /// Returns a [`SectionReader`] that reads from `r`
+    /// starting at offset `off` and stops with EOF after `n` bytes.
+    #[inline]
+    pub fn new(r: R, off: u64, n: u64) -> Self {
+        // Assume we can read up to an offset of u64::MAX.
+        let limit = off.saturating_add(n);
+        Self {
+            r,
+            base: off,
+            off,
+            limit,
+            n,
+        }
+    }
+}
+
+impl<R> SectionReader<R> {
+    /// Returns the size of the section in bytes.
+    #[inline]
+    pub const fn size(&self) -> u64 {
+        self.limit - self.base
+    }
+
+    /// Returns the underlying [`ReaderAt`] and offsets for the section.
+    ///
+    /// The returned values are the same that were passed to [`SectionReader::new`]
+    /// when the [`SectionReader`] was created.
+    #[inline]
+    pub const fn outer(&self) -> (&R, u64, u64) {
+        (&self.r, self.base, self.n)
+    }
+
+    /// Consumes the [`SectionReader`], returning the underlying [`ReaderAt`].
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+}
+
+impl<R: ReaderAt> std::io::Read for SectionReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.off >= self.limit {
+            return Ok(0);
+        }
+
+        let max = self.limit - self.off;
+        let buf = match usize::try_from(max) {
+            Ok(max) if max < buf.len() => &mut buf[..max],
+            _ => buf,
+        };
+        let n = self.r.read_at(buf, self.off)?;
+        self.off += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: ReaderAt> std::io::Seek for SectionReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let off = match pos {
+            SeekFrom::Start(off) => self.base.checked_add(off),
+            SeekFrom::Current(off) => self.off.checked_add_signed(off),
+            SeekFrom::End(off) => self.limit.checked_add_signed(off),
+        };
+
+        match off {
+            Some(off) if off >= self.base => {
+                self.off = off;
+                Ok(off - self.base)
+            }
+            _ => Err(invalid_offset()),
+        }
+    }
+}
+
+impl<R: ReaderAt> ReaderAt for SectionReader<R> {
+    fn read_at(&self, buf: &mut [u8], off: u64) -> std::io::Result<usize> {
+        if off >= self.size() {
+            return Ok(0);
+        }
+
+        let off = off + self.base;
+        let max = self.limit - off;
+        let buf = match usize::try_from(max) {
+            Ok(max) if max < buf.len() => &mut buf[..max],
+            _ => buf,
+        };
+        self.r.read_at(buf, off)
+    }
+}
+
+/// Maps writes at offset `base` to offset `base + off` in the underlying writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OffsetWriter<W> {
+    w: W,
+    /// the original offset
+    base: u64,
+    /// the current offset
+    off: u64,
+}
+
+impl<W: WriterAt> OffsetWriter<W> {
+    /// Returns an [`OffsetWriter`] that writes to `w`
+    /// starting at offset `off`.
+    #[inline]
+    pub const fn new(w: W, off: u64) -> Self {
+        Self { w, base: off, off }
+    }
+}
+
+impl<W> OffsetWriter<W> {
+    /// Consumes the [`OffsetWriter`], returning the underlying [`WriterAt`].
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+impl<W: WriterAt> std::io::Write for OffsetWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.w.write_at(buf, self.off)?;
+        self.off += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: WriterAt> WriterAt for OffsetWriter<W> {
+    fn write_at(&mut self, buf: &[u8], off: u64) -> std::io::Result<usize> {
+        let off = self.base.checked_add(off).ok_or_else(invalid_offset)?;
+        self.w.write_at(buf, off)
+    }
+}
+
+impl<W: WriterAt> std::io::Seek for OffsetWriter<W> {
+    /// Seeks relative to the original offset, [`SeekFrom::End`] is not supported.
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let off = match pos {
+            SeekFrom::Start(off) => self.base.checked_add(off),
+            SeekFrom::Current(off) => self.off.checked_add_signed(off),
+            SeekFrom::End(_) => return Err(invalid_whence()),
+        };
+
+        match off {
+            Some(off) if off >= self.base => {
+                self.off = off;
+                Ok(off - self.base)
+            }
+            _ => Err(invalid_offset()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Seek, Write};
+
+    #[test]
+    fn test_section_reader_read_at() {
+        let dat = b"a long sample data, 1234567890";
+        struct Test {
+            data: &'static [u8],
+            off: u64,
+            n: u64,
+            buf_len: usize,
+            at: u64,
+            exp: &'static [u8],
+        }
+
+        let tests = [
+            Test {
+                data: b"",
+                off: 0,
+                n: 10,
+                buf_len: 2,
+                at: 0,
+                exp: b"",
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: dat.len() as u64,
+                buf_len: 0,
+                at: 0,
+                exp: b"",
+            },
+            Test {
+                data: dat,
+                off: dat.len() as u64,
+                n: 1,
+                buf_len: 1,
+                at: 0,
+                exp: b"",
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: dat.len() as u64 + 2,
+                buf_len: dat.len(),
+                at: 0,
+                exp: dat,
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: dat.len() as u64,
+                buf_len: dat.len() / 2,
+                at: 0,
+                exp: &dat[..dat.len() / 2],
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: dat.len() as u64,
+                buf_len: dat.len(),
+                at: 0,
+                exp: dat,
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: dat.len() as u64,
+                buf_len: dat.len() / 2,
+                at: 2,
+                exp: &dat[2..2 + dat.len() / 2],
+            },
+            Test {
+                data: dat,
+                off: 3,
+                n: dat.len() as u64,
+                buf_len: dat.len() / 2,
+                at: 2,
+                exp: &dat[5..5 + dat.len() / 2],
+            },
+            Test {
+                data: dat,
+                off: 3,
+                n: dat.len() as u64 / 2,
+                buf_len: dat.len() / 2 - 2,
+                at: 2,
+                exp: &dat[5..5 + dat.len() / 2 - 2],
+            },
+            Test {
+                data: dat,
+                off: 3,
+                n: dat.len() as u64 / 2,
+                buf_len: dat.len() / 2 + 2,
+                at: 2,
+                exp: &dat[5..5 + dat.len() / 2 - 2],
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: 0,
+                buf_len: 0,
+                at: u64::MAX,
+                exp: b"",
+            },
+            Test {
+                data: dat,
+                off: 0,
+                n: 0,
+                buf_len: 0,
+                at: 1,
+                exp: b"",
+            },
+        ];
+
+        for (i, t) in tests.iter().enumerate() {
+            let s = SectionReader::new(t.data, t.off, t.n);
+            let mut buf = vec![0; t.buf_len];
+            let n = s.read_at(&mut buf, t.at).unwrap();
+            assert_eq!(&buf[..n], t.exp, "{i}: read_at({}) = {:?}", t.at, &buf[..n]);
+        }
+    }
+
+    #[test]
+    fn test_section_reader_seek() {
+        // Verifies that seeking behaves the same way as a Cursor.
+        let data = b"foo";
+        let mut br = std::io::Cursor::new(&data[..]);
+        let mut sr = SectionReader::new(&data[..], 0, data.len() as u64);
+
+        for pos in [
+            SeekFrom::Start(0),
+            SeekFrom::Current(1),
+            SeekFrom::End(-1),
+            SeekFrom::End(3),
+        ] {
+            for offset in [-3i64, -2, -1, 0, 1, 2, 3, 4] {
+                let pos = match pos {
+                    SeekFrom::Start(n) => SeekFrom::Start((n as i64 + offset).max(0) as u64),
+                    SeekFrom::Current(n) => SeekFrom::Current(n + offset),
+                    SeekFrom::End(n) => SeekFrom::End(n + offset),
+                };
+                br.rewind().unwrap();
+                sr.rewind().unwrap();
+                let bres = br.seek(pos);
+                let sres = sr.seek(pos);
+                assert_eq!(bres.is_ok(), sres.is_ok(), "{pos:?}");
+                if let (Ok(b), Ok(s)) = (bres, sres) {
+                    assert_eq!(b, s, "{pos:?}");
+                    let (mut bbuf, mut sbuf) = (Vec::new(), Vec::new());
+                    br.read_to_end(&mut bbuf).unwrap();
+                    sr.read_to_end(&mut sbuf).unwrap();
+                    assert_eq!(bbuf, sbuf, "{pos:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_section_reader_size() {
+        let s = SectionReader::new(&b"hello"[..], 1, 3);
+        assert_eq!(s.size(), 3);
+        let (_, off, n) = s.outer();
+        assert_eq!((off, n), (1, 3));
+
+        let s = SectionReader::new(&b"hello"[..], 1, u64::MAX);
+        assert_eq!(s.size(), u64::MAX - 1);
+        let (_, off, n) = s.outer();
+        assert_eq!((off, n), (1, u64::MAX));
+    }
+
+    #[test]
+    fn test_offset_writer() {
+        let mut w = OffsetWriter::new(Vec::new(), 4);
+        w.write_all(b"abc").unwrap();
+        assert_eq!(w.seek(SeekFrom::Current(-1)).unwrap(), 2);
+        w.write_all(b"de").unwrap();
+        assert!(w.seek(SeekFrom::End(0)).is_err());
+        w.write_at(b"x", 0).unwrap();
+        assert_eq!(w.into_inner(), b"\0\0\0\0xbde");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_file_reader_at() {
+        let path = std::env::temp_dir().join(format!("crabmole-reader-at-{}", std::process::id()));
+        let mut f = std::fs::File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        f.write_at(b"hello, world", 0).unwrap();
+
+        let f = alloc::sync::Arc::new(f);
+        std::thread::scope(|s| {
+            for (off, exp) in [(0, b"hello"), (7, b"world")] {
+                let f = f.clone();
+                s.spawn(move || {
+                    let mut sr = SectionReader::new(f, off, 5);
+                    let mut buf = Vec::new();
+                    sr.read_to_end(&mut buf).unwrap();
+                    assert_eq!(&buf, exp);
+                });
+            }
+        });
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -8,11 +8,25 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+// Lets the derive macros' `::crabmole` paths resolve in the unit tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as crabmole;
+
 /// Go sort library
 #[cfg(feature = "sort")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sort")))]
 pub mod sort;
 
+/// Go slices library
+#[cfg(feature = "slices")]
+#[cfg_attr(docsrs, doc(cfg(feature = "slices")))]
+pub mod slices;
+
+/// Go container library
+#[cfg(feature = "container")]
+#[cfg_attr(docsrs, doc(cfg(feature = "container")))]
+pub mod container;
+
 /// Go encoding library
 #[cfg(any(
     feature = "encoding",
@@ -35,9 +49,39 @@ pub mod sort;
 )]
 pub mod encoding;
 
+/// Go hash library
+#[cfg(any(
+    feature = "hash",
+    feature = "adler32",
+    feature = "crc32",
+    feature = "crc64",
+    feature = "fnv"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "hash",
+        feature = "adler32",
+        feature = "crc32",
+        feature = "crc64",
+        feature = "fnv"
+    )))
+)]
+pub mod hash;
+
 mod macros;
 pub use macros::*;
 
+/// Go internal/bytealg library
+#[cfg(feature = "bytealg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytealg")))]
+pub mod bytealg;
+
+/// Go unicode library
+#[cfg(feature = "unicode")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode")))]
+pub mod unicode;
+
 /// Go io library
 #[cfg(any(
     feature = "io",
@@ -56,6 +100,29 @@ pub use macros::*;
 )]
 pub mod io;
 
+/// Go bufio library
+#[cfg(any(feature = "bufio", feature = "async-io"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bufio", feature = "async-io"))))]
+pub mod bufio;
+
+/// Go bytes library
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+pub mod bytes;
+
+/// Go strings library
+#[cfg(feature = "strings")]
+#[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+pub mod strings;
+
+/// Go strconv library
+#[cfg(feature = "strconv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "strconv")))]
+pub mod strconv;
+
+#[cfg(any(feature = "bytes", feature = "strings"))]
+mod case;
+
 /// Copies elements from a source slice into a destination slice. (As a special case, it also will copy bytes from a string to a slice of bytes.) The source and destination may overlap.
 /// Copy returns the number of elements copied, which will be the minimum of `src.len()` and `dst.len()`.
 #[inline]
@@ -0,0 +1,401 @@
+//! Functions useful with slices of any type, ported from Go's generic
+//! `slices` package.
+//!
+//! Go's comparison functions return a negative number, zero or a positive
+//! number; here they return an [`Ordering`]. Sorting goes through the
+//! [`Sort`] trait, so it uses the same algorithms as [`crate::sort`].
+//!
+//! [`Sort`]: crate::sort::Sort
+//! [`Ordering`]: core::cmp::Ordering
+use crate::sort::{__less, Sort};
+use core::cmp::Ordering;
+
+/// Sorts a slice of any ordered type in ascending order.
+/// When sorting floating-point numbers, NaNs are ordered before other values.
+#[inline]
+pub fn sort<T: PartialOrd>(x: &mut [T]) {
+    CmpSwap {
+        data: x,
+        cmp: compare,
+    }
+    .sort();
+}
+
+/// Sorts the slice `x` in ascending order as determined by the `cmp`
+/// function. This sort is not guaranteed to be stable.
+/// `cmp(a, b)` should return `Less` when `a < b`, `Greater` when `a > b` and
+/// `Equal` when `a == b`.
+///
+/// `sort_func` requires that `cmp` is a strict weak ordering.
+/// See <https://en.wikipedia.org/wiki/Weak_ordering#Strict_weak_orderings>.
+#[inline]
+pub fn sort_func<T, F>(x: &mut [T], cmp: F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    CmpSwap { data: x, cmp }.sort();
+}
+
+/// Sorts the slice `x` while keeping the original order of equal
+/// elements, using `cmp` to compare elements in the same way as [`sort_func`].
+#[inline]
+pub fn sort_stable_func<T, F>(x: &mut [T], cmp: F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    CmpSwap { data: x, cmp }.sort_stable();
+}
+
+/// Reports whether `x` is sorted in ascending order.
+#[inline]
+pub fn is_sorted<T: PartialOrd>(x: &[T]) -> bool {
+    is_sorted_func(x, compare)
+}
+
+/// Reports whether `x` is sorted in ascending order, with `cmp` as the
+/// comparison function as defined by [`sort_func`].
+#[inline]
+pub fn is_sorted_func<T, F>(x: &[T], cmp: F) -> bool
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    for i in (1..x.len()).rev() {
+        if cmp(&x[i], &x[i - 1]) == Ordering::Less {
+            return false;
+        }
+    }
+    true
+}
+
+/// Searches for `target` in a sorted slice and returns the earliest
+/// position where `target` is found, or the position where `target` would appear
+/// in the sort order; it also returns a bool saying whether the target is
+/// really found in the slice. The slice must be sorted in increasing order.
+#[inline]
+pub fn binary_search<T: PartialOrd>(x: &[T], target: &T) -> (usize, bool) {
+    binary_search_func(x, target, compare)
+}
+
+/// Works like [`binary_search`], but uses a custom comparison
+/// function. The slice must be sorted in increasing order, where "increasing"
+/// is defined by `cmp`. `cmp` should return `Equal` if the slice element matches
+/// the target, `Less` if the slice element precedes the target,
+/// or `Greater` if the slice element follows the target.
+/// `cmp` must implement the same ordering as the slice, such that if
+/// `cmp(a, t) == Less` and `cmp(b, t) != Less`, then `a` must precede `b` in the slice.
+#[inline]
+pub fn binary_search_func<E, T: ?Sized, F>(x: &[E], target: &T, cmp: F) -> (usize, bool)
+where
+    F: Fn(&E, &T) -> Ordering,
+{
+    crate::sort::find(x.len(), |h| cmp(&x[h], target).reverse())
+}
+
+/// Orders `a` and `b` like Go's `cmp.Compare`: NaNs (values not equal to
+/// themselves) come before everything else and equal each other.
+#[inline]
+fn compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    if __less(a, b) {
+        Ordering::Less
+    } else if __less(b, a) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+struct CmpSwap<'a, T, F> {
+    data: &'a mut [T],
+    cmp: F,
+}
+
+impl<'a, T, F> Sort for CmpSwap<'a, T, F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        (self.cmp)(&self.data[i], &self.data[j]) == Ordering::Less
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.data.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const INTS: &[isize] = &[
+        74, 59, 238, -784, 9845, 959, 905, 0, 0, 42, 7586, -5467984, 7586,
+    ];
+
+    const FLOAT64S: &[f64] = &[
+        74.3,
+        59.0,
+        f64::INFINITY,
+        238.2,
+        -784.0,
+        2.3,
+        f64::NAN,
+        f64::NAN,
+        f64::NEG_INFINITY,
+        9845.768,
+        -959.7485,
+        905.0,
+        7.8,
+        7.8,
+    ];
+
+    const STRS: &[&str] = &["", "Hello", "foo", "bar", "foo", "f00", "%*&^*&^&", "***"];
+
+    #[test]
+    fn test_sort_int_slice() {
+        let mut data = INTS.to_vec();
+        sort(&mut data);
+        assert!(is_sorted(&data), "sorted {INTS:?}, got {data:?}");
+    }
+
+    #[test]
+    fn test_sort_func_int_slice() {
+        let mut data = INTS.to_vec();
+        sort_func(&mut data, |a, b| a.cmp(b));
+        assert!(is_sorted(&data), "sorted {INTS:?}, got {data:?}");
+        assert!(is_sorted_func(&data, |a, b| a.cmp(b)));
+        assert!(!is_sorted_func(&data, |a, b| b.cmp(a)));
+    }
+
+    #[test]
+    fn test_sort_float64_slice() {
+        let mut data = FLOAT64S.to_vec();
+        sort(&mut data);
+        assert!(is_sorted(&data), "sorted {FLOAT64S:?}, got {data:?}");
+        assert!(data[0].is_nan() && data[1].is_nan());
+        assert_eq!(data[2], f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_sort_string_slice() {
+        let mut data = STRS.to_vec();
+        sort(&mut data);
+        assert!(is_sorted(&data), "sorted {STRS:?}, got {data:?}");
+    }
+
+    #[test]
+    fn test_sort_large_random() {
+        let n = 100_000;
+        let mut rng = rand::thread_rng();
+        let mut data = (0..n).map(|_| rng.gen_range(0..100)).collect::<Vec<i32>>();
+        sort(&mut data);
+        assert!(is_sorted(&data), "sort didn't sort - {n} ints");
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct IntPair {
+        a: i32,
+        b: i32,
+    }
+
+    // Pairs compare on a only.
+    fn int_pair_cmp(x: &IntPair, y: &IntPair) -> Ordering {
+        x.a.cmp(&y.a)
+    }
+
+    // Record initial order in B.
+    fn init_b(d: &mut [IntPair]) {
+        for (i, p) in d.iter_mut().enumerate() {
+            p.b = i as i32;
+        }
+    }
+
+    // in_order checks if a-equal elements were not reordered.
+    fn in_order(d: &[IntPair], reversed: bool) -> bool {
+        let (mut last_a, mut last_b) = (-1, 0);
+        for p in d {
+            if last_a != p.a {
+                last_a = p.a;
+                last_b = p.b;
+                continue;
+            }
+            if !reversed {
+                if p.b <= last_b {
+                    return false;
+                }
+            } else if p.b >= last_b {
+                return false;
+            }
+            last_b = p.b;
+        }
+        true
+    }
+
+    #[test]
+    fn test_stability() {
+        let n = 100_000;
+        let m = 1000;
+        let mut rng = rand::thread_rng();
+        let mut data = (0..n)
+            .map(|_| IntPair {
+                a: rng.gen_range(0..m),
+                b: 0,
+            })
+            .collect::<Vec<_>>();
+        assert!(!is_sorted_func(&data, int_pair_cmp), "terrible rand");
+
+        // random distribution
+        init_b(&mut data);
+        sort_stable_func(&mut data, int_pair_cmp);
+        assert!(
+            is_sorted_func(&data, int_pair_cmp),
+            "Stable didn't sort {n} ints"
+        );
+        assert!(in_order(&data, false), "Stable wasn't stable on {n} ints");
+
+        // already sorted
+        init_b(&mut data);
+        sort_stable_func(&mut data, int_pair_cmp);
+        assert!(
+            is_sorted_func(&data, int_pair_cmp),
+            "Stable shuffled sorted {n} ints (order)"
+        );
+        assert!(
+            in_order(&data, false),
+            "Stable shuffled sorted {n} ints (stability)"
+        );
+
+        // sorted reversed
+        for (i, p) in data.iter_mut().enumerate() {
+            p.a = (n - i) as i32;
+        }
+        init_b(&mut data);
+        sort_stable_func(&mut data, int_pair_cmp);
+        assert!(
+            is_sorted_func(&data, int_pair_cmp),
+            "Stable didn't sort {n} ints"
+        );
+        assert!(in_order(&data, false), "Stable wasn't stable on {n} ints");
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let str1 = ["foo"];
+        let str2 = ["ab", "ca"];
+        let str3 = ["mo", "qo", "vo"];
+        let str4 = ["ab", "ad", "ca", "xy"];
+        // slice with repeating elements
+        let str_repeats = ["ba", "ca", "da", "da", "da", "ka", "ma", "ma", "ta"];
+        // slice with all element equal
+        let str_same = ["xx", "xx", "xx"];
+
+        let tests: &[(&[&str], &str, usize, bool)] = &[
+            (&[], "foo", 0, false),
+            (&[], "", 0, false),
+            (&str1, "foo", 0, true),
+            (&str1, "bar", 0, false),
+            (&str1, "zx", 1, false),
+            (&str2, "aa", 0, false),
+            (&str2, "ab", 0, true),
+            (&str2, "ad", 1, false),
+            (&str2, "ca", 1, true),
+            (&str2, "ra", 2, false),
+            (&str3, "bb", 0, false),
+            (&str3, "mo", 0, true),
+            (&str3, "nb", 1, false),
+            (&str3, "qo", 1, true),
+            (&str3, "tr", 2, false),
+            (&str3, "vo", 2, true),
+            (&str3, "xr", 3, false),
+            (&str4, "aa", 0, false),
+            (&str4, "ab", 0, true),
+            (&str4, "ac", 1, false),
+            (&str4, "ad", 1, true),
+            (&str4, "ax", 2, false),
+            (&str4, "ca", 2, true),
+            (&str4, "cc", 3, false),
+            (&str4, "dd", 3, false),
+            (&str4, "xy", 3, true),
+            (&str4, "zz", 4, false),
+            (&str_repeats, "da", 2, true),
+            (&str_repeats, "db", 5, false),
+            (&str_repeats, "ma", 6, true),
+            (&str_repeats, "mb", 8, false),
+            (&str_same, "xx", 0, true),
+            (&str_same, "ab", 0, false),
+            (&str_same, "zz", 3, false),
+        ];
+        for (data, target, want_pos, want_found) in tests {
+            let got = binary_search(data, target);
+            assert_eq!(
+                got,
+                (*want_pos, *want_found),
+                "binary_search({data:?}, {target:?})"
+            );
+
+            let got = binary_search_func(data, *target, |e, t| (*e).cmp(t));
+            assert_eq!(
+                got,
+                (*want_pos, *want_found),
+                "binary_search_func({data:?}, {target:?})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_search_ints() {
+        let data = [20, 30, 40, 50, 60, 70, 80, 90];
+        let tests = [(20, 0, true), (23, 1, false), (43, 3, false), (80, 6, true)];
+        for (target, want_pos, want_found) in tests {
+            let cmp = |a: &i32, b: &i32| a.cmp(b);
+            let pos = binary_search_func(&data, &target, cmp);
+            assert_eq!(pos, (want_pos, want_found), "binary_search_func({target})");
+        }
+    }
+
+    #[test]
+    fn test_binary_search_floats() {
+        let data = [f64::NAN, -0.25, 0.0, 1.4];
+        let tests = [
+            (f64::NAN, 0, true),
+            (f64::INFINITY, 4, false),
+            (-0.25, 1, true),
+            (-0.0, 2, true),
+            (1.4, 3, true),
+            (1.5, 4, false),
+        ];
+        for (target, want_pos, want_found) in tests {
+            let got = binary_search(&data, &target);
+            assert_eq!(got, (want_pos, want_found), "binary_search({target})");
+        }
+    }
+
+    #[test]
+    fn test_binary_search_func_struct() {
+        #[derive(Debug)]
+        struct Person {
+            name: &'static str,
+            age: u32,
+        }
+        let people = [
+            Person {
+                name: "Alice",
+                age: 55,
+            },
+            Person {
+                name: "Bob",
+                age: 24,
+            },
+            Person {
+                name: "Gopher",
+                age: 13,
+            },
+        ];
+        let (i, found) = binary_search_func(&people, "Bob", |a, b| a.name.cmp(b));
+        assert_eq!((i, found), (1, true));
+        assert_eq!(people[i].age, 24);
+    }
+}
@@ -1,3 +1,53 @@
+use core::cmp::Ordering;
+
+/// External merge sort for record streams that do not fit in memory
+#[cfg(feature = "external")]
+#[cfg_attr(docsrs, doc(cfg(feature = "external")))]
+pub mod external;
+
+#[cfg(feature = "alloc")]
+mod radix;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use radix::{radix_sort, radix_sort_by_key, radix_sort_bytes, RadixKey};
+
+/// Derives [`Sort`] for a struct of columns, sorting every column by the
+/// one marked `#[sort(key)]`.
+///
+/// ```
+/// use crabmole::sort::Sort;
+///
+/// #[derive(Sort)]
+/// struct Trades {
+///     #[sort(key)]
+///     time: Vec<u64>,
+///     price: Vec<f64>,
+///     symbol: Vec<&'static str>,
+/// }
+///
+/// let mut trades = Trades {
+///     time: vec![3, 1, 2],
+///     price: vec![30.5, 10.5, 20.5],
+///     symbol: vec!["c", "a", "b"],
+/// };
+/// trades.sort_stable();
+/// assert_eq!(trades.time, [1, 2, 3]);
+/// assert_eq!(trades.price, [10.5, 20.5, 30.5]);
+/// assert_eq!(trades.symbol, ["a", "b", "c"]);
+/// ```
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use crabmole_derive::Sort;
+
+mod instrument;
+pub use instrument::{checked_sort, checked_sort_stable, ComparatorError, Instrumented, Stats};
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "parallel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parallel")))]
+pub use parallel::{par_sort, par_sort_slice, par_sort_slice_stable, par_sort_stable};
+
 struct LessSwap<'a, T, L> {
     data: &'a mut [T],
     less: L,
@@ -81,6 +131,70 @@ pub trait SliceSortExt {
         let sorter = ImmutableLessSwap { data, less };
         sorter.is_sorted()
     }
+
+    /// Sorts the slice data by the key `f` extracts from each element.
+    ///
+    /// `f` is called O(n*log(n)) times; when keys are expensive to compute,
+    /// use [`SliceSortExt::sort_slice_by_cached_key`].
+    /// The sort is not guaranteed to be stable.
+    #[inline]
+    fn sort_by_key<K, F>(data: &mut [Self::Item], f: F)
+    where
+        K: PartialOrd,
+        F: Fn(&Self::Item) -> K,
+    {
+        sort_by_key(data, f)
+    }
+
+    /// Sorts the slice data by the key `f` extracts from each element,
+    /// keeping equal elements in their original order.
+    #[inline]
+    fn sort_stable_by_key<K, F>(data: &mut [Self::Item], f: F)
+    where
+        K: PartialOrd,
+        F: Fn(&Self::Item) -> K,
+    {
+        sort_stable_by_key(data, f)
+    }
+
+    /// Sorts the slice data by the key `f` extracts from each element,
+    /// calling `f` only once per element. See [`sort_slice_by_cached_key`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn sort_slice_by_cached_key<K, F>(data: &mut [Self::Item], f: F)
+    where
+        K: PartialOrd,
+        F: FnMut(&Self::Item) -> K,
+    {
+        sort_slice_by_cached_key(data, f)
+    }
+
+    /// Like [`SliceSortExt::sort_slice`], but splits the work across threads.
+    /// See [`par_sort_slice`] for how `less` is called.
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parallel")))]
+    #[inline]
+    fn par_sort_slice<L>(data: &mut [Self::Item], less: L)
+    where
+        Self::Item: Send,
+        L: Fn(&[Self::Item], usize, usize) -> bool + Sync,
+    {
+        parallel::par_sort_slice(data, less)
+    }
+
+    /// Like [`SliceSortExt::sort_slice_stable`], but splits the work across threads.
+    /// See [`par_sort_slice`] for how `less` is called.
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parallel")))]
+    #[inline]
+    fn par_sort_slice_stable<L>(data: &mut [Self::Item], less: L)
+    where
+        Self::Item: Send,
+        L: Fn(&[Self::Item], usize, usize) -> bool + Sync,
+    {
+        parallel::par_sort_slice_stable(data, less)
+    }
 }
 
 impl<T> SliceSortExt for T {
@@ -122,6 +236,198 @@ where
     sorter.is_sorted()
 }
 
+/// Sorts the slice data by the key `f` extracts from each element.
+///
+/// `f` is called O(n*log(n)) times; when keys are expensive to compute,
+/// use [`sort_slice_by_cached_key`].
+/// The sort is not guaranteed to be stable.
+#[inline]
+pub fn sort_by_key<T, K, F>(data: &mut [T], f: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    sort_slice(data, |d, i, j| __less(&f(&d[i]), &f(&d[j])))
+}
+
+/// Sorts the slice data by the key `f` extracts from each element,
+/// keeping equal elements in their original order.
+#[inline]
+pub fn sort_stable_by_key<T, K, F>(data: &mut [T], f: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    sort_slice_stable(data, |d, i, j| __less(&f(&d[i]), &f(&d[j])))
+}
+
+/// Sorts the slice data by the key `f` extracts from each element, calling
+/// `f` exactly once per element (the Schwartzian transform).
+///
+/// The keys are collected together with each element's original index, and
+/// ties are broken by that index, so the sort is stable. The permutation is
+/// then applied to data with at most n swaps.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn sort_slice_by_cached_key<T, K, F>(data: &mut [T], f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    let mut indices = data
+        .iter()
+        .map(f)
+        .enumerate()
+        .map(|(i, k)| (k, i))
+        .collect::<::alloc::vec::Vec<_>>();
+    sort_slice(&mut indices, |d, i, j| {
+        let ((ki, ii), (kj, ij)) = (&d[i], &d[j]);
+        __less(ki, kj) || (!__less(kj, ki) && ii < ij)
+    });
+
+    let mut order = indices
+        .into_iter()
+        .map(|(_, i)| i)
+        .collect::<::alloc::vec::Vec<_>>();
+    apply_permutation(data, &mut order);
+}
+
+/// Moves every element of data to its sorted position with at most n swaps,
+/// where order[i] is the original position of the element that belongs at i.
+/// order is left in an unspecified state.
+#[cfg(feature = "alloc")]
+fn apply_permutation<T>(data: &mut [T], order: &mut [usize]) {
+    // Elements before i have already been moved, so follow the chain of
+    // positions they were swapped to until it leaves the finished prefix.
+    for i in 0..order.len() {
+        let mut index = order[i];
+        while index < i {
+            index = order[index];
+        }
+        order[i] = index;
+        data.swap(i, index);
+    }
+}
+
+/// Elements that are swapped in step with the keys of a [`ByKey`].
+///
+/// Implemented for mutable slices and for tuples of up to eight of them.
+pub trait Swap {
+    /// Reports whether every slice has `n` elements.
+    fn has_len(&self, n: usize) -> bool;
+
+    /// Swaps the elements with indexes i and j.
+    fn swap(&mut self, i: usize, j: usize);
+}
+
+impl<T> Swap for &mut [T] {
+    #[inline]
+    fn has_len(&self, n: usize) -> bool {
+        self.len() == n
+    }
+
+    #[inline]
+    fn swap(&mut self, i: usize, j: usize) {
+        <[T]>::swap(self, i, j);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Swap for &mut ::alloc::vec::Vec<T> {
+    #[inline]
+    fn has_len(&self, n: usize) -> bool {
+        self.len() == n
+    }
+
+    #[inline]
+    fn swap(&mut self, i: usize, j: usize) {
+        <[T]>::swap(self, i, j);
+    }
+}
+
+macro_rules! impl_swap_for_tuple {
+    ($($name:ident.$idx:tt),+) => {
+        impl<$($name: Swap),+> Swap for ($($name,)+) {
+            #[inline]
+            fn has_len(&self, n: usize) -> bool {
+                $(self.$idx.has_len(n))&&+
+            }
+
+            #[inline]
+            fn swap(&mut self, i: usize, j: usize) {
+                $(self.$idx.swap(i, j);)+
+            }
+        }
+    };
+}
+
+impl_swap_for_tuple!(A.0);
+impl_swap_for_tuple!(A.0, B.1);
+impl_swap_for_tuple!(A.0, B.1, C.2);
+impl_swap_for_tuple!(A.0, B.1, C.2, D.3);
+impl_swap_for_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_swap_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+impl_swap_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_swap_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+
+/// Sorts several parallel slices together, ordered by one slice of keys.
+///
+/// This is the Go idiom of a struct of slices whose `Swap` swaps every
+/// slice: `keys` decides the order, and `values` is moved along with it.
+///
+/// ```
+/// use crabmole::sort::{ByKey, Sort};
+///
+/// let mut ages = [42, 7, 19];
+/// let mut names = ["carol", "alice", "bob"];
+/// let mut ids = [3u32, 1, 2];
+/// ByKey::new(&mut ages, (&mut names[..], &mut ids[..])).sort();
+/// assert_eq!(ages, [7, 19, 42]);
+/// assert_eq!(names, ["alice", "bob", "carol"]);
+/// assert_eq!(ids, [1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct ByKey<'a, K, S> {
+    keys: &'a mut [K],
+    values: S,
+}
+
+impl<'a, K, S: Swap> ByKey<'a, K, S> {
+    /// Returns a [`Sort`] over `keys` that swaps `values` along with them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the `values` slices does not have the same length as `keys`.
+    #[inline]
+    pub fn new(keys: &'a mut [K], values: S) -> Self {
+        assert!(
+            values.has_len(keys.len()),
+            "sort: parallel slices have different lengths"
+        );
+        Self { keys, values }
+    }
+}
+
+impl<K: PartialOrd, S: Swap> Sort for ByKey<'_, K, S> {
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        __less(&self.keys[i], &self.keys[j])
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.keys.swap(i, j);
+        self.values.swap(i, j);
+    }
+}
+
 /// Sort in reverse helper structure
 struct Reverse<'a, T>(&'a mut T);
 
@@ -158,6 +464,15 @@ impl<'a, T: Sort> Sort for ImmutableReverse<'a, T> {
 /// Golang sort interface in Rust.
 ///
 /// Implement this trait for type to unlock all the sorting methods in Go standard library.
+///
+/// # Ordering of the built-in implementations
+///
+/// Slices, arrays, `Vec`, `Box<[T]>` and `VecDeque` order their elements with
+/// [`PartialOrd`]. Like Go's `sort.Float64s`, values that are not comparable
+/// with themselves, such as NaN, are placed before all other values, so that
+/// the ordering stays a strict weak order. This only costs extra comparisons
+/// for pairs that [`PartialOrd::partial_cmp`] cannot order, so element types
+/// with a total order are compared exactly once per call to `less`.
 #[allow(clippy::len_without_is_empty)]
 pub trait Sort {
     /// Len is the number of elements in the collection.
@@ -187,8 +502,7 @@ pub trait Sort {
     where
         Self: Sized,
     {
-        let n = self.len();
-        quick_sort(self, 0, n, max_depth(n));
+        pdqsort_all(self);
     }
 
     /// Notes on stable sorting:
@@ -270,8 +584,7 @@ pub trait Sort {
     where
         Self: Sized,
     {
-        let n = self.len();
-        quick_sort(&mut Reverse(self), 0, n, max_depth(n));
+        pdqsort_all(&mut Reverse(self));
     }
 
     /// Sorts (stable) data in reverse order.
@@ -315,6 +628,31 @@ pub trait Sort {
         let n = self.len();
         stable(&mut Reverse(self), n);
     }
+
+    /// Sorts data like [`Sort::sort`] and returns how many calls it made to
+    /// data.Less and data.Swap.
+    #[inline]
+    fn sort_with_stats(&mut self) -> Stats
+    where
+        Self: Sized,
+    {
+        let mut counted = Instrumented::new(self);
+        pdqsort_all(&mut counted);
+        counted.stats()
+    }
+
+    /// Sorts data like [`Sort::sort_stable`] and returns how many calls it
+    /// made to data.Less and data.Swap.
+    #[inline]
+    fn sort_stable_with_stats(&mut self) -> Stats
+    where
+        Self: Sized,
+    {
+        let mut counted = Instrumented::new(self);
+        let n = counted.len();
+        stable(&mut counted, n);
+        counted.stats()
+    }
 }
 
 #[inline]
@@ -327,6 +665,26 @@ const fn __slice_len<T>(data: &[T]) -> usize {
     data.len()
 }
 
+/// Like Go's `Float64Slice.Less`: values that are not comparable with
+/// themselves (NaNs) are placed before any other values, which keeps the
+/// ordering a strict weak order. Only pairs `partial_cmp` cannot order are
+/// checked for NaNs, so totally ordered types pay for a single comparison.
+///
+/// Public only for the code generated by the `Sort` derive.
+#[doc(hidden)]
+#[inline]
+pub fn __less<T: PartialOrd>(a: &T, b: &T) -> bool {
+    match a.partial_cmp(b) {
+        Some(ord) => ord == Ordering::Less,
+        None => {
+            #[allow(clippy::eq_op)]
+            let is_nan = |x: &T| x != x;
+            is_nan(a) && !is_nan(b)
+        }
+    }
+}
+
+/// Orders elements with [`PartialOrd`], NaNs first; see [`Sort`].
 #[cfg(feature = "alloc")]
 impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::vec::Vec<T> {
     fn len(&self) -> usize {
@@ -334,7 +692,7 @@ impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::vec::Vec<T> {
     }
 
     fn less(&self, i: usize, j: usize) -> bool {
-        self[i] < self[j]
+        __less(&self[i], &self[j])
     }
 
     fn swap(&mut self, i: usize, j: usize) {
@@ -342,13 +700,14 @@ impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::vec::Vec<T> {
     }
 }
 
+/// Orders elements with [`PartialOrd`], NaNs first; see [`Sort`].
 impl<'a, T: PartialOrd + core::fmt::Debug> Sort for &'a mut [T] {
     fn len(&self) -> usize {
         __slice_len(self)
     }
 
     fn less(&self, i: usize, j: usize) -> bool {
-        self[i] < self[j]
+        __less(&self[i], &self[j])
     }
 
     fn swap(&mut self, i: usize, j: usize) {
@@ -356,13 +715,14 @@ impl<'a, T: PartialOrd + core::fmt::Debug> Sort for &'a mut [T] {
     }
 }
 
+/// Orders elements with [`PartialOrd`], NaNs first; see [`Sort`].
 impl<const N: usize, T: PartialOrd + core::fmt::Debug> Sort for [T; N] {
     fn len(&self) -> usize {
         __slice_len(self)
     }
 
     fn less(&self, i: usize, j: usize) -> bool {
-        self[i] < self[j]
+        __less(&self[i], &self[j])
     }
 
     fn swap(&mut self, i: usize, j: usize) {
@@ -370,6 +730,7 @@ impl<const N: usize, T: PartialOrd + core::fmt::Debug> Sort for [T; N] {
     }
 }
 
+/// Orders elements with [`PartialOrd`], NaNs first; see [`Sort`].
 #[cfg(feature = "alloc")]
 impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::boxed::Box<[T]> {
     fn len(&self) -> usize {
@@ -377,7 +738,7 @@ impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::boxed::Box<[T]> {
     }
 
     fn less(&self, i: usize, j: usize) -> bool {
-        self[i] < self[j]
+        __less(&self[i], &self[j])
     }
 
     fn swap(&mut self, i: usize, j: usize) {
@@ -385,6 +746,65 @@ impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::boxed::Box<[T]> {
     }
 }
 
+/// Orders elements with [`PartialOrd`], NaNs first; see [`Sort`].
+#[cfg(feature = "alloc")]
+impl<T: PartialOrd + core::fmt::Debug> Sort for ::alloc::collections::VecDeque<T> {
+    fn len(&self) -> usize {
+        ::alloc::collections::VecDeque::len(self)
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        __less(&self[i], &self[j])
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        ::alloc::collections::VecDeque::swap(self, i, j);
+    }
+}
+
+/// Implements [`Sort`] for tuples of columns, ordered by the first column
+/// and with every other column swapped along with it, like [`ByKey`].
+/// `len` panics if the columns do not all have the same length.
+macro_rules! impl_sort_for_columns {
+    ($key:ty; $($name:ident.$idx:tt),+) => {
+        impl<K: PartialOrd, $($name: Swap),+> Sort for ($key, $($name,)+) {
+            fn len(&self) -> usize {
+                let n = self.0.len();
+                assert!(
+                    $(self.$idx.has_len(n))&&+,
+                    "sort: parallel slices have different lengths"
+                );
+                n
+            }
+
+            fn less(&self, i: usize, j: usize) -> bool {
+                __less(&self.0[i], &self.0[j])
+            }
+
+            fn swap(&mut self, i: usize, j: usize) {
+                self.0.swap(i, j);
+                $(Swap::swap(&mut self.$idx, i, j);)+
+            }
+        }
+    };
+}
+
+macro_rules! impl_sort_for_columns_keyed_by {
+    ($key:ty) => {
+        impl_sort_for_columns!($key; B.1);
+        impl_sort_for_columns!($key; B.1, C.2);
+        impl_sort_for_columns!($key; B.1, C.2, D.3);
+        impl_sort_for_columns!($key; B.1, C.2, D.3, E.4);
+        impl_sort_for_columns!($key; B.1, C.2, D.3, E.4, F.5);
+        impl_sort_for_columns!($key; B.1, C.2, D.3, E.4, F.5, G.6);
+        impl_sort_for_columns!($key; B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+    };
+}
+
+impl_sort_for_columns_keyed_by!(&mut [K]);
+#[cfg(feature = "alloc")]
+impl_sort_for_columns_keyed_by!(&mut ::alloc::vec::Vec<K>);
+
 /// Sorts data[a:b] using insertion sort.
 #[inline]
 fn insertion_sort(data: &mut impl Sort, a: usize, b: usize) {
@@ -450,161 +870,377 @@ fn heap_sort(data: &mut impl Sort, a: usize, b: usize) {
 }
 
 #[inline]
-fn median_of_three(data: &mut impl Sort, m1: usize, m0: usize, m2: usize) {
-    // sort 3 elements
-    if data.less(m1, m0) {
-        data.swap(m1, m0);
+fn swap_range(data: &mut impl Sort, a: usize, b: usize, n: usize) {
+    for i in 0..n {
+        data.swap(a + i, b + i);
     }
+}
 
-    // data[m0] <= data[m1]
-    if data.less(m2, m1) {
-        data.swap(m2, m1);
-        // data[m0] <= data[m2] && data[m1] < data[m2]
-        if data.less(m1, m0) {
-            data.swap(m1, m0);
-        }
+/// Hint for pdqsort when choosing the pivot.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum SortedHint {
+    Unknown,
+    Increasing,
+    Decreasing,
+}
+
+/// xorshift paper: <https://www.jstatsoft.org/article/view/v008i14/xorshift.pdf>
+struct XorShift(u64);
+
+impl XorShift {
+    #[inline]
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
     }
-    // now data[m0] <= data[m1] <= data[m2]
 }
 
+/// Returns the minimum number of bits required to represent `n`.
 #[inline]
-fn swap_range(data: &mut impl Sort, a: usize, b: usize, n: usize) {
-    for i in 0..n {
-        data.swap(a + i, b + i);
-    }
+const fn bits_len(n: usize) -> u32 {
+    usize::BITS - n.leading_zeros()
+}
+
+#[inline]
+fn next_power_of_two(length: usize) -> usize {
+    1 << bits_len(length)
 }
 
+/// Sorts all of data, allowing `bits_len(n)` bad pivots before falling back to heapsort.
 #[inline]
-fn do_pivot(data: &mut impl Sort, lo: usize, hi: usize) -> (usize, usize) {
-    let m = (lo + hi) >> 1;
-    if hi - lo > 40 {
-        // Tukey ninther, median of three medians of three
-        let s = (hi - lo) / 8;
-        median_of_three(data, lo, lo + s, lo + 2 * s);
-        median_of_three(data, m, m - s, m + s);
-        median_of_three(data, hi - 1, hi - 1 - s, hi - 1 - 2 * s);
-    }
-    median_of_three(data, lo, m, hi - 1);
-    // Invariants are:
-    //	data[lo] = pivot (set up by ChoosePivot)
-    //	data[lo < i < a] < pivot
-    //	data[a <= i < b] <= pivot
-    //	data[b <= i < c] unexamined
-    //	data[c <= i < hi-1] > pivot
-    //	data[hi-1] >= pivot
-    let pivot = lo;
-    let (mut a, mut c) = (lo + 1, hi - 1);
-    while a < c && data.less(a, pivot) {
-        a += 1;
-    }
-    let mut b = a;
+fn pdqsort_all(data: &mut impl Sort) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+    let limit = bits_len(n);
+    pdqsort(data, 0, n, limit);
+}
+
+/// Sorts data[a:b].
+/// The algorithm based on pattern-defeating quicksort(pdqsort), but without the optimizations from BlockQuicksort.
+/// pdqsort paper: <https://arxiv.org/pdf/2106.05123.pdf>
+/// C++ implementation: <https://github.com/orlp/pdqsort>
+/// Rust implementation: <https://docs.rs/pdqsort/latest/pdqsort/>
+/// limit is the number of allowed bad (very unbalanced) pivots before falling back to heapsort.
+fn pdqsort(data: &mut impl Sort, mut a: usize, mut b: usize, mut limit: u32) {
+    const MAX_INSERTION: usize = 12;
+
+    let mut was_balanced = true; // whether the last partitioning was reasonably balanced
+    let mut was_partitioned = true; // whether the slice was already partitioned
+
     loop {
-        // data[b] <= pivot
-        while b < c && !data.less(pivot, b) {
-            b += 1;
+        let length = b - a;
+
+        if length <= MAX_INSERTION {
+            insertion_sort(data, a, b);
+            return;
         }
-        // data[c-1] > pivot
-        while b < c && data.less(pivot, c - 1) {
-            c -= 1;
+
+        // Fall back to heapsort if too many bad choices were made.
+        if limit == 0 {
+            heap_sort(data, a, b);
+            return;
         }
-        if b >= c {
-            break;
+
+        // If the last partitioning was imbalanced, we need to break patterns.
+        if !was_balanced {
+            break_patterns(data, a, b);
+            limit -= 1;
         }
-        // data[b] > pivot; data[c-1] <= pivot
-        data.swap(b, c - 1);
-        b += 1;
-        c -= 1;
-    }
-
-    // If hi-c<3 then there are duplicates (by property of median of nine).
-    // Let's be a bit more conservative, and set border to 5.
-    let mut protect = hi - c < 5;
-    if !protect && hi - c < (hi - lo) / 4 {
-        // Lets test some points for equality to pivot
-        let mut dups = 0;
-        // data[hi-1] = pivot
-        if !data.less(pivot, hi - 1) {
-            data.swap(c, hi - 1);
-            c += 1;
-            dups += 1;
-        }
-
-        // data[b-1] = pivot
-        if !data.less(b - 1, pivot) {
-            b -= 1;
-            dups += 1;
-        }
-
-        // m-lo = (hi-lo)/2 > 6
-        // b-lo > (hi-lo)*3/4-1 > 8
-        // ==> m < b ==> data[m] <= pivot
-        if !data.less(m, pivot) {
-            data.swap(m, b - 1);
-            b -= 1;
-            dups += 1;
-        }
-
-        // if at least 2 points are equal to pivot, assume skewed distribution
-        protect = dups > 1;
-    }
-
-    if protect {
-        // Protect against a lot of duplicates
-        // Add invariant:
-        //	data[a <= i < b] unexamined
-        //	data[b <= i < c] = pivot
-        loop {
-            // data[b] == pivot
-            while a < b && !data.less(b - 1, pivot) {
-                b -= 1;
-            }
-            // data[a] < pivot
-            while a < b && data.less(a, pivot) {
-                a += 1;
-            }
-            if a >= b {
-                break;
-            }
-            // data[a] == pivot; data[b-1] < pivot
-            data.swap(a, b - 1);
-            a += 1;
-            b -= 1;
+
+        let (mut pivot, mut hint) = choose_pivot(data, a, b);
+        if hint == SortedHint::Decreasing {
+            reverse_range(data, a, b);
+            // The chosen pivot was pivot-a elements after the start of the array.
+            // After reversing it is pivot-a elements before the end of the array.
+            // The idea came from Rust's implementation.
+            pivot = (b - 1) - (pivot - a);
+            hint = SortedHint::Increasing;
+        }
+
+        // The slice is likely already sorted.
+        if was_balanced
+            && was_partitioned
+            && hint == SortedHint::Increasing
+            && partial_insertion_sort(data, a, b)
+        {
+            return;
+        }
+
+        // Probably the slice contains many duplicate elements, partition the slice into
+        // elements equal to and elements greater than the pivot.
+        if a > 0 && !data.less(a - 1, pivot) {
+            a = partition_equal(data, a, b, pivot);
+            continue;
+        }
+
+        let (mid, already_partitioned) = partition(data, a, b, pivot);
+        was_partitioned = already_partitioned;
+
+        let (left_len, right_len) = (mid - a, b - mid);
+        let balance_threshold = length / 8;
+        if left_len < right_len {
+            was_balanced = left_len >= balance_threshold;
+            pdqsort(data, a, mid, limit);
+            a = mid + 1;
+        } else {
+            was_balanced = right_len >= balance_threshold;
+            pdqsort(data, mid + 1, b, limit);
+            b = mid;
         }
     }
-    // Swap pivot into middle
-    data.swap(pivot, b - 1);
-    (b - 1, c)
 }
 
-#[inline]
-fn quick_sort(data: &mut impl Sort, mut a: usize, mut b: usize, mut max_depth: usize) {
-    while b - a > 12 {
-        if max_depth == 0 {
+/// Moves data[k] into place within data[a:b], like pdqsort but only
+/// continuing into the side of each partition that holds k.
+/// limit is the number of allowed bad (very unbalanced) pivots before falling back to heapsort.
+fn introselect(data: &mut impl Sort, mut a: usize, mut b: usize, k: usize, mut limit: u32) {
+    const MAX_INSERTION: usize = 12;
+
+    let mut was_balanced = true; // whether the last partitioning was reasonably balanced
+
+    loop {
+        let length = b - a;
+
+        if length <= MAX_INSERTION {
+            insertion_sort(data, a, b);
+            return;
+        }
+
+        // Fall back to heapsort if too many bad choices were made.
+        if limit == 0 {
             heap_sort(data, a, b);
             return;
         }
 
-        max_depth -= 1;
-        let (mlo, mhi) = do_pivot(data, a, b);
-        // Avoiding recursion on the larger subproblem guarantees
-        // a stack depth of at most lg(b-a).
-        if mlo - a < b - mhi {
-            quick_sort(data, a, mlo, max_depth);
-            a = mhi; // i.e., quickSort(data, mhi, b)
-        } else {
-            quick_sort(data, mhi, b, max_depth);
-            b = mlo; // i.e., quickSort(data, a, mlo)
+        // If the last partitioning was imbalanced, we need to break patterns.
+        if !was_balanced {
+            break_patterns(data, a, b);
+            limit -= 1;
+        }
+
+        let (pivot, _) = choose_pivot(data, a, b);
+
+        // Probably the slice contains many duplicate elements. The ones equal to
+        // data[a-1] are all in place if k is among them.
+        if a > 0 && !data.less(a - 1, pivot) {
+            a = partition_equal(data, a, b, pivot);
+            if k < a {
+                return;
+            }
+            continue;
+        }
+
+        let (mid, _) = partition(data, a, b, pivot);
+        let (left_len, right_len) = (mid - a, b - mid);
+        was_balanced = left_len.min(right_len) >= length / 8;
+        match k.cmp(&mid) {
+            Ordering::Less => b = mid,
+            Ordering::Equal => return,
+            Ordering::Greater => a = mid + 1,
+        }
+    }
+}
+
+/// Does one quicksort partition.
+/// Let p = data[pivot]
+/// Moves elements in data[a:b] around, so that data[i]<p and data[j]>=p for i<newpivot and j>newpivot.
+/// On return, data[newpivot] = p
+fn partition(data: &mut impl Sort, a: usize, b: usize, pivot: usize) -> (usize, bool) {
+    data.swap(a, pivot);
+    // i and j are inclusive of the elements remaining to be partitioned,
+    // kept one past their Go counterparts so that j never underflows.
+    let (mut i, mut j) = (a + 1, b);
+
+    while i < j && data.less(i, a) {
+        i += 1;
+    }
+    while i < j && !data.less(j - 1, a) {
+        j -= 1;
+    }
+    if i >= j {
+        data.swap(j - 1, a);
+        return (j - 1, true);
+    }
+    data.swap(i, j - 1);
+    i += 1;
+    j -= 1;
+
+    loop {
+        while i < j && data.less(i, a) {
+            i += 1;
+        }
+        while i < j && !data.less(j - 1, a) {
+            j -= 1;
         }
+        if i >= j {
+            break;
+        }
+        data.swap(i, j - 1);
+        i += 1;
+        j -= 1;
     }
-    if b - a > 1 {
-        // Do ShellSort pass with gap 6
-        // It could be written in this simplified form cause b-a <= 12
-        for i in a + 6..b {
-            if data.less(i, i - 6) {
-                data.swap(i, i - 6);
+    data.swap(j - 1, a);
+    (j - 1, false)
+}
+
+/// Partitions data[a:b] into elements equal to data[pivot] followed by elements greater than data[pivot].
+/// It assumed that data[a:b] does not contain elements smaller than the data[pivot].
+fn partition_equal(data: &mut impl Sort, a: usize, b: usize, pivot: usize) -> usize {
+    data.swap(a, pivot);
+    // i and j are inclusive of the elements remaining to be partitioned,
+    // with j kept one past its Go counterpart.
+    let (mut i, mut j) = (a + 1, b);
+
+    loop {
+        while i < j && !data.less(a, i) {
+            i += 1;
+        }
+        while i < j && data.less(a, j - 1) {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        data.swap(i, j - 1);
+        i += 1;
+        j -= 1;
+    }
+    i
+}
+
+/// Partially sorts a slice, returns true if the slice is sorted at the end.
+fn partial_insertion_sort(data: &mut impl Sort, a: usize, b: usize) -> bool {
+    const MAX_STEPS: usize = 5; // maximum number of adjacent out-of-order pairs that will get shifted
+    const SHORTEST_SHIFTING: usize = 50; // don't shift any elements on short arrays
+
+    let mut i = a + 1;
+    for _ in 0..MAX_STEPS {
+        while i < b && !data.less(i, i - 1) {
+            i += 1;
+        }
+
+        if i == b {
+            return true;
+        }
+
+        if b - a < SHORTEST_SHIFTING {
+            return false;
+        }
+
+        data.swap(i, i - 1);
+
+        // Shift the smaller one to the left.
+        if i - a >= 2 {
+            for j in (a + 1..i).rev() {
+                if !data.less(j, j - 1) {
+                    break;
+                }
+                data.swap(j, j - 1);
+            }
+        }
+        // Shift the greater one to the right.
+        if b - i >= 2 {
+            for j in i + 1..b {
+                if !data.less(j, j - 1) {
+                    break;
+                }
+                data.swap(j, j - 1);
             }
         }
-        insertion_sort(data, a, b)
+    }
+    false
+}
+
+/// Scatters some elements around in an attempt to break some patterns
+/// that might cause imbalanced partitions in quicksort.
+fn break_patterns(data: &mut impl Sort, a: usize, b: usize) {
+    let length = b - a;
+    if length >= 8 {
+        let mut random = XorShift(length as u64);
+        let modulus = next_power_of_two(length);
+
+        let idx = a + (length / 4) * 2 - 1;
+        for i in 0..3 {
+            let mut other = (random.next() as usize) & (modulus - 1);
+            if other >= length {
+                other -= length;
+            }
+            data.swap(idx - 1 + i, a + other);
+        }
+    }
+}
+
+/// Chooses a pivot in data[a:b].
+///
+/// - [0,8): chooses a static pivot.
+/// - [8,shortestNinther): uses the simple median-of-three method.
+/// - [shortestNinther,∞): uses the Tukey ninther method.
+fn choose_pivot(data: &impl Sort, a: usize, b: usize) -> (usize, SortedHint) {
+    const SHORTEST_NINTHER: usize = 50;
+    const MAX_SWAPS: usize = 4 * 3;
+
+    let l = b - a;
+
+    let mut swaps = 0;
+    let mut i = a + l / 4;
+    let mut j = a + l / 4 * 2;
+    let mut k = a + l / 4 * 3;
+
+    if l >= 8 {
+        if l >= SHORTEST_NINTHER {
+            // Tukey ninther method, the idea came from Rust's implementation.
+            i = median_adjacent(data, i, &mut swaps);
+            j = median_adjacent(data, j, &mut swaps);
+            k = median_adjacent(data, k, &mut swaps);
+        }
+        // Find the median among i, j, k and stores it into j.
+        j = median(data, i, j, k, &mut swaps);
+    }
+
+    match swaps {
+        0 => (j, SortedHint::Increasing),
+        MAX_SWAPS => (j, SortedHint::Decreasing),
+        _ => (j, SortedHint::Unknown),
+    }
+}
+
+/// Returns x,y where data[x] <= data[y], where x,y=a,b or x,y=y,x.
+#[inline]
+fn order2(data: &impl Sort, a: usize, b: usize, swaps: &mut usize) -> (usize, usize) {
+    if data.less(b, a) {
+        *swaps += 1;
+        return (b, a);
+    }
+    (a, b)
+}
+
+/// Returns x where data[x] is the median of a,b,c, where x is a, b, or c.
+#[inline]
+fn median(data: &impl Sort, a: usize, b: usize, c: usize, swaps: &mut usize) -> usize {
+    let (a, b) = order2(data, a, b, swaps);
+    let (b, _) = order2(data, b, c, swaps);
+    let (_, b) = order2(data, a, b, swaps);
+    b
+}
+
+/// Finds the median of data[a - 1], data[a], data[a + 1] and stores the index into a.
+#[inline]
+fn median_adjacent(data: &impl Sort, a: usize, swaps: &mut usize) -> usize {
+    median(data, a - 1, a, a + 1, swaps)
+}
+
+#[inline]
+fn reverse_range(data: &mut impl Sort, a: usize, b: usize) {
+    let mut i = a;
+    let mut j = b - 1;
+    while i < j {
+        data.swap(i, j);
+        i += 1;
+        j -= 1;
     }
 }
 
@@ -759,26 +1395,12 @@ fn rotate(data: &mut impl Sort, a: usize, m: usize, b: usize) {
     swap_range(data, m - i, m, i);
 }
 
-/// Returns a threshold at which quicksort should switch
-/// to heapsort. It returns 2*ceil(lg(n+1)).
-#[inline]
-fn max_depth(n: usize) -> usize {
-    let mut depth = 0;
-    let mut i = n;
-    while i > 0 {
-        depth += 1;
-        i >>= 1;
-    }
-    depth * 2
-}
-
 /// Sort data.
 /// It makes one call to `data.len` to determine n and `O(n*log(n))` calls to
 /// `data.less` and `data.swap`. The sort is not guaranteed to be stable.
 #[inline]
 pub fn sort(data: &mut impl Sort) {
-    let n = data.len();
-    quick_sort(data, 0, n, max_depth(n));
+    pdqsort_all(data);
 }
 
 /// Sort data (stable).
@@ -791,8 +1413,7 @@ pub fn sort_stable(data: &mut impl Sort) {
 /// Sort data in reverse order.
 #[inline]
 pub fn sort_reverse(data: &mut impl Sort) {
-    let n = data.len();
-    quick_sort(&mut Reverse(data), 0, n, max_depth(n));
+    pdqsort_all(&mut Reverse(data));
 }
 
 /// Sort data in reverse order (stable).
@@ -802,6 +1423,73 @@ pub fn sort_stable_reverse(data: &mut impl Sort) {
     stable(&mut Reverse(data), n);
 }
 
+/// Rearranges data so that the element at index `k` is the one that would be
+/// there if data were sorted, every element before it is not greater and
+/// every element after it is not less.
+///
+/// It uses introselect: quickselect with the pivots and partitioning of
+/// [`sort`], falling back to heapsort after too many bad pivots, so it makes
+/// O(n) calls to data.Less and data.Swap on average and O(n*log(n)) in the
+/// worst case.
+///
+/// # Panics
+///
+/// Panics if `k >= data.len()`.
+#[inline]
+pub fn select(data: &mut impl Sort, k: usize) {
+    let n = data.len();
+    assert!(k < n, "sort: select index out of range");
+    introselect(data, 0, n, k, bits_len(n));
+}
+
+/// Sorts the smallest `k` elements of data into `data[0:k]`, leaving the rest
+/// in unspecified order. If `k >= data.len()`, all of data is sorted.
+///
+/// It makes O(n + k*log(k)) calls to data.Less and data.Swap on average.
+/// The sort is not guaranteed to be stable.
+#[inline]
+pub fn partial_sort(data: &mut impl Sort, k: usize) {
+    let n = data.len();
+    if k >= n {
+        pdqsort_all(data);
+        return;
+    }
+    if k == 0 {
+        return;
+    }
+    // data[k-1] is in place and nothing before it is greater,
+    // so sorting data[0:k-1] leaves data[0:k] sorted.
+    introselect(data, 0, n, k - 1, bits_len(n));
+    pdqsort(data, 0, k - 1, bits_len(k - 1));
+}
+
+/// Moves the `k` greatest elements of data to its front in descending order
+/// and returns them. NaNs are treated as smaller than any other value.
+/// If `k >= data.len()`, all of data is sorted in descending order.
+#[inline]
+pub fn top_k<T: PartialOrd>(data: &mut [T], k: usize) -> &mut [T] {
+    top_k_slice(data, k, |d, i, j| __less(&d[i], &d[j]))
+}
+
+/// Moves the `k` greatest elements of data, as ordered by `less`, to its front
+/// in descending order and returns them.
+/// If `k >= data.len()`, all of data is sorted in descending order.
+#[inline]
+pub fn top_k_slice<T, L>(data: &mut [T], k: usize, less: L) -> &mut [T]
+where
+    L: Fn(&[T], usize, usize) -> bool,
+{
+    let k = k.min(data.len());
+    partial_sort(
+        &mut Reverse(&mut LessSwap {
+            data: &mut *data,
+            less,
+        }),
+        k,
+    );
+    &mut data[..k]
+}
+
 /// Golang's `sort.Search` in Rust.
 #[inline]
 pub fn search<F>(n: usize, mut f: F) -> usize
@@ -821,6 +1509,84 @@ where
     i
 }
 
+/// Golang's `sort.Find` in Rust.
+///
+/// Uses binary search to find and return the smallest index `i`
+/// in `[0, n)` at which `cmp(i) <= Equal`. If there is no such index `i`,
+/// `find` returns `i = n`. The found result is true if `i < n` and `cmp(i) == Equal`.
+/// `find` calls `cmp(i)` only for `i` in the range `[0, n)`.
+///
+/// To permit binary search, `find` requires that `cmp(i) == Greater` for
+/// a leading prefix of the range, `cmp(i) == Equal` in the middle of the range,
+/// and `cmp(i) == Less` for the final suffix of the range. (Each subrange
+/// could be empty.) The usual way to establish this condition is to
+/// interpret `cmp(i)` as a comparison of a desired target value `t`
+/// against entry `i` in an underlying indexed data structure `x`,
+/// returning `Less`, `Equal`, and `Greater` when `t < x[i]`, `t == x[i]`,
+/// and `t > x[i]`, respectively.
+///
+/// For example, to look for a particular string in a sorted, random-access
+/// list of strings:
+///
+/// ```
+/// use crabmole::sort::find;
+///
+/// let list = ["ab", "ca", "mo", "qo"];
+/// let target = "mo";
+/// let (i, found) = find(list.len(), |i| target.cmp(list[i]));
+/// assert!(found);
+/// assert_eq!(list[i], target);
+/// ```
+#[inline]
+pub fn find<F>(n: usize, mut cmp: F) -> (usize, bool)
+where
+    F: FnMut(usize) -> Ordering,
+{
+    // The invariants here are similar to the ones in search.
+    // Define cmp(-1) > 0 and cmp(n) <= 0
+    // Invariant: cmp(i-1) > 0, cmp(j) <= 0
+    let mut i = 0;
+    let mut j = n;
+    while i < j {
+        let h = (i + j) >> 1;
+        // i ≤ h < j
+        if cmp(h) == Ordering::Greater {
+            i = h + 1; // preserves cmp(i-1) > 0
+        } else {
+            j = h; // preserves cmp(j) <= 0
+        }
+    }
+    // i == j, cmp(i-1) > 0 and cmp(j) <= 0
+    (i, i < n && cmp(i) == Ordering::Equal)
+}
+
+/// Searches for `x` in a sorted slice of ints and returns the index
+/// as specified by [`search`]. The return value is the index to insert `x` if `x` is
+/// not present (it could be `a.len()`).
+/// The slice must be sorted in ascending order.
+#[inline]
+pub fn search_ints(a: &[isize], x: isize) -> usize {
+    search(a.len(), |i| a[i] >= x)
+}
+
+/// Searches for `x` in a sorted slice of float64s and returns the index
+/// as specified by [`search`]. The return value is the index to insert `x` if `x` is not
+/// present (it could be `a.len()`).
+/// The slice must be sorted in ascending order.
+#[inline]
+pub fn search_float64s(a: &[f64], x: f64) -> usize {
+    search(a.len(), |i| a[i] >= x)
+}
+
+/// Searches for `x` in a sorted slice of strings and returns the index
+/// as specified by [`search`]. The return value is the index to insert `x` if `x` is not
+/// present (it could be `a.len()`).
+/// The slice must be sorted in ascending order.
+#[inline]
+pub fn search_strings<S: AsRef<str>>(a: &[S], x: &str) -> usize {
+    search(a.len(), |i| a[i].as_ref() >= x)
+}
+
 #[cfg(test)]
 #[allow(warnings)]
 mod tests {
@@ -873,6 +1639,32 @@ mod tests {
         let mut data = FLOATS.to_vec();
         Sort::sort_stable(&mut data);
         assert!(Sort::is_sorted(&data));
+        assert!(data[0].is_nan() && data[1].is_nan());
+    }
+
+    #[test]
+    fn test_sort_total_order_skips_nan_check() {
+        // The NaN check compares elements with themselves, which a totally
+        // ordered type must never pay for.
+        #[derive(Debug)]
+        struct Total(u32);
+
+        impl PartialEq for Total {
+            fn eq(&self, _: &Self) -> bool {
+                panic!("Total compared for equality")
+            }
+        }
+
+        impl PartialOrd for Total {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.0.cmp(&other.0))
+            }
+        }
+
+        let mut data = INTS.iter().map(|&x| Total(x as u32)).collect::<Vec<_>>();
+        Sort::sort(&mut data);
+        Sort::sort_stable(&mut data);
+        assert!(Sort::is_sorted(&data));
     }
 
     #[test]
@@ -905,20 +1697,197 @@ mod tests {
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn test_sort_large_random() {
-        let mut data = (0..1000000)
-            .map(|_| rand::random::<isize>())
-            .collect::<Vec<_>>();
-        Sort::sort(&mut data);
-        assert!(Sort::is_sorted(&data));
+    fn test_sort_by_key() {
+        let mut data = STRINGS.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        String::sort_by_key(&mut data, |s| s.len());
+        assert!(String::slice_is_sorted(&data, |i, j| data[i].len() < data[j].len()));
+
+        // Stable variants keep strings of equal length in input order.
+        let mut want = STRINGS.to_vec();
+        want.sort_by_key(|s| s.len());
+
+        let mut data = STRINGS.to_vec();
+        sort_stable_by_key(&mut data, |s| s.len());
+        assert_eq!(data, want);
+
+        let mut calls = 0;
+        let mut data = STRINGS.to_vec();
+        sort_slice_by_cached_key(&mut data, |s| {
+            calls += 1;
+            s.len()
+        });
+        assert_eq!(data, want);
+        assert_eq!(
+            calls,
+            STRINGS.len(),
+            "key computed more than once per element"
+        );
+    }
 
-        let mut data = (0..1000000)
-            .map(|_| rand::random::<isize>())
-            .collect::<Vec<_>>();
-        Sort::sort_stable(&mut data);
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_sort_by_cached_key_permutation() {
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 3, 10, 100, 1000, 10000] {
+            let data = (0..n)
+                .map(|i| (rng.gen_range(0..n / 3 + 1), i))
+                .collect::<Vec<(usize, usize)>>();
+            let mut want = data.clone();
+            want.sort_by_key(|p| p.0);
+            let mut got = data;
+            sort_slice_by_cached_key(&mut got, |p| p.0);
+            assert_eq!(got, want, "sort_slice_by_cached_key on {n} pairs");
+        }
+
+        let mut data = FLOATS.to_vec();
+        sort_slice_by_cached_key(&mut data, |f| *f);
         assert!(Sort::is_sorted(&data));
     }
 
+    #[test]
+    fn test_by_key() {
+        let mut keys = [5, 2, 8, 2, 1];
+        let mut names = ["e", "b", "h", "c", "a"];
+        let mut values = vec![50, 20, 80, 21, 10];
+        let mut sorter = ByKey::new(&mut keys, (&mut names[..], &mut values));
+        sorter.sort_stable();
+        assert_eq!(keys, [1, 2, 2, 5, 8]);
+        assert_eq!(names, ["a", "b", "c", "e", "h"]);
+        assert_eq!(values, [10, 20, 21, 50, 80]);
+
+        let mut rng = rand::thread_rng();
+        let mut keys = (0..10000).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+        let mut copy = keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        ByKey::new(&mut keys, (&mut copy,)).sort();
+        assert!(Sort::is_sorted(&keys));
+        assert!(keys.iter().zip(&copy).all(|(k, c)| k.to_string() == *c));
+    }
+
+    #[test]
+    #[should_panic(expected = "sort: parallel slices have different lengths")]
+    fn test_by_key_len_mismatch() {
+        let mut keys = [1, 2, 3];
+        let mut values = [1, 2];
+        ByKey::new(&mut keys, &mut values[..]);
+    }
+
+    #[test]
+    fn test_sort_vec_deque() {
+        let mut data = std::collections::VecDeque::new();
+        for &x in INTS {
+            // Push to both ends so that the elements wrap around the buffer.
+            if x % 2 == 0 {
+                data.push_front(x);
+            } else {
+                data.push_back(x);
+            }
+        }
+        data.sort();
+        assert!(data.iter().zip(data.iter().skip(1)).all(|(a, b)| a <= b));
+        assert_eq!(data.len(), INTS.len());
+    }
+
+    #[test]
+    fn test_sort_columns() {
+        let mut keys = [5, 2, 8, 2, 1];
+        let mut names = ["e", "b", "h", "c", "a"];
+        (&mut keys[..], &mut names[..]).sort_stable();
+        assert_eq!(keys, [1, 2, 2, 5, 8]);
+        assert_eq!(names, ["a", "b", "c", "e", "h"]);
+
+        let mut keys = vec![3.5, 1.5, 2.5];
+        let mut names = vec!["c", "a", "b"];
+        let mut values = vec![30, 10, 20];
+        (&mut keys, &mut names, &mut values).sort();
+        assert_eq!(keys, [1.5, 2.5, 3.5]);
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(values, [10, 20, 30]);
+    }
+
+    #[test]
+    #[should_panic(expected = "sort: parallel slices have different lengths")]
+    fn test_sort_columns_different_lengths() {
+        let mut keys = [3, 1, 2];
+        let mut names = ["c", "a"];
+        (&mut keys[..], &mut names[..]).sort();
+    }
+
+    #[cfg(feature = "derive")]
+    #[derive(Sort)]
+    struct Rows {
+        id: Vec<u32>,
+        #[sort(key)]
+        score: Vec<f64>,
+        name: ::alloc::collections::VecDeque<&'static str>,
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn test_derive_sort_columns() {
+        let mut rows = Rows {
+            id: vec![1, 2, 3, 4, 5],
+            score: vec![2.5, f64::NAN, 1.5, 2.5, 0.5],
+            name: ["b1", "nan", "a", "b2", "z"].into_iter().collect(),
+        };
+        rows.sort_stable();
+        assert!(rows.score[0].is_nan());
+        assert_eq!(rows.score[1..], [0.5, 1.5, 2.5, 2.5]);
+        assert_eq!(rows.id, [2, 5, 3, 1, 4]);
+        assert_eq!(rows.name, ["nan", "z", "a", "b1", "b2"]);
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn test_derive_sort_tuple_struct() {
+        #[derive(Sort)]
+        struct Pair<'a>(Vec<char>, #[sort(key)] &'a mut [u8]);
+
+        let mut keys = [3, 1, 2];
+        let mut pair = Pair(vec!['c', 'a', 'b'], &mut keys);
+        pair.sort();
+        assert_eq!(pair.0, ['a', 'b', 'c']);
+        assert_eq!(keys, [1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    #[should_panic(expected = "sort: parallel slices have different lengths")]
+    fn test_derive_sort_different_lengths() {
+        let mut rows = Rows {
+            id: vec![1, 2],
+            score: vec![2.5, 1.5, 0.5],
+            name: ["a", "b", "c"].into_iter().collect(),
+        };
+        rows.sort();
+    }
+
+    /// The sorts of `Vec<isize>` that share the regression tests.
+    fn sorts() -> Vec<(&'static str, fn(&mut Vec<isize>))> {
+        #[allow(unused_mut)]
+        let mut sorts: Vec<(&'static str, fn(&mut Vec<isize>))> = vec![
+            ("sort", |d| Sort::sort(d)),
+            ("sort_stable", |d| Sort::sort_stable(d)),
+        ];
+        #[cfg(feature = "parallel")]
+        sorts.extend([
+            ("par_sort", (|d| par_sort(d)) as fn(&mut Vec<isize>)),
+            ("par_sort_stable", |d| par_sort_stable(d)),
+        ]);
+        sorts
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_sort_large_random() {
+        for (name, sort) in sorts() {
+            let mut data = (0..1000000)
+                .map(|_| rand::random::<isize>())
+                .collect::<Vec<_>>();
+            sort(&mut data);
+            assert!(Sort::is_sorted(&data), "{name} didn't sort 1000000 ints");
+        }
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn test_reverse_sort_int_slice() {
@@ -935,6 +1904,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_find() {
+        let str1 = ["foo"];
+        let str2 = ["ab", "ca"];
+        let str3 = ["mo", "qo", "vo"];
+        let str4 = ["ab", "ad", "ca", "xy"];
+        // slice with repeating elements
+        let str_repeats = ["ba", "ca", "da", "da", "da", "ka", "ma", "ma", "ta"];
+        // slice with all element equal
+        let str_same = ["xx", "xx", "xx"];
+
+        let tests: &[(&[&str], &str, usize, bool)] = &[
+            (&[], "foo", 0, false),
+            (&[], "", 0, false),
+            (&str1, "foo", 0, true),
+            (&str1, "bar", 0, false),
+            (&str1, "zx", 1, false),
+            (&str2, "aa", 0, false),
+            (&str2, "ab", 0, true),
+            (&str2, "ad", 1, false),
+            (&str2, "ca", 1, true),
+            (&str2, "ra", 2, false),
+            (&str3, "bb", 0, false),
+            (&str3, "mo", 0, true),
+            (&str3, "nb", 1, false),
+            (&str3, "qo", 1, true),
+            (&str3, "tr", 2, false),
+            (&str3, "vo", 2, true),
+            (&str3, "xr", 3, false),
+            (&str4, "aa", 0, false),
+            (&str4, "ab", 0, true),
+            (&str4, "ac", 1, false),
+            (&str4, "ad", 1, true),
+            (&str4, "ax", 2, false),
+            (&str4, "ca", 2, true),
+            (&str4, "cc", 3, false),
+            (&str4, "dd", 3, false),
+            (&str4, "xy", 3, true),
+            (&str4, "zz", 4, false),
+            (&str_repeats, "da", 2, true),
+            (&str_repeats, "db", 5, false),
+            (&str_repeats, "ma", 6, true),
+            (&str_repeats, "mb", 8, false),
+            (&str_same, "xx", 0, true),
+            (&str_same, "ab", 0, false),
+            (&str_same, "zz", 3, false),
+        ];
+        for (data, target, want_pos, want_found) in tests {
+            let mut calls = 0;
+            let (pos, found) = find(data.len(), |i| {
+                calls += 1;
+                target.cmp(&data[i])
+            });
+            assert_eq!(
+                (pos, found),
+                (*want_pos, *want_found),
+                "find({data:?}, {target:?})"
+            );
+            assert!(calls <= bits_len(data.len()) + 1, "find used {calls} calls");
+        }
+    }
+
+    #[test]
+    fn test_search_wrappers() {
+        let data: &[isize] = &[-10, -5, 0, 1, 2, 3, 5, 7, 11, 100, 100, 100, 1000, 10000];
+        let fdata = [-3.25, 0.0, 1.0, 2.0, 1000.7];
+        let sdata = ["f", "foo", "foobar", "x"];
+
+        assert_eq!(search_ints(data, 11), 8);
+        assert_eq!(search_ints(data, 100), 9);
+        assert_eq!(search_ints(data, 10001), data.len());
+        assert_eq!(search_float64s(&fdata, 2.1), 4);
+        assert_eq!(search_float64s(&fdata, -4.0), 0);
+        assert_eq!(search_strings(&sdata, ""), 0);
+        assert_eq!(search_strings(&sdata, "foo"), 1);
+        assert_eq!(search_strings(&sdata, "z"), 4);
+    }
+
     #[derive(Debug)]
     struct NonDeterministicTestingData;
 
@@ -1293,6 +2340,140 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_sort_patterns_linear() {
+        // pdqsort recognizes these inputs and finishes them in linear time.
+        const N: usize = 100_000;
+        let patterns: [(&str, Vec<usize>); 4] = [
+            ("sorted", (0..N).collect()),
+            ("reversed", (0..N).rev().collect()),
+            ("equal", vec![7; N]),
+            ("sorted with a swapped pair", {
+                let mut v = (0..N).collect::<Vec<_>>();
+                v.swap(N / 2, N / 2 + 1);
+                v
+            }),
+        ];
+        for (name, data) in patterns {
+            let mut d = TestingData {
+                desc: name.to_string(),
+                data,
+                max_swap: N,
+                ncmp: Cell::new(0),
+                nswap: 0,
+            };
+            d.sort();
+            let ncmp = d.ncmp.get();
+            assert!(d.is_sorted(), "{name}: not sorted");
+            assert!(
+                ncmp <= 3 * N,
+                "{name}: used {ncmp} comparisons sorting {N} elements"
+            );
+        }
+    }
+
+    fn select_inputs(n: usize) -> Vec<(&'static str, Vec<usize>)> {
+        let mut rng = rand::thread_rng();
+        vec![
+            ("random", (0..n).map(|_| rng.gen_range(0..n)).collect()),
+            (
+                "few distinct",
+                (0..n).map(|_| rng.gen_range(0..3)).collect(),
+            ),
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+            ("equal", vec![7; n]),
+            ("sawtooth", (0..n).map(|i| i % 37).collect()),
+            ("organ pipe", (0..n).map(|i| i.min(n - i)).collect()),
+        ]
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_select() {
+        for n in [1, 2, 13, 100, 1000, 100_000] {
+            for (name, data) in select_inputs(n) {
+                let mut want = data.clone();
+                want.sort();
+                for k in [0, n / 4, n / 2, n - 1] {
+                    let mut d = TestingData {
+                        desc: name.to_string(),
+                        data: data.clone(),
+                        max_swap: usize::MAX,
+                        ncmp: Cell::new(0),
+                        nswap: 0,
+                    };
+                    select(&mut d, k);
+                    let ncmp = d.ncmp.get();
+                    assert_eq!(d.data[k], want[k], "{name}: select({n}, {k})");
+                    assert!(
+                        d.data[..k].iter().all(|x| *x <= d.data[k])
+                            && d.data[k + 1..].iter().all(|x| *x >= d.data[k]),
+                        "{name}: select({n}, {k}) didn't partition"
+                    );
+                    assert!(
+                        n < 1000 || ncmp <= 8 * n,
+                        "{name}: select used {ncmp} comparisons on {n} elements"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sort: select index out of range")]
+    fn test_select_out_of_range() {
+        select(&mut vec![1, 2, 3], 3);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_partial_sort() {
+        for n in [0, 1, 2, 13, 100, 10_000] {
+            for (name, data) in select_inputs(n.max(1)) {
+                let mut want = data.clone();
+                want.sort();
+                for k in [0, 1, 5, n / 2, n, n + 1] {
+                    let mut d = data.clone();
+                    partial_sort(&mut d, k);
+                    let k = k.min(d.len());
+                    assert_eq!(d[..k], want[..k], "{name}: partial_sort({n}, {k})");
+                    d.sort();
+                    assert_eq!(d, want, "{name}: partial_sort({n}, {k}) lost elements");
+                }
+            }
+        }
+
+        let mut data = FLOATS.to_vec();
+        partial_sort(&mut data, 4);
+        assert!(data[0].is_nan() && data[1].is_nan());
+        assert_eq!(data[2..4], [f64::NEG_INFINITY, -959.7485]);
+        assert!(Sort::is_sorted(&data[..4].to_vec()));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_top_k() {
+        let mut rng = rand::thread_rng();
+        let mut data = (0..10_000)
+            .map(|_| rng.gen_range(0..1_000_000))
+            .collect::<Vec<i64>>();
+        let mut want = data.clone();
+        want.sort_by(|a, b| b.cmp(a));
+        assert_eq!(top_k(&mut data, 10), &want[..10]);
+        assert_eq!(top_k(&mut data, 20_000), &want[..]);
+        assert!(top_k(&mut data, 0).is_empty());
+
+        let mut data = FLOATS.to_vec();
+        let top = top_k(&mut data, 2);
+        assert_eq!(top, [f64::INFINITY, 9845.768]);
+
+        let mut scores = [("a", 3), ("b", 9), ("c", 1), ("d", 9), ("e", 5)];
+        let top = top_k_slice(&mut scores, 3, |d, i, j| d[i].1 < d[j].1);
+        assert_eq!(top.iter().map(|s| s.1).collect::<Vec<_>>(), [9, 9, 5]);
+    }
+
     #[derive(Clone, Copy)]
     struct Pair {
         a: isize,
@@ -1348,9 +2529,27 @@ mod tests {
         }
     }
 
+    /// The stable sorts of [`Pairs`] that share the stability tests.
+    fn stable_sorts() -> Vec<(&'static str, fn(&mut Pairs))> {
+        #[allow(unused_mut)]
+        let mut sorts: Vec<(&'static str, fn(&mut Pairs))> =
+            vec![("sort_stable", |d| d.sort_stable())];
+        #[cfg(feature = "parallel")]
+        sorts.push(("par_sort_slice_stable", |d| {
+            Pair::par_sort_slice_stable(&mut d.data, |d, i, j| d[i].a < d[j].a)
+        }));
+        sorts
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn test_stability() {
+        for (name, sort) in stable_sorts() {
+            check_stability(name, sort);
+        }
+    }
+
+    fn check_stability(name: &str, sort: fn(&mut Pairs)) {
         const N: usize = 100_000;
         const M: usize = 1000;
         let mut data = Pairs {
@@ -1365,18 +2564,18 @@ mod tests {
         assert!(!data.is_sorted(), "terrible rand");
 
         data.init_b();
-        data.sort_stable();
-        assert!(data.is_sorted(), "Stable didn't sort {N} ints");
-        assert!(data.in_order(), "Stable wasn't stable on {N} ints");
+        sort(&mut data);
+        assert!(data.is_sorted(), "{name} didn't sort {N} ints");
+        assert!(data.in_order(), "{name} wasn't stable on {N} ints");
 
         // already sorted
         data.init_b();
-        data.sort_stable();
-        assert!(data.is_sorted(), "Stable shuffled sorted {N} ints (order)");
+        sort(&mut data);
+        assert!(data.is_sorted(), "{name} shuffled sorted {N} ints (order)");
 
         assert!(
             data.in_order(),
-            "Stable shuffled sorted {N} ints (stability)"
+            "{name} shuffled sorted {N} ints (stability)"
         );
 
         // sorted reversed
@@ -1387,9 +2586,9 @@ mod tests {
             data.data[i].a = (N - i) as isize;
         }
         data.init_b();
-        data.sort_stable();
-        assert!(data.is_sorted(), "Stable didn't sort {N} ints");
-        assert!(data.in_order(), "Stable wasn't stable on {N} ints");
+        sort(&mut data);
+        assert!(data.is_sorted(), "{name} didn't sort {N} ints");
+        assert!(data.in_order(), "{name} wasn't stable on {N} ints");
     }
 
     const COUNT_OPS_SIZES: &[usize] =
@@ -0,0 +1,491 @@
+//! Sorting of record streams that do not fit in memory.
+//!
+//! Records are byte strings, each framed by its length as a uvarint (see
+//! [`write_uvarint`]). A [`Sorter`] reads records from a source until its
+//! memory budget is used up, sorts them with [`sort_slice_stable`] and
+//! spills them as a run to a temporary file. The runs are then merged with
+//! a heap from [`container::heap`] into the sink, which receives the records
+//! framed the same way. Inputs that fit in the budget never touch the disk.
+//!
+//! The sort is stable: records that compare equal keep their input order.
+//!
+//! [`write_uvarint`]: crate::encoding::binary::write_uvarint
+//! [`sort_slice_stable`]: crate::sort::sort_slice_stable
+//! [`container::heap`]: crate::container::heap
+//! [`Sorter`]: crate::sort::external::Sorter
+use super::{sort_slice_stable, Sort};
+use crate::container::heap::{self, Heap};
+use crate::encoding::binary::{read_uvarint, write_uvarint};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The default memory budget of a [`Sorter`], 64 MiB.
+pub const DEFAULT_MEMORY_BUDGET: usize = 64 << 20;
+
+/// The default number of runs a [`Sorter`] merges at once.
+pub const DEFAULT_FAN_IN: usize = 128;
+
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes `record` to `w`, preceded by its length as a uvarint.
+#[inline]
+pub fn write_record<W: Write>(mut w: W, record: &[u8]) -> io::Result<()> {
+    write_uvarint(&mut w, record.len() as u64)?;
+    w.write_all(record)
+}
+
+/// Reads the next record from `r` and appends it to `buf`.
+///
+/// Returns `Ok(false)` if `r` is at EOF before the record starts, and an
+/// error of kind [`io::ErrorKind::UnexpectedEof`] if it ends inside one.
+pub fn read_record<R: BufRead>(r: &mut R, buf: &mut Vec<u8>) -> io::Result<bool> {
+    if r.fill_buf()?.is_empty() {
+        return Ok(false);
+    }
+    let (len, _) = read_uvarint::<_, u64>(&mut *r)?;
+    // Don't trust len with an allocation up front, the input may be corrupt.
+    let n = r.take(len).read_to_end(buf)?;
+    if n as u64 != len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "sort: record is truncated",
+        ));
+    }
+    Ok(true)
+}
+
+/// An external merge sort over length-framed records.
+///
+/// The records are ordered by the `less` function, which reports whether
+/// record `a` must sort before record `b` and must describe a transitive
+/// ordering like [`Sort::less`]. [`Sorter::new`] orders records bytewise.
+#[derive(Debug, Clone)]
+pub struct Sorter<L> {
+    memory_budget: usize,
+    fan_in: usize,
+    temp_dir: PathBuf,
+    less: L,
+}
+
+impl Default for Sorter<fn(&[u8], &[u8]) -> bool> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sorter<fn(&[u8], &[u8]) -> bool> {
+    /// Returns a [`Sorter`] that orders records bytewise, with a memory
+    /// budget of [`DEFAULT_MEMORY_BUDGET`] and runs spilled to
+    /// [`std::env::temp_dir`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            fan_in: DEFAULT_FAN_IN,
+            temp_dir: std::env::temp_dir(),
+            less: |a, b| a < b,
+        }
+    }
+}
+
+impl<L> Sorter<L>
+where
+    L: Fn(&[u8], &[u8]) -> bool,
+{
+    /// Sets the number of bytes of records, plus their bookkeeping, held in
+    /// memory before a run is spilled to disk. A run always holds at least
+    /// one record, however large.
+    #[inline]
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// Sets how many runs are merged at once, which bounds the number of
+    /// temporary files open at the same time. When there are more runs,
+    /// they are merged into longer runs first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n < 2`.
+    #[inline]
+    pub fn fan_in(mut self, n: usize) -> Self {
+        assert!(n >= 2, "sort: fan-in must be at least 2");
+        self.fan_in = n;
+        self
+    }
+
+    /// Sets the directory the runs are spilled to.
+    #[inline]
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    /// Replaces the comparator with `less`.
+    #[inline]
+    pub fn less<M>(self, less: M) -> Sorter<M>
+    where
+        M: Fn(&[u8], &[u8]) -> bool,
+    {
+        Sorter {
+            memory_budget: self.memory_budget,
+            fan_in: self.fan_in,
+            temp_dir: self.temp_dir,
+            less,
+        }
+    }
+
+    /// Reads every record from `r`, and writes them to `w` in sorted order.
+    /// Returns the number of records.
+    ///
+    /// The temporary files are removed before returning, also on error.
+    pub fn sort<R: Read, W: Write>(&self, r: R, w: W) -> io::Result<u64> {
+        let mut r = BufReader::new(r);
+        let mut w = BufWriter::new(w);
+        let mut run = Run::default();
+        let mut runs = Vec::new();
+        let mut count = 0;
+
+        while run.read(&mut r)? {
+            count += 1;
+            if run.size() >= self.memory_budget {
+                runs.push(self.spill(&mut run)?);
+            }
+        }
+
+        if runs.is_empty() {
+            run.write_sorted(&self.less, &mut w)?;
+        } else {
+            if !run.is_empty() {
+                runs.push(self.spill(&mut run)?);
+            }
+            drop(run);
+
+            while runs.len() > self.fan_in {
+                runs = runs
+                    .chunks(self.fan_in)
+                    .map(|group| {
+                        let (merged, file) = TempRun::create(&self.temp_dir)?;
+                        self.merge(group, BufWriter::new(file))?;
+                        Ok(merged)
+                    })
+                    .collect::<io::Result<_>>()?;
+            }
+            self.merge(&runs, &mut w)?;
+        }
+        w.flush()?;
+        Ok(count)
+    }
+
+    /// Sorts `run` into a new temporary file and clears it.
+    fn spill(&self, run: &mut Run) -> io::Result<TempRun> {
+        let (spilled, file) = TempRun::create(&self.temp_dir)?;
+        run.write_sorted(&self.less, BufWriter::new(file))?;
+        run.clear();
+        Ok(spilled)
+    }
+
+    /// Merges `runs`, which are consecutive in input order, into `w`.
+    fn merge<W: Write>(&self, runs: &[TempRun], mut w: W) -> io::Result<()> {
+        let mut readers = Vec::with_capacity(runs.len());
+        let mut heads = Heads {
+            heads: Vec::with_capacity(runs.len()),
+            less: &self.less,
+        };
+        for (i, run) in runs.iter().enumerate() {
+            let mut r = BufReader::new(File::open(&run.path)?);
+            let mut record = Vec::new();
+            if read_record(&mut r, &mut record)? {
+                heads.heads.push((record, i));
+            }
+            readers.push(r);
+        }
+
+        heap::init(&mut heads);
+        while let Some((mut record, i)) = heap::pop(&mut heads) {
+            write_record(&mut w, &record)?;
+            record.clear();
+            if read_record(&mut readers[i], &mut record)? {
+                heap::push(&mut heads, (record, i));
+            }
+        }
+        w.flush()
+    }
+}
+
+/// The records of one run, stored back to back in `data`.
+#[derive(Default)]
+struct Run {
+    data: Vec<u8>,
+    bounds: Vec<(usize, usize)>,
+}
+
+impl Run {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.data.len() + self.bounds.len() * core::mem::size_of::<(usize, usize)>()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.data.clear();
+        self.bounds.clear();
+    }
+
+    fn read<R: BufRead>(&mut self, r: &mut R) -> io::Result<bool> {
+        let start = self.data.len();
+        if !read_record(r, &mut self.data)? {
+            return Ok(false);
+        }
+        self.bounds.push((start, self.data.len()));
+        Ok(true)
+    }
+
+    fn write_sorted<L, W>(&mut self, less: &L, mut w: W) -> io::Result<()>
+    where
+        L: Fn(&[u8], &[u8]) -> bool,
+        W: Write,
+    {
+        let data = &self.data;
+        sort_slice_stable(&mut self.bounds, |d, i, j| {
+            less(&data[d[i].0..d[i].1], &data[d[j].0..d[j].1])
+        });
+        for &(start, end) in &self.bounds {
+            write_record(&mut w, &data[start..end])?;
+        }
+        w.flush()
+    }
+}
+
+/// The path of a spilled run, removed when dropped. The file is only open
+/// while the run is written or merged.
+struct TempRun {
+    path: PathBuf,
+}
+
+impl TempRun {
+    fn create(dir: &Path) -> io::Result<(Self, File)> {
+        let path = dir.join(format!(
+            "crabmole-sort-{}-{}.run",
+            std::process::id(),
+            NEXT_RUN.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok((Self { path }, file))
+    }
+}
+
+impl Drop for TempRun {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The next record of every run that is not exhausted yet, as a min-heap.
+/// Equal records are ordered by run, which keeps the merge stable.
+struct Heads<'a, L> {
+    heads: Vec<(Vec<u8>, usize)>,
+    less: &'a L,
+}
+
+impl<L> Sort for Heads<'_, L>
+where
+    L: Fn(&[u8], &[u8]) -> bool,
+{
+    fn len(&self) -> usize {
+        self.heads.len()
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        let ((a, ra), (b, rb)) = (&self.heads[i], &self.heads[j]);
+        (self.less)(a, b) || (!(self.less)(b, a) && ra < rb)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heads.swap(i, j);
+    }
+}
+
+impl<L> Heap for Heads<'_, L>
+where
+    L: Fn(&[u8], &[u8]) -> bool,
+{
+    type Item = (Vec<u8>, usize);
+
+    fn push(&mut self, x: Self::Item) {
+        self.heads.push(x);
+    }
+
+    fn pop(&mut self) -> Option<Self::Item> {
+        self.heads.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn frame(records: &[Vec<u8>]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for r in records {
+            write_record(&mut buf, r).unwrap();
+        }
+        buf
+    }
+
+    fn unframe(mut buf: &[u8]) -> Vec<Vec<u8>> {
+        let mut records = Vec::new();
+        loop {
+            let mut r = Vec::new();
+            if !read_record(&mut buf, &mut r).unwrap() {
+                return records;
+            }
+            records.push(r);
+        }
+    }
+
+    fn random_records(n: usize) -> Vec<Vec<u8>> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| {
+                let len = rng.gen_range(0..40);
+                (0..len).map(|_| rng.gen_range(b'a'..=b'e')).collect()
+            })
+            .collect()
+    }
+
+    // A directory of its own, so that the test can check nothing is left behind.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("crabmole-sort-test-{name}-{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn is_empty(&self) -> bool {
+            fs::read_dir(&self.0).unwrap().next().is_none()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        let records = vec![vec![], b"a".to_vec(), vec![7; 300]];
+        assert_eq!(unframe(&frame(&records)), records);
+
+        let buf = frame(&[vec![7; 300]]);
+        let mut r = &buf[..buf.len() - 1];
+        let err = read_record(&mut r, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_sort_in_memory() {
+        let dir = TempDir::new("memory");
+        let records = random_records(1000);
+        let mut want = records.clone();
+        want.sort();
+
+        let mut out = Vec::new();
+        let n = Sorter::new()
+            .temp_dir(&dir.0)
+            .sort(&frame(&records)[..], &mut out)
+            .unwrap();
+        assert_eq!(n, 1000);
+        assert_eq!(unframe(&out), want);
+        assert!(dir.is_empty());
+
+        let mut out = Vec::new();
+        assert_eq!(Sorter::new().sort(&[][..], &mut out).unwrap(), 0);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_sort_spilled() {
+        let dir = TempDir::new("spilled");
+        let records = random_records(20_000);
+        let mut want = records.clone();
+        want.sort();
+
+        // About 600 runs, merged in three passes.
+        for fan_in in [DEFAULT_FAN_IN, 9, 2] {
+            let mut out = Vec::new();
+            let n = Sorter::new()
+                .memory_budget(1 << 10)
+                .fan_in(fan_in)
+                .temp_dir(&dir.0)
+                .sort(&frame(&records)[..], &mut out)
+                .unwrap();
+            assert_eq!(n, records.len() as u64);
+            assert!(unframe(&out) == want, "fan-in {fan_in}: wrong order");
+            assert!(dir.is_empty(), "fan-in {fan_in}: runs left behind");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_sort_stable_custom_less() {
+        let dir = TempDir::new("stable");
+        // Order by the first byte only, descending; the rest records the input position.
+        let mut rng = rand::thread_rng();
+        let records = (0..10_000u32)
+            .map(|i| {
+                let mut r = vec![rng.gen_range(0..20u8)];
+                r.extend_from_slice(&i.to_be_bytes());
+                r
+            })
+            .collect::<Vec<_>>();
+        let mut want = records.clone();
+        want.sort_by(|a, b| b[0].cmp(&a[0]));
+
+        let mut out = Vec::new();
+        Sorter::new()
+            .memory_budget(4 << 10)
+            .fan_in(4)
+            .temp_dir(&dir.0)
+            .less(|a, b| a[0] > b[0])
+            .sort(&frame(&records)[..], &mut out)
+            .unwrap();
+        assert!(unframe(&out) == want, "external sort wasn't stable");
+        assert!(dir.is_empty());
+    }
+
+    #[test]
+    fn test_sort_truncated_input() {
+        let dir = TempDir::new("truncated");
+        let mut records = random_records(1000);
+        records.push(b"last".to_vec());
+        let buf = frame(&records);
+        let err = Sorter::new()
+            .memory_budget(256)
+            .temp_dir(&dir.0)
+            .sort(&buf[..buf.len() - 1], io::sink())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(dir.is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+use super::{pdqsort_all, stable, Sort};
+use core::cell::Cell;
+
+/// Counts of the calls a sort made to [`Sort::less`] and [`Sort::swap`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The number of calls to [`Sort::less`].
+    pub compares: usize,
+    /// The number of calls to [`Sort::swap`].
+    pub swaps: usize,
+}
+
+/// A [`Sort`] that forwards to `data` and counts the calls to `less` and `swap`.
+///
+/// ```
+/// use crabmole::sort::{Instrumented, Sort};
+///
+/// let mut data = vec![5, 2, 4, 1, 3];
+/// let mut counted = Instrumented::new(&mut data);
+/// counted.sort();
+/// let stats = counted.stats();
+/// assert!(stats.compares > 0 && stats.swaps > 0);
+/// assert_eq!(data, [1, 2, 3, 4, 5]);
+/// ```
+#[derive(Debug)]
+pub struct Instrumented<'a, S> {
+    data: &'a mut S,
+    compares: Cell<usize>,
+    swaps: usize,
+}
+
+impl<'a, S: Sort> Instrumented<'a, S> {
+    /// Returns a [`Sort`] over `data` with both counts at zero.
+    #[inline]
+    pub fn new(data: &'a mut S) -> Self {
+        Self {
+            data,
+            compares: Cell::new(0),
+            swaps: 0,
+        }
+    }
+
+    /// Returns the calls counted so far.
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        Stats {
+            compares: self.compares.get(),
+            swaps: self.swaps,
+        }
+    }
+
+    /// Sets both counts back to zero.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.compares.set(0);
+        self.swaps = 0;
+    }
+}
+
+impl<S: Sort> Sort for Instrumented<'_, S> {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        self.compares.set(self.compares.get() + 1);
+        self.data.less(i, j)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.swaps += 1;
+        self.data.swap(i, j);
+    }
+}
+
+/// A way in which [`Sort::less`] was found not to be a strict weak ordering.
+///
+/// The indexes are those of the elements when the problem was detected,
+/// which may differ from their positions in the input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ComparatorError {
+    /// `less(i, i)` returned true.
+    Reflexive {
+        /// The index of the element that is less than itself.
+        index: usize,
+    },
+    /// Both `less(i, j)` and `less(j, i)` returned true.
+    Symmetric {
+        /// The index of the first element.
+        i: usize,
+        /// The index of the second element.
+        j: usize,
+    },
+    /// `less(j, i)` returned true for `i < j` after sorting, so the ordering
+    /// is not transitive.
+    Intransitive {
+        /// The index of the element sorted first.
+        i: usize,
+        /// The index of the element sorted later that is less than it.
+        j: usize,
+    },
+}
+
+impl core::fmt::Display for ComparatorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Reflexive { index } => {
+                write!(f, "sort: less({index}, {index}) is true")
+            }
+            Self::Symmetric { i, j } => {
+                write!(f, "sort: less({i}, {j}) and less({j}, {i}) are both true")
+            }
+            Self::Intransitive { i, j } => write!(
+                f,
+                "sort: less({j}, {i}) is true after sorting, the ordering is not transitive"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ComparatorError {}
+
+/// Like [`sort`](super::sort), but checks that `less` is a strict weak
+/// ordering and returns the first violation found instead of silently
+/// misordering data.
+///
+/// Before sorting, every element is checked not to be less than itself. Every
+/// comparison the sort makes is also made the other way round, to catch
+/// pairs that are less than each other. After sorting, elements `2^k` apart
+/// are compared for every `k`, which may detect orderings that are not
+/// transitive. This makes two to three times as many calls to [`Sort::less`]
+/// as [`sort`](super::sort), so it is meant for tests and debug builds, for
+/// example behind `cfg!(debug_assertions)`.
+///
+/// On error, data holds a permutation of its elements in unspecified order.
+#[inline]
+pub fn checked_sort(data: &mut impl Sort) -> Result<(), ComparatorError> {
+    checked(data, |c| pdqsort_all(c))
+}
+
+/// Like [`checked_sort`], but sorts data with
+/// [`sort_stable`](super::sort_stable).
+#[inline]
+pub fn checked_sort_stable(data: &mut impl Sort) -> Result<(), ComparatorError> {
+    checked(data, |c| {
+        let n = c.len();
+        stable(c, n);
+    })
+}
+
+fn checked<S, F>(data: &mut S, sort: F) -> Result<(), ComparatorError>
+where
+    S: Sort,
+    F: FnOnce(&mut Checked<'_, S>),
+{
+    let n = data.len();
+    if let Some(index) = (0..n).find(|&i| data.less(i, i)) {
+        return Err(ComparatorError::Reflexive { index });
+    }
+
+    let mut checked = Checked {
+        data,
+        err: Cell::new(None),
+    };
+    sort(&mut checked);
+    if let Some(err) = checked.err.get() {
+        return Err(err);
+    }
+
+    let mut step = 1;
+    while step < n {
+        if let Some(i) = (0..n - step).find(|&i| data.less(i + step, i)) {
+            return Err(ComparatorError::Intransitive { i, j: i + step });
+        }
+        step <<= 1;
+    }
+    Ok(())
+}
+
+/// Checks every comparison against its reverse and remembers the first
+/// pair found to be less than each other. Once one is found, everything
+/// compares equal so the sort finishes quickly.
+struct Checked<'a, S> {
+    data: &'a mut S,
+    err: Cell<Option<ComparatorError>>,
+}
+
+impl<S: Sort> Sort for Checked<'_, S> {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        if self.err.get().is_some() {
+            return false;
+        }
+        let less = self.data.less(i, j);
+        if less && self.data.less(j, i) {
+            self.err.set(Some(if i == j {
+                ComparatorError::Reflexive { index: i }
+            } else {
+                ComparatorError::Symmetric { i, j }
+            }));
+            return false;
+        }
+        less
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.data.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    // Compares through a function, so that tests can plug in broken orderings.
+    struct Cmp<'a> {
+        data: Vec<usize>,
+        less: &'a dyn Fn(usize, usize) -> bool,
+    }
+
+    impl Sort for Cmp<'_> {
+        fn len(&self) -> usize {
+            self.data.len()
+        }
+
+        fn less(&self, i: usize, j: usize) -> bool {
+            (self.less)(self.data[i], self.data[j])
+        }
+
+        fn swap(&mut self, i: usize, j: usize) {
+            self.data.swap(i, j);
+        }
+    }
+
+    fn random(n: usize, max: usize) -> Vec<usize> {
+        let mut rng = rand::thread_rng();
+        (0..n).map(|_| rng.gen_range(0..max)).collect()
+    }
+
+    #[test]
+    fn test_instrumented() {
+        let mut data = random(1000, 100);
+        let mut counted = Instrumented::new(&mut data);
+        counted.sort();
+        let stats = counted.stats();
+        assert!(counted.is_sorted());
+        assert!(stats.compares > 1000 && stats.swaps > 0, "{stats:?}");
+
+        counted.reset();
+        assert_eq!(counted.stats(), Stats::default());
+        counted.sort_stable();
+        assert_eq!(counted.stats().swaps, 0, "sorting sorted data swapped");
+
+        // Sorting is deterministic, so the counts of equal inputs match.
+        let mut data = random(1000, 100);
+        let mut copy = data.clone();
+        let want = Instrumented::new(&mut copy).sort_with_stats();
+        assert_eq!(data.sort_with_stats(), want);
+        let mut sorted = data.clone();
+        let stats = sorted.sort_stable_with_stats();
+        assert!(Sort::is_sorted(&sorted) && stats.compares > 0);
+    }
+
+    #[test]
+    fn test_checked_sort() {
+        for n in [0, 1, 2, 10, 1000] {
+            let mut d = Cmp {
+                data: random(n, 50),
+                less: &|a, b| a < b,
+            };
+            assert_eq!(checked_sort(&mut d), Ok(()));
+            assert!(d.is_sorted());
+            d.data = random(n, 50);
+            assert_eq!(checked_sort_stable(&mut d), Ok(()));
+            assert!(d.is_sorted());
+        }
+    }
+
+    #[test]
+    fn test_checked_sort_reflexive() {
+        let mut d = Cmp {
+            data: random(100, 50),
+            less: &|a, b| a <= b,
+        };
+        let err = checked_sort(&mut d).unwrap_err();
+        assert_eq!(err, ComparatorError::Reflexive { index: 0 });
+        assert_eq!(err.to_string(), "sort: less(0, 0) is true");
+
+        // Only some elements are less than themselves.
+        d.less = &|a, b| a < b || (a == b && a == 42);
+        d.data = (0..100).collect();
+        assert_eq!(
+            checked_sort_stable(&mut d),
+            Err(ComparatorError::Reflexive { index: 42 })
+        );
+    }
+
+    #[test]
+    fn test_checked_sort_symmetric() {
+        // 0 and 1 are each less than the other.
+        let mut d = Cmp {
+            data: (0..200).map(|i| i % 2).collect(),
+            less: &|a, b| a != b,
+        };
+        assert!(matches!(
+            checked_sort(&mut d),
+            Err(ComparatorError::Symmetric { .. })
+        ));
+        let mut data = d.data.clone();
+        data.sort();
+        assert_eq!(data, (0..200).map(|i| i / 100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_checked_sort_intransitive() {
+        // Rock, paper, scissors: each beats the next one around.
+        let mut d = Cmp {
+            data: random(300, 3),
+            less: &|a, b| (a + 1) % 3 == b,
+        };
+        assert!(matches!(
+            checked_sort(&mut d),
+            Err(ComparatorError::Intransitive { .. })
+        ));
+
+        // Equality that is not transitive: numbers within 10 of each other are equal.
+        d.less = &|a, b| a + 10 < b;
+        d.data = random(1000, 1000);
+        assert!(checked_sort(&mut d).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use super::{
+    __less, bits_len, choose_pivot, heap_sort, partial_insertion_sort, partition, partition_equal,
+    pdqsort, reverse_range, rotate, stable, syn_merge, LessSwap, Sort, SortedHint,
+};
+use std::thread;
+
+/// Ranges at most this long are sorted (or merged) on the current thread.
+const PARALLEL_THRESHOLD: usize = 1 << 13;
+
+/// Sorts `data` in ascending order, splitting the work across threads.
+///
+/// The result is the same as [`Sort::sort`] on the slice: the sort is not
+/// guaranteed to be stable, and NaNs are ordered before other values.
+#[inline]
+pub fn par_sort<T: PartialOrd + Send>(data: &mut [T]) {
+    par_sort_slice(data, |d: &[T], i, j| __less(&d[i], &d[j]));
+}
+
+/// Sorts `data` in ascending order, splitting the work across threads and
+/// keeping equal elements in their original order.
+///
+/// The result is the same as [`Sort::sort_stable`] on the slice.
+#[inline]
+pub fn par_sort_stable<T: PartialOrd + Send>(data: &mut [T]) {
+    par_sort_slice_stable(data, |d: &[T], i, j| __less(&d[i], &d[j]));
+}
+
+/// Parallel version of [`sort_slice`](super::sort_slice).
+///
+/// `less` is called with sub-slices of `data`, and `i` and `j` index the
+/// sub-slice it is given, so it must compare `d[i]` and `d[j]` of its first
+/// argument rather than capture `data`.
+///
+/// The sort is not guaranteed to be stable.
+#[inline]
+pub fn par_sort_slice<T, L>(data: &mut [T], less: L)
+where
+    T: Send,
+    L: Fn(&[T], usize, usize) -> bool + Sync,
+{
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+    par_pdqsort(data, 0, &less, bits_len(n), max_depth());
+}
+
+/// Parallel version of [`sort_slice_stable`](super::sort_slice_stable).
+///
+/// `less` is called with sub-slices of `data` in the same way as for
+/// [`par_sort_slice`]. The halves are sorted concurrently and then merged by
+/// a SymMerge whose independent sub-merges also run concurrently, so no
+/// additional buffer is needed.
+#[inline]
+pub fn par_sort_slice_stable<T, L>(data: &mut [T], less: L)
+where
+    T: Send,
+    L: Fn(&[T], usize, usize) -> bool + Sync,
+{
+    par_stable(data, &less, max_depth());
+}
+
+/// Returns how many times the work may be split in two, so that there are
+/// about twice as many tasks as threads to even out unbalanced splits.
+#[inline]
+fn max_depth() -> u32 {
+    match thread::available_parallelism().map_or(1, |n| n.get()) {
+        1 => 0,
+        n => bits_len(n - 1) + 1,
+    }
+}
+
+/// Sorts `data[a..]` like [`pdqsort`], running both sides of each partition on
+/// their own thread for the first `depth` levels.
+///
+/// `a` is 0 or 1; when it is 1, `data[0]` is a previous pivot that is not
+/// greater than any element of `data[1..]`, which lets runs of elements equal
+/// to it be split off as in the sequential algorithm.
+fn par_pdqsort<T, L>(mut data: &mut [T], mut a: usize, less: &L, limit: u32, depth: u32)
+where
+    T: Send,
+    L: Fn(&[T], usize, usize) -> bool + Sync,
+{
+    loop {
+        let b = data.len();
+        if depth == 0 || b - a <= PARALLEL_THRESHOLD {
+            pdqsort(&mut LessSwap { data, less }, a, b, limit);
+            return;
+        }
+
+        let mut sorter = LessSwap {
+            data: &mut *data,
+            less,
+        };
+        if limit == 0 {
+            heap_sort(&mut sorter, a, b);
+            return;
+        }
+
+        let (mut pivot, mut hint) = choose_pivot(&sorter, a, b);
+        if hint == SortedHint::Decreasing {
+            reverse_range(&mut sorter, a, b);
+            pivot = (b - 1) - (pivot - a);
+            hint = SortedHint::Increasing;
+        }
+
+        // The slice is likely already sorted.
+        if hint == SortedHint::Increasing && partial_insertion_sort(&mut sorter, a, b) {
+            return;
+        }
+
+        // Probably the slice contains many duplicate elements, split off the
+        // ones equal to the previous pivot and go on with the rest.
+        if a > 0 && !sorter.less(a - 1, pivot) {
+            let start = partition_equal(&mut sorter, a, b, pivot);
+            data = &mut core::mem::take(&mut data)[start - 1..];
+            a = 1;
+            continue;
+        }
+
+        let (mid, _) = partition(&mut sorter, a, b, pivot);
+        // The pivot stays with the right half as the bound of its elements.
+        let (left, right) = data.split_at_mut(mid);
+        thread::scope(|s| {
+            s.spawn(|| par_pdqsort(left, a, less, limit, depth - 1));
+            par_pdqsort(right, 1, less, limit, depth - 1);
+        });
+        return;
+    }
+}
+
+/// Sorts `data` stably, sorting the two halves concurrently for the first
+/// `depth` levels before merging them.
+fn par_stable<T, L>(data: &mut [T], less: &L, depth: u32)
+where
+    T: Send,
+    L: Fn(&[T], usize, usize) -> bool + Sync,
+{
+    let n = data.len();
+    if depth == 0 || n <= PARALLEL_THRESHOLD {
+        stable(&mut LessSwap { data, less }, n);
+        return;
+    }
+
+    let m = n >> 1;
+    let (left, right) = data.split_at_mut(m);
+    thread::scope(|s| {
+        s.spawn(|| par_stable(left, less, depth - 1));
+        par_stable(right, less, depth - 1);
+    });
+    par_sym_merge(data, m, less, depth);
+}
+
+/// Merges the sorted `data[..m]` and `data[m..]` like [`syn_merge`].
+///
+/// After the rotation, SymMerge recurses into two disjoint ranges split at
+/// the middle of `data`; for the first `depth` levels these run concurrently.
+fn par_sym_merge<T, L>(data: &mut [T], m: usize, less: &L, depth: u32)
+where
+    T: Send,
+    L: Fn(&[T], usize, usize) -> bool + Sync,
+{
+    let b = data.len();
+    if m == 0 || m == b {
+        return;
+    }
+    if depth == 0 || b <= PARALLEL_THRESHOLD {
+        syn_merge(&mut LessSwap { data, less }, 0, m, b);
+        return;
+    }
+
+    let mid = b >> 1;
+    let n = mid + m;
+    let (mut start, mut r) = if m > mid { (n - b, mid) } else { (0, m) };
+
+    let p = n - 1;
+    while start < r {
+        let c = (start + r) >> 1;
+        if !less(data, p - c, c) {
+            start = c + 1;
+        } else {
+            r = c;
+        }
+    }
+
+    let end = n - start;
+    if start < m && m < end {
+        rotate(
+            &mut LessSwap {
+                data: &mut *data,
+                less,
+            },
+            start,
+            m,
+            end,
+        );
+    }
+
+    let (left, right) = data.split_at_mut(mid);
+    thread::scope(|s| {
+        s.spawn(|| par_sym_merge(left, start, less, depth - 1));
+        par_sym_merge(right, end - mid, less, depth - 1);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    // The test machine may have a single core, so drive the splitting
+    // directly with a fixed depth instead of relying on max_depth.
+    const DEPTH: u32 = 4;
+    const N: usize = 200_000;
+
+    fn less(d: &[isize], i: usize, j: usize) -> bool {
+        d[i] < d[j]
+    }
+
+    fn inputs() -> Vec<(&'static str, Vec<isize>)> {
+        let mut rng = rand::thread_rng();
+        vec![
+            ("random", (0..N).map(|_| rng.gen()).collect()),
+            (
+                "few distinct",
+                (0..N).map(|_| rng.gen_range(0..4)).collect(),
+            ),
+            ("sorted", (0..N as isize).collect()),
+            ("reversed", (0..N as isize).rev().collect()),
+            ("all equal", vec![7; N]),
+            ("sawtooth", (0..N as isize).map(|i| i % 1000).collect()),
+        ]
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_par_pdqsort() {
+        for (name, mut data) in inputs() {
+            let mut want = data.clone();
+            want.sort();
+            par_pdqsort(&mut data, 0, &less, bits_len(N), DEPTH);
+            assert!(data == want, "{name}: par_pdqsort didn't sort");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_par_stable() {
+        let mut rng = rand::thread_rng();
+        let mut data = (0..N)
+            .map(|i| (rng.gen_range(0..1000), i))
+            .collect::<Vec<(isize, usize)>>();
+        let mut want = data.clone();
+        want.sort_by_key(|p| p.0);
+        par_stable(
+            &mut data,
+            &|d: &[(isize, usize)], i, j| d[i].0 < d[j].0,
+            DEPTH,
+        );
+        assert!(data == want, "par_stable wasn't stable on {N} pairs");
+
+        for (name, mut data) in inputs() {
+            let mut want = data.clone();
+            want.sort();
+            par_stable(&mut data, &less, DEPTH);
+            assert!(data == want, "{name}: par_stable didn't sort");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_par_sym_merge() {
+        let mut rng = rand::thread_rng();
+        for m in [1, 17, N / 3, N / 2, N - 1] {
+            let mut data = (0..N)
+                .map(|i| (rng.gen_range(0..100), i))
+                .collect::<Vec<(isize, usize)>>();
+            data[..m].sort_by_key(|p| p.0);
+            data[m..].sort_by_key(|p| p.0);
+            let mut want = data.clone();
+            want.sort_by_key(|p| p.0);
+            par_sym_merge(
+                &mut data,
+                m,
+                &|d: &[(isize, usize)], i, j| d[i].0 < d[j].0,
+                DEPTH,
+            );
+            assert!(data == want, "par_sym_merge at {m} wasn't a stable merge");
+        }
+    }
+}
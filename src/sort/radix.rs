@@ -0,0 +1,391 @@
+use alloc::vec::Vec;
+
+/// Ranges of byte strings at most this long are finished by insertion sort.
+const MSD_INSERTION: usize = 32;
+
+/// Keys that [`radix_sort`] can sort a byte at a time.
+///
+/// Implemented for the primitive integers and floats. Floats are ordered by
+/// the IEEE 754 totalOrder predicate, like [`f64::total_cmp`]:
+/// negative NaNs, -∞, negative numbers, -0.0, +0.0, positive numbers, +∞,
+/// positive NaNs.
+pub trait RadixKey: Copy {
+    /// The number of bytes in the key.
+    const BYTES: usize;
+
+    /// Returns byte `i` of the key, byte 0 being the least significant.
+    /// Comparing the bytes of two keys from the most significant one down
+    /// must order them.
+    fn radix_byte(&self, i: usize) -> u8;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl RadixKey for $ty {
+                const BYTES: usize = core::mem::size_of::<$ty>();
+
+                #[inline]
+                fn radix_byte(&self, i: usize) -> u8 {
+                    (*self >> (i * 8)) as u8
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($ty:ty => $uty:ty),+ $(,)?) => {
+        $(
+            impl RadixKey for $ty {
+                const BYTES: usize = core::mem::size_of::<$ty>();
+
+                #[inline]
+                fn radix_byte(&self, i: usize) -> u8 {
+                    // Flipping the sign bit puts negative numbers first.
+                    ((*self as $uty ^ (1 << (<$uty>::BITS - 1))) >> (i * 8)) as u8
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_float {
+    ($($ty:ty => $uty:ty),+ $(,)?) => {
+        $(
+            impl RadixKey for $ty {
+                const BYTES: usize = core::mem::size_of::<$ty>();
+
+                #[inline]
+                fn radix_byte(&self, i: usize) -> u8 {
+                    // Flip every bit of negative numbers, so that larger
+                    // magnitudes come first, and only the sign bit of
+                    // positive ones, so that they come after.
+                    let bits = self.to_bits();
+                    let mask = if bits >> (<$uty>::BITS - 1) == 1 {
+                        <$uty>::MAX
+                    } else {
+                        1 << (<$uty>::BITS - 1)
+                    };
+                    ((bits ^ mask) >> (i * 8)) as u8
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_radix_key_signed!(
+    i8 => u8,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    i128 => u128,
+    isize => usize,
+);
+impl_radix_key_float!(f32 => u32, f64 => u64);
+
+/// Sorts a slice of integers or floats in ascending order with an LSD radix
+/// sort, using a scratch copy of data.
+///
+/// The sort is stable and makes one pass over data per byte of the key,
+/// skipping bytes that are the same in every element, so it runs in
+/// `O(n*size_of::<T>())` time. Floats are ordered as described in [`RadixKey`].
+#[inline]
+pub fn radix_sort<T: RadixKey>(data: &mut [T]) {
+    radix_sort_by_key(data, |x| *x)
+}
+
+/// Sorts data in ascending order of the key `f` extracts from each element,
+/// with an LSD radix sort like [`radix_sort`].
+///
+/// The sort is stable. `f` is called `O(n*size_of::<K>())` times, so it should be cheap.
+pub fn radix_sort_by_key<T, K, F>(data: &mut [T], f: F)
+where
+    T: Copy,
+    K: RadixKey,
+    F: Fn(&T) -> K,
+{
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    let mut counts = alloc::vec![[0usize; 256]; K::BYTES];
+    for x in data.iter() {
+        let key = f(x);
+        for (i, count) in counts.iter_mut().enumerate() {
+            count[key.radix_byte(i) as usize] += 1;
+        }
+    }
+
+    let mut scratch = data.to_vec();
+    let mut in_scratch = false;
+    {
+        let (mut src, mut dst) = (&mut *data, &mut scratch[..]);
+        for (i, count) in counts.iter_mut().enumerate() {
+            // Every element has the same byte here, the pass would not move anything.
+            if count.contains(&n) {
+                continue;
+            }
+
+            let mut offset = 0;
+            for c in count.iter_mut() {
+                let next = offset + *c;
+                *c = offset;
+                offset = next;
+            }
+            for x in src.iter() {
+                let b = f(x).radix_byte(i) as usize;
+                dst[count[b]] = *x;
+                count[b] += 1;
+            }
+            core::mem::swap(&mut src, &mut dst);
+            in_scratch = !in_scratch;
+        }
+    }
+    if in_scratch {
+        data.copy_from_slice(&scratch);
+    }
+}
+
+/// Sorts a slice of byte strings in ascending lexicographic order with an
+/// MSD radix sort.
+///
+/// The sort is stable. It sorts a permutation of indexes into data first and
+/// then moves every element into place with at most n swaps, so the
+/// elements themselves need not be [`Copy`]. It runs in time proportional to
+/// the total length of the distinguishing prefixes of the strings.
+pub fn radix_sort_bytes<S: AsRef<[u8]>>(data: &mut [S]) {
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    let mut scratch = alloc::vec![0; n];
+    // (lo, hi, depth): order[lo..hi] share their first depth bytes.
+    let mut stack = alloc::vec![(0, n, 0)];
+    while let Some((lo, hi, depth)) = stack.pop() {
+        let order = &mut order[lo..hi];
+        if order.len() <= MSD_INSERTION {
+            insertion_sort_suffixes(data, order, depth);
+            continue;
+        }
+
+        // Bucket 0 holds the strings that end at depth, bucket b+1 those
+        // whose byte at depth is b.
+        let bucket = |i: usize| data[i].as_ref().get(depth).map_or(0, |b| *b as usize + 1);
+        let mut count = [0usize; 257];
+        for &i in order.iter() {
+            count[bucket(i)] += 1;
+        }
+        let mut offset = 0;
+        for c in count.iter_mut() {
+            let next = offset + *c;
+            *c = offset;
+            offset = next;
+        }
+        let scratch = &mut scratch[..order.len()];
+        for &i in order.iter() {
+            let b = bucket(i);
+            scratch[count[b]] = i;
+            count[b] += 1;
+        }
+        order.copy_from_slice(scratch);
+
+        // count[b] is now the end of bucket b. The strings that ended are
+        // equal and already in input order.
+        for b in 1..257 {
+            let (start, end) = (count[b - 1], count[b]);
+            if end - start > 1 {
+                stack.push((lo + start, lo + end, depth + 1));
+            }
+        }
+    }
+
+    super::apply_permutation(data, &mut order);
+}
+
+/// Stably sorts order by the strings of data it indexes, which all share their
+/// first depth bytes.
+fn insertion_sort_suffixes<S: AsRef<[u8]>>(data: &[S], order: &mut [usize], depth: usize) {
+    for i in 1..order.len() {
+        let mut j = i;
+        while j > 0 && data[order[j]].as_ref()[depth..] < data[order[j - 1]].as_ref()[depth..] {
+            order.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{distributions::Standard, prelude::Distribution, Rng};
+
+    fn check_ints<T>()
+    where
+        T: RadixKey + Ord + core::fmt::Debug,
+        Standard: Distribution<T>,
+    {
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 100, 10_000] {
+            let mut data = (0..n).map(|_| rng.gen::<T>()).collect::<Vec<_>>();
+            let mut want = data.clone();
+            want.sort();
+            radix_sort(&mut data);
+            assert_eq!(
+                data,
+                want,
+                "radix_sort of {n} {}",
+                core::any::type_name::<T>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_radix_sort_ints() {
+        check_ints::<u8>();
+        check_ints::<u16>();
+        check_ints::<u32>();
+        check_ints::<u64>();
+        check_ints::<u128>();
+        check_ints::<usize>();
+        check_ints::<i8>();
+        check_ints::<i16>();
+        check_ints::<i32>();
+        check_ints::<i64>();
+        check_ints::<i128>();
+        check_ints::<isize>();
+
+        let mut data = [0, i64::MAX, -1, i64::MIN, 1, i64::MIN + 1, i64::MAX - 1];
+        radix_sort(&mut data);
+        assert_eq!(
+            data,
+            [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX]
+        );
+
+        // Small values leave the high bytes equal, and their passes are skipped.
+        let mut rng = rand::thread_rng();
+        let mut data = (0..10_000)
+            .map(|_| rng.gen_range(0..1000u64))
+            .collect::<Vec<_>>();
+        let mut want = data.clone();
+        want.sort();
+        radix_sort(&mut data);
+        assert_eq!(data, want);
+    }
+
+    #[test]
+    fn test_radix_sort_floats() {
+        let mut data = vec![
+            74.3,
+            59.0,
+            f64::INFINITY,
+            238.2,
+            -784.0,
+            2.3,
+            f64::NAN,
+            -f64::NAN,
+            f64::NEG_INFINITY,
+            9845.768,
+            -959.7485,
+            905.0,
+            7.8,
+            7.8,
+            0.0,
+            -0.0,
+            f64::MIN_POSITIVE,
+            -f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::MIN,
+        ];
+        let mut rng = rand::thread_rng();
+        data.extend((0..10_000).map(|_| rng.gen_range(-1e6..1e6)));
+        let mut want = data.clone();
+        want.sort_by(f64::total_cmp);
+        radix_sort(&mut data);
+        assert!(
+            data.iter()
+                .zip(&want)
+                .all(|(a, b)| a.to_bits() == b.to_bits()),
+            "radix_sort didn't order f64 by total order"
+        );
+
+        let mut data = (0..10_000)
+            .map(|_| rng.gen::<f32>() - 0.5)
+            .collect::<Vec<_>>();
+        let mut want = data.clone();
+        want.sort_by(f32::total_cmp);
+        radix_sort(&mut data);
+        assert_eq!(data, want);
+    }
+
+    #[test]
+    fn test_radix_sort_by_key_stable() {
+        let mut rng = rand::thread_rng();
+        let mut data = (0..10_000)
+            .map(|i| (rng.gen_range(-50i32..50), i))
+            .collect::<Vec<_>>();
+        let mut want = data.clone();
+        want.sort_by_key(|p| p.0);
+        radix_sort_by_key(&mut data, |p| p.0);
+        assert_eq!(data, want);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Tagged(Vec<u8>, usize);
+
+    impl AsRef<[u8]> for Tagged {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn test_radix_sort_bytes() {
+        let mut data = vec![
+            "", "Hello", "foo", "bar", "foo", "f00", "%*&^*&^&", "***", "fo", "",
+        ];
+        let mut want = data.clone();
+        want.sort();
+        radix_sort_bytes(&mut data);
+        assert_eq!(data, want);
+
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 33, 1000, 20_000] {
+            let mut data = (0..n)
+                .map(|i| {
+                    let len = rng.gen_range(0..8);
+                    Tagged((0..len).map(|_| rng.gen_range(b'a'..b'e')).collect(), i)
+                })
+                .collect::<Vec<_>>();
+            let mut want = data.clone();
+            want.sort_by(|a, b| a.0.cmp(&b.0));
+            radix_sort_bytes(&mut data);
+            assert!(
+                data == want,
+                "radix_sort_bytes of {n} strings wasn't stable"
+            );
+        }
+    }
+
+    #[test]
+    fn test_radix_sort_bytes_long_prefix() {
+        // The common prefix is longer than any recursion would allow.
+        let prefix = vec![b'x'; 100_000];
+        let mut rng = rand::thread_rng();
+        let mut data = (0..100)
+            .map(|_| {
+                let mut s = prefix.clone();
+                s.push(rng.gen());
+                s
+            })
+            .collect::<Vec<_>>();
+        let mut want = data.clone();
+        want.sort();
+        radix_sort_bytes(&mut data);
+        assert!(data == want);
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::{string::String, vec::Vec};
+
+mod atoi;
+pub use atoi::*;
+
+mod ftoa;
+pub use ftoa::*;
+
+mod itoa;
+pub use itoa::*;
+
+mod quote;
+pub use quote::*;
+
+/// Errors reported by this package.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Indicates that a value is out of range for the target type (Go's `ErrRange`).
+    Range,
+    /// Indicates that a value does not have the right syntax for the target type (Go's `ErrSyntax`).
+    Syntax,
+    /// The base passed to a parse function is not 0 or in `2..=36`.
+    InvalidBase(u32),
+    /// The bit size passed to a parse function is not in `0..=64`.
+    InvalidBitSize(u32),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Range => write!(f, "value out of range"),
+            Error::Syntax => write!(f, "invalid syntax"),
+            Error::InvalidBase(base) => write!(f, "invalid base {base}"),
+            Error::InvalidBitSize(bit_size) => write!(f, "invalid bit size {bit_size}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Records a failed conversion.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumError {
+    /// The failing function, named as in Go (`ParseBool`, `ParseInt`, `ParseUint`, `Atoi`)
+    pub func: &'static str,
+    /// The input
+    pub num: String,
+    /// The reason the conversion failed (e.g. [`Error::Range`], [`Error::Syntax`], etc.)
+    pub err: Error,
+}
+
+impl NumError {
+    #[inline]
+    fn new(func: &'static str, num: &str, err: Error) -> Self {
+        Self {
+            func,
+            num: num.into(),
+            err,
+        }
+    }
+}
+
+impl core::fmt::Display for NumError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "strconv.{}: parsing {}: {}",
+            self.func,
+            quote(&self.num),
+            self.err
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NumError {}
+
+/// Returns the boolean value represented by the string.
+/// It accepts 1, t, T, TRUE, true, True, 0, f, F, FALSE, false, False.
+/// Any other value returns an error.
+pub fn parse_bool(s: &str) -> Result<bool, NumError> {
+    match s {
+        "1" | "t" | "T" | "true" | "TRUE" | "True" => Ok(true),
+        "0" | "f" | "F" | "false" | "FALSE" | "False" => Ok(false),
+        _ => Err(NumError::new("ParseBool", s, Error::Syntax)),
+    }
+}
+
+/// Returns "true" or "false" according to the value of `b`.
+#[inline]
+pub const fn format_bool(b: bool) -> &'static str {
+    if b {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+/// Appends "true" or "false", according to the value of `b`, to `dst`.
+#[inline]
+pub fn append_bool(dst: &mut Vec<u8>, b: bool) {
+    dst.extend_from_slice(format_bool(b).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bool() {
+        let tests: &[(&str, Result<bool, Error>)] = &[
+            ("", Err(Error::Syntax)),
+            ("asdf", Err(Error::Syntax)),
+            ("0", Ok(false)),
+            ("f", Ok(false)),
+            ("F", Ok(false)),
+            ("FALSE", Ok(false)),
+            ("false", Ok(false)),
+            ("False", Ok(false)),
+            ("1", Ok(true)),
+            ("t", Ok(true)),
+            ("T", Ok(true)),
+            ("TRUE", Ok(true)),
+            ("true", Ok(true)),
+            ("True", Ok(true)),
+        ];
+        for (s, want) in tests {
+            let got = parse_bool(s).map_err(|e| {
+                assert_eq!(e.func, "ParseBool");
+                assert_eq!(e.num, *s);
+                e.err
+            });
+            assert_eq!(got, *want, "parse_bool({s:?})");
+        }
+    }
+
+    #[test]
+    fn test_format_bool() {
+        assert_eq!(format_bool(true), "true");
+        assert_eq!(format_bool(false), "false");
+        let mut b = b"foo ".to_vec();
+        append_bool(&mut b, true);
+        assert_eq!(b, b"foo true");
+    }
+
+    #[test]
+    fn test_num_error() {
+        let err = NumError::new("ParseFloat", "1\x00.2", Error::Syntax);
+        assert_eq!(
+            err.to_string(),
+            "strconv.ParseFloat: parsing \"1\\x00.2\": invalid syntax"
+        );
+        let err = parse_int("99999999999999999999", 10, 64).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "strconv.ParseInt: parsing \"99999999999999999999\": value out of range"
+        );
+        let err = parse_uint("1", 1, 64).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "strconv.ParseUint: parsing \"1\": invalid base 1"
+        );
+    }
+}
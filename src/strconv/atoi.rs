@@ -0,0 +1,563 @@
+use super::{Error, NumError};
+
+/// The size in bits of an `isize` or `usize` value.
+pub const INT_SIZE: u32 = usize::BITS;
+
+/// Returns the lower-case ASCII letter for `c`.
+#[inline]
+const fn lower(c: u8) -> u8 {
+    c | (b'x' - b'X')
+}
+
+/// Is like [`parse_int`] but for unsigned numbers.
+///
+/// A sign prefix is not permitted.
+pub fn parse_uint(s: &str, base: u32, bit_size: u32) -> Result<u64, NumError> {
+    const FN_PARSE_UINT: &str = "ParseUint";
+
+    if s.is_empty() {
+        return Err(NumError::new(FN_PARSE_UINT, s, Error::Syntax));
+    }
+
+    let base0 = base == 0;
+    let s0 = s;
+    let mut b = s.as_bytes();
+    let base = match base {
+        2..=36 => base, // valid base; nothing to do
+        0 => {
+            // Look for octal, hex prefix.
+            if b[0] == b'0' {
+                let prefixed = match b.get(1).map(|c| lower(*c)) {
+                    Some(b'b') if b.len() >= 3 => Some(2),
+                    Some(b'o') if b.len() >= 3 => Some(8),
+                    Some(b'x') if b.len() >= 3 => Some(16),
+                    _ => None,
+                };
+                match prefixed {
+                    Some(base) => {
+                        b = &b[2..];
+                        base
+                    }
+                    None => {
+                        b = &b[1..];
+                        8
+                    }
+                }
+            } else {
+                10
+            }
+        }
+        _ => return Err(NumError::new(FN_PARSE_UINT, s0, Error::InvalidBase(base))),
+    };
+
+    let bit_size = match bit_size {
+        0 => INT_SIZE,
+        1..=64 => bit_size,
+        _ => {
+            return Err(NumError::new(
+                FN_PARSE_UINT,
+                s0,
+                Error::InvalidBitSize(bit_size),
+            ))
+        }
+    };
+
+    // Cutoff is the smallest number such that cutoff*base > u64::MAX.
+    let cutoff = u64::MAX / base as u64 + 1;
+
+    let max_val = u64::MAX >> (64 - bit_size);
+
+    let mut underscores = false;
+    let mut n = 0u64;
+    for &c in b {
+        let d = match c {
+            b'_' if base0 => {
+                underscores = true;
+                continue;
+            }
+            b'0'..=b'9' => c - b'0',
+            _ if lower(c).is_ascii_lowercase() => lower(c) - b'a' + 10,
+            _ => return Err(NumError::new(FN_PARSE_UINT, s0, Error::Syntax)),
+        };
+
+        if d as u32 >= base {
+            return Err(NumError::new(FN_PARSE_UINT, s0, Error::Syntax));
+        }
+
+        if n >= cutoff {
+            // n*base overflows
+            return Err(NumError::new(FN_PARSE_UINT, s0, Error::Range));
+        }
+        n *= base as u64;
+
+        match n.checked_add(d as u64) {
+            Some(n1) if n1 <= max_val => n = n1,
+            // n+d overflows
+            _ => return Err(NumError::new(FN_PARSE_UINT, s0, Error::Range)),
+        }
+    }
+
+    if underscores && !underscore_ok(s0) {
+        return Err(NumError::new(FN_PARSE_UINT, s0, Error::Syntax));
+    }
+
+    Ok(n)
+}
+
+/// Interprets a string `s` in the given base (0, 2 to 36) and
+/// bit size (0 to 64) and returns the corresponding value `i`.
+///
+/// The string may begin with a leading sign: "+" or "-".
+///
+/// If the base argument is 0, the true base is implied by the string's
+/// prefix following the sign (if present): 2 for "0b", 8 for "0" or "0o",
+/// 16 for "0x", and 10 otherwise. Also, for argument base 0 only,
+/// underscore characters are permitted as defined by the Go syntax for
+/// [integer literals](https://go.dev/ref/spec#Integer_literals).
+///
+/// The `bit_size` argument specifies the integer type
+/// that the result must fit into. Bit sizes 0, 8, 16, 32, and 64
+/// correspond to `isize`, `i8`, `i16`, `i32`, and `i64`.
+///
+/// The errors that `parse_int` returns have concrete type [`NumError`].
+/// If `s` is empty or contains invalid
+/// digits, `err` is [`Error::Syntax`];
+/// if the value corresponding to `s` cannot be represented by a
+/// signed integer of the given size, `err` is [`Error::Range`].
+pub fn parse_int(s: &str, base: u32, bit_size: u32) -> Result<i64, NumError> {
+    const FN_PARSE_INT: &str = "ParseInt";
+
+    if s.is_empty() {
+        return Err(NumError::new(FN_PARSE_INT, s, Error::Syntax));
+    }
+
+    // Pick off leading sign.
+    let s0 = s;
+    let (neg, s) = match s.as_bytes()[0] {
+        b'+' => (false, &s[1..]),
+        b'-' => (true, &s[1..]),
+        _ => (false, s),
+    };
+
+    // Convert unsigned and check range.
+    let un = match parse_uint(s, base, bit_size) {
+        Ok(un) => un,
+        Err(e) => {
+            return Err(NumError::new(FN_PARSE_INT, s0, e.err));
+        }
+    };
+
+    let bit_size = if bit_size == 0 { INT_SIZE } else { bit_size };
+
+    let cutoff = 1u64 << (bit_size - 1);
+    if (!neg && un >= cutoff) || (neg && un > cutoff) {
+        return Err(NumError::new(FN_PARSE_INT, s0, Error::Range));
+    }
+    Ok(if neg {
+        (un as i64).wrapping_neg()
+    } else {
+        un as i64
+    })
+}
+
+/// Equivalent to `parse_int(s, 10, 0)`, converted to `isize`.
+pub fn atoi(s: &str) -> Result<isize, NumError> {
+    const FN_ATOI: &str = "Atoi";
+
+    parse_int(s, 10, 0)
+        .map(|i| i as isize)
+        .map_err(|e| NumError::new(FN_ATOI, s, e.err))
+}
+
+/// Reports whether the underscores in `s` are allowed.
+/// Checking them in this one function lets all the parsers skip over them simply.
+/// Underscore must appear only between digits or between a base prefix and a digit.
+fn underscore_ok(s: &str) -> bool {
+    // saw tracks the last character (class) we saw:
+    // ^ for beginning of number,
+    // 0 for a digit or base prefix,
+    // _ for an underscore,
+    // ! for none of the above.
+    let mut saw = b'^';
+    let mut i = 0;
+
+    // Optional sign.
+    let mut s = s.as_bytes();
+    if !s.is_empty() && (s[0] == b'-' || s[0] == b'+') {
+        s = &s[1..];
+    }
+
+    // Optional base prefix.
+    let mut hex = false;
+    if s.len() >= 2 && s[0] == b'0' && matches!(lower(s[1]), b'b' | b'o' | b'x') {
+        i = 2;
+        saw = b'0'; // base prefix counts as a digit for "underscore as digit separator"
+        hex = lower(s[1]) == b'x';
+    }
+
+    // Number proper.
+    for &c in &s[i..] {
+        // Digits are always okay.
+        if c.is_ascii_digit() || (hex && (b'a'..=b'f').contains(&lower(c))) {
+            saw = b'0';
+            continue;
+        }
+        // Underscore must follow digit.
+        if c == b'_' {
+            if saw != b'0' {
+                return false;
+            }
+            saw = b'_';
+            continue;
+        }
+        // Underscore must also be followed by digit.
+        if saw == b'_' {
+            return false;
+        }
+        // Saw non-digit, non-underscore.
+        saw = b'!';
+    }
+    saw != b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uint64() {
+        let tests: &[(&str, Result<u64, Error>)] = &[
+            ("", Err(Error::Syntax)),
+            ("0", Ok(0)),
+            ("1", Ok(1)),
+            ("12345", Ok(12345)),
+            ("012345", Ok(12345)),
+            ("12345x", Err(Error::Syntax)),
+            ("98765432100", Ok(98765432100)),
+            ("18446744073709551615", Ok(u64::MAX)),
+            ("18446744073709551616", Err(Error::Range)),
+            ("18446744073709551620", Err(Error::Range)),
+            ("1_2_3_4_5", Err(Error::Syntax)), // base=10 so no underscores allowed
+            ("_12345", Err(Error::Syntax)),
+            ("1__2345", Err(Error::Syntax)),
+            ("12345_", Err(Error::Syntax)),
+            ("-0", Err(Error::Syntax)),
+            ("-1", Err(Error::Syntax)),
+            ("+1", Err(Error::Syntax)),
+        ];
+        for (s, want) in tests {
+            assert_eq!(
+                parse_uint(s, 10, 64).map_err(|e| e.err),
+                *want,
+                "parse_uint({s:?}, 10, 64)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_uint64_base() {
+        let tests: &[(&str, u32, Result<u64, Error>)] = &[
+            ("", 0, Err(Error::Syntax)),
+            ("0", 0, Ok(0)),
+            ("0x", 0, Err(Error::Syntax)),
+            ("0X", 0, Err(Error::Syntax)),
+            ("1", 0, Ok(1)),
+            ("12345", 0, Ok(12345)),
+            ("012345", 0, Ok(0o12345)),
+            ("0x12345", 0, Ok(0x12345)),
+            ("0X12345", 0, Ok(0x12345)),
+            ("12345x", 0, Err(Error::Syntax)),
+            ("0xabcdefg123", 0, Err(Error::Syntax)),
+            ("123456789abc", 0, Err(Error::Syntax)),
+            ("98765432100", 0, Ok(98765432100)),
+            ("18446744073709551615", 0, Ok(u64::MAX)),
+            ("18446744073709551616", 0, Err(Error::Range)),
+            ("18446744073709551620", 0, Err(Error::Range)),
+            ("0xFFFFFFFFFFFFFFFF", 0, Ok(u64::MAX)),
+            ("0x10000000000000000", 0, Err(Error::Range)),
+            ("01777777777777777777777", 0, Ok(u64::MAX)),
+            ("01777777777777777777778", 0, Err(Error::Syntax)),
+            ("02000000000000000000000", 0, Err(Error::Range)),
+            ("0200000000000000000000", 0, Ok(1 << 61)),
+            ("0b", 0, Err(Error::Syntax)),
+            ("0B", 0, Err(Error::Syntax)),
+            ("0b101", 0, Ok(5)),
+            ("0B101", 0, Ok(5)),
+            ("0o", 0, Err(Error::Syntax)),
+            ("0O", 0, Err(Error::Syntax)),
+            ("0o377", 0, Ok(255)),
+            ("0O377", 0, Ok(255)),
+            // underscores allowed with base == 0 only
+            ("1_2_3_4_5", 0, Ok(12345)), // base 0 => 10
+            ("_12345", 0, Err(Error::Syntax)),
+            ("1__2345", 0, Err(Error::Syntax)),
+            ("12345_", 0, Err(Error::Syntax)),
+            ("1_2_3_4_5", 10, Err(Error::Syntax)), // base 10
+            ("_12345", 10, Err(Error::Syntax)),
+            ("1__2345", 10, Err(Error::Syntax)),
+            ("12345_", 10, Err(Error::Syntax)),
+            ("0x_1_2_3_4_5", 0, Ok(0x12345)), // base 0 => 16
+            ("_0x12345", 0, Err(Error::Syntax)),
+            ("0x__12345", 0, Err(Error::Syntax)),
+            ("0x1__2345", 0, Err(Error::Syntax)),
+            ("0x1234__5", 0, Err(Error::Syntax)),
+            ("0x12345_", 0, Err(Error::Syntax)),
+            ("1_2_3_4_5", 16, Err(Error::Syntax)), // base 16
+            ("_12345", 16, Err(Error::Syntax)),
+            ("1__2345", 16, Err(Error::Syntax)),
+            ("1234__5", 16, Err(Error::Syntax)),
+            ("12345_", 16, Err(Error::Syntax)),
+            ("0_1_2_3_4_5", 0, Ok(0o12345)), // base 0 => 8 (0377)
+            ("_012345", 0, Err(Error::Syntax)),
+            ("0__12345", 0, Err(Error::Syntax)),
+            ("01234__5", 0, Err(Error::Syntax)),
+            ("012345_", 0, Err(Error::Syntax)),
+            ("0o_1_2_3_4_5", 0, Ok(0o12345)), // base 0 => 8 (0o377)
+            ("_0o12345", 0, Err(Error::Syntax)),
+            ("0o__12345", 0, Err(Error::Syntax)),
+            ("0o1234__5", 0, Err(Error::Syntax)),
+            ("0o12345_", 0, Err(Error::Syntax)),
+            ("0_1_2_3_4_5", 8, Err(Error::Syntax)), // base 8
+            ("_012345", 8, Err(Error::Syntax)),
+            ("0__12345", 8, Err(Error::Syntax)),
+            ("01234__5", 8, Err(Error::Syntax)),
+            ("012345_", 8, Err(Error::Syntax)),
+            ("0b_1_0_1", 0, Ok(5)), // base 0 => 2 (0b101)
+            ("_0b101", 0, Err(Error::Syntax)),
+            ("0b__101", 0, Err(Error::Syntax)),
+            ("0b1__01", 0, Err(Error::Syntax)),
+            ("0b10__1", 0, Err(Error::Syntax)),
+            ("0b101_", 0, Err(Error::Syntax)),
+            ("1_0_1", 2, Err(Error::Syntax)), // base 2
+            ("_101", 2, Err(Error::Syntax)),
+            ("1_01", 2, Err(Error::Syntax)),
+            ("10_1", 2, Err(Error::Syntax)),
+            ("101_", 2, Err(Error::Syntax)),
+        ];
+        for (s, base, want) in tests {
+            assert_eq!(
+                parse_uint(s, *base, 64).map_err(|e| e.err),
+                *want,
+                "parse_uint({s:?}, {base}, 64)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_int64() {
+        let tests: &[(&str, Result<i64, Error>)] = &[
+            ("", Err(Error::Syntax)),
+            ("0", Ok(0)),
+            ("-0", Ok(0)),
+            ("+0", Ok(0)),
+            ("1", Ok(1)),
+            ("-1", Ok(-1)),
+            ("+1", Ok(1)),
+            ("12345", Ok(12345)),
+            ("-12345", Ok(-12345)),
+            ("012345", Ok(12345)),
+            ("-012345", Ok(-12345)),
+            ("98765432100", Ok(98765432100)),
+            ("-98765432100", Ok(-98765432100)),
+            ("9223372036854775807", Ok(i64::MAX)),
+            ("-9223372036854775807", Ok(-i64::MAX)),
+            ("9223372036854775808", Err(Error::Range)),
+            ("-9223372036854775808", Ok(i64::MIN)),
+            ("9223372036854775809", Err(Error::Range)),
+            ("-9223372036854775809", Err(Error::Range)),
+            ("-1_2_3_4_5", Err(Error::Syntax)), // base=10 so no underscores allowed
+            ("-_12345", Err(Error::Syntax)),
+            ("_12345", Err(Error::Syntax)),
+            ("1__2345", Err(Error::Syntax)),
+            ("12345_", Err(Error::Syntax)),
+            ("123%45", Err(Error::Syntax)),
+        ];
+        for (s, want) in tests {
+            let got = parse_int(s, 10, 64).map_err(|e| {
+                assert_eq!(e.func, "ParseInt");
+                assert_eq!(e.num, *s);
+                e.err
+            });
+            assert_eq!(got, *want, "parse_int({s:?}, 10, 64)");
+        }
+    }
+
+    #[test]
+    fn test_parse_int64_base() {
+        let tests: &[(&str, u32, Result<i64, Error>)] = &[
+            ("", 0, Err(Error::Syntax)),
+            ("0", 0, Ok(0)),
+            ("-0", 0, Ok(0)),
+            ("1", 0, Ok(1)),
+            ("-1", 0, Ok(-1)),
+            ("12345", 0, Ok(12345)),
+            ("-12345", 0, Ok(-12345)),
+            ("012345", 0, Ok(0o12345)),
+            ("-012345", 0, Ok(-0o12345)),
+            ("0x12345", 0, Ok(0x12345)),
+            ("-0X12345", 0, Ok(-0x12345)),
+            ("12345x", 0, Err(Error::Syntax)),
+            ("-12345x", 0, Err(Error::Syntax)),
+            ("98765432100", 0, Ok(98765432100)),
+            ("-98765432100", 0, Ok(-98765432100)),
+            ("9223372036854775807", 0, Ok(i64::MAX)),
+            ("-9223372036854775807", 0, Ok(-i64::MAX)),
+            ("9223372036854775808", 0, Err(Error::Range)),
+            ("-9223372036854775808", 0, Ok(i64::MIN)),
+            ("9223372036854775809", 0, Err(Error::Range)),
+            ("-9223372036854775809", 0, Err(Error::Range)),
+            // other bases
+            ("g", 17, Ok(16)),
+            ("10", 25, Ok(25)),
+            (
+                "holycow",
+                35,
+                Ok((((((17 * 35 + 24) * 35 + 21) * 35 + 34) * 35 + 12) * 35 + 24) * 35 + 32),
+            ),
+            (
+                "holycow",
+                36,
+                Ok((((((17 * 36 + 24) * 36 + 21) * 36 + 34) * 36 + 12) * 36 + 24) * 36 + 32),
+            ),
+            // base 2
+            ("0", 2, Ok(0)),
+            ("-1", 2, Ok(-1)),
+            ("1010", 2, Ok(10)),
+            ("1000000000000000", 2, Ok(1 << 15)),
+            (
+                "111111111111111111111111111111111111111111111111111111111111111",
+                2,
+                Ok(i64::MAX),
+            ),
+            (
+                "1000000000000000000000000000000000000000000000000000000000000000",
+                2,
+                Err(Error::Range),
+            ),
+            (
+                "-1000000000000000000000000000000000000000000000000000000000000000",
+                2,
+                Ok(i64::MIN),
+            ),
+            (
+                "-1000000000000000000000000000000000000000000000000000000000000001",
+                2,
+                Err(Error::Range),
+            ),
+            // base 8
+            ("-10", 8, Ok(-8)),
+            ("57635436545", 8, Ok(0o57635436545)),
+            ("100000000", 8, Ok(1 << 24)),
+            // base 16
+            ("10", 16, Ok(16)),
+            ("-123456789abcdef", 16, Ok(-0x123456789abcdef)),
+            ("7fffffffffffffff", 16, Ok(i64::MAX)),
+            // underscores
+            ("-0x_1_2_3_4_5", 0, Ok(-0x12345)),
+            ("0x_1_2_3_4_5", 0, Ok(0x12345)),
+            ("-_0x12345", 0, Err(Error::Syntax)),
+            ("_-0x12345", 0, Err(Error::Syntax)),
+            ("_0x12345", 0, Err(Error::Syntax)),
+            ("0x__12345", 0, Err(Error::Syntax)),
+            ("0x1__2345", 0, Err(Error::Syntax)),
+            ("0x1234__5", 0, Err(Error::Syntax)),
+            ("0x12345_", 0, Err(Error::Syntax)),
+            ("-0_1_2_3_4_5", 0, Ok(-0o12345)), // octal
+            ("-_012345", 0, Err(Error::Syntax)),
+            ("_-012345", 0, Err(Error::Syntax)),
+            ("_012345", 0, Err(Error::Syntax)),
+            ("0__12345", 0, Err(Error::Syntax)),
+            ("01234__5", 0, Err(Error::Syntax)),
+            ("012345_", 0, Err(Error::Syntax)),
+            ("+0xf", 0, Ok(0xf)),
+            ("-0xf", 0, Ok(-0xf)),
+            ("0x+f", 0, Err(Error::Syntax)),
+            ("0x-f", 0, Err(Error::Syntax)),
+        ];
+        for (s, base, want) in tests {
+            assert_eq!(
+                parse_int(s, *base, 64).map_err(|e| e.err),
+                *want,
+                "parse_int({s:?}, {base}, 64)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_bit_sizes() {
+        let tests: &[(&str, u32, Result<i64, Error>)] = &[
+            ("127", 8, Ok(127)),
+            ("128", 8, Err(Error::Range)),
+            ("-128", 8, Ok(-128)),
+            ("-129", 8, Err(Error::Range)),
+            ("2147483647", 32, Ok(i32::MAX as i64)),
+            ("2147483648", 32, Err(Error::Range)),
+            ("-2147483648", 32, Ok(i32::MIN as i64)),
+            ("-2147483649", 32, Err(Error::Range)),
+            ("0", 65, Err(Error::InvalidBitSize(65))),
+        ];
+        for (s, bit_size, want) in tests {
+            assert_eq!(
+                parse_int(s, 10, *bit_size).map_err(|e| e.err),
+                *want,
+                "parse_int({s:?}, 10, {bit_size})"
+            );
+        }
+
+        assert_eq!(parse_uint("255", 10, 8).map_err(|e| e.err), Ok(255));
+        assert_eq!(
+            parse_uint("256", 10, 8).map_err(|e| e.err),
+            Err(Error::Range)
+        );
+        assert_eq!(
+            parse_uint("4294967295", 10, 32).map_err(|e| e.err),
+            Ok(u32::MAX as u64)
+        );
+        assert_eq!(
+            parse_uint("4294967296", 10, 32).map_err(|e| e.err),
+            Err(Error::Range)
+        );
+        assert_eq!(
+            parse_uint("0", 10, 65).map_err(|e| e.err),
+            Err(Error::InvalidBitSize(65))
+        );
+    }
+
+    #[test]
+    fn test_parse_base() {
+        for base in [1, 37] {
+            assert_eq!(
+                parse_int("0", base, 64).map_err(|e| e.err),
+                Err(Error::InvalidBase(base))
+            );
+            assert_eq!(
+                parse_uint("0", base, 64).map_err(|e| e.err),
+                Err(Error::InvalidBase(base))
+            );
+        }
+    }
+
+    #[test]
+    fn test_atoi() {
+        let tests: &[(&str, Result<isize, Error>)] = &[
+            ("", Err(Error::Syntax)),
+            ("0", Ok(0)),
+            ("-0", Ok(0)),
+            ("+1", Ok(1)),
+            ("-12345", Ok(-12345)),
+            ("012345", Ok(12345)),
+            ("9223372036854775807", Ok(isize::MAX)),
+            ("9223372036854775808", Err(Error::Range)),
+            ("-9223372036854775808", Ok(isize::MIN)),
+            ("0x10", Err(Error::Syntax)),
+        ];
+        for (s, want) in tests {
+            let got = atoi(s).map_err(|e| {
+                assert_eq!(e.func, "Atoi");
+                assert_eq!(e.num, *s);
+                e.err
+            });
+            assert_eq!(got, *want, "atoi({s:?})");
+        }
+    }
+}
@@ -0,0 +1,610 @@
+use super::append_int;
+use alloc::{format, string::String, vec::Vec};
+
+const LOWERHEX: &[u8; 16] = b"0123456789abcdef";
+const UPPERHEX: &[u8; 16] = b"0123456789ABCDEF";
+
+struct FloatInfo {
+    mantbits: u32,
+    expbits: u32,
+    bias: i32,
+}
+
+const FLOAT32_INFO: FloatInfo = FloatInfo {
+    mantbits: 23,
+    expbits: 8,
+    bias: -127,
+};
+
+const FLOAT64_INFO: FloatInfo = FloatInfo {
+    mantbits: 52,
+    expbits: 11,
+    bias: -1023,
+};
+
+/// Decimal digits `0.d[0]d[1]...d[nd-1] * 10^dp`, without trailing zeros.
+struct DecimalSlice {
+    d: Vec<u8>,
+    dp: isize,
+}
+
+/// Converts the floating-point number `f` to a string,
+/// according to the format `fmt` and precision `prec`. It rounds the
+/// result assuming that the original was obtained from a floating-point
+/// value of `bit_size` bits (32 for `f32`, 64 for `f64`).
+///
+/// The format `fmt` is one of
+/// - `b'b'` (`-ddddp±ddd`, a binary exponent),
+/// - `b'e'` (`-d.dddde±dd`, a decimal exponent),
+/// - `b'E'` (`-d.ddddE±dd`, a decimal exponent),
+/// - `b'f'` (`-ddd.dddd`, no exponent),
+/// - `b'g'` (`b'e'` for large exponents, `b'f'` otherwise),
+/// - `b'G'` (`b'E'` for large exponents, `b'f'` otherwise),
+/// - `b'x'` (`-0xd.ddddp±ddd`, a hexadecimal fraction and binary exponent), or
+/// - `b'X'` (`-0Xd.ddddP±ddd`, a hexadecimal fraction and binary exponent).
+///
+/// The precision `prec` controls the number of digits (excluding the exponent)
+/// printed by the `b'e'`, `b'E'`, `b'f'`, `b'g'`, `b'G'`, `b'x'`, and `b'X'` formats.
+/// For `b'e'`, `b'E'`, `b'f'`, `b'x'`, and `b'X'`, it is the number of digits after the decimal point.
+/// For `b'g'` and `b'G'` it is the maximum number of significant digits (trailing
+/// zeros are removed).
+/// The special precision -1 uses the smallest number of digits
+/// necessary such that parsing the result back returns `f` exactly.
+///
+/// # Panics
+///
+/// Panics if `bit_size` is neither 32 nor 64.
+#[inline]
+pub fn format_float(f: f64, fmt: u8, prec: isize, bit_size: u32) -> String {
+    let mut buf = Vec::with_capacity(prec.max(0) as usize + 24);
+    append_float(&mut buf, f, fmt, prec, bit_size);
+    buf.into_iter().map(|b| b as char).collect()
+}
+
+/// Appends the string form of the floating-point number `f`,
+/// as generated by [`format_float`], to `dst`.
+///
+/// # Panics
+///
+/// Panics if `bit_size` is neither 32 nor 64.
+pub fn append_float(dst: &mut Vec<u8>, f: f64, fmt: u8, mut prec: isize, bit_size: u32) {
+    let (bits, flt) = match bit_size {
+        32 => ((f as f32).to_bits() as u64, &FLOAT32_INFO),
+        64 => (f.to_bits(), &FLOAT64_INFO),
+        _ => panic!("strconv: illegal AppendFloat/FormatFloat bitSize"),
+    };
+
+    let neg = bits >> (flt.expbits + flt.mantbits) != 0;
+    let mut exp = (bits >> flt.mantbits) as i32 & ((1 << flt.expbits) - 1);
+    let mut mant = bits & ((1u64 << flt.mantbits) - 1);
+
+    if exp == (1 << flt.expbits) - 1 {
+        // Inf, NaN
+        let s: &[u8] = if mant != 0 {
+            b"NaN"
+        } else if neg {
+            b"-Inf"
+        } else {
+            b"+Inf"
+        };
+        dst.extend_from_slice(s);
+        return;
+    } else if exp == 0 {
+        // denormalized
+        exp += 1;
+    } else {
+        // add implicit top bit
+        mant |= 1u64 << flt.mantbits;
+    }
+    exp += flt.bias;
+
+    // Pick off easy binary, hex formats.
+    match fmt {
+        b'b' => return fmt_b(dst, neg, mant, exp, flt),
+        b'x' | b'X' => return fmt_x(dst, prec, fmt, neg, mant, exp, flt),
+        b'e' | b'E' | b'f' | b'g' | b'G' => {}
+        // unknown format
+        _ => {
+            dst.push(b'%');
+            dst.push(fmt);
+            return;
+        }
+    }
+
+    // Both core::fmt implementations used below are exact and round
+    // half to even, so they produce the same digits as Go's Ryū and
+    // multiprecision paths.
+    let shortest = prec < 0;
+    let magnitude = f.abs();
+    let digs = if shortest {
+        let digs = if bit_size == 32 {
+            scientific_digits(&format!("{:e}", magnitude as f32))
+        } else {
+            scientific_digits(&format!("{magnitude:e}"))
+        };
+        // Precision for shortest representation mode.
+        let nd = digs.d.len() as isize;
+        prec = match fmt {
+            b'e' | b'E' => (nd - 1).max(0),
+            b'f' => (nd - digs.dp).max(0),
+            _ => nd,
+        };
+        digs
+    } else {
+        match fmt {
+            b'e' | b'E' => scientific_digits(&format!("{magnitude:.*e}", prec as usize)),
+            b'g' | b'G' => {
+                if prec == 0 {
+                    prec = 1;
+                }
+                scientific_digits(&format!("{magnitude:.*e}", prec as usize - 1))
+            }
+            _ => fixed_digits(&format!("{magnitude:.*}", prec as usize)),
+        }
+    };
+    format_digits(dst, shortest, neg, &digs, prec, fmt);
+}
+
+/// Collects the digits of `d.ddde±x` as produced by `{:e}`.
+fn scientific_digits(s: &str) -> DecimalSlice {
+    let (mantissa, exp) = s.split_once('e').unwrap_or((s, "0"));
+    let d = mantissa.bytes().filter(|b| *b != b'.').collect();
+    let dp = exp.parse::<isize>().unwrap_or(0) + 1;
+    trim(DecimalSlice { d, dp })
+}
+
+/// Collects the digits of `ddd.ddd` as produced by `{:.prec}`.
+fn fixed_digits(s: &str) -> DecimalSlice {
+    let int_len = s.find('.').unwrap_or(s.len()) as isize;
+    let mut d: Vec<u8> = s.bytes().filter(|b| *b != b'.').collect();
+    let lead = d.iter().take_while(|b| **b == b'0').count();
+    d.drain(..lead);
+    trim(DecimalSlice {
+        d,
+        dp: int_len - lead as isize,
+    })
+}
+
+fn trim(mut digs: DecimalSlice) -> DecimalSlice {
+    while digs.d.last() == Some(&b'0') {
+        digs.d.pop();
+    }
+    if digs.d.is_empty() {
+        digs.dp = 0;
+    }
+    digs
+}
+
+fn format_digits(
+    dst: &mut Vec<u8>,
+    shortest: bool,
+    neg: bool,
+    digs: &DecimalSlice,
+    mut prec: isize,
+    fmt: u8,
+) {
+    let nd = digs.d.len() as isize;
+    match fmt {
+        b'e' | b'E' => fmt_e(dst, neg, digs, prec, fmt),
+        b'f' => fmt_f(dst, neg, digs, prec),
+        _ => {
+            let mut eprec = prec;
+            if eprec > nd && nd >= digs.dp {
+                eprec = nd;
+            }
+            // %e is used if the exponent from the conversion
+            // is less than -4 or greater than or equal to the precision.
+            // if precision was the shortest possible, use precision 6 for this decision.
+            if shortest {
+                eprec = 6;
+            }
+            let exp = digs.dp - 1;
+            if exp < -4 || exp >= eprec {
+                if prec > nd {
+                    prec = nd;
+                }
+                return fmt_e(dst, neg, digs, prec - 1, fmt + b'e' - b'g');
+            }
+            if prec > digs.dp {
+                prec = nd;
+            }
+            fmt_f(dst, neg, digs, (prec - digs.dp).max(0));
+        }
+    }
+}
+
+// %e: -d.ddddde±dd
+fn fmt_e(dst: &mut Vec<u8>, neg: bool, d: &DecimalSlice, prec: isize, fmt: u8) {
+    // sign
+    if neg {
+        dst.push(b'-');
+    }
+
+    // first digit
+    dst.push(d.d.first().copied().unwrap_or(b'0'));
+
+    // .moredigits
+    if prec > 0 {
+        dst.push(b'.');
+        let m = d.d.len().min(prec as usize + 1);
+        if m > 1 {
+            dst.extend_from_slice(&d.d[1..m]);
+        }
+        dst.resize(dst.len() + (prec as usize + 1 - m.max(1)), b'0');
+    }
+
+    // e±
+    dst.push(fmt);
+    let mut exp = if d.d.is_empty() { 0 } else { d.dp - 1 };
+    if exp < 0 {
+        dst.push(b'-');
+        exp = -exp;
+    } else {
+        dst.push(b'+');
+    }
+
+    // dd or ddd
+    let exp = exp as usize;
+    if exp < 10 {
+        dst.extend_from_slice(&[b'0', exp as u8 + b'0']);
+    } else if exp < 100 {
+        dst.extend_from_slice(&[(exp / 10) as u8 + b'0', (exp % 10) as u8 + b'0']);
+    } else {
+        dst.extend_from_slice(&[
+            (exp / 100) as u8 + b'0',
+            (exp / 10 % 10) as u8 + b'0',
+            (exp % 10) as u8 + b'0',
+        ]);
+    }
+}
+
+// %f: -ddddddd.ddddd
+fn fmt_f(dst: &mut Vec<u8>, neg: bool, d: &DecimalSlice, prec: isize) {
+    // sign
+    if neg {
+        dst.push(b'-');
+    }
+
+    // integer, padded with zeros as needed.
+    if d.dp > 0 {
+        let m = d.d.len().min(d.dp as usize);
+        dst.extend_from_slice(&d.d[..m]);
+        dst.resize(dst.len() + (d.dp as usize - m), b'0');
+    } else {
+        dst.push(b'0');
+    }
+
+    // fraction
+    if prec > 0 {
+        dst.push(b'.');
+        for i in 1..=prec {
+            let j = d.dp + i - 1;
+            let ch = if 0 <= j && j < d.d.len() as isize {
+                d.d[j as usize]
+            } else {
+                b'0'
+            };
+            dst.push(ch);
+        }
+    }
+}
+
+// %b: -ddddddddp±ddd
+fn fmt_b(dst: &mut Vec<u8>, neg: bool, mant: u64, exp: i32, flt: &FloatInfo) {
+    // sign
+    if neg {
+        dst.push(b'-');
+    }
+
+    // mantissa
+    super::append_uint(dst, mant, 10);
+
+    // p
+    dst.push(b'p');
+
+    // ±exponent
+    let exp = exp - flt.mantbits as i32;
+    if exp >= 0 {
+        dst.push(b'+');
+    }
+    append_int(dst, exp as i64, 10);
+}
+
+// %x: -0x1.yyyyyyyyp±ddd or -0x0p+0. (y is hex digit, d is decimal digit)
+fn fmt_x(
+    dst: &mut Vec<u8>,
+    prec: isize,
+    fmt: u8,
+    neg: bool,
+    mut mant: u64,
+    mut exp: i32,
+    flt: &FloatInfo,
+) {
+    if mant == 0 {
+        exp = 0;
+    }
+
+    // Shift digits so leading 1 (if any) is at bit 1<<60.
+    mant <<= 60 - flt.mantbits;
+    while mant != 0 && mant & (1 << 60) == 0 {
+        mant <<= 1;
+        exp -= 1;
+    }
+
+    // Round if requested.
+    if (0..15).contains(&prec) {
+        let shift = prec as u32 * 4;
+        let extra = (mant << shift) & ((1 << 60) - 1);
+        mant >>= 60 - shift;
+        if extra | (mant & 1) > 1 << 59 {
+            mant += 1;
+        }
+        mant <<= 60 - shift;
+        if mant & (1 << 61) != 0 {
+            // Wrapped around.
+            mant >>= 1;
+            exp += 1;
+        }
+    }
+
+    let hex = if fmt == b'X' { UPPERHEX } else { LOWERHEX };
+
+    // sign, 0x, leading digit
+    if neg {
+        dst.push(b'-');
+    }
+    dst.extend_from_slice(&[b'0', fmt, b'0' + ((mant >> 60) & 1) as u8]);
+
+    // .fraction
+    mant <<= 4; // remove leading 0 or 1
+    if prec < 0 && mant != 0 {
+        dst.push(b'.');
+        while mant != 0 {
+            dst.push(hex[((mant >> 60) & 15) as usize]);
+            mant <<= 4;
+        }
+    } else if prec > 0 {
+        dst.push(b'.');
+        for _ in 0..prec {
+            dst.push(hex[((mant >> 60) & 15) as usize]);
+            mant <<= 4;
+        }
+    }
+
+    // p±
+    dst.push(if fmt == b'X' { b'P' } else { b'p' });
+    if exp < 0 {
+        dst.push(b'-');
+        exp = -exp;
+    } else {
+        dst.push(b'+');
+    }
+
+    // dd or ddd or dddd
+    let exp = exp as u32;
+    if exp < 100 {
+        dst.extend_from_slice(&[(exp / 10) as u8 + b'0', (exp % 10) as u8 + b'0']);
+    } else if exp < 1000 {
+        dst.extend_from_slice(&[
+            (exp / 100) as u8 + b'0',
+            ((exp / 10) % 10) as u8 + b'0',
+            (exp % 10) as u8 + b'0',
+        ]);
+    } else {
+        dst.extend_from_slice(&[
+            (exp / 1000) as u8 + b'0',
+            ((exp / 100) % 10) as u8 + b'0',
+            ((exp / 10) % 10) as u8 + b'0',
+            (exp % 10) as u8 + b'0',
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BELOW1E23: f64 = 99999999999999974834176.0;
+    const ABOVE1E23: f64 = 100000000000000008388608.0;
+
+    const FTOA_TESTS: &[(f64, u8, isize, &str)] = &[
+        (1.0, b'e', 5, "1.00000e+00"),
+        (1.0, b'f', 5, "1.00000"),
+        (1.0, b'g', 5, "1"),
+        (1.0, b'g', -1, "1"),
+        (20.0, b'g', -1, "20"),
+        (1234567.8, b'g', -1, "1.2345678e+06"),
+        (200000.0, b'g', -1, "200000"),
+        (2000000.0, b'g', -1, "2e+06"),
+        // g conversion and zero suppression
+        (400.0, b'g', 2, "4e+02"),
+        (40.0, b'g', 2, "40"),
+        (4.0, b'g', 2, "4"),
+        (0.4, b'g', 2, "0.4"),
+        (0.04, b'g', 2, "0.04"),
+        (0.004, b'g', 2, "0.004"),
+        (0.0004, b'g', 2, "0.0004"),
+        (0.00004, b'g', 2, "4e-05"),
+        (0.000004, b'g', 2, "4e-06"),
+        (0.0, b'e', 5, "0.00000e+00"),
+        (0.0, b'f', 5, "0.00000"),
+        (0.0, b'g', 5, "0"),
+        (0.0, b'g', -1, "0"),
+        (-1.0, b'e', 5, "-1.00000e+00"),
+        (-1.0, b'f', 5, "-1.00000"),
+        (-1.0, b'g', 5, "-1"),
+        (-1.0, b'g', -1, "-1"),
+        (12.0, b'e', 5, "1.20000e+01"),
+        (12.0, b'f', 5, "12.00000"),
+        (12.0, b'g', 5, "12"),
+        (12.0, b'g', -1, "12"),
+        (123456700.0, b'e', 5, "1.23457e+08"),
+        (123456700.0, b'f', 5, "123456700.00000"),
+        (123456700.0, b'g', 5, "1.2346e+08"),
+        (123456700.0, b'g', -1, "1.234567e+08"),
+        (1.2345e6, b'e', 5, "1.23450e+06"),
+        (1.2345e6, b'f', 5, "1234500.00000"),
+        (1.2345e6, b'g', 5, "1.2345e+06"),
+        // Round to even
+        (1.2345e6, b'e', 3, "1.234e+06"),
+        (1.2355e6, b'e', 3, "1.236e+06"),
+        (1.2345, b'f', 3, "1.234"),
+        (1.2355, b'f', 3, "1.236"),
+        (1234567890123456789.0, b'e', 3, "1.235e+18"),
+        (1234567890123456789.0, b'f', 3, "1234567890123456768.000"),
+        (1.2345e-6, b'e', 3, "1.234e-06"),
+        (1e23, b'e', 17, "9.99999999999999916e+22"),
+        (1e23, b'f', 17, "99999999999999991611392.00000000000000000"),
+        (1e23, b'g', 17, "9.9999999999999992e+22"),
+        (1e23, b'e', -1, "1e+23"),
+        (1e23, b'f', -1, "100000000000000000000000"),
+        (1e23, b'g', -1, "1e+23"),
+        (BELOW1E23, b'e', 17, "9.99999999999999748e+22"),
+        (
+            BELOW1E23,
+            b'f',
+            17,
+            "99999999999999974834176.00000000000000000",
+        ),
+        (BELOW1E23, b'g', 17, "9.9999999999999975e+22"),
+        (BELOW1E23, b'e', -1, "9.999999999999997e+22"),
+        (BELOW1E23, b'f', -1, "99999999999999970000000"),
+        (BELOW1E23, b'g', -1, "9.999999999999997e+22"),
+        (ABOVE1E23, b'e', 17, "1.00000000000000008e+23"),
+        (
+            ABOVE1E23,
+            b'f',
+            17,
+            "100000000000000008388608.00000000000000000",
+        ),
+        (ABOVE1E23, b'g', 17, "1.0000000000000001e+23"),
+        (ABOVE1E23, b'e', -1, "1.0000000000000001e+23"),
+        (ABOVE1E23, b'f', -1, "100000000000000010000000"),
+        (ABOVE1E23, b'g', -1, "1.0000000000000001e+23"),
+        (f64::MAX, b'g', -1, "1.7976931348623157e+308"),
+        (f64::MIN_POSITIVE, b'g', -1, "2.2250738585072014e-308"),
+        (5e-324, b'g', -1, "5e-324"),
+        (-5e-324, b'g', -1, "-5e-324"),
+        (32.0, b'g', -1, "32"),
+        (32.0, b'g', 0, "3e+01"),
+        (100.0, b'x', -1, "0x1.9p+06"),
+        (100.0, b'y', -1, "%y"),
+        (f64::NAN, b'g', -1, "NaN"),
+        (-f64::NAN, b'g', -1, "NaN"),
+        (f64::INFINITY, b'g', -1, "+Inf"),
+        (f64::NEG_INFINITY, b'g', -1, "-Inf"),
+        (-f64::INFINITY, b'x', -1, "-Inf"),
+        (-1.0, b'b', -1, "-4503599627370496p-52"),
+        // fixed bugs
+        (0.9, b'f', 1, "0.9"),
+        (0.09, b'f', 1, "0.1"),
+        (0.0999, b'f', 1, "0.1"),
+        (0.05, b'f', 1, "0.1"),
+        (0.05, b'f', 0, "0"),
+        (0.5, b'f', 1, "0.5"),
+        (0.5, b'f', 0, "0"),
+        (1.5, b'f', 0, "2"),
+        (2.275555555555555, b'x', -1, "0x1.23456789abcdep+01"),
+        (2.275555555555555, b'x', 0, "0x1p+01"),
+        (2.275555555555555, b'x', 2, "0x1.23p+01"),
+        (2.275555555555555, b'x', 16, "0x1.23456789abcde000p+01"),
+        (2.275555555555555, b'x', 21, "0x1.23456789abcde00000000p+01"),
+        (2.2755555510520935, b'x', -1, "0x1.2345678p+01"),
+        (2.2755555510520935, b'x', 6, "0x1.234568p+01"),
+        (2.275555431842804, b'x', -1, "0x1.2345668p+01"),
+        (2.275555431842804, b'x', 6, "0x1.234566p+01"),
+        (3.999969482421875, b'x', -1, "0x1.ffffp+01"),
+        (3.999969482421875, b'x', 4, "0x1.ffffp+01"),
+        (3.999969482421875, b'x', 3, "0x1.000p+02"),
+        (3.999969482421875, b'x', 2, "0x1.00p+02"),
+        (3.999969482421875, b'x', 1, "0x1.0p+02"),
+        (3.999969482421875, b'x', 0, "0x1p+02"),
+        // Issue 29491.
+        (498484681984085570.0, b'f', -1, "498484681984085570"),
+        (-5.8339553793802237e+23, b'g', -1, "-5.8339553793802237e+23"),
+        // Issue 52187
+        (123.45, b'?', 0, "%?"),
+        (123.45, b'?', 1, "%?"),
+        (123.45, b'?', -1, "%?"),
+        // rounding
+        (2.275555555555555, b'x', -1, "0x1.23456789abcdep+01"),
+        (2.275555555555555, b'X', -1, "0X1.23456789ABCDEP+01"),
+        (1.0, b'x', -1, "0x1p+00"),
+        (0.0, b'x', -1, "0x0p+00"),
+        (0.0, b'x', 3, "0x0.000p+00"),
+        (-0.0, b'g', -1, "-0"),
+        (5e-324, b'x', -1, "0x1p-1074"),
+        (f64::MAX, b'x', -1, "0x1.fffffffffffffp+1023"),
+    ];
+
+    #[test]
+    fn test_ftoa() {
+        for (f, fmt, prec, want) in FTOA_TESTS {
+            let got = format_float(*f, *fmt, *prec, 64);
+            assert_eq!(
+                got, *want,
+                "format_float({f}, {}, {prec}, 64)",
+                *fmt as char
+            );
+
+            let mut buf = b"abc".to_vec();
+            append_float(&mut buf, *f, *fmt, *prec, 64);
+            assert_eq!(&buf[3..], want.as_bytes());
+
+            if (*f as f32) as f64 == *f && *fmt != b'b' {
+                assert_eq!(
+                    format_float(*f, *fmt, *prec, 32),
+                    *want,
+                    "format_float({f}, {}, {prec}, 32)",
+                    *fmt as char
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ftoa32() {
+        let tests: &[(f32, u8, isize, &str)] = &[
+            (1.0, b'g', -1, "1"),
+            (0.1, b'g', -1, "0.1"),
+            (1.0e-45, b'g', -1, "1e-45"),
+            (f32::MAX, b'g', -1, "3.4028235e+38"),
+            (16777215.0, b'g', -1, "1.6777215e+07"),
+            (3.4028235e38, b'e', 3, "3.403e+38"),
+            (-1.0, b'b', -1, "-8388608p-23"),
+            (0.1, b'f', -1, "0.1"),
+            (0.1, b'f', 10, "0.1000000015"),
+            (1.0, b'x', -1, "0x1p+00"),
+            (3.4028235e38, b'x', -1, "0x1.fffffep+127"),
+            (1.0e-45, b'x', -1, "0x1p-149"),
+        ];
+        for (f, fmt, prec, want) in tests {
+            assert_eq!(
+                format_float(*f as f64, *fmt, *prec, 32),
+                *want,
+                "format_float({f}, {}, {prec}, 32)",
+                *fmt as char
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_float_prec() {
+        // Large precisions print exact decimal expansions.
+        assert_eq!(
+            format_float(0.1, b'e', 25, 64),
+            "1.0000000000000000555111512e-01"
+        );
+        assert_eq!(format_float(5e-324, b'e', 5, 64), "4.94066e-324");
+        assert_eq!(format_float(1e100, b'g', 3, 64), "1e+100");
+        assert_eq!(format_float(123456.0, b'G', 3, 64), "1.23E+05");
+        assert_eq!(format_float(1e-5, b'E', -1, 64), "1E-05");
+        assert_eq!(format_float(0.000123, b'f', -1, 64), "0.000123");
+    }
+
+    #[test]
+    #[should_panic(expected = "strconv: illegal AppendFloat/FormatFloat bitSize")]
+    fn test_illegal_bit_size() {
+        format_float(1.0, b'g', -1, 16);
+    }
+}
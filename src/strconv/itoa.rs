@@ -0,0 +1,192 @@
+use alloc::{string::String, vec::Vec};
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+const BUF_LEN: usize = 64 + 1; // +1 for sign of 64bit value in base 2
+
+/// Returns the string representation of `i` in the given base,
+/// for `2 <= base <= 36`. The result uses the lower-case letters 'a' to 'z'
+/// for digit values >= 10.
+///
+/// # Panics
+///
+/// Panics if `base` is not in `2..=36`.
+#[inline]
+pub fn format_uint(i: u64, base: u32) -> String {
+    let mut a = [0; BUF_LEN];
+    let i = format_bits(&mut a, i, base, false);
+    a[i..].iter().map(|b| *b as char).collect()
+}
+
+/// Returns the string representation of `i` in the given base,
+/// for `2 <= base <= 36`. The result uses the lower-case letters 'a' to 'z'
+/// for digit values >= 10.
+///
+/// # Panics
+///
+/// Panics if `base` is not in `2..=36`.
+#[inline]
+pub fn format_int(i: i64, base: u32) -> String {
+    let mut a = [0; BUF_LEN];
+    let i = format_bits(&mut a, i.unsigned_abs(), base, i < 0);
+    a[i..].iter().map(|b| *b as char).collect()
+}
+
+/// Equivalent to `format_int(i as i64, 10)`.
+#[inline]
+pub fn itoa(i: isize) -> String {
+    format_int(i as i64, 10)
+}
+
+/// Appends the string form of the integer `i`,
+/// as generated by [`format_int`], to `dst`.
+///
+/// # Panics
+///
+/// Panics if `base` is not in `2..=36`.
+#[inline]
+pub fn append_int(dst: &mut Vec<u8>, i: i64, base: u32) {
+    let mut a = [0; BUF_LEN];
+    let i = format_bits(&mut a, i.unsigned_abs(), base, i < 0);
+    dst.extend_from_slice(&a[i..]);
+}
+
+/// Appends the string form of the unsigned integer `i`,
+/// as generated by [`format_uint`], to `dst`.
+///
+/// # Panics
+///
+/// Panics if `base` is not in `2..=36`.
+#[inline]
+pub fn append_uint(dst: &mut Vec<u8>, i: u64, base: u32) {
+    let mut a = [0; BUF_LEN];
+    let i = format_bits(&mut a, i, base, false);
+    dst.extend_from_slice(&a[i..]);
+}
+
+/// Computes the string representation of `u` in the given base into the tail of `a`,
+/// and returns the index where it starts.
+/// If `neg` is set, `u` is treated as the magnitude of a negative value.
+fn format_bits(a: &mut [u8; BUF_LEN], mut u: u64, base: u32, neg: bool) -> usize {
+    assert!(
+        (2..=36).contains(&base),
+        "strconv: illegal AppendInt/FormatInt base"
+    );
+
+    let mut i = a.len();
+    let b = base as u64;
+    while u >= b {
+        i -= 1;
+        a[i] = DIGITS[(u % b) as usize];
+        u /= b;
+    }
+    // u < base
+    i -= 1;
+    a[i] = DIGITS[u as usize];
+
+    // add sign, if any
+    if neg {
+        i -= 1;
+        a[i] = b'-';
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITOB64_TESTS: &[(i64, u32, &str)] = &[
+        (0, 10, "0"),
+        (1, 10, "1"),
+        (-1, 10, "-1"),
+        (12345678, 10, "12345678"),
+        (-987654321, 10, "-987654321"),
+        (1 << 31, 10, "2147483648"),
+        (-(1 << 31), 10, "-2147483648"),
+        (1 << 32, 10, "4294967296"),
+        (i64::MAX, 10, "9223372036854775807"),
+        (i64::MIN, 10, "-9223372036854775808"),
+        (0, 2, "0"),
+        (10, 2, "1010"),
+        (-1, 2, "-1"),
+        (1 << 15, 2, "1000000000000000"),
+        (-8, 8, "-10"),
+        (0o57635436545, 8, "57635436545"),
+        (1 << 24, 8, "100000000"),
+        (16, 16, "10"),
+        (-0x123456789abcdef, 16, "-123456789abcdef"),
+        (i64::MAX, 16, "7fffffffffffffff"),
+        (
+            i64::MAX,
+            2,
+            "111111111111111111111111111111111111111111111111111111111111111",
+        ),
+        (
+            i64::MIN,
+            2,
+            "-1000000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (16, 17, "g"),
+        (25, 25, "10"),
+        (
+            (((((17 * 35 + 24) * 35 + 21) * 35 + 34) * 35 + 12) * 35 + 24) * 35 + 32,
+            35,
+            "holycow",
+        ),
+        (
+            (((((17 * 36 + 24) * 36 + 21) * 36 + 34) * 36 + 12) * 36 + 24) * 36 + 32,
+            36,
+            "holycow",
+        ),
+    ];
+
+    #[test]
+    fn test_itoa() {
+        for (input, base, out) in ITOB64_TESTS {
+            assert_eq!(
+                format_int(*input, *base),
+                *out,
+                "format_int({input}, {base})"
+            );
+            let mut dst = b"x".to_vec();
+            append_int(&mut dst, *input, *base);
+            assert_eq!(&dst[1..], out.as_bytes());
+
+            if *input >= 0 {
+                assert_eq!(format_uint(*input as u64, *base), *out);
+                let mut dst = b"x".to_vec();
+                append_uint(&mut dst, *input as u64, *base);
+                assert_eq!(&dst[1..], out.as_bytes());
+            }
+
+            if *base == 10 {
+                assert_eq!(itoa(*input as isize), *out);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uitoa() {
+        let tests: &[(u64, u32, &str)] = &[
+            (1 << 63, 10, "9223372036854775808"),
+            ((1 << 63) + 1, 10, "9223372036854775809"),
+            (u64::MAX - 1, 10, "18446744073709551614"),
+            (u64::MAX, 10, "18446744073709551615"),
+            (
+                u64::MAX,
+                2,
+                "1111111111111111111111111111111111111111111111111111111111111111",
+            ),
+        ];
+        for (input, base, out) in tests {
+            assert_eq!(format_uint(*input, *base), *out);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "illegal AppendInt/FormatInt base")]
+    fn test_format_uint_bad_base() {
+        format_uint(12345678, 1);
+    }
+}
@@ -0,0 +1,629 @@
+use super::Error;
+use crate::{bytealg, unicode::utf8};
+use alloc::{string::String, vec::Vec};
+
+const LOWERHEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Reports whether the rune is defined as printable by Go:
+/// letters, numbers, punctuation, symbols and ASCII space.
+pub fn is_print(r: char) -> bool {
+    // Fast check for Latin-1
+    if (r as u32) < 0x80 {
+        return (0x20..0x7F).contains(&(r as u32));
+    }
+    // core's table of printable characters excludes exactly the categories
+    // Go's does: Cc, Cf, Cs, Co, Cn and the Z* separators other than ASCII
+    // space. `str::escape_debug` consults it for every character but the first,
+    // which it also escapes when it is a grapheme extender, so prefix one.
+    let mut buf = [0; 1 + utf8::UTF_MAX];
+    buf[0] = b'a';
+    let n = r.encode_utf8(&mut buf[1..]).len();
+    match core::str::from_utf8(&buf[..1 + n]) {
+        Ok(s) => s.escape_debug().nth(1) == Some(r),
+        Err(_) => false,
+    }
+}
+
+/// Returns a double-quoted Go string literal representing `s`. The
+/// returned string uses Go escape sequences (`\t`, `\n`, `\xFF`, `\u0100`) for
+/// control characters and non-printable characters as defined by [`is_print`].
+///
+/// `s` may be any byte string: bytes that are not valid UTF-8 are written as `\x` escapes.
+#[inline]
+pub fn quote<S: AsRef<[u8]> + ?Sized>(s: &S) -> String {
+    quote_with(s.as_ref(), b'"', false)
+}
+
+/// Appends to `dst` the double-quoted Go string literal representing `s`,
+/// as generated by [`quote`].
+#[inline]
+pub fn append_quote<S: AsRef<[u8]> + ?Sized>(dst: &mut Vec<u8>, s: &S) {
+    append_quoted_with(dst, s.as_ref(), b'"', false);
+}
+
+/// Returns a double-quoted Go string literal representing `s`.
+/// The returned string uses Go escape sequences (`\t`, `\n`, `\xFF`, `\u0100`) for
+/// non-ASCII characters and non-printable characters as defined by [`is_print`].
+#[inline]
+pub fn quote_to_ascii<S: AsRef<[u8]> + ?Sized>(s: &S) -> String {
+    quote_with(s.as_ref(), b'"', true)
+}
+
+/// Appends to `dst` the double-quoted Go string literal representing `s`,
+/// as generated by [`quote_to_ascii`].
+#[inline]
+pub fn append_quote_to_ascii<S: AsRef<[u8]> + ?Sized>(dst: &mut Vec<u8>, s: &S) {
+    append_quoted_with(dst, s.as_ref(), b'"', true);
+}
+
+/// Returns a single-quoted Go character literal representing the
+/// rune. The returned string uses Go escape sequences (`\t`, `\n`, `\xFF`, `\u0100`)
+/// for control characters and non-printable characters as defined by [`is_print`].
+#[inline]
+pub fn quote_rune(r: char) -> String {
+    quote_rune_with(r, b'\'', false)
+}
+
+/// Appends to `dst` the single-quoted Go character literal representing the rune,
+/// as generated by [`quote_rune`].
+#[inline]
+pub fn append_quote_rune(dst: &mut Vec<u8>, r: char) {
+    append_quoted_rune_with(dst, r, b'\'', false);
+}
+
+/// Returns a single-quoted Go character literal representing
+/// the rune. The returned string uses Go escape sequences (`\t`, `\n`, `\xFF`,
+/// `\u0100`) for non-ASCII characters and non-printable characters as defined
+/// by [`is_print`].
+#[inline]
+pub fn quote_rune_to_ascii(r: char) -> String {
+    quote_rune_with(r, b'\'', true)
+}
+
+/// Appends to `dst` the single-quoted Go character literal representing the rune,
+/// as generated by [`quote_rune_to_ascii`].
+#[inline]
+pub fn append_quote_rune_to_ascii(dst: &mut Vec<u8>, r: char) {
+    append_quoted_rune_with(dst, r, b'\'', true);
+}
+
+fn quote_with(s: &[u8], quote: u8, ascii_only: bool) -> String {
+    let mut buf = Vec::with_capacity(3 * s.len() / 2);
+    append_quoted_with(&mut buf, s, quote, ascii_only);
+    // Invalid UTF-8 has been escaped, everything else is copied rune by rune.
+    String::from_utf8(buf).unwrap_or_default()
+}
+
+fn quote_rune_with(r: char, quote: u8, ascii_only: bool) -> String {
+    let mut buf = Vec::with_capacity(3);
+    append_quoted_rune_with(&mut buf, r, quote, ascii_only);
+    // Everything written is either an ASCII escape or a valid rune.
+    String::from_utf8(buf).unwrap_or_default()
+}
+
+fn append_quoted_with(buf: &mut Vec<u8>, s: &[u8], quote: u8, ascii_only: bool) {
+    buf.push(quote);
+    for (i, r, width) in utf8::runes(s) {
+        if width == 1 && r == utf8::RUNE_ERROR {
+            buf.extend_from_slice(b"\\x");
+            buf.push(LOWERHEX[(s[i] >> 4) as usize]);
+            buf.push(LOWERHEX[(s[i] & 0xF) as usize]);
+            continue;
+        }
+        append_escaped_rune(buf, r, quote, ascii_only);
+    }
+    buf.push(quote);
+}
+
+fn append_quoted_rune_with(buf: &mut Vec<u8>, r: char, quote: u8, ascii_only: bool) {
+    buf.push(quote);
+    append_escaped_rune(buf, r, quote, ascii_only);
+    buf.push(quote);
+}
+
+fn append_escaped_rune(buf: &mut Vec<u8>, r: char, quote: u8, ascii_only: bool) {
+    if r == quote as char || r == '\\' {
+        // always backslashed
+        buf.push(b'\\');
+        utf8::append_rune(buf, r);
+        return;
+    }
+    if ascii_only {
+        if r.is_ascii() && is_print(r) {
+            buf.push(r as u8);
+            return;
+        }
+    } else if is_print(r) {
+        utf8::append_rune(buf, r);
+        return;
+    }
+    match r {
+        '\x07' => buf.extend_from_slice(b"\\a"),
+        '\x08' => buf.extend_from_slice(b"\\b"),
+        '\x0C' => buf.extend_from_slice(b"\\f"),
+        '\n' => buf.extend_from_slice(b"\\n"),
+        '\r' => buf.extend_from_slice(b"\\r"),
+        '\t' => buf.extend_from_slice(b"\\t"),
+        '\x0B' => buf.extend_from_slice(b"\\v"),
+        _ => {
+            let r = r as u32;
+            if r < ' ' as u32 || r == 0x7f {
+                buf.extend_from_slice(b"\\x");
+                buf.push(LOWERHEX[(r >> 4) as usize & 0xF]);
+                buf.push(LOWERHEX[r as usize & 0xF]);
+            } else if r < 0x10000 {
+                buf.extend_from_slice(b"\\u");
+                for s in (0..=12).rev().step_by(4) {
+                    buf.push(LOWERHEX[(r >> s) as usize & 0xF]);
+                }
+            } else {
+                buf.extend_from_slice(b"\\U");
+                for s in (0..=28).rev().step_by(4) {
+                    buf.push(LOWERHEX[(r >> s) as usize & 0xF]);
+                }
+            }
+        }
+    }
+}
+
+/// Reports whether the string `s` can be represented
+/// unchanged as a single-line backquoted string without control
+/// characters other than tab.
+pub fn can_backquote<S: AsRef<[u8]> + ?Sized>(s: &S) -> bool {
+    for (_, r, wid) in utf8::runes(s.as_ref()) {
+        if wid > 1 {
+            if r == '\u{feff}' {
+                return false; // BOMs are invisible and should not be quoted.
+            }
+            continue; // All other multibyte runes are correctly encoded and assumed printable.
+        }
+        if r == utf8::RUNE_ERROR {
+            return false;
+        }
+        if (r < ' ' && r != '\t') || r == '`' || r == '\u{007F}' {
+            return false;
+        }
+    }
+    true
+}
+
+#[inline]
+const fn unhex(b: u8) -> Option<u32> {
+    match b {
+        b'0'..=b'9' => Some((b - b'0') as u32),
+        b'a'..=b'f' => Some((b - b'a' + 10) as u32),
+        b'A'..=b'F' => Some((b - b'A' + 10) as u32),
+        _ => None,
+    }
+}
+
+/// Decodes the first character or byte in the escaped string
+/// or character literal represented by the string `s`.
+/// It returns three values:
+///
+/// 1. `value`, the decoded Unicode code point or byte value;
+/// 2. `multibyte`, a boolean indicating whether the decoded character requires a multibyte UTF-8 representation;
+/// 3. `tail`, the remainder of the string after the character.
+///
+/// The second argument, `quote`, specifies the type of literal being parsed
+/// and therefore which escaped quote character is permitted.
+/// If set to a single quote, it permits the sequence `\'` and disallows unescaped `'`.
+/// If set to a double quote, it permits `\"` and disallows unescaped `"`.
+/// If set to zero, it does not permit either escape and allows both quote characters to appear unescaped.
+///
+/// A value that is not `multibyte` is a single byte, e.g. `\xFF` decodes to
+/// `'\u{FF}'` standing for the byte `0xFF`.
+pub fn unquote_char(s: &[u8], quote: u8) -> Result<(char, bool, &[u8]), Error> {
+    // easy cases
+    let c = match s.first() {
+        None => return Err(Error::Syntax),
+        Some(c) => *c,
+    };
+    if c == quote && (quote == b'\'' || quote == b'"') {
+        return Err(Error::Syntax);
+    }
+    if c >= utf8::RUNE_SELF {
+        let (r, size) = utf8::decode_rune(s);
+        return Ok((r, true, &s[size..]));
+    }
+    if c != b'\\' {
+        return Ok((c as char, false, &s[1..]));
+    }
+
+    // hard case: c is backslash
+    if s.len() <= 1 {
+        return Err(Error::Syntax);
+    }
+    let c = s[1];
+    let mut s = &s[2..];
+
+    let mut multibyte = false;
+    let value = match c {
+        b'a' => '\x07',
+        b'b' => '\x08',
+        b'f' => '\x0C',
+        b'n' => '\n',
+        b'r' => '\r',
+        b't' => '\t',
+        b'v' => '\x0B',
+        b'x' | b'u' | b'U' => {
+            let n = match c {
+                b'x' => 2,
+                b'u' => 4,
+                _ => 8,
+            };
+            if s.len() < n {
+                return Err(Error::Syntax);
+            }
+            let mut v = 0u32;
+            for &b in &s[..n] {
+                v = v << 4 | unhex(b).ok_or(Error::Syntax)?;
+            }
+            s = &s[n..];
+            if c == b'x' {
+                // single-byte string, possibly not UTF-8
+                v as u8 as char
+            } else {
+                multibyte = true;
+                char::from_u32(v).ok_or(Error::Syntax)?
+            }
+        }
+        b'0'..=b'7' => {
+            let mut v = (c - b'0') as u32;
+            if s.len() < 2 {
+                return Err(Error::Syntax);
+            }
+            for &b in &s[..2] {
+                // one digit already; two more
+                if !(b'0'..=b'7').contains(&b) {
+                    return Err(Error::Syntax);
+                }
+                v = (v << 3) | (b - b'0') as u32;
+            }
+            s = &s[2..];
+            if v > 255 {
+                return Err(Error::Syntax);
+            }
+            v as u8 as char
+        }
+        b'\\' => '\\',
+        b'\'' | b'"' => {
+            if c != quote {
+                return Err(Error::Syntax);
+            }
+            c as char
+        }
+        _ => return Err(Error::Syntax),
+    };
+    Ok((value, multibyte, s))
+}
+
+/// Interprets `s` as a single-quoted, double-quoted,
+/// or backquoted Go string literal, returning the string value
+/// that `s` quotes. (If `s` is single-quoted, it would be a Go
+/// character literal; `unquote` returns the corresponding
+/// one-character string. For an empty character literal
+/// `unquote` returns the empty string.)
+///
+/// Go strings may hold arbitrary bytes, so a literal whose value is not valid
+/// UTF-8 (e.g. `"\xff"`) is reported as [`Error::Syntax`]; use [`unquote_bytes`] for those.
+pub fn unquote(s: &str) -> Result<String, Error> {
+    unquote_bytes(s.as_bytes()).and_then(|b| String::from_utf8(b).map_err(|_| Error::Syntax))
+}
+
+/// Is like [`unquote`], but returns the bytes the literal quotes,
+/// which need not be valid UTF-8.
+pub fn unquote_bytes(s: &[u8]) -> Result<Vec<u8>, Error> {
+    // Determine the quote form and optimistically find the terminating quote.
+    if s.len() < 2 {
+        return Err(Error::Syntax);
+    }
+    let quote = s[0];
+    let end = match bytealg::index_byte(&s[1..], quote) {
+        // position after terminating quote; may be wrong if escape sequences are present
+        Some(end) => end + 2,
+        None => return Err(Error::Syntax),
+    };
+
+    match quote {
+        b'`' => {
+            if end != s.len() {
+                return Err(Error::Syntax);
+            }
+            // Carriage return characters ('\r') inside raw string literals
+            // are discarded from the raw string value.
+            Ok(s[1..end - 1]
+                .iter()
+                .copied()
+                .filter(|b| *b != b'\r')
+                .collect())
+        }
+        b'"' | b'\'' => {
+            // Handle quoted strings without any escape sequences.
+            let body = &s[1..end - 1];
+            if end == s.len() && !body.contains(&b'\\') && !body.contains(&b'\n') {
+                let valid = if quote == b'"' {
+                    utf8::valid(body)
+                } else {
+                    let (r, n) = utf8::decode_rune(body);
+                    1 + n + 1 == end && (r != utf8::RUNE_ERROR || n != 1)
+                };
+                if valid {
+                    return Ok(body.to_vec());
+                }
+            }
+
+            // Handle quoted strings with escape sequences.
+            let mut buf = Vec::with_capacity(3 * end / 2); // try to avoid more allocations
+            let mut rest = &s[1..]; // skip starting quote
+            while !rest.is_empty() && rest[0] != quote {
+                // Process the next character,
+                // rejecting any unescaped newline characters which are invalid.
+                if rest[0] == b'\n' {
+                    return Err(Error::Syntax);
+                }
+                let (r, multibyte, tail) = unquote_char(rest, quote)?;
+                rest = tail;
+
+                // Append the character.
+                if (r as u32) < utf8::RUNE_SELF as u32 || !multibyte {
+                    buf.push(r as u32 as u8);
+                } else {
+                    utf8::append_rune(&mut buf, r);
+                }
+
+                // Single quoted strings must be a single character.
+                if quote == b'\'' {
+                    break;
+                }
+            }
+
+            // Verify that the string ends with a terminating quote.
+            if rest != [quote] {
+                return Err(Error::Syntax);
+            }
+            Ok(buf)
+        }
+        _ => Err(Error::Syntax),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_print() {
+        for r in '\0'..='\u{FFFF}' {
+            let want = match r {
+                ' '..='~' => true,
+                _ if r.is_ascii() => false,
+                // A sample of the categories Go's table leaves out.
+                '\u{80}'..='\u{9F}' // Cc
+                | '\u{A0}' | '\u{2000}'..='\u{200A}' | '\u{3000}' // Zs
+                | '\u{AD}' | '\u{200B}'..='\u{200F}' | '\u{FEFF}' // Cf
+                | '\u{2028}' | '\u{2029}' // Zl, Zp
+                | '\u{E000}'..='\u{F8FF}' // Co
+                | '\u{378}' | '\u{FFFE}' | '\u{FFFF}' => false, // Cn
+                // Marks are printable even though they combine.
+                '\u{300}'..='\u{36F}' => true,
+                'é' | '☺' | '日' | '\u{FFFD}' => true,
+                _ => continue,
+            };
+            assert_eq!(is_print(r), want, "is_print({r:?})");
+        }
+        assert!(is_print('\u{1F600}'));
+        assert!(!is_print('\u{E0001}'));
+        assert!(!is_print('\u{10FFFF}'));
+    }
+
+    const QUOTE_TESTS: &[(&[u8], &str, &str)] = &[
+        (
+            b"\x07\x08\x0C\r\n\t\x0B",
+            r#""\a\b\f\r\n\t\v""#,
+            r#""\a\b\f\r\n\t\v""#,
+        ),
+        (b"\\", r#""\\""#, r#""\\""#),
+        (b"abc\xffdef", r#""abc\xffdef""#, r#""abc\xffdef""#),
+        ("\u{263a}".as_bytes(), "\"☺\"", r#""\u263a""#),
+        (
+            "\u{10ffff}".as_bytes(),
+            r#""\U0010ffff""#,
+            r#""\U0010ffff""#,
+        ),
+        (b"\x04", r#""\x04""#, r#""\x04""#),
+        // Some non-printable but graphic runes. Final column is double-quoted.
+        (
+            "!\u{00a0}!\u{2000}!\u{3000}!".as_bytes(),
+            r#""!\u00a0!\u2000!\u3000!""#,
+            r#""!\u00a0!\u2000!\u3000!""#,
+        ),
+        (b"\x7f", r#""\x7f""#, r#""\x7f""#),
+    ];
+
+    #[test]
+    fn test_quote() {
+        for (input, out, ascii) in QUOTE_TESTS {
+            assert_eq!(quote(input), *out, "quote({input:?})");
+            let mut buf = b"abc".to_vec();
+            append_quote(&mut buf, input);
+            assert_eq!(&buf[3..], out.as_bytes());
+
+            assert_eq!(quote_to_ascii(input), *ascii, "quote_to_ascii({input:?})");
+            let mut buf = b"abc".to_vec();
+            append_quote_to_ascii(&mut buf, input);
+            assert_eq!(&buf[3..], ascii.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_quote_rune() {
+        let tests: &[(char, &str, &str)] = &[
+            ('a', "'a'", "'a'"),
+            ('\x07', r"'\a'", r"'\a'"),
+            ('\\', r"'\\'", r"'\\'"),
+            ('\u{FF}', "'ÿ'", r"'\u00ff'"),
+            ('\u{263a}', "'☺'", r"'\u263a'"),
+            ('\u{fffd}', "'�'", r"'\ufffd'"),
+            ('\u{10ffff}', r"'\U0010ffff'", r"'\U0010ffff'"),
+            ('\u{0400}', "'Ѐ'", r"'\u0400'"),
+            ('\'', r"'\''", r"'\''"),
+            ('"', "'\"'", "'\"'"),
+        ];
+        for (r, out, ascii) in tests {
+            assert_eq!(quote_rune(*r), *out, "quote_rune({r:?})");
+            assert_eq!(
+                quote_rune_to_ascii(*r),
+                *ascii,
+                "quote_rune_to_ascii({r:?})"
+            );
+            let mut buf = b"abc".to_vec();
+            append_quote_rune(&mut buf, *r);
+            assert_eq!(&buf[3..], out.as_bytes());
+            let mut buf = b"abc".to_vec();
+            append_quote_rune_to_ascii(&mut buf, *r);
+            assert_eq!(&buf[3..], ascii.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_can_backquote() {
+        let tests: &[(&[u8], bool)] = &[
+            (b"`", false),
+            (b"\x00", false),
+            (b"\x01", false),
+            (b"\x1f", false),
+            (b"\x20", true),
+            (b"\x7e", true),
+            (b"\x7f", false),
+            (b"\t", true),
+            (b"\n", false),
+            (b"\r", false),
+            (b"abc\xffdef", false),
+            (b"\xef\xbb\xbf", false), // BOM
+            (b"\\", true),
+            ("☺".as_bytes(), true),
+            (b" !\"#$%&'()*+,-./:;<=>?@[\\]^_{|}~", true),
+            (b"0123456789", true),
+            (b"ABCDEFGHIJKLMNOPQRSTUVWXYZ", true),
+            (b"abcdefghijklmnopqrstuvwxyz", true),
+        ];
+        for (s, want) in tests {
+            assert_eq!(can_backquote(s), *want, "can_backquote({s:?})");
+        }
+    }
+
+    #[test]
+    fn test_unquote() {
+        let tests: &[(&str, &[u8])] = &[
+            (r#""""#, b""),
+            (r#""a""#, b"a"),
+            (r#""abc""#, b"abc"),
+            (r#""☺""#, "☺".as_bytes()),
+            (r#""hello world""#, b"hello world"),
+            (r#""\xFF""#, b"\xFF"),
+            (r#""\377""#, b"\xFF"),
+            (r#""\u1234""#, "\u{1234}".as_bytes()),
+            (r#""\U00010111""#, "\u{10111}".as_bytes()),
+            (r#""\U0001011111""#, "\u{10111}11".as_bytes()),
+            (r#""\a\b\f\n\r\t\v\\\"""#, b"\x07\x08\x0C\n\r\t\x0B\\\""),
+            (r#""'""#, b"'"),
+            (r#"'a'"#, b"a"),
+            (r#"'☹'"#, "☹".as_bytes()),
+            (r#"'\a'"#, b"\x07"),
+            (r#"'\x10'"#, b"\x10"),
+            (r#"'\377'"#, b"\xFF"),
+            (r#"'\u1234'"#, "\u{1234}".as_bytes()),
+            (r#"'\U00010111'"#, "\u{10111}".as_bytes()),
+            (r#"'\t'"#, b"\t"),
+            (r#"' '"#, b" "),
+            (r#"'\''"#, b"'"),
+            (r#"'"'"#, b"\""),
+            ("``", b""),
+            ("`a`", b"a"),
+            ("`abc`", b"abc"),
+            ("`☺`", "☺".as_bytes()),
+            ("`hello world`", b"hello world"),
+            (r"`\xFF`", br"\xFF"),
+            (r"`\377`", br"\377"),
+            ("`\\`", b"\\"),
+            ("`\n`", b"\n"),
+            ("`\t`", b"\t"),
+            ("` `", b" "),
+            ("`a\rb`", b"ab"),
+        ];
+        for (input, out) in tests {
+            assert_eq!(
+                unquote_bytes(input.as_bytes()).as_deref(),
+                Ok(*out),
+                "unquote_bytes({input:?})"
+            );
+            match core::str::from_utf8(out) {
+                Ok(out) => assert_eq!(unquote(input).as_deref(), Ok(out), "unquote({input:?})"),
+                Err(_) => assert_eq!(unquote(input), Err(Error::Syntax)),
+            }
+        }
+
+        // All the quote tests round trip.
+        for (input, out, ascii) in QUOTE_TESTS {
+            assert_eq!(unquote_bytes(out.as_bytes()).as_deref(), Ok(*input));
+            assert_eq!(unquote_bytes(ascii.as_bytes()).as_deref(), Ok(*input));
+        }
+    }
+
+    #[test]
+    fn test_unquote_invalid() {
+        let tests: &[&[u8]] = &[
+            b"",
+            b"\"",
+            b"\"a",
+            b"\"'",
+            b"b\"",
+            b"\"\\\"",
+            b"\"\\9\"",
+            b"\"\\19\"",
+            b"\"\\129\"",
+            b"'\\'",
+            b"'\\9'",
+            b"'\\19'",
+            b"'\\129'",
+            b"'ab'",
+            b"\"\\x1!\"",
+            b"\"\\U12345678\"",
+            b"\"\\z\"",
+            b"`",
+            b"`xxx",
+            b"``x\r",
+            b"`\"",
+            b"\"\\'\"",
+            b"'\\\"'",
+            b"\"\n\"",
+            b"\"\\n\n\"",
+            b"'\n'",
+            b"\"\\udead\"",
+            b"\"\\ud83d\\ude4f\"",
+            b"\"a\"b",
+            b"'a'b",
+            b"`a`b",
+        ];
+        for s in tests {
+            assert_eq!(unquote_bytes(s), Err(Error::Syntax), "unquote_bytes({s:?})");
+        }
+    }
+
+    #[test]
+    fn test_unquote_char() {
+        assert_eq!(
+            unquote_char(b"\\xffabc", b'"'),
+            Ok(('\u{ff}', false, &b"abc"[..]))
+        );
+        assert_eq!(
+            unquote_char("ÿabc".as_bytes(), b'"'),
+            Ok(('\u{ff}', true, &b"abc"[..]))
+        );
+        assert_eq!(unquote_char(b"'", 0), Ok(('\'', false, &b""[..])));
+        assert_eq!(unquote_char(b"\"", b'"'), Err(Error::Syntax));
+        assert_eq!(unquote_char(b"\\'", b'"'), Err(Error::Syntax));
+        assert_eq!(unquote_char(b"", b'"'), Err(Error::Syntax));
+    }
+}
@@ -0,0 +1,911 @@
+use crate::{bytealg, case};
+use core::cmp::Ordering;
+
+mod builder;
+pub use builder::*;
+
+mod reader;
+pub use reader::*;
+
+mod replace;
+pub use replace::*;
+
+/// Returns an integer comparing two strings lexicographically.
+#[inline]
+pub fn compare(a: &str, b: &str) -> Ordering {
+    a.cmp(b)
+}
+
+/// Reports whether `substr` is within `s`.
+#[inline]
+pub fn contains(s: &str, substr: &str) -> bool {
+    index(s, substr).is_some()
+}
+
+/// Reports whether any Unicode code points in `chars` are within `s`.
+#[inline]
+pub fn contains_any(s: &str, chars: &str) -> bool {
+    index_any(s, chars).is_some()
+}
+
+/// Reports whether the Unicode code point `r` is within `s`.
+#[inline]
+pub fn contains_rune(s: &str, r: char) -> bool {
+    index_rune(s, r).is_some()
+}
+
+/// Reports whether any Unicode code points `r` within `s` satisfy `f(r)`.
+#[inline]
+pub fn contains_func(s: &str, f: impl FnMut(char) -> bool) -> bool {
+    index_func(s, f).is_some()
+}
+
+/// Counts the number of non-overlapping instances of `substr` in `s`.
+/// If `substr` is an empty string, `count` returns 1 + the number of Unicode code points in `s`.
+#[inline]
+pub fn count(mut s: &str, substr: &str) -> usize {
+    // special case
+    if substr.is_empty() {
+        return s.chars().count() + 1;
+    }
+    if substr.len() == 1 {
+        return bytealg::count(s.as_bytes(), substr.as_bytes()[0]);
+    }
+    let mut n = 0;
+    while let Some(i) = index(s, substr) {
+        n += 1;
+        s = &s[i + substr.len()..];
+    }
+    n
+}
+
+/// Slices `s` around the first instance of `sep`,
+/// returning the text before and after `sep`.
+/// If `sep` does not appear in `s`, `cut` returns `None`.
+#[inline]
+pub fn cut<'a>(s: &'a str, sep: &str) -> Option<(&'a str, &'a str)> {
+    index(s, sep).map(|i| (&s[..i], &s[i + sep.len()..]))
+}
+
+/// Returns `s` without the provided leading `prefix` string,
+/// or `None` if `s` doesn't start with `prefix`.
+/// If `prefix` is the empty string, `cut_prefix` returns `Some(s)`.
+#[inline]
+pub fn cut_prefix<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.strip_prefix(prefix)
+}
+
+/// Returns `s` without the provided ending `suffix` string,
+/// or `None` if `s` doesn't end with `suffix`.
+/// If `suffix` is the empty string, `cut_suffix` returns `Some(s)`.
+#[inline]
+pub fn cut_suffix<'a>(s: &'a str, suffix: &str) -> Option<&'a str> {
+    s.strip_suffix(suffix)
+}
+
+/// Reports whether `s` and `t`, interpreted as UTF-8 strings,
+/// are equal under simple Unicode case-folding, which is a more general
+/// form of case-insensitivity.
+pub fn equal_fold(s: &str, t: &str) -> bool {
+    let mut t = t.chars();
+    for sr in s.chars() {
+        match t.next() {
+            Some(tr) if case::equal_fold(sr, tr) => {}
+            _ => return false,
+        }
+    }
+    // One string is empty. Are both?
+    t.next().is_none()
+}
+
+/// Splits the string `s` around each instance of one or more consecutive white space
+/// characters, as defined by [`char::is_whitespace`], returning a vector of substrings of `s` or an
+/// empty vector if `s` contains only white space.
+#[inline]
+pub fn fields(s: &str) -> Vec<&str> {
+    s.split_whitespace().collect()
+}
+
+/// Splits the string `s` at each run of Unicode code points `c` satisfying `f(c)`
+/// and returns a vector of slices of `s`. If all code points in `s` satisfy `f(c)` or the
+/// string is empty, an empty vector is returned.
+#[inline]
+pub fn fields_func(s: &str, f: impl FnMut(char) -> bool) -> Vec<&str> {
+    s.split(f).filter(|field| !field.is_empty()).collect()
+}
+
+/// Tests whether the string `s` begins with `prefix`.
+#[inline]
+pub fn has_prefix(s: &str, prefix: &str) -> bool {
+    s.starts_with(prefix)
+}
+
+/// Tests whether the string `s` ends with `suffix`.
+#[inline]
+pub fn has_suffix(s: &str, suffix: &str) -> bool {
+    s.ends_with(suffix)
+}
+
+/// Returns the index of the first instance of `substr` in `s`, or `None` if `substr` is not present in `s`.
+#[inline]
+pub fn index(s: &str, substr: &str) -> Option<usize> {
+    bytealg::index(s.as_bytes(), substr.as_bytes())
+}
+
+/// Returns the index of the first instance of any Unicode code point
+/// from `chars` in `s`, or `None` if no Unicode code point from `chars` is present in `s`.
+#[inline]
+pub fn index_any(s: &str, chars: &str) -> Option<usize> {
+    s.find(|r| chars.contains(r))
+}
+
+/// Returns the index of the first instance of `c` in `s`, or `None` if `c` is not present in `s`.
+#[inline]
+pub fn index_byte(s: &str, c: u8) -> Option<usize> {
+    bytealg::index_byte(s.as_bytes(), c)
+}
+
+/// Returns the index into `s` of the first Unicode
+/// code point satisfying `f(c)`, or `None` if none do.
+#[inline]
+pub fn index_func(s: &str, f: impl FnMut(char) -> bool) -> Option<usize> {
+    s.find(f)
+}
+
+/// Returns the index of the first instance of the Unicode code point
+/// `r`, or `None` if rune is not present in `s`.
+#[inline]
+pub fn index_rune(s: &str, r: char) -> Option<usize> {
+    if r.is_ascii() {
+        return index_byte(s, r as u8);
+    }
+    index(s, r.encode_utf8(&mut [0; 4]))
+}
+
+/// Concatenates the elements of its first argument to create a single string. The separator
+/// string `sep` is placed between elements in the resulting string.
+#[inline]
+pub fn join<S: AsRef<str>>(elems: &[S], sep: &str) -> String {
+    let mut b = String::new();
+    for (i, e) in elems.iter().enumerate() {
+        if i > 0 {
+            b.push_str(sep);
+        }
+        b.push_str(e.as_ref());
+    }
+    b
+}
+
+/// Returns the index of the last instance of `substr` in `s`, or `None` if `substr` is not present in `s`.
+#[inline]
+pub fn last_index(s: &str, substr: &str) -> Option<usize> {
+    bytealg::last_index(s.as_bytes(), substr.as_bytes())
+}
+
+/// Returns the index of the last instance of any Unicode code
+/// point from `chars` in `s`, or `None` if no Unicode code point from `chars` is
+/// present in `s`.
+#[inline]
+pub fn last_index_any(s: &str, chars: &str) -> Option<usize> {
+    s.rfind(|r| chars.contains(r))
+}
+
+/// Returns the index of the last instance of `c` in `s`, or `None` if `c` is not present in `s`.
+#[inline]
+pub fn last_index_byte(s: &str, c: u8) -> Option<usize> {
+    bytealg::last_index_byte(s.as_bytes(), c)
+}
+
+/// Returns the index into `s` of the last
+/// Unicode code point satisfying `f(c)`, or `None` if none do.
+#[inline]
+pub fn last_index_func(s: &str, f: impl FnMut(char) -> bool) -> Option<usize> {
+    s.rfind(f)
+}
+
+/// Returns a copy of the string `s` with all its characters modified
+/// according to the mapping function. If mapping returns `None`, the character is
+/// dropped from the string with no replacement.
+#[inline]
+pub fn map(mapping: impl FnMut(char) -> Option<char>, s: &str) -> String {
+    s.chars().filter_map(mapping).collect()
+}
+
+/// Returns a new string consisting of `count` copies of the string `s`.
+#[inline]
+pub fn repeat(s: &str, count: usize) -> String {
+    s.repeat(count)
+}
+
+/// Returns a copy of the string `s` with the first `n`
+/// non-overlapping instances of `old` replaced by `new`.
+/// If `old` is empty, it matches at the beginning of the string
+/// and after each UTF-8 sequence, yielding up to k+1 replacements
+/// for a k-rune string.
+/// If `n < 0`, there is no limit on the number of replacements.
+pub fn replace(s: &str, old: &str, new: &str, n: isize) -> String {
+    if old == new || n == 0 {
+        return s.to_string(); // avoid allocation
+    }
+
+    // Compute number of replacements.
+    let m = count(s, old);
+    if m == 0 {
+        return s.to_string(); // avoid allocation
+    }
+    let n = if n < 0 || m < n as usize {
+        m
+    } else {
+        n as usize
+    };
+
+    // Apply replacements to buffer.
+    let mut b = String::with_capacity(s.len() + n * new.len());
+    let mut start = 0;
+    for i in 0..n {
+        let mut j = start;
+        if old.is_empty() {
+            if i > 0 {
+                j += s[start..].chars().next().map_or(0, char::len_utf8);
+            }
+        } else {
+            j += index(&s[start..], old).unwrap_or(0);
+        }
+        b.push_str(&s[start..j]);
+        b.push_str(new);
+        start = j + old.len();
+    }
+    b.push_str(&s[start..]);
+    b
+}
+
+/// Returns a copy of the string `s` with all
+/// non-overlapping instances of `old` replaced by `new`.
+/// If `old` is empty, it matches at the beginning of the string
+/// and after each UTF-8 sequence, yielding up to k+1 replacements
+/// for a k-rune string.
+#[inline]
+pub fn replace_all(s: &str, old: &str, new: &str) -> String {
+    replace(s, old, new, -1)
+}
+
+/// Splits a string into its UTF-8 sequences, one per Unicode code point,
+/// up to a maximum of `n` (`n < 0` means no limit).
+fn explode(s: &str, n: isize) -> Vec<&str> {
+    let l = s.chars().count();
+    let n = if n < 0 || n as usize > l {
+        l
+    } else {
+        n as usize
+    };
+    let mut a = Vec::with_capacity(n);
+    let mut chars = s.char_indices();
+    for _ in 1..n {
+        let (i, r) = chars.next().unwrap();
+        a.push(&s[i..i + r.len_utf8()]);
+    }
+    if n > 0 {
+        a.push(&s[chars.next().map_or(s.len(), |(i, _)| i)..]);
+    }
+    a
+}
+
+/// Generic split: splits after each instance of `sep`,
+/// including `sep_save` bytes of `sep` in the subslices.
+fn gen_split<'a>(mut s: &'a str, sep: &str, sep_save: usize, n: isize) -> Vec<&'a str> {
+    if n == 0 {
+        return Vec::new();
+    }
+    if sep.is_empty() {
+        return explode(s, n);
+    }
+    let n = if n < 0 { count(s, sep) + 1 } else { n as usize }.min(s.len() + 1);
+
+    let mut a = Vec::with_capacity(n);
+    while a.len() < n - 1 {
+        match index(s, sep) {
+            Some(m) => {
+                a.push(&s[..m + sep_save]);
+                s = &s[m + sep.len()..];
+            }
+            None => break,
+        }
+    }
+    a.push(s);
+    a
+}
+
+/// Slices `s` into substrings separated by `sep` and returns a vector of
+/// the substrings between those separators.
+///
+/// The count determines the number of substrings to return:
+///
+/// - `n > 0`: at most `n` substrings; the last substring will be the unsplit remainder.
+/// - `n == 0`: the result is empty (zero substrings)
+/// - `n < 0`: all substrings
+///
+/// Edge cases for `s` and `sep` (for example, empty strings) are handled
+/// as described in the documentation for [`split`].
+#[inline]
+pub fn split_n<'a>(s: &'a str, sep: &str, n: isize) -> Vec<&'a str> {
+    gen_split(s, sep, 0, n)
+}
+
+/// Slices `s` into substrings after each instance of `sep` and
+/// returns a vector of those substrings.
+///
+/// The count determines the number of substrings to return, as in [`split_n`].
+#[inline]
+pub fn split_after_n<'a>(s: &'a str, sep: &str, n: isize) -> Vec<&'a str> {
+    gen_split(s, sep, sep.len(), n)
+}
+
+/// Slices `s` into all substrings separated by `sep` and returns a vector of
+/// the substrings between those separators.
+///
+/// If `s` does not contain `sep` and `sep` is not empty, `split` returns a
+/// vector of length 1 whose only element is `s`.
+///
+/// If `sep` is empty, `split` splits after each UTF-8 sequence. If both `s`
+/// and `sep` are empty, `split` returns an empty vector.
+///
+/// It is equivalent to [`split_n`] with a count of -1.
+#[inline]
+pub fn split<'a>(s: &'a str, sep: &str) -> Vec<&'a str> {
+    gen_split(s, sep, 0, -1)
+}
+
+/// Slices `s` into all substrings after each instance of `sep` and
+/// returns a vector of those substrings.
+///
+/// If `s` does not contain `sep` and `sep` is not empty, `split_after` returns
+/// a vector of length 1 whose only element is `s`.
+///
+/// If `sep` is empty, `split_after` splits after each UTF-8 sequence. If
+/// both `s` and `sep` are empty, `split_after` returns an empty vector.
+///
+/// It is equivalent to [`split_after_n`] with a count of -1.
+#[inline]
+pub fn split_after<'a>(s: &'a str, sep: &str) -> Vec<&'a str> {
+    gen_split(s, sep, sep.len(), -1)
+}
+
+/// Returns a copy of the string `s` with all letters that begin words
+/// mapped to their Unicode title case.
+pub fn title(s: &str) -> String {
+    // Use a closure here to remember state.
+    // Hackish but effective. Depends on map scanning in order and calling
+    // the closure once per rune.
+    let mut prev = ' ';
+    map(
+        |r| {
+            let rst = if case::is_separator(prev) {
+                case::to_title(r)
+            } else {
+                r
+            };
+            prev = r;
+            Some(rst)
+        },
+        s,
+    )
+}
+
+/// Returns `s` with all Unicode letters mapped to their lower case.
+#[inline]
+pub fn to_lower(s: &str) -> String {
+    map(|r| Some(case::to_lower(r)), s)
+}
+
+/// Returns a copy of the string `s` with all Unicode letters mapped to
+/// their Unicode title case.
+#[inline]
+pub fn to_title(s: &str) -> String {
+    map(|r| Some(case::to_title(r)), s)
+}
+
+/// Returns `s` with all Unicode letters mapped to their upper case.
+#[inline]
+pub fn to_upper(s: &str) -> String {
+    map(|r| Some(case::to_upper(r)), s)
+}
+
+/// Returns a slice of the string `s` with all leading and
+/// trailing Unicode code points contained in `cutset` removed.
+#[inline]
+pub fn trim<'a>(s: &'a str, cutset: &str) -> &'a str {
+    s.trim_matches(|r| cutset.contains(r))
+}
+
+/// Returns a slice of the string `s` with all leading
+/// Unicode code points contained in `cutset` removed.
+///
+/// To remove a prefix, use [`trim_prefix`] instead.
+#[inline]
+pub fn trim_left<'a>(s: &'a str, cutset: &str) -> &'a str {
+    s.trim_start_matches(|r| cutset.contains(r))
+}
+
+/// Returns a slice of the string `s`, with all trailing
+/// Unicode code points contained in `cutset` removed.
+///
+/// To remove a suffix, use [`trim_suffix`] instead.
+#[inline]
+pub fn trim_right<'a>(s: &'a str, cutset: &str) -> &'a str {
+    s.trim_end_matches(|r| cutset.contains(r))
+}
+
+/// Returns a slice of the string `s` with all leading
+/// and trailing Unicode code points `c` satisfying `f(c)` removed.
+#[inline]
+pub fn trim_func(s: &str, f: impl FnMut(char) -> bool) -> &str {
+    s.trim_matches(f)
+}
+
+/// Returns a slice of the string `s` with all leading
+/// Unicode code points `c` satisfying `f(c)` removed.
+#[inline]
+pub fn trim_left_func(s: &str, f: impl FnMut(char) -> bool) -> &str {
+    s.trim_start_matches(f)
+}
+
+/// Returns a slice of the string `s` with all trailing
+/// Unicode code points `c` satisfying `f(c)` removed.
+#[inline]
+pub fn trim_right_func(s: &str, f: impl FnMut(char) -> bool) -> &str {
+    s.trim_end_matches(f)
+}
+
+/// Returns a slice of the string `s`, with all leading
+/// and trailing white space removed, as defined by [`char::is_whitespace`].
+#[inline]
+pub fn trim_space(s: &str) -> &str {
+    s.trim()
+}
+
+/// Returns `s` without the provided leading `prefix` string.
+/// If `s` doesn't start with `prefix`, `s` is returned unchanged.
+#[inline]
+pub fn trim_prefix<'a>(s: &'a str, prefix: &str) -> &'a str {
+    s.strip_prefix(prefix).unwrap_or(s)
+}
+
+/// Returns `s` without the provided trailing `suffix` string.
+/// If `s` doesn't end with `suffix`, `s` is returned unchanged.
+#[inline]
+pub fn trim_suffix<'a>(s: &'a str, suffix: &str) -> &'a str {
+    s.strip_suffix(suffix).unwrap_or(s)
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+mod tests {
+    use super::*;
+
+    const ABCD: &str = "abcd";
+    const FAFF: &str = "\u{FAFF}";
+    const COMMAS: &str = "1,2,3,4";
+    const DOTS: &str = "1....2....3....4";
+
+    #[test]
+    fn test_index() {
+        let tests: &[(&str, &str, Option<usize>)] = &[
+            ("", "", Some(0)),
+            ("", "a", None),
+            ("", "foo", None),
+            ("fo", "foo", None),
+            ("foo", "foo", Some(0)),
+            ("oofofoofooo", "f", Some(2)),
+            ("oofofoofooo", "foo", Some(4)),
+            ("barfoobarfoo", "foo", Some(3)),
+            ("foo", "", Some(0)),
+            ("foo", "o", Some(1)),
+            ("abcABCabc", "A", Some(3)),
+            (
+                "jrzm6jjhorimglljrea4w3rlgosts0w2gia17hno2td4qd1jz",
+                "jz",
+                Some(47),
+            ),
+            (
+                "ekkuk5oft4eq0ocpacknhwouic1uua46unx12l37nioq9wbpnocqks6",
+                "ks6",
+                Some(52),
+            ),
+            ("999f2xmimunbuyew5vrkla9cpwhmxan8o98ec", "98ec", Some(33)),
+            ("9lpt9r98i04k8bz6c6dsrthb96bhi", "96bhi", Some(24)),
+            (
+                "55u558eqfaod2r2gu42xxsu631xf0zobs5840vl",
+                "5840vl",
+                Some(33),
+            ),
+        ];
+        for (s, sep, out) in tests {
+            assert_eq!(index(s, sep), *out, "index({s:?}, {sep:?})");
+        }
+
+        let last: &[(&str, &str, Option<usize>)] = &[
+            ("", "", Some(0)),
+            ("", "a", None),
+            ("fo", "foo", None),
+            ("foo", "foo", Some(0)),
+            ("foo", "f", Some(0)),
+            ("oofofoofooo", "f", Some(7)),
+            ("oofofoofooo", "foo", Some(7)),
+            ("barfoobarfoo", "foo", Some(9)),
+            ("foo", "", Some(3)),
+            ("foo", "o", Some(2)),
+            ("abcABCabc", "A", Some(3)),
+            ("abcABCabc", "a", Some(6)),
+        ];
+        for (s, sep, out) in last {
+            assert_eq!(last_index(s, sep), *out, "last_index({s:?}, {sep:?})");
+        }
+    }
+
+    #[test]
+    fn test_index_any() {
+        let tests: &[(&str, &str, Option<usize>, Option<usize>)] = &[
+            ("", "", None, None),
+            ("", "a", None, None),
+            ("", "abc", None, None),
+            ("a", "", None, None),
+            ("a", "a", Some(0), Some(0)),
+            ("aaa", "a", Some(0), Some(2)),
+            ("abc", "xyz", None, None),
+            ("abc", "xcz", Some(2), Some(2)),
+            ("ab☺c", "x☺yz", Some(2), Some(2)),
+            ("a☺b☻c☹d", "cx", Some(8), Some(8)),
+            ("a☺b☻c☹d", "uvw☻xyz", Some(5), Some(5)),
+            ("aRegExp*", ".(|)*+?^$[]", Some(7), Some(7)),
+            ("1....2....3....4", "\\.", Some(1), Some(14)),
+            ("x☺y", "☺", Some(1), Some(1)),
+        ];
+        for (s, chars, first, last) in tests {
+            assert_eq!(index_any(s, chars), *first, "index_any({s:?}, {chars:?})");
+            assert_eq!(
+                last_index_any(s, chars),
+                *last,
+                "last_index_any({s:?}, {chars:?})"
+            );
+            assert_eq!(contains_any(s, chars), first.is_some());
+        }
+        assert_eq!(index_byte("a☺b", b'b'), Some(4));
+        assert_eq!(last_index_byte("abcabc", b'a'), Some(3));
+        assert_eq!(last_index_byte("abc", b'z'), None);
+        assert_eq!(index_rune("a☺b☻", '☻'), Some(5));
+        assert!(contains_rune("a☺b", '☺'));
+        assert_eq!(index_func("abc☺", |r| !r.is_ascii()), Some(3));
+        assert_eq!(last_index_func("1a2b", |r| r.is_ascii_digit()), Some(2));
+        assert!(!contains_func("abc", char::is_whitespace));
+    }
+
+    #[test]
+    fn test_split() {
+        let tests: &[(&str, &str, isize, &[&str])] = &[
+            ("", "", -1, &[]),
+            (ABCD, "", 2, &["a", "bcd"]),
+            (ABCD, "", 4, &["a", "b", "c", "d"]),
+            (ABCD, "", -1, &["a", "b", "c", "d"]),
+            ("☺☻☹", "", -1, &["☺", "☻", "☹"]),
+            ("☺☻☹", "", 3, &["☺", "☻", "☹"]),
+            ("☺☻☹", "", 17, &["☺", "☻", "☹"]),
+            ("\u{FAFF}", "", -1, &[FAFF]),
+            (ABCD, "a", 0, &[]),
+            (ABCD, "a", -1, &["", "bcd"]),
+            (ABCD, "z", -1, &["abcd"]),
+            (COMMAS, ",", -1, &["1", "2", "3", "4"]),
+            (DOTS, "...", -1, &["1", ".2", ".3", ".4"]),
+            ("☺☻☹", "☹", -1, &["☺☻", ""]),
+            ("☺☻☹", "~", -1, &["☺☻☹"]),
+            ("1 2 3 4", " ", 3, &["1", "2", "3 4"]),
+            ("1 2", " ", 3, &["1", "2"]),
+        ];
+        for (s, sep, n, out) in tests {
+            let a = split_n(s, sep, *n);
+            assert_eq!(&a, out, "split({s:?}, {sep:?}, {n})");
+            if *n == 0 {
+                continue;
+            }
+            if *n < 0 {
+                assert_eq!(join(&a, sep), *s);
+                assert_eq!(split(s, sep), a);
+            }
+        }
+
+        let after: &[(&str, &str, isize, &[&str])] = &[
+            (ABCD, "a", -1, &["a", "bcd"]),
+            (ABCD, "z", -1, &["abcd"]),
+            (ABCD, "", -1, &["a", "b", "c", "d"]),
+            (COMMAS, ",", -1, &["1,", "2,", "3,", "4"]),
+            (DOTS, "...", -1, &["1...", ".2...", ".3...", ".4"]),
+            ("☺☻☹", "☹", -1, &["☺☻☹", ""]),
+            ("☺☻☹", "~", -1, &["☺☻☹"]),
+            ("☺☻☹", "", -1, &["☺", "☻", "☹"]),
+            ("1 2 3 4", " ", 3, &["1 ", "2 ", "3 4"]),
+            ("1 2 3", " ", 3, &["1 ", "2 ", "3"]),
+            ("1 2", " ", 3, &["1 ", "2"]),
+            ("123", "", 2, &["1", "23"]),
+            ("123", "", 17, &["1", "2", "3"]),
+        ];
+        for (s, sep, n, out) in after {
+            let a = split_after_n(s, sep, *n);
+            assert_eq!(&a, out, "split_after({s:?}, {sep:?}, {n})");
+            assert_eq!(a.concat(), *s);
+            if *n < 0 {
+                assert_eq!(split_after(s, sep), a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let tests: &[(&str, &[&str])] = &[
+            ("", &[]),
+            (" ", &[]),
+            (" \t ", &[]),
+            ("\u{2000}", &[]),
+            ("  abc  ", &["abc"]),
+            ("1 2 3 4", &["1", "2", "3", "4"]),
+            ("1  2  3  4", &["1", "2", "3", "4"]),
+            ("1\t\t2\t\t3\t4", &["1", "2", "3", "4"]),
+            ("1\u{2000}2\u{2001}3\u{2002}4", &["1", "2", "3", "4"]),
+            ("\u{2000}\u{2001}\u{2002}", &[]),
+            ("\n™\t™\n", &["™", "™"]),
+            ("☺", &["☺"]),
+        ];
+        for (s, out) in tests {
+            assert_eq!(&fields(s), out, "fields({s:?})");
+            assert_eq!(
+                &fields_func(s, char::is_whitespace),
+                out,
+                "fields_func({s:?})"
+            );
+        }
+        assert_eq!(
+            fields_func("1,2;;3", |r| r == ',' || r == ';'),
+            ["1", "2", "3"]
+        );
+    }
+
+    #[test]
+    fn test_cut() {
+        assert_eq!(cut("abc", "b"), Some(("a", "c")));
+        assert_eq!(cut("abc", "a"), Some(("", "bc")));
+        assert_eq!(cut("abc", "c"), Some(("ab", "")));
+        assert_eq!(cut("abc", "abc"), Some(("", "")));
+        assert_eq!(cut("abc", ""), Some(("", "abc")));
+        assert_eq!(cut("abc", "d"), None);
+        assert_eq!(cut("", "d"), None);
+        assert_eq!(cut("", ""), Some(("", "")));
+
+        assert_eq!(cut_prefix("abc", "a"), Some("bc"));
+        assert_eq!(cut_prefix("abc", "abc"), Some(""));
+        assert_eq!(cut_prefix("abc", ""), Some("abc"));
+        assert_eq!(cut_prefix("abc", "d"), None);
+        assert_eq!(cut_suffix("abc", "bc"), Some("a"));
+        assert_eq!(cut_suffix("abc", ""), Some("abc"));
+        assert_eq!(cut_suffix("abc", "d"), None);
+    }
+
+    #[test]
+    fn test_equal_fold() {
+        let tests = [
+            ("abc", "abc", true),
+            ("ABcd", "ABcd", true),
+            ("123abc", "123ABC", true),
+            ("αβδ", "ΑΒΔ", true),
+            ("abc", "xyz", false),
+            ("abc", "XYZ", false),
+            ("abcdefghijk", "abcdefghijX", false),
+            ("abcdefghijk", "abcdefghij\u{212A}", true),
+            ("abcdefghijK", "abcdefghij\u{212A}", true),
+            ("abcdefghijkz", "abcdefghij\u{212A}Z", true),
+            ("abcdefghijkz", "abcdefghij\u{212A}y", false),
+            ("1", "2", false),
+            ("utf-8", "US-ASCII", false),
+            ("σ", "ς", true),
+            ("ſ", "S", true),
+            ("ab", "abc", false),
+        ];
+        for (s, t, out) in tests {
+            assert_eq!(equal_fold(s, t), out, "equal_fold({s:?}, {t:?})");
+            assert_eq!(equal_fold(t, s), out, "equal_fold({t:?}, {s:?})");
+        }
+    }
+
+    #[test]
+    fn test_case() {
+        let upper = [
+            ("", ""),
+            ("ONLYUPPER", "ONLYUPPER"),
+            ("abc", "ABC"),
+            ("AbC123", "ABC123"),
+            ("azAZ09_", "AZAZ09_"),
+            (
+                "longStrinGwitHmixofsmaLLandcAps",
+                "LONGSTRINGWITHMIXOFSMALLANDCAPS",
+            ),
+            (
+                "long\u{0250}string\u{0250}with\u{0250}nonascii\u{2C6F}chars",
+                "LONG\u{2C6F}STRING\u{2C6F}WITH\u{2C6F}NONASCII\u{2C6F}CHARS",
+            ),
+            (
+                "\u{0250}\u{0250}\u{0250}\u{0250}\u{0250}",
+                "\u{2C6F}\u{2C6F}\u{2C6F}\u{2C6F}\u{2C6F}",
+            ),
+            ("a\u{0080}\u{10FFFF}", "A\u{0080}\u{10FFFF}"),
+            // no simple upper case mapping
+            ("ß", "ß"),
+        ];
+        for (s, out) in upper {
+            assert_eq!(to_upper(s), out);
+        }
+
+        let lower = [
+            ("", ""),
+            ("abc", "abc"),
+            ("AbC123", "abc123"),
+            ("azAZ09_", "azaz09_"),
+            (
+                "longStrinGwitHmixofsmaLLandcAps",
+                "longstringwithmixofsmallandcaps",
+            ),
+            (
+                "LONG\u{2C6F}STRING\u{2C6F}WITH\u{2C6F}NONASCII\u{2C6F}CHARS",
+                "long\u{0250}string\u{0250}with\u{0250}nonascii\u{0250}chars",
+            ),
+            (
+                "\u{2C6D}\u{2C6D}\u{2C6D}\u{2C6D}\u{2C6D}",
+                "\u{0251}\u{0251}\u{0251}\u{0251}\u{0251}",
+            ),
+            ("A\u{0080}\u{10FFFF}", "a\u{0080}\u{10FFFF}"),
+            ("\u{0130}", "i"),
+        ];
+        for (s, out) in lower {
+            assert_eq!(to_lower(s), out);
+        }
+
+        let title_tests = [
+            ("", ""),
+            ("a", "A"),
+            (" aaa aaa aaa ", " Aaa Aaa Aaa "),
+            (" Aaa Aaa Aaa ", " Aaa Aaa Aaa "),
+            ("123a456", "123a456"),
+            ("double-blind", "Double-Blind"),
+            ("ÿøû", "Ÿøû"),
+            ("with_underscore", "With_underscore"),
+            (
+                "unicode \u{2028} line separator",
+                "Unicode \u{2028} Line Separator",
+            ),
+        ];
+        for (s, out) in title_tests {
+            assert_eq!(title(s), out);
+        }
+        assert_eq!(to_title("ǆemal"), "ǅEMAL");
+    }
+
+    #[test]
+    fn test_map() {
+        // Run a couple of awful growth/shrinkage tests
+        let a = "a".repeat(100);
+        // 1.  Grow. This triggers two reallocations in map.
+        let m = map(|_| Some('\u{0250}'), &a);
+        assert_eq!(m, "\u{0250}".repeat(100));
+        // 2. Shrink
+        let m = map(|_| Some('a'), &m);
+        assert_eq!(m, a);
+        // 3. Drop some characters
+        let drop_not_latin = |r: char| if r.is_ascii() { Some(r) } else { None };
+        assert_eq!(map(drop_not_latin, "Hello, 세계"), "Hello, ");
+        // 4. Rotate
+        let rot13 = |r: char| {
+            Some(match r {
+                'a'..='z' => (((r as u8 - b'a') + 13) % 26 + b'a') as char,
+                'A'..='Z' => (((r as u8 - b'A') + 13) % 26 + b'A') as char,
+                _ => r,
+            })
+        };
+        assert_eq!(map(rot13, "a to zed"), "n gb mrq");
+        assert_eq!(map(rot13, &map(rot13, "a to zed")), "a to zed");
+    }
+
+    #[test]
+    fn test_trim() {
+        let tests: &[(for<'a> fn(&'a str, &str) -> &'a str, &str, &str, &str)] = &[
+            (trim, "abba", "a", "bb"),
+            (trim, "abba", "ab", ""),
+            (trim_left, "abba", "ab", ""),
+            (trim_right, "abba", "ab", ""),
+            (trim_left, "abba", "a", "bba"),
+            (trim_left, "abba", "b", "abba"),
+            (trim_right, "abba", "a", "abb"),
+            (trim_right, "abba", "b", "abba"),
+            (trim, "<tag>", "<>", "tag"),
+            (trim, "* listitem", " *", "listitem"),
+            (trim, "\"quote\"", "\"", "quote"),
+            (
+                trim,
+                "\u{2C6F}\u{2C6F}\u{0250}\u{0250}\u{2C6F}\u{2C6F}",
+                "\u{2C6F}",
+                "\u{0250}\u{0250}",
+            ),
+            (trim, "abba", "", "abba"),
+            (trim, "", "123", ""),
+            (trim, "", "", ""),
+            (trim_left, "abba", "", "abba"),
+            (trim_right, "abba", "", "abba"),
+            (trim_prefix, "aabb", "a", "abb"),
+            (trim_prefix, "aabb", "b", "aabb"),
+            (trim_suffix, "aabb", "a", "aabb"),
+            (trim_suffix, "aabb", "b", "aab"),
+        ];
+        for (f, s, arg, out) in tests {
+            assert_eq!(f(s, arg), *out, "{s:?} {arg:?}");
+        }
+
+        assert_eq!(trim_space(" \t\n a lone gopher \n\t\r\n"), "a lone gopher");
+        assert_eq!(trim_space("\u{2000}\u{3000} x \u{0085}"), "x");
+        assert_eq!(trim_func("123abc456", |r| r.is_ascii_digit()), "abc");
+        assert_eq!(
+            trim_left_func("123abc456", |r| r.is_ascii_digit()),
+            "abc456"
+        );
+        assert_eq!(
+            trim_right_func("123abc456", |r| r.is_ascii_digit()),
+            "123abc"
+        );
+    }
+
+    #[test]
+    fn test_replace() {
+        let tests: &[(&str, &str, &str, isize, &str)] = &[
+            ("hello", "l", "L", 0, "hello"),
+            ("hello", "l", "L", -1, "heLLo"),
+            ("hello", "x", "X", -1, "hello"),
+            ("", "x", "X", -1, ""),
+            ("radar", "r", "<r>", -1, "<r>ada<r>"),
+            ("", "", "<>", -1, "<>"),
+            ("banana", "a", "<>", -1, "b<>n<>n<>"),
+            ("banana", "a", "<>", 1, "b<>nana"),
+            ("banana", "a", "<>", 1000, "b<>n<>n<>"),
+            ("banana", "an", "<>", -1, "b<><>a"),
+            ("banana", "ana", "<>", -1, "b<>na"),
+            ("banana", "", "<>", -1, "<>b<>a<>n<>a<>n<>a<>"),
+            ("banana", "", "<>", 10, "<>b<>a<>n<>a<>n<>a<>"),
+            ("banana", "", "<>", 6, "<>b<>a<>n<>a<>n<>a"),
+            ("banana", "", "<>", 5, "<>b<>a<>n<>a<>na"),
+            ("banana", "", "<>", 1, "<>banana"),
+            ("banana", "a", "a", -1, "banana"),
+            ("banana", "a", "a", 1, "banana"),
+            ("☺☻☹", "", "<>", -1, "<>☺<>☻<>☹<>"),
+        ];
+        for (s, old, new, n, out) in tests {
+            assert_eq!(
+                replace(s, old, new, *n),
+                *out,
+                "replace({s:?}, {old:?}, {new:?}, {n})"
+            );
+            if *n == -1 {
+                assert_eq!(replace_all(s, old, new), *out);
+            }
+        }
+    }
+
+    #[test]
+    fn test_misc() {
+        assert_eq!(count("cheese", "e"), 3);
+        assert_eq!(count("five", ""), 5);
+        assert_eq!(count("☺☻☹", ""), 4);
+        assert_eq!(count("aaaa", "aa"), 2);
+        assert_eq!(repeat("-", 3), "---");
+        assert_eq!(repeat("ab", 0), "");
+        assert_eq!(join(&["foo", "bar", "baz"], ", "), "foo, bar, baz");
+        assert_eq!(join::<&str>(&[], ", "), "");
+        assert_eq!(compare("a", "b"), Ordering::Less);
+        assert!(has_prefix("Gopher", "Go"));
+        assert!(has_suffix("Amigo", "go"));
+        assert!(contains("seafood", "foo"));
+        assert!(!contains("seafood", "bar"));
+    }
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+/// Used to efficiently build a string using write methods.
+/// It minimizes memory copying. The zero value (`Builder::new()`) is ready to use.
+///
+/// A [`Builder`] accepts arbitrary bytes through [`std::io::Write`]; invalid UTF-8
+/// sequences are replaced with `U+FFFD` when the string is built.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Builder {
+    buf: Vec<u8>,
+}
+
+impl Builder {
+    /// Returns an empty [`Builder`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    /// Returns the accumulated string.
+    #[inline]
+    pub fn string(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.buf)
+    }
+
+    /// Consumes the [`Builder`], returning the accumulated string.
+    #[inline]
+    pub fn into_string(self) -> String {
+        String::from_utf8(self.buf)
+            .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+    }
+
+    /// Returns the number of accumulated bytes; `b.len() == b.string().len()`
+    /// if the accumulated bytes are valid UTF-8.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Reports whether no bytes have been accumulated.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the capacity of the builder's underlying byte slice. It is the
+    /// total space allocated for the string being built and includes any bytes
+    /// already written.
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Resets the [`Builder`] to be empty.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.buf = Vec::new();
+    }
+
+    /// Grows `b`'s capacity, if necessary, to guarantee space for
+    /// another `n` bytes. After `grow(n)`, at least `n` bytes can be written to `b`
+    /// without another allocation.
+    #[inline]
+    pub fn grow(&mut self, n: usize) {
+        self.buf.reserve(n);
+    }
+
+    /// Appends the contents of `s` to `b`'s buffer.
+    /// It returns the length of `s`.
+    #[inline]
+    pub fn write_str(&mut self, s: &str) -> usize {
+        self.buf.extend_from_slice(s.as_bytes());
+        s.len()
+    }
+
+    /// Appends the UTF-8 encoding of Unicode code point `r` to `b`'s buffer.
+    /// It returns the length of `r`.
+    #[inline]
+    pub fn write_rune(&mut self, r: char) -> usize {
+        let mut buf = [0; 4];
+        self.write_str(r.encode_utf8(&mut buf))
+    }
+}
+
+impl std::io::Write for Builder {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl core::fmt::Write for Builder {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        Builder::write_str(self, s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{ByteWriter, StringWriter};
+
+    fn check(b: &Builder, want: &str) {
+        assert_eq!(b.string(), want);
+        assert_eq!(b.len(), want.len());
+        assert!(b.cap() >= b.len());
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut b = Builder::new();
+        check(&b, "");
+        assert_eq!(b.write_str("hello"), 5);
+        check(&b, "hello");
+        b.write_byte(b' ').unwrap();
+        check(&b, "hello ");
+        assert_eq!(b.write_string("world").unwrap(), 5);
+        check(&b, "hello world");
+        assert_eq!(b.write_rune('☺'), 3);
+        check(&b, "hello world☺");
+        core::fmt::Write::write_fmt(&mut b, format_args!("{}", 42)).unwrap();
+        check(&b, "hello world☺42");
+        assert_eq!(b.clone().into_string(), "hello world☺42");
+
+        b.reset();
+        check(&b, "");
+        std::io::Write::write_all(&mut b, b"a\xffb").unwrap();
+        assert_eq!(b.string(), "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn test_builder_grow() {
+        for grow_len in [0, 100, 1000, 10000, 100000] {
+            let p = "a".repeat(grow_len);
+            let mut b = Builder::new();
+            b.grow(grow_len);
+            assert!(b.cap() >= grow_len);
+            let cap = b.cap();
+            b.write_str(&p);
+            // growing beforehand means no allocation on write
+            assert_eq!(b.cap(), cap);
+            assert_eq!(b.string(), p);
+        }
+    }
+}
@@ -0,0 +1,751 @@
+use crate::bytealg;
+use std::io::Write;
+
+/// Replaces a list of strings with replacements.
+/// It is safe for concurrent use by multiple threads.
+#[derive(Debug, Clone)]
+pub struct Replacer {
+    r: Algorithm,
+}
+
+/// The replacement algorithm picked for a set of replacements, as in Go.
+#[derive(Debug, Clone)]
+enum Algorithm {
+    Generic(Box<GenericReplacer>),
+    SingleString(SingleStringReplacer),
+    Byte(Box<ByteReplacer>),
+    ByteString(ByteStringReplacer),
+}
+
+/// Returns a new [`Replacer`] from a list of old, new string
+/// pairs. Replacements are performed in the order they appear in the
+/// target string, without overlapping matches, and comparisons
+/// are done in argument order.
+#[inline]
+pub fn new_replacer(oldnew: &[(&str, &str)]) -> Replacer {
+    Replacer::new(oldnew)
+}
+
+impl Replacer {
+    /// Returns a new [`Replacer`] from a list of old, new string
+    /// pairs. Replacements are performed in the order they appear in the
+    /// target string, without overlapping matches, and comparisons
+    /// are done in argument order.
+    pub fn new(oldnew: &[(&str, &str)]) -> Self {
+        if oldnew.len() == 1 && oldnew[0].0.len() > 1 {
+            return Self {
+                r: Algorithm::SingleString(SingleStringReplacer {
+                    pattern: oldnew[0].0.into(),
+                    value: oldnew[0].1.into(),
+                }),
+            };
+        }
+
+        let mut all_new_bytes = true;
+        for (old, new) in oldnew {
+            if old.len() != 1 {
+                return Self {
+                    r: Algorithm::Generic(Box::new(GenericReplacer::new(oldnew))),
+                };
+            }
+            if new.len() != 1 {
+                all_new_bytes = false;
+            }
+        }
+
+        if all_new_bytes {
+            let mut r = ByteReplacer([0; 256]);
+            for (i, b) in r.0.iter_mut().enumerate() {
+                *b = i as u8;
+            }
+            // The first occurrence of old->new map takes precedence
+            // over the others with the same old string.
+            for (old, new) in oldnew.iter().rev() {
+                r.0[old.as_bytes()[0] as usize] = new.as_bytes()[0];
+            }
+            return Self {
+                r: Algorithm::Byte(Box::new(r)),
+            };
+        }
+
+        let mut r = ByteStringReplacer {
+            replacements: vec![None; 256],
+            to_replace: Vec::with_capacity(oldnew.len()),
+        };
+        // The first occurrence of old->new map takes precedence
+        // over the others with the same old string.
+        for (old, new) in oldnew.iter().rev() {
+            let o = old.as_bytes()[0];
+            // To avoid counting repetitions multiple times.
+            if r.replacements[o as usize].is_none() {
+                r.to_replace.push(o);
+            }
+            r.replacements[o as usize] = Some(Box::from(*new));
+        }
+        Self {
+            r: Algorithm::ByteString(r),
+        }
+    }
+
+    /// Returns a copy of `s` with all replacements performed.
+    pub fn replace(&self, s: &str) -> String {
+        match &self.r {
+            Algorithm::Generic(r) => r.replace(s),
+            Algorithm::SingleString(r) => r.replace(s),
+            Algorithm::Byte(r) => r.replace(s),
+            Algorithm::ByteString(r) => r.replace(s),
+        }
+    }
+
+    /// Writes `s` to `w` with all replacements performed.
+    pub fn write_string<W: Write + ?Sized>(&self, w: &mut W, s: &str) -> std::io::Result<usize> {
+        match &self.r {
+            Algorithm::Generic(r) => r.write_string(w, s),
+            Algorithm::SingleString(r) => r.write_string(w, s),
+            Algorithm::Byte(r) => r.write_string(w, s),
+            Algorithm::ByteString(r) => r.write_string(w, s),
+        }
+    }
+}
+
+/// Converts the output of a replacer back to a string. Replacers only ever split
+/// the input at UTF-8 sequence boundaries, so the output is valid.
+#[inline]
+fn into_string(buf: Vec<u8>) -> String {
+    String::from_utf8(buf).expect("strings.Replacer: replacement produced invalid UTF-8")
+}
+
+/// Returns the width of the UTF-8 sequence starting at `s[i]`, or 1 at the end of `s`.
+#[inline]
+fn width(s: &[u8], i: usize) -> usize {
+    match s.get(i) {
+        Some(b) if *b >= 0xF0 => 4,
+        Some(b) if *b >= 0xE0 => 3,
+        Some(b) if *b >= 0xC0 => 2,
+        _ => 1,
+    }
+}
+
+/// A node in a lookup trie for prioritized key/value pairs. Keys
+/// and values may be empty. For example, the trie containing keys "ax", "ay",
+/// "bcbc", "x" and "xy" could have eight nodes:
+///
+/// ```text
+///  n0  -
+///  n1  a-
+///  n2  .x+
+///  n3  .y+
+///  n4  b-
+///  n5  .cbc+
+///  n6  x+
+///  n7  .y+
+/// ```
+///
+/// n0 is the root node, and its children are n1, n4 and n6; n1's children are
+/// n2 and n3; n4's child is n5; n6's child is n7. Nodes n0, n1 and n4 (marked
+/// with a trailing "-") are partial keys, and nodes n2, n3, n5, n6 and n7
+/// (marked with a trailing "+") are complete keys.
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    /// The value of the key/value pair, if this node is a complete key.
+    value: Box<str>,
+    /// The priority (higher is more important) of the trie node's
+    /// key/value pair; keys are not necessarily matched shortest- or longest-
+    /// first. Priority is positive if this node is a complete key, and zero
+    /// otherwise. In the example above, positive/zero priorities are marked
+    /// with a trailing "+" or "-".
+    priority: usize,
+
+    // A trie node may have zero, one or more child nodes:
+    //  * if the remaining fields are zero, there are no children.
+    //  * if prefix and next are non-zero, there is one child in next.
+    //  * if table is non-zero, it defines all the children.
+    //
+    // Prefixes are preferred over tables when there is one child, but the
+    // root node always uses a table for lookup efficiency.
+    /// The difference in keys between this trie node and the next.
+    /// In the example above, node n4 has prefix "cbc" and n4's next node is n5.
+    /// Node n5 has no children and so has zero prefix, next and table fields.
+    prefix: Vec<u8>,
+    next: Option<usize>,
+
+    /// A lookup table indexed by the next byte in the key, after
+    /// remapping that byte through [`GenericReplacer::mapping`] to create a dense
+    /// index. In the example above, the keys only use 'a', 'b', 'c', 'x' and
+    /// 'y', which remap to 0, 1, 2, 3 and 4. All other bytes remap to 5, and
+    /// 5 is also the length of the table (table_size).
+    table: Vec<Option<usize>>,
+}
+
+/// The fully generic algorithm.
+/// It's used as a fallback when nothing faster can be used.
+#[derive(Debug, Clone)]
+struct GenericReplacer {
+    /// The trie nodes, the root is at index 0.
+    nodes: Vec<TrieNode>,
+    /// The size of a trie node's lookup table. It is the number
+    /// of unique key bytes.
+    table_size: usize,
+    /// Maps from key bytes to a dense index for [`TrieNode::table`].
+    mapping: [usize; 256],
+}
+
+impl GenericReplacer {
+    fn new(oldnew: &[(&str, &str)]) -> Self {
+        let mut used = [false; 256];
+        // Find each byte used, then assign them each an index.
+        for (key, _) in oldnew {
+            for b in key.bytes() {
+                used[b as usize] = true;
+            }
+        }
+
+        let table_size = used.iter().filter(|u| **u).count();
+        let mut mapping = [table_size; 256];
+        let mut index = 0;
+        for (i, u) in used.iter().enumerate() {
+            if *u {
+                mapping[i] = index;
+                index += 1;
+            }
+        }
+
+        let mut r = Self {
+            // Ensure root node uses a lookup table (for performance).
+            nodes: vec![TrieNode {
+                table: vec![None; table_size],
+                ..Default::default()
+            }],
+            table_size,
+            mapping,
+        };
+
+        for (i, (old, new)) in oldnew.iter().enumerate() {
+            r.add(0, old.as_bytes(), new, oldnew.len() - i);
+        }
+        r
+    }
+
+    #[inline]
+    fn push(&mut self, node: TrieNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn add(&mut self, t: usize, key: &[u8], val: &str, priority: usize) {
+        if key.is_empty() {
+            let node = &mut self.nodes[t];
+            if node.priority == 0 {
+                node.value = val.into();
+                node.priority = priority;
+            }
+            return;
+        }
+
+        if !self.nodes[t].prefix.is_empty() {
+            let prefix = core::mem::take(&mut self.nodes[t].prefix);
+            let next = self.nodes[t].next;
+            // Need to split the prefix among multiple nodes.
+            // n is the length of the longest common prefix
+            let n = prefix.iter().zip(key).take_while(|(a, b)| a == b).count();
+            if n == prefix.len() {
+                self.nodes[t].prefix = prefix;
+                self.add(next.unwrap(), &key[n..], val, priority);
+            } else if n == 0 {
+                // First byte differs, start a new lookup table here. Looking up
+                // what is currently prefix[0] will lead to prefix_node, and
+                // looking up key[0] will lead to key_node.
+                let prefix_node = if prefix.len() == 1 {
+                    next.unwrap()
+                } else {
+                    self.push(TrieNode {
+                        prefix: prefix[1..].to_vec(),
+                        next,
+                        ..Default::default()
+                    })
+                };
+                let key_node = self.push(TrieNode::default());
+                let mut table = vec![None; self.table_size];
+                table[self.mapping[prefix[0] as usize]] = Some(prefix_node);
+                table[self.mapping[key[0] as usize]] = Some(key_node);
+                let node = &mut self.nodes[t];
+                node.table = table;
+                node.next = None;
+                self.add(key_node, &key[1..], val, priority);
+            } else {
+                // Insert new node after the common section of the prefix.
+                let next = self.push(TrieNode {
+                    prefix: prefix[n..].to_vec(),
+                    next,
+                    ..Default::default()
+                });
+                let node = &mut self.nodes[t];
+                node.prefix = prefix[..n].to_vec();
+                node.next = Some(next);
+                self.add(next, &key[n..], val, priority);
+            }
+        } else if !self.nodes[t].table.is_empty() {
+            // Insert into existing table.
+            let m = self.mapping[key[0] as usize];
+            let child = match self.nodes[t].table[m] {
+                Some(child) => child,
+                None => {
+                    let child = self.push(TrieNode::default());
+                    self.nodes[t].table[m] = Some(child);
+                    child
+                }
+            };
+            self.add(child, &key[1..], val, priority);
+        } else {
+            let next = self.push(TrieNode::default());
+            let node = &mut self.nodes[t];
+            node.prefix = key.to_vec();
+            node.next = Some(next);
+            self.add(next, &[], val, priority);
+        }
+    }
+
+    /// Iterates down the trie to the end, and grabs the value and keylen with
+    /// the highest priority.
+    fn lookup(&self, mut s: &[u8], ignore_root: bool) -> Option<(&str, usize)> {
+        let mut best_priority = 0;
+        let mut rst = None;
+        let mut node = Some(0);
+        let mut n = 0;
+        while let Some(t) = node {
+            let nd = &self.nodes[t];
+            if nd.priority > best_priority && !(ignore_root && t == 0) {
+                best_priority = nd.priority;
+                rst = Some((&*nd.value, n));
+            }
+
+            if s.is_empty() {
+                break;
+            }
+            if !nd.table.is_empty() {
+                let index = self.mapping[s[0] as usize];
+                if index == self.table_size {
+                    break;
+                }
+                node = nd.table[index];
+                s = &s[1..];
+                n += 1;
+            } else if !nd.prefix.is_empty() && s.starts_with(&nd.prefix) {
+                n += nd.prefix.len();
+                s = &s[nd.prefix.len()..];
+                node = nd.next;
+            } else {
+                break;
+            }
+        }
+        rst
+    }
+
+    fn replace(&self, s: &str) -> String {
+        let mut buf = Vec::with_capacity(s.len());
+        // Writing to a Vec never fails.
+        let _ = self.write_string(&mut buf, s);
+        into_string(buf)
+    }
+
+    fn write_string<W: Write + ?Sized>(&self, w: &mut W, s: &str) -> std::io::Result<usize> {
+        let s = s.as_bytes();
+        let root = &self.nodes[0];
+        let mut n = 0;
+        let mut last = 0;
+        let mut prev_match_empty = false;
+        let mut i = 0;
+        while i <= s.len() {
+            // Fast path: s[i] is not a prefix of any pattern.
+            if i != s.len() && root.priority == 0 {
+                let index = self.mapping[s[i] as usize];
+                if index == self.table_size || root.table[index].is_none() {
+                    i += 1;
+                    continue;
+                }
+            }
+
+            // Ignore the empty match iff the previous loop found the empty match.
+            match self.lookup(&s[i..], prev_match_empty) {
+                Some((val, keylen)) => {
+                    prev_match_empty = keylen == 0;
+                    w.write_all(&s[last..i])?;
+                    n += i - last;
+                    w.write_all(val.as_bytes())?;
+                    n += val.len();
+                    i += keylen;
+                    last = i;
+                }
+                None => {
+                    prev_match_empty = false;
+                    // Step over a whole UTF-8 sequence, so that empty matches
+                    // never split one.
+                    i += width(s, i);
+                }
+            }
+        }
+        if last != s.len() {
+            w.write_all(&s[last..])?;
+            n += s.len() - last;
+        }
+        Ok(n)
+    }
+}
+
+/// The implementation that's used when there is only
+/// one string to replace (and that string has more than one byte).
+#[derive(Debug, Clone)]
+struct SingleStringReplacer {
+    pattern: Box<str>,
+    /// The value to replace the pattern with
+    value: Box<str>,
+}
+
+impl SingleStringReplacer {
+    fn replace(&self, s: &str) -> String {
+        let mut buf = String::new();
+        let mut i = 0;
+        while let Some(m) = bytealg::index(&s.as_bytes()[i..], self.pattern.as_bytes()) {
+            buf.reserve(m + self.value.len());
+            buf.push_str(&s[i..i + m]);
+            buf.push_str(&self.value);
+            i += m + self.pattern.len();
+        }
+        if i == 0 {
+            return s.to_string();
+        }
+        buf.push_str(&s[i..]);
+        buf
+    }
+
+    fn write_string<W: Write + ?Sized>(&self, w: &mut W, s: &str) -> std::io::Result<usize> {
+        let mut n = 0;
+        let mut i = 0;
+        while let Some(m) = bytealg::index(&s.as_bytes()[i..], self.pattern.as_bytes()) {
+            w.write_all(&s.as_bytes()[i..i + m])?;
+            w.write_all(self.value.as_bytes())?;
+            n += m + self.value.len();
+            i += m + self.pattern.len();
+        }
+        w.write_all(&s.as_bytes()[i..])?;
+        n += s.len() - i;
+        Ok(n)
+    }
+}
+
+/// The implementation that's used when all the "old"
+/// and "new" values are single ASCII bytes.
+/// The array contains replacement bytes indexed by old byte.
+#[derive(Debug, Clone)]
+struct ByteReplacer([u8; 256]);
+
+impl ByteReplacer {
+    fn replace(&self, s: &str) -> String {
+        // lazily allocated
+        let mut buf: Option<Vec<u8>> = None;
+        for (i, b) in s.bytes().enumerate() {
+            let r = self.0[b as usize];
+            if r != b {
+                buf.get_or_insert_with(|| s.as_bytes().to_vec())[i] = r;
+            }
+        }
+        match buf {
+            Some(buf) => into_string(buf),
+            None => s.to_string(),
+        }
+    }
+
+    fn write_string<W: Write + ?Sized>(&self, w: &mut W, s: &str) -> std::io::Result<usize> {
+        let mut buf = [0; 32 << 10];
+        let mut n = 0;
+        for chunk in s.as_bytes().chunks(buf.len()) {
+            for (dst, b) in buf.iter_mut().zip(chunk) {
+                *dst = self.0[*b as usize];
+            }
+            w.write_all(&buf[..chunk.len()])?;
+            n += chunk.len();
+        }
+        Ok(n)
+    }
+}
+
+/// The implementation that's used when all the
+/// "old" values are single ASCII bytes but the "new" values vary in size.
+#[derive(Debug, Clone)]
+struct ByteStringReplacer {
+    /// The replacement byte slice indexed by old byte.
+    /// A `None` value means that the old byte should not be replaced.
+    replacements: Vec<Option<Box<str>>>,
+    /// Keeps a list of bytes to replace. Depending on length of `to_replace`
+    /// and length of target string it may be faster to use `count`, or a plain loop.
+    /// We store single byte as a string, because `count` takes a string as argument.
+    to_replace: Vec<u8>,
+}
+
+/// The number of `to_replace` bytes for which counting each of them in the
+/// input is cheaper than a single pass over it.
+const COUNT_CUT_OFF: usize = 8;
+
+impl ByteStringReplacer {
+    fn replace(&self, s: &str) -> String {
+        let bytes = s.as_bytes();
+        let mut new_size = s.len();
+        let mut any_changes = false;
+        // Is it faster to use count?
+        if self.to_replace.len() * COUNT_CUT_OFF <= s.len() {
+            for x in &self.to_replace {
+                let c = bytes.iter().filter(|b| *b == x).count();
+                if c != 0 {
+                    let r = self.replacements[*x as usize]
+                        .as_deref()
+                        .unwrap_or_default();
+                    // The -1 is because we are replacing 1 byte with r.len() bytes.
+                    new_size = new_size + c * r.len() - c;
+                    any_changes = true;
+                }
+            }
+        } else {
+            for b in bytes {
+                if let Some(r) = &self.replacements[*b as usize] {
+                    // See above for explanation of -1
+                    new_size = new_size + r.len() - 1;
+                    any_changes = true;
+                }
+            }
+        }
+        if !any_changes {
+            return s.to_string();
+        }
+
+        let mut buf = String::with_capacity(new_size);
+        let mut last = 0;
+        for (i, b) in bytes.iter().enumerate() {
+            if let Some(r) = &self.replacements[*b as usize] {
+                buf.push_str(&s[last..i]);
+                buf.push_str(r);
+                last = i + 1;
+            }
+        }
+        buf.push_str(&s[last..]);
+        buf
+    }
+
+    fn write_string<W: Write + ?Sized>(&self, w: &mut W, s: &str) -> std::io::Result<usize> {
+        let bytes = s.as_bytes();
+        let mut n = 0;
+        let mut last = 0;
+        for (i, b) in bytes.iter().enumerate() {
+            let r = match &self.replacements[*b as usize] {
+                Some(r) => r,
+                None => continue,
+            };
+            if last != i {
+                w.write_all(&bytes[last..i])?;
+                n += i - last;
+            }
+            last = i + 1;
+            w.write_all(r.as_bytes())?;
+            n += r.len();
+        }
+        if last != bytes.len() {
+            w.write_all(&bytes[last..])?;
+            n += bytes.len() - last;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html_escaper() -> Replacer {
+        new_replacer(&[
+            ("&", "&amp;"),
+            ("<", "&lt;"),
+            (">", "&gt;"),
+            ("\"", "&quot;"),
+            ("'", "&apos;"),
+        ])
+    }
+
+    fn html_unescaper() -> Replacer {
+        new_replacer(&[
+            ("&amp;", "&"),
+            ("&lt;", "<"),
+            ("&gt;", ">"),
+            ("&quot;", "\""),
+            ("&apos;", "'"),
+        ])
+    }
+
+    /// Returns a replacer that replaces all ASCII lower-case letters by their
+    /// upper-case counterpart, or by the given single-byte string when `lower` is one byte.
+    fn oldnew_upper(new: &dyn Fn(u8) -> String) -> Vec<(String, String)> {
+        (b'a'..=b'z')
+            .map(|b| ((b as char).to_string(), new(b)))
+            .collect()
+    }
+
+    fn replacer_of(pairs: &[(String, String)]) -> Replacer {
+        let pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|(a, b)| (a.as_str(), b.as_str()))
+            .collect();
+        new_replacer(&pairs)
+    }
+
+    #[test]
+    fn test_replacer() {
+        let capital_letters = new_replacer(&[("a", "A"), ("b", "B")]);
+        let inc = replacer_of(&oldnew_upper(&|b| {
+            (((b - b'a' + 1) % 26 + b'a') as char).to_string()
+        }));
+        let inc_with_empty = replacer_of(&oldnew_upper(&|b| {
+            if b == b'y' {
+                String::new()
+            } else {
+                (((b - b'a' + 1) % 26 + b'a') as char).to_string()
+            }
+        }));
+        let mut s: Vec<(String, String)> = (0..256u32)
+            .filter_map(|i| char::from_u32(i).filter(char::is_ascii))
+            .map(|c| (c.to_string(), format!("[{}]", c as u32)))
+            .collect();
+        s.truncate(128);
+        let ascii_to_num = replacer_of(&s);
+        let repeat = replacer_of(&oldnew_upper(&|b| {
+            (b as char).to_string().repeat((b - b'a' + 1) as usize)
+        }));
+        let generic = new_replacer(&[("a", "1"), ("aa", "2"), ("aaa", "3")]);
+        let blank_to_x1 = new_replacer(&[("", "X")]);
+        let blank_to_x2 = new_replacer(&[("", "X"), ("", "")]);
+        let blank_high_priority = new_replacer(&[("", "X"), ("o", "O")]);
+        let blank_low_priority = new_replacer(&[("o", "O"), ("", "X")]);
+        let blank_no_op1 = new_replacer(&[("", "")]);
+        let blank_no_op2 = new_replacer(&[("", ""), ("", "A")]);
+        let blank_foo = new_replacer(&[("", "X"), ("foobar", "R"), ("foobaz", "Z")]);
+        let no_hello = new_replacer(&[("Hello", "")]);
+        let abc = new_replacer(&[("a", "1"), ("b", "2"), ("c", "3")]);
+        let simple = new_replacer(&[("a", "1"), ("b", "2")]);
+        let simple2 = new_replacer(&[("a", "11"), ("a", "22")]);
+        let html_esc = html_escaper();
+        let html_unesc = html_unescaper();
+        let gen1 = new_replacer(&[
+            ("aaa", "3[aaa]"),
+            ("aa", "2[aa]"),
+            ("a", "1[a]"),
+            ("i", "i"),
+            ("longerst", "most long"),
+            ("longer", "medium"),
+            ("long", "short"),
+            ("xx", "xx"),
+            ("x", "X"),
+            ("X", "Y"),
+            ("Y", "Z"),
+        ]);
+        let gen2 = new_replacer(&[("aaa", "3[aaa]"), ("aa", "2[aa]")]);
+        let gen3 = new_replacer(&[("fooaaa", "3[aaa]"), ("fooaa", "2[aa]"), ("fooa", "1[a]")]);
+
+        let tests: &[(&Replacer, &str, &str)] = &[
+            (&html_esc, "No changes", "No changes"),
+            (
+                &html_esc,
+                "I <3 escaping & stuff",
+                "I &lt;3 escaping &amp; stuff",
+            ),
+            (&html_esc, "&&&", "&amp;&amp;&amp;"),
+            (&html_esc, "", ""),
+            (&capital_letters, "brad", "BrAd"),
+            (&capital_letters, "", ""),
+            (&inc, "brad", "csbe"),
+            (&inc, "\x00\x7f", "\x00\x7f"),
+            (&inc, "", ""),
+            (&inc, "zzz", "aaa"),
+            (&inc_with_empty, "xyz", "ya"),
+            (&ascii_to_num, "ABC", "[65][66][67]"),
+            (&ascii_to_num, "日本", "日本"),
+            (&repeat, "brad", "bbrrrrrrrrrrrrrrrrrradddd"),
+            (&repeat, "abba", "abbbba"),
+            (&repeat, "", ""),
+            (&simple, "abba", "1221"),
+            (&simple2, "abba", "11bb11"),
+            (&html_unesc, "&amp;&lt;&gt;&quot;&apos;", "&<>\"'"),
+            (
+                &html_unesc,
+                "&lt;b&gt;HTML&apos;s neat&lt;/b&gt;",
+                "<b>HTML's neat</b>",
+            ),
+            (&html_unesc, "&amp;amp;", "&amp;"),
+            (&generic, "a", "1"),
+            (&generic, "aa", "11"),
+            (&generic, "aaa", "111"),
+            (&gen1, "fooaaabar", "foo3[aaa]b1[a]r"),
+            (&gen1, "long, longerst, longer", "short, most long, medium"),
+            (&gen1, "xxxxx", "xxxxX"),
+            (&gen1, "XiX", "YiY"),
+            (&gen1, "", ""),
+            (&gen2, "aaaa", "3[aaa]a"),
+            (&gen2, "aaaa", "3[aaa]a"),
+            (&gen3, "fooaaa", "3[aaa]"),
+            (&gen3, "fooaa", "2[aa]"),
+            (&gen3, "fooa", "1[a]"),
+            (&gen3, "foo", "foo"),
+            (&blank_to_x1, "foo", "XfXoXoX"),
+            (&blank_to_x1, "", "X"),
+            (&blank_to_x1, "☺", "X☺X"),
+            (&blank_to_x2, "foo", "XfXoXoX"),
+            (&blank_to_x2, "", "X"),
+            (&blank_high_priority, "oo", "XOXOX"),
+            (&blank_high_priority, "ii", "XiXiX"),
+            (&blank_high_priority, "oiio", "XOXiXiXOX"),
+            (&blank_high_priority, "iooi", "XiXOXOXiX"),
+            (&blank_high_priority, "", "X"),
+            (&blank_low_priority, "oo", "OOX"),
+            (&blank_low_priority, "ii", "XiXiX"),
+            (&blank_low_priority, "oiio", "OXiXiOX"),
+            (&blank_low_priority, "iooi", "XiOOXiX"),
+            (&blank_low_priority, "", "X"),
+            (&blank_no_op1, "foo", "foo"),
+            (&blank_no_op1, "", ""),
+            (&blank_no_op2, "foo", "foo"),
+            (&blank_no_op2, "", ""),
+            (&blank_foo, "foobarfoobaz", "XRXZX"),
+            (&blank_foo, "foobar-foobaz", "XRX-XZX"),
+            (&blank_foo, "", "X"),
+            (&no_hello, "Hello", ""),
+            (&no_hello, "Hellox", "x"),
+            (&no_hello, "xHello", "x"),
+            (&no_hello, "xHellox", "xx"),
+            (&abc, "", ""),
+            (&abc, "a", "1"),
+            (&abc, "abc", "123"),
+            (&abc, "zcbaz", "z321z"),
+        ];
+
+        for (i, (r, input, out)) in tests.iter().enumerate() {
+            assert_eq!(r.replace(input), *out, "{i}. replace({input:?})");
+            let mut buf = Vec::new();
+            let n = r.write_string(&mut buf, input).unwrap();
+            assert_eq!(n, out.len(), "{i}. write_string({input:?})");
+            assert_eq!(buf, out.as_bytes(), "{i}. write_string({input:?})");
+        }
+    }
+
+    #[test]
+    fn test_pick_algorithm() {
+        let tests: &[(Replacer, &str)] = &[
+            (new_replacer(&[("a", "1")]), "Byte"),
+            (new_replacer(&[("a", "1"), ("b", "2")]), "Byte"),
+            (new_replacer(&[("a", "11")]), "ByteString"),
+            (new_replacer(&[("a", "1"), ("b", "22")]), "ByteString"),
+            (new_replacer(&[("aa", "1")]), "SingleString"),
+            (new_replacer(&[("aa", "1"), ("b", "2")]), "Generic"),
+            (new_replacer(&[("", "X")]), "Generic"),
+        ];
+        for (r, want) in tests {
+            let got = format!("{:?}", r.r);
+            assert!(got.starts_with(want), "{got} does not start with {want}");
+        }
+    }
+}
@@ -0,0 +1,5 @@
+/// Go unicode/utf8 library
+pub mod utf8;
+
+/// Go unicode/utf16 library
+pub mod utf16;
@@ -0,0 +1,260 @@
+//! Implements encoding and decoding of UTF-16 sequences.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+const REPLACEMENT_CHAR: char = '\u{FFFD}'; // Unicode replacement character
+
+// 0xd800-0xdc00 encodes the high 10 bits of a pair.
+// 0xdc00-0xe000 encodes the low 10 bits of a pair.
+// the value is those 20 bits plus 0x10000.
+const SURR1: u32 = 0xd800;
+const SURR2: u32 = 0xdc00;
+const SURR3: u32 = 0xe000;
+
+const SURR_SELF: u32 = 0x10000;
+
+/// Reports whether the specified Unicode code point
+/// can appear in a surrogate pair.
+#[inline]
+pub const fn is_surrogate(r: u32) -> bool {
+    SURR1 <= r && r < SURR3
+}
+
+/// Returns the UTF-16 decoding of a surrogate pair.
+/// If the pair is not a valid UTF-16 surrogate pair, `decode_rune` returns
+/// the Unicode replacement code point U+FFFD.
+#[inline]
+pub const fn decode_rune(r1: u16, r2: u16) -> char {
+    let (r1, r2) = (r1 as u32, r2 as u32);
+    if SURR1 <= r1 && r1 < SURR2 && SURR2 <= r2 && r2 < SURR3 {
+        // The pair always decodes to a code point in 0x10000..=0x10FFFF.
+        return match char::from_u32(((r1 - SURR1) << 10 | (r2 - SURR2)) + SURR_SELF) {
+            Some(r) => r,
+            None => REPLACEMENT_CHAR,
+        };
+    }
+    REPLACEMENT_CHAR
+}
+
+/// Returns the UTF-16 surrogates `r1`, `r2` for the given rune.
+/// If the rune is not a valid Unicode code point or does not need encoding,
+/// `encode_rune` returns U+FFFD, U+FFFD.
+#[inline]
+pub const fn encode_rune(r: char) -> (u16, u16) {
+    let r = r as u32;
+    if r < SURR_SELF {
+        return (REPLACEMENT_CHAR as u16, REPLACEMENT_CHAR as u16);
+    }
+    let r = r - SURR_SELF;
+    (
+        (SURR1 + ((r >> 10) & 0x3ff)) as u16,
+        (SURR2 + (r & 0x3ff)) as u16,
+    )
+}
+
+/// Returns the number of 16-bit words in the UTF-16 encoding of the rune.
+#[inline]
+pub const fn rune_len(r: char) -> usize {
+    r.len_utf16()
+}
+
+/// Returns the UTF-16 encoding of the Unicode code point sequence `s`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode(s: &[char]) -> Vec<u16> {
+    let n = s.iter().map(|r| rune_len(*r)).sum();
+    let mut a = Vec::with_capacity(n);
+    for r in s {
+        append_rune(&mut a, *r);
+    }
+    a
+}
+
+/// Appends the UTF-16 encoding of the Unicode code point `r`
+/// to the end of `a`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn append_rune(a: &mut Vec<u16>, r: char) {
+    // This function is inlineable for fast handling of ASCII.
+    if (r as u32) < SURR_SELF {
+        a.push(r as u16);
+    } else {
+        let (r1, r2) = encode_rune(r);
+        a.push(r1);
+        a.push(r2);
+    }
+}
+
+/// Returns the Unicode code point sequence represented
+/// by the UTF-16 encoding `s`. Unpaired surrogates are replaced with U+FFFD.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn decode(s: &[u16]) -> Vec<char> {
+    let mut buf = Vec::with_capacity(s.len());
+    let mut i = 0;
+    while i < s.len() {
+        let r = s[i] as u32;
+        let ar = if !is_surrogate(r) {
+            // Not a surrogate, so always a valid code point.
+            char::from_u32(r).unwrap_or(REPLACEMENT_CHAR)
+        } else if r < SURR2
+            && i + 1 < s.len()
+            && SURR2 <= s[i + 1] as u32
+            && (s[i + 1] as u32) < SURR3
+        {
+            // valid surrogate sequence
+            i += 1;
+            decode_rune(r as u16, s[i])
+        } else {
+            // invalid surrogate sequence
+            REPLACEMENT_CHAR
+        };
+        buf.push(ar);
+        i += 1;
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODE_TESTS: &[(&[char], &[u16])] = &[
+        (&['\u{1}', '\u{2}', '\u{3}', '\u{4}'], &[1, 2, 3, 4]),
+        (
+            &[
+                '\u{ffff}',
+                '\u{10000}',
+                '\u{10001}',
+                '\u{12345}',
+                '\u{10ffff}',
+            ],
+            &[
+                0xffff, 0xd800, 0xdc00, 0xd800, 0xdc01, 0xd808, 0xdf45, 0xdbff, 0xdfff,
+            ],
+        ),
+        (
+            &['a', 'b', '\u{d7ff}', '\u{e000}', '\u{ffff}', '\u{10000}'],
+            &[
+                b'a' as u16,
+                b'b' as u16,
+                0xd7ff,
+                0xe000,
+                0xffff,
+                0xd800,
+                0xdc00,
+            ],
+        ),
+    ];
+
+    #[test]
+    fn test_encode() {
+        for (input, out) in ENCODE_TESTS {
+            assert_eq!(encode(input), *out, "encode({input:?})");
+        }
+    }
+
+    #[test]
+    fn test_append_rune() {
+        for (input, out) in ENCODE_TESTS {
+            let mut a = Vec::new();
+            for r in *input {
+                append_rune(&mut a, *r);
+            }
+            assert_eq!(a, *out);
+        }
+    }
+
+    #[test]
+    fn test_encode_rune() {
+        for (i, (input, out)) in ENCODE_TESTS.iter().enumerate() {
+            let mut j = 0;
+            for r in *input {
+                let (r1, r2) = encode_rune(*r);
+                if (*r as u32) < 0x10000 {
+                    assert_eq!((r1, r2), (0xfffd, 0xfffd), "#{i}: encode_rune({r:?})");
+                    j += 1;
+                    continue;
+                }
+                assert_eq!((r1, r2), (out[j], out[j + 1]), "#{i}: encode_rune({r:?})");
+                j += 2;
+                assert_eq!(decode_rune(r1, r2), *r, "#{i}: decode_rune({r1:x}, {r2:x})");
+            }
+            assert_eq!(j, out.len());
+        }
+    }
+
+    #[test]
+    fn test_rune_len() {
+        let tests: &[(char, usize)] = &[
+            ('\u{0}', 1),
+            ('A', 1),
+            ('\u{D7FF}', 1),
+            ('\u{E000}', 1),
+            ('\u{FFFF}', 1),
+            ('\u{10000}', 2),
+            ('\u{10FFFF}', 2),
+        ];
+        for (r, n) in tests {
+            assert_eq!(rune_len(*r), *n, "rune_len({r:?})");
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let tests: &[(&[u16], &[char])] = &[
+            (&[], &[]),
+            (&[1, 2, 3, 4], &['\u{1}', '\u{2}', '\u{3}', '\u{4}']),
+            (
+                &[
+                    0xd800, 0xdc00, 0xd800, 0xdc01, 0xd808, 0xdf45, 0xdbff, 0xdfff,
+                ],
+                &['\u{10000}', '\u{10001}', '\u{12345}', '\u{10ffff}'],
+            ),
+            (&[0xd800, b'a' as u16], &['\u{fffd}', 'a']),
+            (&[0xdfff], &['\u{fffd}']),
+        ];
+        for (input, out) in tests {
+            assert_eq!(decode(input), *out, "decode({input:?})");
+        }
+    }
+
+    #[test]
+    fn test_decode_rune() {
+        let tests: &[(u16, u16, char)] = &[
+            // surrogate pair
+            (0xd800, 0xdc00, '\u{10000}'),
+            (0xd800, 0xdc01, '\u{10001}'),
+            (0xd808, 0xdf45, '\u{12345}'),
+            (0xdbff, 0xdfff, '\u{10ffff}'),
+            // invalid surrogate pair
+            (0xd800, b'a' as u16, '\u{fffd}'),
+            (0xdfff, 0xd800, '\u{fffd}'),
+            (b'a' as u16, 0xdc00, '\u{fffd}'),
+        ];
+        for (r1, r2, want) in tests {
+            assert_eq!(decode_rune(*r1, *r2), *want, "decode_rune({r1:x}, {r2:x})");
+        }
+    }
+
+    #[test]
+    fn test_is_surrogate() {
+        let tests: &[(u32, bool)] = &[
+            // from https://en.wikipedia.org/wiki/UTF-16
+            (0x10000, false),  // U+10000
+            (0xd7ff, false),   // '퟿'
+            (0xd800, true),    // '\uD800'
+            (0xdbff, true),    // '\uDBFF'
+            (0xdc00, true),    // '\uDC00'
+            (0xdfff, true),    // '\uDFFF'
+            (0xe000, false),   // ''
+            (0xffff, false),   // '￿'
+            (0x10ffff, false), // U+10FFFF
+        ];
+        for (r, want) in tests {
+            assert_eq!(is_surrogate(*r), *want, "is_surrogate({r:#x})");
+        }
+    }
+}
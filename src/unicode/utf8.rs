@@ -0,0 +1,484 @@
+//! Functions and constants to support text encoded in UTF-8. Invalid input
+//! decodes as [`RUNE_ERROR`](crate::unicode::utf8::RUNE_ERROR) with a width of one byte, as in Go.
+//!
+//! Go's `...InString` variants are not ported separately: a Go string may hold
+//! arbitrary bytes, so pass `s.as_bytes()` to the byte slice function instead.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The "error" rune or "Unicode replacement character".
+pub const RUNE_ERROR: char = '\u{FFFD}';
+
+/// Characters below RUNE_SELF are represented as themselves in a single byte.
+pub const RUNE_SELF: u8 = 0x80;
+
+/// Maximum valid Unicode code point.
+pub const MAX_RUNE: char = '\u{10FFFF}';
+
+/// Maximum number of bytes of a UTF-8 encoded Unicode character.
+pub const UTF_MAX: usize = 4;
+
+const SURROGATE_MIN: u32 = 0xD800;
+const SURROGATE_MAX: u32 = 0xDFFF;
+
+const LOCB: u8 = 0x80;
+const HICB: u8 = 0xBF;
+
+/// Returns the expected width of the encoding starting with `b`
+/// and the accepted range of the second byte, `None` if `b` can not start a rune.
+#[inline]
+const fn first(b: u8) -> Option<(usize, u8, u8)> {
+    match b {
+        0x00..=0x7F => Some((1, LOCB, HICB)),
+        0xC2..=0xDF => Some((2, LOCB, HICB)),
+        0xE0 => Some((3, 0xA0, HICB)),
+        0xE1..=0xEC | 0xEE..=0xEF => Some((3, LOCB, HICB)),
+        0xED => Some((3, LOCB, 0x9F)),
+        0xF0 => Some((4, 0x90, HICB)),
+        0xF1..=0xF3 => Some((4, LOCB, HICB)),
+        0xF4 => Some((4, LOCB, 0x8F)),
+        _ => None,
+    }
+}
+
+/// Unpacks the first UTF-8 encoding in `p` and returns the rune and
+/// its width in bytes. If `p` is empty it returns `(RUNE_ERROR, 0)`. Otherwise, if
+/// the encoding is invalid, it returns `(RUNE_ERROR, 1)`.
+pub fn decode_rune(p: &[u8]) -> (char, usize) {
+    let n = p.len();
+    if n < 1 {
+        return (RUNE_ERROR, 0);
+    }
+
+    let p0 = p[0];
+    let (sz, lo, hi) = match first(p0) {
+        Some((1, _, _)) => return (p0 as char, 1),
+        Some(x) => x,
+        None => return (RUNE_ERROR, 1),
+    };
+
+    if n < sz {
+        return (RUNE_ERROR, 1);
+    }
+
+    let b1 = p[1];
+    if b1 < lo || hi < b1 {
+        return (RUNE_ERROR, 1);
+    }
+
+    let mut r = (u32::from(p0) & (0xFF >> (sz + 1))) << 6 | u32::from(b1 & 0x3F);
+    for &b in &p[2..sz] {
+        if !(LOCB..=HICB).contains(&b) {
+            return (RUNE_ERROR, 1);
+        }
+        r = r << 6 | u32::from(b & 0x3F);
+    }
+
+    // The accept ranges above guarantee r is a valid scalar value.
+    (char::from_u32(r).unwrap_or(RUNE_ERROR), sz)
+}
+
+/// Reports whether the bytes in `p` begin with a full UTF-8 encoding of a rune.
+/// An invalid encoding is considered a full rune since it will convert as a width-1 error rune.
+pub fn full_rune(p: &[u8]) -> bool {
+    let n = p.len();
+    if n == 0 {
+        return false;
+    }
+
+    let (sz, lo, hi) = match first(p[0]) {
+        Some(x) => x,
+        None => return true,
+    };
+
+    if n >= sz {
+        return true;
+    }
+
+    // Must be short or invalid.
+    if n > 1 && (p[1] < lo || hi < p[1]) {
+        return true;
+    }
+    n > 2 && !(LOCB..=HICB).contains(&p[2])
+}
+
+/// Unpacks the last UTF-8 encoding in `p` and returns the rune and
+/// its width in bytes, with the same conventions as [`decode_rune`].
+pub fn decode_last_rune(p: &[u8]) -> (char, usize) {
+    let end = p.len();
+    if end == 0 {
+        return (RUNE_ERROR, 0);
+    }
+    if p[end - 1] < RUNE_SELF {
+        return (p[end - 1] as char, 1);
+    }
+
+    // guard against O(n^2) behavior when traversing
+    // backwards through strings with long sequences of
+    // invalid UTF-8.
+    let lim = end.saturating_sub(UTF_MAX);
+    let mut start = end - 1;
+    while start > lim {
+        start -= 1;
+        if rune_start(p[start]) {
+            break;
+        }
+    }
+
+    let (r, size) = decode_rune(&p[start..end]);
+    if start + size != end {
+        return (RUNE_ERROR, 1);
+    }
+    (r, size)
+}
+
+/// Reports whether the byte could be the first byte of an encoded,
+/// possibly invalid rune. Second and subsequent bytes always have the top two
+/// bits set to 10.
+#[inline]
+pub const fn rune_start(b: u8) -> bool {
+    b & 0xC0 != 0x80
+}
+
+/// Returns the number of runes in `p`. Erroneous and short
+/// encodings are treated as single runes of width 1 byte.
+pub fn rune_count(p: &[u8]) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < p.len() {
+        if p[i] < RUNE_SELF {
+            i += 1;
+        } else {
+            i += decode_rune(&p[i..]).1;
+        }
+        n += 1;
+    }
+    n
+}
+
+/// Returns the number of bytes required to encode the rune.
+#[inline]
+pub const fn rune_len(r: char) -> usize {
+    r.len_utf8()
+}
+
+/// Writes into `p` (which must be large enough) the UTF-8 encoding of the rune.
+/// It returns the number of bytes written.
+///
+/// # Panics
+///
+/// Panics if `p` is shorter than [`rune_len(r)`](rune_len).
+#[inline]
+pub fn encode_rune(p: &mut [u8], r: char) -> usize {
+    r.encode_utf8(p).len()
+}
+
+/// Appends the UTF-8 encoding of `r` to the end of `p`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn append_rune(p: &mut Vec<u8>, r: char) {
+    let mut buf = [0; UTF_MAX];
+    p.extend_from_slice(r.encode_utf8(&mut buf).as_bytes());
+}
+
+/// Reports whether `p` consists entirely of valid UTF-8-encoded runes.
+#[inline]
+pub fn valid(p: &[u8]) -> bool {
+    // Go's notion of valid UTF-8 is exactly the one of `str`.
+    core::str::from_utf8(p).is_ok()
+}
+
+/// Reports whether `r` can be legally encoded as UTF-8.
+/// Code points that are out of range or a surrogate half are illegal.
+#[inline]
+pub const fn valid_rune(r: u32) -> bool {
+    r < SURROGATE_MIN || (SURROGATE_MAX < r && r <= MAX_RUNE as u32)
+}
+
+/// Iterates over the runes of `p`, yielding the byte offset, the rune
+/// and its width, like Go's `for i, r := range string(p)`. Invalid encodings
+/// yield [`RUNE_ERROR`] with a width of 1.
+#[inline]
+pub fn runes(p: &[u8]) -> impl DoubleEndedIterator<Item = (usize, char, usize)> + '_ {
+    Runes {
+        p,
+        front: 0,
+        back: p.len(),
+    }
+}
+
+struct Runes<'a> {
+    p: &'a [u8],
+    front: usize,
+    back: usize,
+}
+
+impl Iterator for Runes<'_> {
+    type Item = (usize, char, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let i = self.front;
+        let (r, size) = decode_rune(&self.p[i..self.back]);
+        self.front += size;
+        Some((i, r, size))
+    }
+}
+
+impl DoubleEndedIterator for Runes<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let (r, size) = decode_last_rune(&self.p[self.front..self.back]);
+        self.back -= size;
+        Some((self.back, r, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UTF8_MAP: &[(char, &[u8])] = &[
+        ('\u{0000}', b"\x00"),
+        ('\u{0001}', b"\x01"),
+        ('\u{007e}', b"\x7e"),
+        ('\u{007f}', b"\x7f"),
+        ('\u{0080}', b"\xc2\x80"),
+        ('\u{0081}', b"\xc2\x81"),
+        ('\u{00bf}', b"\xc2\xbf"),
+        ('\u{00c0}', b"\xc3\x80"),
+        ('\u{00c1}', b"\xc3\x81"),
+        ('\u{00c8}', b"\xc3\x88"),
+        ('\u{00d0}', b"\xc3\x90"),
+        ('\u{00e0}', b"\xc3\xa0"),
+        ('\u{00f0}', b"\xc3\xb0"),
+        ('\u{00f8}', b"\xc3\xb8"),
+        ('\u{00ff}', b"\xc3\xbf"),
+        ('\u{0100}', b"\xc4\x80"),
+        ('\u{07ff}', b"\xdf\xbf"),
+        ('\u{0400}', b"\xd0\x80"),
+        ('\u{0800}', b"\xe0\xa0\x80"),
+        ('\u{0801}', b"\xe0\xa0\x81"),
+        ('\u{1000}', b"\xe1\x80\x80"),
+        ('\u{d000}', b"\xed\x80\x80"),
+        ('\u{d7ff}', b"\xed\x9f\xbf"),
+        ('\u{e000}', b"\xee\x80\x80"),
+        ('\u{fffe}', b"\xef\xbf\xbe"),
+        ('\u{ffff}', b"\xef\xbf\xbf"),
+        ('\u{10000}', b"\xf0\x90\x80\x80"),
+        ('\u{10001}', b"\xf0\x90\x80\x81"),
+        ('\u{40000}', b"\xf1\x80\x80\x80"),
+        ('\u{10fffe}', b"\xf4\x8f\xbf\xbe"),
+        ('\u{10ffff}', b"\xf4\x8f\xbf\xbf"),
+        ('\u{FFFD}', b"\xef\xbf\xbd"),
+    ];
+
+    const INVALID_SEQUENCES: &[&[u8]] = &[
+        b"\xed\xa0\x80\x80", // surrogate min
+        b"\xed\xbf\xbf\x80", // surrogate max
+        // xx
+        b"\x91\x80\x80\x80",
+        // s1
+        b"\xC2\x7F\x80\x80",
+        b"\xC2\xC0\x80\x80",
+        b"\xDF\x7F\x80\x80",
+        b"\xDF\xC0\x80\x80",
+        // s2
+        b"\xE0\x9F\xBF\x80",
+        b"\xE0\xA0\x7F\x80",
+        b"\xE0\xBF\xC0\x80",
+        b"\xE0\xC0\x80\x80",
+        // s3
+        b"\xE1\x7F\xBF\x80",
+        b"\xE1\x80\x7F\x80",
+        b"\xE1\xBF\xC0\x80",
+        b"\xE1\xC0\x80\x80",
+        // s4
+        b"\xED\x7F\xBF\x80",
+        b"\xED\x80\x7F\x80",
+        b"\xED\x9F\xC0\x80",
+        b"\xED\xA0\x80\x80",
+        // s5
+        b"\xF0\x8F\xBF\xBF",
+        b"\xF0\x90\x7F\xBF",
+        b"\xF0\x90\x80\x7F",
+        b"\xF0\xBF\xBF\xC0",
+        b"\xF0\xBF\xC0\x80",
+        b"\xF0\xC0\x80\x80",
+        // s6
+        b"\xF1\x7F\xBF\xBF",
+        b"\xF1\x80\x7F\xBF",
+        b"\xF1\x80\x80\x7F",
+        b"\xF1\xBF\xBF\xC0",
+        b"\xF1\xBF\xC0\x80",
+        b"\xF1\xC0\x80\x80",
+        // s7
+        b"\xF4\x7F\xBF\xBF",
+        b"\xF4\x80\x7F\xBF",
+        b"\xF4\x80\x80\x7F",
+        b"\xF4\x8F\xBF\xC0",
+        b"\xF4\x8F\xC0\x80",
+        b"\xF4\x90\x80\x80",
+    ];
+
+    #[test]
+    fn test_full_rune() {
+        for (_, s) in UTF8_MAP {
+            assert!(full_rune(s), "full_rune({s:?})");
+            assert!(!full_rune(&s[..s.len() - 1]), "full_rune({s:?}[:-1])");
+        }
+        for s in [&b"\xc0"[..], b"\xc1"] {
+            assert!(full_rune(s), "full_rune({s:?})");
+        }
+    }
+
+    #[test]
+    fn test_encode_rune() {
+        for (r, s) in UTF8_MAP {
+            let mut buf = [0; UTF_MAX];
+            let n = encode_rune(&mut buf, *r);
+            assert_eq!(&buf[..n], *s);
+            assert_eq!(rune_len(*r), s.len());
+
+            let mut p = b"init".to_vec();
+            append_rune(&mut p, *r);
+            assert_eq!(&p[..4], b"init");
+            assert_eq!(&p[4..], *s);
+        }
+    }
+
+    #[test]
+    fn test_decode_rune() {
+        for (r, s) in UTF8_MAP {
+            assert_eq!(decode_rune(s), (*r, s.len()), "decode_rune({s:?})");
+            assert_eq!(decode_last_rune(s), (*r, s.len()));
+
+            // there's an extra byte that bytes left behind - make sure trailing byte works
+            let mut b = s.to_vec();
+            b.push(0);
+            assert_eq!(decode_rune(&b), (*r, s.len()));
+
+            // make sure missing bytes fail
+            let wantsize = if s.len() == 1 { 0 } else { 1 };
+            assert_eq!(decode_rune(&s[..s.len() - 1]), (RUNE_ERROR, wantsize));
+
+            // make sure bad sequences fail
+            let mut b = s.to_vec();
+            let last = b.len() - 1;
+            if b.len() == 1 {
+                b[0] = 0x80;
+            } else {
+                b[last] = 0x7F;
+            }
+            assert_eq!(decode_rune(&b), (RUNE_ERROR, 1), "decode_rune({b:?})");
+        }
+        assert_eq!(decode_rune(b""), (RUNE_ERROR, 0));
+        assert_eq!(decode_last_rune(b""), (RUNE_ERROR, 0));
+    }
+
+    #[test]
+    fn test_decode_invalid_sequence() {
+        for s in INVALID_SEQUENCES {
+            assert_eq!(decode_rune(s).0, RUNE_ERROR, "decode_rune({s:?})");
+            assert!(!valid(s), "valid({s:?})");
+        }
+    }
+
+    #[test]
+    fn test_runes() {
+        let s = "\u{0}a\u{80}\u{800}\u{10000}";
+        let fwd: Vec<_> = runes(s.as_bytes()).collect();
+        let want: Vec<_> = s
+            .char_indices()
+            .map(|(i, r)| (i, r, r.len_utf8()))
+            .collect();
+        assert_eq!(fwd, want);
+        let mut back: Vec<_> = runes(s.as_bytes()).rev().collect();
+        back.reverse();
+        assert_eq!(back, want);
+
+        let b = b"a\xffb\xe2\x98";
+        let got: Vec<_> = runes(b).collect();
+        assert_eq!(
+            got,
+            [
+                (0, 'a', 1),
+                (1, RUNE_ERROR, 1),
+                (2, 'b', 1),
+                (3, RUNE_ERROR, 1),
+                (4, RUNE_ERROR, 1)
+            ]
+        );
+        let mut back: Vec<_> = runes(b).rev().collect();
+        back.reverse();
+        assert_eq!(back, got);
+    }
+
+    #[test]
+    fn test_rune_count() {
+        let tests: &[(&[u8], usize)] = &[
+            (b"abcd", 4),
+            ("☺☻☹".as_bytes(), 3),
+            (b"1,2,3,4", 7),
+            (b"\xe2\x00", 2),
+            (b"\xe2\x80", 2),
+            (b"a\xe2\x80", 3),
+        ];
+        for (s, n) in tests {
+            assert_eq!(rune_count(s), *n, "rune_count({s:?})");
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        let tests: &[(&[u8], bool)] = &[
+            (b"", true),
+            (b"a", true),
+            (b"abc", true),
+            ("Ж".as_bytes(), true),
+            ("ЖЖ".as_bytes(), true),
+            ("брэд-ЛГТМ".as_bytes(), true),
+            ("☺☻☹".as_bytes(), true),
+            (b"aa\xe2", false),
+            (&[66, 250], false),
+            (&[66, 250, 67], false),
+            ("a\u{FFFD}b".as_bytes(), true),
+            (b"\xF4\x8F\xBF\xBF", true),      // U+10FFFF
+            (b"\xF4\x90\x80\x80", false),     // U+10FFFF+1; out of range
+            (b"\xF7\xBF\xBF\xBF", false),     // 0x1FFFFF; out of range
+            (b"\xFB\xBF\xBF\xBF\xBF", false), // 0x3FFFFFF; out of range
+            (b"\xc0\x80", false),             // U+0000 encoded in two bytes: incorrect
+            (b"\xed\xa0\x80", false),         // U+D800 high surrogate (sic)
+            (b"\xed\xbf\xbf", false),         // U+DFFF low surrogate (sic)
+        ];
+        for (s, ok) in tests {
+            assert_eq!(valid(s), *ok, "valid({s:?})");
+        }
+
+        let runes: &[(u32, bool)] = &[
+            (0, true),
+            ('e' as u32, true),
+            ('é' as u32, true),
+            ('☺' as u32, true),
+            (RUNE_ERROR as u32, true),
+            (MAX_RUNE as u32, true),
+            (0xD7FF, true),
+            (0xD800, false),
+            (0xDFFF, false),
+            (0xE000, true),
+            (MAX_RUNE as u32 + 1, false),
+            (u32::MAX, false),
+        ];
+        for (r, ok) in runes {
+            assert_eq!(valid_rune(*r), *ok, "valid_rune({r:#x})");
+        }
+    }
+}